@@ -7,11 +7,12 @@
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::process::Command as ProcessCommand;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 // Import oops types
-use oops::core::{Command as TfCommand, Rule};
+use oops::core::Command as TfCommand;
 use oops::rules::get_all_rules;
+use oops::utils::fuzzy::{get_close_matches_with, Algorithm};
 
 /// Benchmark the startup time of the Rust binary.
 fn bench_rust_startup(c: &mut Criterion) {
@@ -245,7 +246,40 @@ fn bench_command_parsing(c: &mut Criterion) {
             |b, &script| {
                 b.iter(|| {
                     let cmd = TfCommand::new(script, "");
-                    black_box(cmd.script_parts())
+                    black_box(cmd.script_parts().len())
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Benchmark fuzzy matching against a PATH-sized candidate set.
+fn bench_fuzzy_matching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fuzzy_matching");
+
+    let candidates: Vec<String> = (0..5000)
+        .map(|i| format!("{}-tool-{}", ["git", "docker", "python", "cargo"][i % 4], i))
+        .collect();
+
+    for algorithm in [
+        Algorithm::JaroWinkler,
+        Algorithm::DamerauLevenshtein,
+        Algorithm::Subsequence,
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new("get_close_matches", format!("{:?}", algorithm)),
+            &algorithm,
+            |b, &algorithm| {
+                b.iter(|| {
+                    black_box(get_close_matches_with(
+                        black_box("dokcer"),
+                        &candidates,
+                        3,
+                        0.6,
+                        algorithm,
+                    ))
                 })
             },
         );
@@ -302,65 +336,6 @@ fn bench_memory_pressure(c: &mut Criterion) {
     });
 }
 
-/// Custom startup time measurement (more accurate than criterion for cold starts).
-fn measure_startup_times() -> (Duration, Option<Duration>) {
-    // Measure Rust startup
-    let rust_start = Instant::now();
-    let _ = ProcessCommand::new(env!("CARGO_BIN_EXE_oops"))
-        .arg("--version")
-        .output();
-    let rust_duration = rust_start.elapsed();
-
-    // Measure Python startup if available
-    let python_duration = if ProcessCommand::new("python")
-        .args(["-c", "import thefuck"])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-    {
-        let python_start = Instant::now();
-        let _ = ProcessCommand::new("python")
-            .args(["-m", "thefuck", "--version"])
-            .output();
-        Some(python_start.elapsed())
-    } else {
-        None
-    };
-
-    (rust_duration, python_duration)
-}
-
-/// Print startup time comparison at the end of benchmarks.
-fn print_startup_comparison() {
-    println!("\n=== Startup Time Comparison (single run) ===");
-
-    // Run multiple times and average
-    let mut rust_times = Vec::new();
-    let mut python_times = Vec::new();
-
-    for _ in 0..5 {
-        let (rust, python) = measure_startup_times();
-        rust_times.push(rust);
-        if let Some(p) = python {
-            python_times.push(p);
-        }
-    }
-
-    let rust_avg: Duration = rust_times.iter().sum::<Duration>() / rust_times.len() as u32;
-    println!("Rust average startup time: {:?}", rust_avg);
-
-    if !python_times.is_empty() {
-        let python_avg: Duration =
-            python_times.iter().sum::<Duration>() / python_times.len() as u32;
-        println!("Python average startup time: {:?}", python_avg);
-
-        let speedup = python_avg.as_secs_f64() / rust_avg.as_secs_f64();
-        println!("Rust is {:.1}x faster than Python", speedup);
-    } else {
-        println!("Python thefuck not available for comparison");
-    }
-}
-
 criterion_group! {
     name = benches;
     config = Criterion::default()
@@ -373,14 +348,9 @@ criterion_group! {
         bench_rule_matching,
         bench_individual_rules,
         bench_command_parsing,
+        bench_fuzzy_matching,
         bench_full_correction,
         bench_memory_pressure
 }
 
 criterion_main!(benches);
-
-// Uncomment to run startup comparison manually
-// #[test]
-// fn test_print_startup_comparison() {
-//     print_startup_comparison();
-// }
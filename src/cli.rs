@@ -2,7 +2,7 @@
 //!
 //! Uses clap derive API to define the command-line interface.
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// Special placeholder used by shell aliases to separate oops args from command args.
 /// When the shell alias is invoked, it passes this placeholder followed by the previous
@@ -16,7 +16,8 @@ pub const THEFUCK_ARGUMENT_PLACEHOLDER: &str = "THEFUCK_ARGUMENT_PLACEHOLDER";
     name = "oops",
     version,
     about = "A blazingly fast command-line typo corrector",
-    long_about = None
+    long_about = None,
+    args_conflicts_with_subcommands = true
 )]
 pub struct Cli {
     /// Generate shell alias
@@ -47,12 +48,77 @@ pub struct Cli {
     #[arg(long = "shell-logger")]
     pub shell_logger: Option<String>,
 
+    /// Overlay a named `[profile.<name>]` section from the settings file
+    #[arg(long, env = "OOPS_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    /// Override a rule's priority (repeatable, e.g. `--priority git_push=50`)
+    #[arg(long, value_name = "RULE=NUM")]
+    pub priority: Vec<String>,
+
     /// Command arguments (from shell alias)
     ///
     /// These are typically passed by the shell alias after the
     /// THEFUCK_ARGUMENT_PLACEHOLDER separator.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
+
+    /// Management subcommand (e.g. `oops config list`)
+    #[command(subcommand)]
+    pub subcommand: Option<Commands>,
+}
+
+/// Management subcommands that run instead of fixing a command.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Commands {
+    /// View or modify the settings file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Manage cached data, like the scan of executables in PATH
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Import settings from thefuck's settings.py and THEFUCK_* variables
+    MigrateThefuck {
+        /// Show what would be migrated without writing the settings file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Actions available under `oops config`.
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the effective value of a setting
+    Get {
+        /// Setting name (e.g. `wait_command`)
+        key: String,
+    },
+    /// Validate and write a setting to the settings file
+    Set {
+        /// Setting name (e.g. `wait_command`)
+        key: String,
+        /// New value, as a TOML literal or colon-separated list
+        value: String,
+    },
+    /// Print all effective settings
+    List,
+    /// Open the settings file in $EDITOR
+    Edit,
+    /// Print a JSON Schema describing the settings file
+    Schema,
+    /// Upgrade the settings file to the current layout
+    Upgrade,
+}
+
+/// Actions available under `oops cache`.
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheAction {
+    /// Remove all cached data, e.g. after installing programs
+    Clear,
 }
 
 impl Cli {
@@ -107,6 +173,9 @@ mod tests {
             instant_mode: false,
             force_command: None,
             shell_logger: None,
+            profile: None,
+            priority: Vec::new(),
+            subcommand: None,
             command: vec![
                 "THEFUCK_ARGUMENT_PLACEHOLDER".to_string(),
                 "git".to_string(),
@@ -128,6 +197,9 @@ mod tests {
             instant_mode: false,
             force_command: None,
             shell_logger: None,
+            profile: None,
+            priority: Vec::new(),
+            subcommand: None,
             command: vec!["git".to_string(), "status".to_string()],
         };
 
@@ -144,6 +216,9 @@ mod tests {
             instant_mode: false,
             force_command: None,
             shell_logger: None,
+            profile: None,
+            priority: Vec::new(),
+            subcommand: None,
             command: vec![],
         };
 
@@ -160,6 +235,9 @@ mod tests {
             instant_mode: false,
             force_command: None,
             shell_logger: None,
+            profile: None,
+            priority: Vec::new(),
+            subcommand: None,
             command: vec!["git".to_string(), "status".to_string()],
         };
 
@@ -167,4 +245,72 @@ mod tests {
         // Should remain unchanged
         assert_eq!(cli.command, vec!["git", "status"]);
     }
+
+    #[test]
+    fn test_config_subcommand() {
+        let cli = Cli::try_parse_from(["oops", "config", "set", "wait_command", "5"]).unwrap();
+        match cli.subcommand {
+            Some(Commands::Config {
+                action: ConfigAction::Set { key, value },
+            }) => {
+                assert_eq!(key, "wait_command");
+                assert_eq!(value, "5");
+            }
+            other => panic!("unexpected subcommand: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_migrate_thefuck_subcommand() {
+        let cli = Cli::try_parse_from(["oops", "migrate-thefuck", "--dry-run"]).unwrap();
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::MigrateThefuck { dry_run: true })
+        ));
+    }
+
+    #[test]
+    fn test_cache_subcommand() {
+        let cli = Cli::try_parse_from(["oops", "cache", "clear"]).unwrap();
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Cache {
+                action: CacheAction::Clear
+            })
+        ));
+    }
+
+    #[test]
+    fn test_config_word_after_placeholder_is_a_command() {
+        let cli = Cli::try_parse_from([
+            "oops",
+            "THEFUCK_ARGUMENT_PLACEHOLDER",
+            "git",
+            "config",
+            "--list",
+        ])
+        .unwrap();
+        assert!(cli.subcommand.is_none());
+        assert_eq!(
+            cli.command,
+            vec!["THEFUCK_ARGUMENT_PLACEHOLDER", "git", "config", "--list"]
+        );
+    }
+
+    #[test]
+    fn test_priority_flag() {
+        let cli =
+            Cli::try_parse_from(["oops", "--priority", "sudo=10", "--priority", "git_push=50"])
+                .unwrap();
+        assert_eq!(cli.priority, vec!["sudo=10", "git_push=50"]);
+    }
+
+    #[test]
+    fn test_profile_flag() {
+        let cli = Cli::try_parse_from(["oops", "--profile", "work", "-y"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+
+        let cli = Cli::try_parse_from(["oops", "config", "list", "--profile", "home"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("home"));
+    }
 }
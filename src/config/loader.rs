@@ -2,71 +2,140 @@
 //!
 //! This module handles loading settings from multiple sources in order of priority:
 //! 1. Default values
-//! 2. Settings file (~/.config/oops/config.toml or ~/.config/thefuck/settings.toml for migration)
-//! 3. Environment variables (THEFUCK_* for backward compatibility)
-//! 4. CLI arguments
+//! 2. Settings file (~/.config/thefuck/settings.toml, or settings.yaml / settings.json)
+//! 3. Project overrides (`.oops.toml` in the working directory and its parents)
+//! 4. Environment variables (OOPS_*, or THEFUCK_* for backward compatibility)
+//! 5. CLI arguments
 
 use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use once_cell::sync::Lazy;
+use once_cell::unsync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tracing::{debug, warn};
 
+use super::settings::is_pattern;
+use super::validate::validate_settings_file;
 use super::Settings;
 use crate::cli::Cli;
 
-/// Global settings instance, lazily initialized.
+/// Settings file names, in order of precedence.
 ///
-/// This is initialized with default settings and should be updated
-/// via `init_settings()` after CLI arguments are parsed.
-pub static SETTINGS: Lazy<std::sync::RwLock<Settings>> =
-    Lazy::new(|| std::sync::RwLock::new(Settings::default()));
-
-/// Initialize global settings with CLI arguments.
+/// All formats share the same schema; only the first one found is loaded.
+const SETTINGS_FILE_NAMES: &[&str] = &[
+    "settings.toml",
+    "settings.yaml",
+    "settings.yml",
+    "settings.json",
+];
+
+/// Settings file key holding the named profiles (`[profile.<name>]`).
+pub(super) const PROFILES_KEY: &str = "profile";
+
+/// Settings file key holding the layout version of the file.
+pub(super) const VERSION_KEY: &str = "version";
+
+/// Upgrades between settings file layouts; `MIGRATIONS[n]` upgrades a
+/// version `n` file to version `n + 1`.
 ///
-/// This should be called once at startup after parsing CLI arguments.
-pub fn init_settings(cli_args: &Cli) -> Result<()> {
-    let settings = load_settings(cli_args)?;
-    let mut global_settings = SETTINGS
-        .write()
-        .map_err(|e| anyhow::anyhow!("Failed to acquire settings lock: {}", e))?;
-    *global_settings = settings;
-    Ok(())
+/// Files without a `version` key are version 0. Add an entry here whenever
+/// a setting is renamed or moved, so older files keep working.
+const MIGRATIONS: &[fn(&mut toml::Table)] = &[migrate_v0_to_v1];
+
+/// Current settings file layout version.
+pub const SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Name of the per-project settings override file.
+pub const PROJECT_SETTINGS_FILE_NAME: &str = ".oops.toml";
+
+/// Serialization format of a settings file, detected from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SettingsFormat {
+    Toml,
+    Yaml,
+    Json,
 }
 
-/// Get a read-only reference to the current settings.
-///
-/// # Panics
-///
-/// Panics if the settings lock is poisoned.
-pub fn get_settings() -> impl std::ops::Deref<Target = Settings> {
-    SETTINGS.read().expect("Settings lock poisoned")
+impl SettingsFormat {
+    /// Detect the format from a file extension, defaulting to TOML.
+    pub(super) fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => SettingsFormat::Yaml,
+            Some("json") => SettingsFormat::Json,
+            _ => SettingsFormat::Toml,
+        }
+    }
+
+    /// Deserialize file content in this format.
+    pub(super) fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        Ok(match self {
+            SettingsFormat::Toml => toml::from_str(content)?,
+            SettingsFormat::Yaml => serde_yaml::from_str(content)?,
+            SettingsFormat::Json => serde_json::from_str(content)?,
+        })
+    }
+
+    /// Serialize a value in this format.
+    fn serialize<T: Serialize>(self, value: &T) -> Result<String> {
+        Ok(match self {
+            SettingsFormat::Toml => toml::to_string_pretty(value)?,
+            SettingsFormat::Yaml => serde_yaml::to_string(value)?,
+            SettingsFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+        })
+    }
 }
 
 /// Load settings from all sources in order of priority.
 ///
 /// Settings are loaded from:
 /// 1. Default values
-/// 2. Settings file (~/.config/oops/config.toml or fallback to ~/.config/thefuck/)
-/// 3. Environment variables (THEFUCK_* for backward compatibility)
-/// 4. CLI arguments
+/// 2. Settings file (~/.config/thefuck/settings.toml, .yaml or .json)
+/// 3. Project overrides (`.oops.toml` files found from the directory the
+///    command was run in, see [`crate::output::working_dir`])
+/// 4. Environment variables (OOPS_*, or THEFUCK_* for backward compatibility)
+/// 5. CLI arguments
 ///
 /// Later sources override earlier ones.
 pub fn load_settings(cli_args: &Cli) -> Result<Settings> {
+    load_settings_for_dir(cli_args, &crate::output::working_dir())
+}
+
+/// Load settings as [`load_settings`] does, looking for project overrides from `cwd`.
+pub fn load_settings_for_dir(cli_args: &Cli, cwd: &Path) -> Result<Settings> {
     // Start with defaults
     let mut settings = Settings::default();
     debug!("Starting with default settings");
 
     // Load from config file if it exists
     let config_path = get_settings_path();
+    let candidates = existing_settings_files();
+    if candidates.len() > 1 {
+        warn!(
+            "Multiple settings files found, using {} and ignoring: {}",
+            config_path.display(),
+            candidates[1..]
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    let mut profiles = toml::Table::new();
     if config_path.exists() {
         debug!("Loading settings from: {}", config_path.display());
-        match load_from_file(&config_path) {
-            Ok(file_settings) => {
+        match load_from_file_with_profiles(&config_path) {
+            Ok((file_settings, file_profiles)) => {
                 settings.merge(&file_settings);
+                profiles = file_profiles;
                 debug!("Merged settings from config file");
             }
             Err(e) => {
@@ -77,24 +146,111 @@ pub fn load_settings(cli_args: &Cli) -> Result<Settings> {
         debug!("Config file not found at: {}", config_path.display());
     }
 
+    // Overlay the selected profile from the settings file
+    if let Some(ref name) = cli_args.profile {
+        match profiles.get(name) {
+            Some(toml::Value::Table(profile)) => match apply_overrides(&mut settings, profile) {
+                Ok(()) => debug!("Applied profile '{}'", name),
+                Err(e) => warn!("Failed to apply profile '{}': {:#}", name, e),
+            },
+            Some(_) => warn!("Profile '{}' must be a table", name),
+            None => warn!("Profile '{}' not found in {}", name, config_path.display()),
+        }
+    }
+
+    // Layer project overrides, outermost directory first
+    let project_files = find_project_settings_files(cwd);
+    for project_path in &project_files {
+        debug!("Loading project settings from: {}", project_path.display());
+        match read_settings_table(project_path)
+            .and_then(|overrides| apply_overrides(&mut settings, &overrides))
+        {
+            Ok(()) => debug!("Applied project settings"),
+            Err(e) => warn!("Failed to load project settings: {:#}", e),
+        }
+    }
+
     // Override with environment variables
-    let env_settings = load_from_env();
-    settings.merge(&env_settings);
+    apply_env_overrides(&mut settings);
     debug!("Applied environment variable overrides");
 
     // Override with CLI arguments
     apply_cli_args(&mut settings, cli_args);
     debug!("Applied CLI argument overrides");
 
+    // Rule names are only needed when a file or a pattern refers to rules,
+    // so they're listed on first use and then reused
+    let corrections: Vec<String> = settings.corrections.keys().cloned().collect();
+    let rule_names = OnceCell::new();
+    let known_rules = || {
+        rule_names
+            .get_or_init(|| known_rule_names(&corrections))
+            .clone()
+    };
+
+    // Report typos and invalid values in the files that were loaded
+    let mut loaded_files = Vec::new();
+    if config_path.exists() {
+        loaded_files.push(config_path);
+    }
+    loaded_files.extend(project_files);
+    for path in &loaded_files {
+        match validate_settings_file(path, &known_rules) {
+            Ok(diagnostics) => {
+                for diagnostic in diagnostics {
+                    warn!("{}", diagnostic);
+                }
+            }
+            Err(e) => debug!("Skipping validation of {}: {:#}", path.display(), e),
+        }
+    }
+
+    // Expand globs like `git_*` against the rule registry
+    let has_patterns = settings
+        .rules
+        .iter()
+        .chain(&settings.exclude_rules)
+        .any(|rule| is_pattern(rule));
+    if has_patterns {
+        let rule_names = known_rules();
+        let rule_names: Vec<&str> = rule_names.iter().map(String::as_str).collect();
+        settings.expand_rule_patterns(&rule_names);
+    }
+
     Ok(settings)
 }
 
+/// Names of all rules settings can refer to: the built-in rules, the rules
+/// directory and the user-defined `corrections`.
+fn known_rule_names(corrections: &[String]) -> Vec<String> {
+    crate::rules::get_all_rules()
+        .iter()
+        .map(|rule| rule.name().to_string())
+        .chain(crate::rules::drop_in::rule_names(&get_rules_dir()))
+        .chain(corrections.iter().cloned())
+        .collect()
+}
+
 /// Get the path to the settings file.
 ///
-/// Returns the settings file path in the oops config directory.
-/// Falls back to thefuck config directory for migration support.
+/// Returns the first existing `settings.toml`, `settings.yaml`, `settings.yml`
+/// or `settings.json` in the config directory, or the `settings.toml` path if
+/// none exists yet.
 pub fn get_settings_path() -> PathBuf {
-    get_config_dir().join("settings.toml")
+    existing_settings_files()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| get_config_dir().join(SETTINGS_FILE_NAMES[0]))
+}
+
+/// List the settings files that exist in the config directory, in precedence order.
+fn existing_settings_files() -> Vec<PathBuf> {
+    let config_dir = get_config_dir();
+    SETTINGS_FILE_NAMES
+        .iter()
+        .map(|name| config_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
 }
 
 /// Get the path to the rules directory.
@@ -104,6 +260,29 @@ pub fn get_rules_dir() -> PathBuf {
     get_config_dir().join("rules")
 }
 
+/// Get the directory for cached data, like re-run output.
+///
+/// Returns the `cache` directory in the oops config directory.
+pub fn get_cache_dir() -> PathBuf {
+    get_config_dir().join("cache")
+}
+
+/// Remove everything in the cache directory.
+///
+/// Returns the cache directory. A missing cache directory is already clear.
+pub fn clear_cache() -> Result<PathBuf> {
+    let dir = get_cache_dir();
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => debug!("Removed cache directory: {}", dir.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to remove cache directory: {}", dir.display()))
+        }
+    }
+    Ok(dir)
+}
+
 /// Get the oops configuration directory.
 ///
 /// Returns the oops config directory path. Currently uses ~/.config/thefuck/
@@ -152,142 +331,166 @@ pub fn ensure_rules_dir() -> Result<PathBuf> {
     Ok(rules_dir)
 }
 
-/// Load settings from a TOML file.
-fn load_from_file(path: &PathBuf) -> Result<Settings> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-
-    let settings: Settings = toml::from_str(&content)
-        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
-
-    Ok(settings)
-}
-
-/// Load settings from environment variables.
+/// Find `.oops.toml` files from `cwd` up to the enclosing repository root.
 ///
-/// Supported environment variables:
-/// - `THEFUCK_RULES`: colon-separated list of rules
-/// - `THEFUCK_EXCLUDE_RULES`: colon-separated list of rules to exclude
-/// - `THEFUCK_PRIORITY`: format "rule=num:rule=num"
-/// - `THEFUCK_REQUIRE_CONFIRMATION`: "true" or "false"
-/// - `THEFUCK_WAIT_COMMAND`: integer (seconds)
-/// - `THEFUCK_WAIT_SLOW_COMMAND`: integer (seconds)
-/// - `THEFUCK_NO_COLORS`: "true" or "false"
-/// - `THEFUCK_HISTORY_LIMIT`: integer
-/// - `THEFUCK_ALTER_HISTORY`: "true" or "false"
-/// - `THEFUCK_NUM_CLOSE_MATCHES`: integer
-/// - `THEFUCK_INSTANT_MODE`: "true" or "false"
-/// - `THEFUCK_DEBUG`: "true" or "false"
-fn load_from_env() -> Settings {
-    let mut settings = Settings::default();
-
-    // THEFUCK_RULES: colon-separated list
-    if let Ok(rules) = env::var("THEFUCK_RULES") {
-        settings.rules = parse_colon_separated(&rules);
-        debug!("THEFUCK_RULES: {:?}", settings.rules);
+/// A directory containing `.git` marks the repository root and ends the search.
+/// Outside a repository only `cwd` itself is checked. The files are returned
+/// outermost first, so that the closest file is applied last and wins.
+pub fn find_project_settings_files(cwd: &Path) -> Vec<PathBuf> {
+    if cwd.as_os_str().is_empty() {
+        return Vec::new();
     }
 
-    // THEFUCK_EXCLUDE_RULES: colon-separated list
-    if let Ok(exclude_rules) = env::var("THEFUCK_EXCLUDE_RULES") {
-        settings.exclude_rules = parse_colon_separated(&exclude_rules);
-        debug!("THEFUCK_EXCLUDE_RULES: {:?}", settings.exclude_rules);
+    let mut found = Vec::new();
+    let mut reached_root = false;
+    for dir in cwd.ancestors() {
+        let candidate = dir.join(PROJECT_SETTINGS_FILE_NAME);
+        if candidate.is_file() {
+            found.push(candidate);
+        }
+        if dir.join(".git").exists() {
+            reached_root = true;
+            break;
+        }
     }
 
-    // THEFUCK_PRIORITY: format "rule=num:rule=num"
-    if let Ok(priority_str) = env::var("THEFUCK_PRIORITY") {
-        settings.priority = parse_priority(&priority_str);
-        debug!("THEFUCK_PRIORITY: {:?}", settings.priority);
+    if !reached_root {
+        // Not inside a repository: only the working directory counts
+        found.retain(|path| path.parent() == Some(cwd));
     }
 
-    // THEFUCK_REQUIRE_CONFIRMATION: "true" or "false"
-    if let Ok(value) = env::var("THEFUCK_REQUIRE_CONFIRMATION") {
-        settings.require_confirmation = parse_bool(&value, true);
-        debug!(
-            "THEFUCK_REQUIRE_CONFIRMATION: {}",
-            settings.require_confirmation
-        );
-    }
+    found.reverse();
+    found
+}
 
-    // THEFUCK_WAIT_COMMAND: integer (seconds)
-    if let Ok(value) = env::var("THEFUCK_WAIT_COMMAND") {
-        if let Ok(secs) = value.parse::<u64>() {
-            settings.wait_command = secs;
-            debug!("THEFUCK_WAIT_COMMAND: {}", settings.wait_command);
-        } else {
-            warn!("Invalid THEFUCK_WAIT_COMMAND value: {}", value);
-        }
-    }
+/// Overlay the keys present in `overrides` onto `settings`.
+///
+/// Unlike [`Settings::merge`], a key that is present always wins, even when
+/// it sets a value back to its default. Table settings (`priority`, `env`,
+/// `corrections`, `rule`) are extended rather than replaced.
+fn apply_overrides(settings: &mut Settings, overrides: &toml::Table) -> Result<()> {
+    let mut table =
+        match toml::Value::try_from(&*settings).context("Failed to serialize settings")? {
+            toml::Value::Table(table) => table,
+            _ => unreachable!("Settings always serializes to a table"),
+        };
 
-    // THEFUCK_WAIT_SLOW_COMMAND: integer (seconds)
-    if let Ok(value) = env::var("THEFUCK_WAIT_SLOW_COMMAND") {
-        if let Ok(secs) = value.parse::<u64>() {
-            settings.wait_slow_command = secs;
-            debug!("THEFUCK_WAIT_SLOW_COMMAND: {}", settings.wait_slow_command);
-        } else {
-            warn!("Invalid THEFUCK_WAIT_SLOW_COMMAND value: {}", value);
+    for (key, value) in overrides {
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(extra)) => {
+                existing.extend(extra.clone());
+            }
+            _ => {
+                table.insert(key.clone(), value.clone());
+            }
         }
     }
 
-    // THEFUCK_NO_COLORS: "true" or "false"
-    if let Ok(value) = env::var("THEFUCK_NO_COLORS") {
-        settings.no_colors = parse_bool(&value, false);
-        debug!("THEFUCK_NO_COLORS: {}", settings.no_colors);
-    }
+    *settings = toml::Value::Table(table)
+        .try_into()
+        .context("Invalid settings override")?;
+    Ok(())
+}
 
-    // THEFUCK_HISTORY_LIMIT: integer
-    if let Ok(value) = env::var("THEFUCK_HISTORY_LIMIT") {
-        if let Ok(limit) = value.parse::<usize>() {
-            settings.history_limit = Some(limit);
-            debug!("THEFUCK_HISTORY_LIMIT: {:?}", settings.history_limit);
-        } else {
-            warn!("Invalid THEFUCK_HISTORY_LIMIT value: {}", value);
+/// Load settings from a TOML, YAML or JSON file.
+fn load_from_file(path: &Path) -> Result<Settings> {
+    load_from_file_with_profiles(path).map(|(settings, _)| settings)
+}
+
+/// Load settings from a file, returning the named profiles separately.
+///
+/// The `[profile.<name>]` tables are not part of the base settings; they are
+/// overlaid only when selected with `--profile` or `OOPS_PROFILE`.
+fn load_from_file_with_profiles(path: &Path) -> Result<(Settings, toml::Table)> {
+    let mut table = read_settings_table(path)?;
+    table.remove(VERSION_KEY);
+
+    let profiles = match table.remove(PROFILES_KEY) {
+        Some(toml::Value::Table(profiles)) => profiles,
+        Some(_) => {
+            warn!(
+                "Ignoring '{}' in {}: expected a table of profiles",
+                PROFILES_KEY,
+                path.display()
+            );
+            toml::Table::new()
         }
-    }
+        None => toml::Table::new(),
+    };
 
-    // THEFUCK_ALTER_HISTORY: "true" or "false"
-    if let Ok(value) = env::var("THEFUCK_ALTER_HISTORY") {
-        settings.alter_history = parse_bool(&value, true);
-        debug!("THEFUCK_ALTER_HISTORY: {}", settings.alter_history);
-    }
+    let settings: Settings = toml::Value::Table(table)
+        .try_into()
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
 
-    // THEFUCK_NUM_CLOSE_MATCHES: integer
-    if let Ok(value) = env::var("THEFUCK_NUM_CLOSE_MATCHES") {
-        if let Ok(num) = value.parse::<usize>() {
-            settings.num_close_matches = num;
-            debug!("THEFUCK_NUM_CLOSE_MATCHES: {}", settings.num_close_matches);
-        } else {
-            warn!("Invalid THEFUCK_NUM_CLOSE_MATCHES value: {}", value);
-        }
-    }
+    Ok((settings, profiles))
+}
 
-    // THEFUCK_SLOW_COMMANDS: colon-separated list
-    if let Ok(slow_commands) = env::var("THEFUCK_SLOW_COMMANDS") {
-        settings.slow_commands = parse_colon_separated(&slow_commands);
-        debug!("THEFUCK_SLOW_COMMANDS: {:?}", settings.slow_commands);
-    }
+/// Overlay the settings set by environment variables onto `settings`.
+///
+/// Every setting `name` can be set with `OOPS_<NAME>` or, for backward
+/// compatibility, `THEFUCK_<NAME>`; `OOPS_` wins when both are set. Values
+/// are parsed according to the type of the setting:
+/// - booleans: "true"/"false" (also "1"/"0", "yes"/"no", "on"/"off")
+/// - integers: e.g. `OOPS_WAIT_COMMAND=10`
+/// - lists: colon-separated, e.g. `OOPS_RULES=sudo:git_push`
+/// - `priority`: "rule=num:rule=num"
+/// - `env`: "NAME=value:NAME=value"
+///
+/// Like [`apply_overrides`], a variable that is set always wins, even when
+/// it sets a value back to its default.
+fn apply_env_overrides(settings: &mut Settings) {
+    let defaults = match toml::Value::try_from(Settings::default()) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => toml::Table::new(),
+    };
+
+    for key in Settings::KEYS {
+        let Some((name, raw)) = env_var_for_key(key) else {
+            continue;
+        };
 
-    // THEFUCK_EXCLUDED_SEARCH_PATH_PREFIXES: colon-separated list
-    if let Ok(prefixes) = env::var("THEFUCK_EXCLUDED_SEARCH_PATH_PREFIXES") {
-        settings.excluded_search_path_prefixes = parse_colon_separated(&prefixes);
-        debug!(
-            "THEFUCK_EXCLUDED_SEARCH_PATH_PREFIXES: {:?}",
-            settings.excluded_search_path_prefixes
-        );
-    }
+        let Some(value) = parse_env_value(key, &raw, defaults.get(*key)) else {
+            warn!("Invalid {} value: {}", name, raw);
+            continue;
+        };
 
-    // THEFUCK_INSTANT_MODE: "true" or "false"
-    if let Ok(value) = env::var("THEFUCK_INSTANT_MODE") {
-        settings.instant_mode = parse_bool(&value, false);
-        debug!("THEFUCK_INSTANT_MODE: {}", settings.instant_mode);
+        let mut overrides = toml::Table::new();
+        overrides.insert(key.to_string(), value);
+        match apply_overrides(settings, &overrides) {
+            Ok(()) => debug!("{}: {}", name, raw),
+            Err(e) => warn!("Invalid {} value: {} ({:#})", name, raw, e),
+        }
     }
+}
 
-    // THEFUCK_DEBUG: "true" or "false"
-    if let Ok(value) = env::var("THEFUCK_DEBUG") {
-        settings.debug = parse_bool(&value, false);
-        debug!("THEFUCK_DEBUG: {}", settings.debug);
-    }
+/// Environment variable prefixes for settings, highest precedence first.
+const ENV_PREFIXES: &[&str] = &["OOPS_", "THEFUCK_"];
 
-    settings
+/// Find the environment variable setting `key`, returning its name and value.
+fn env_var_for_key(key: &str) -> Option<(String, String)> {
+    ENV_PREFIXES.iter().find_map(|prefix| {
+        let name = format!("{}{}", prefix, key.to_uppercase());
+        env::var(&name).ok().map(|value| (name, value))
+    })
+}
+
+/// Parse an environment variable value for `key`, using the type of its default.
+fn parse_env_value(key: &str, raw: &str, default: Option<&toml::Value>) -> Option<toml::Value> {
+    match default {
+        Some(toml::Value::Boolean(default)) => {
+            Some(toml::Value::Boolean(parse_bool(raw, *default)))
+        }
+        Some(toml::Value::Integer(_)) => raw.trim().parse().ok().map(toml::Value::Integer),
+        Some(toml::Value::Array(_)) if !raw.trim_start().starts_with('[') => {
+            Some(toml::Value::Array(
+                parse_colon_separated(raw)
+                    .into_iter()
+                    .map(toml::Value::String)
+                    .collect(),
+            ))
+        }
+        _ => Some(parse_setting_value(key, raw)),
+    }
 }
 
 /// Apply CLI arguments to settings.
@@ -311,6 +514,13 @@ fn apply_cli_args(settings: &mut Settings, cli_args: &Cli) {
         settings.instant_mode = true;
         debug!("CLI: instant_mode = true (--enable-experimental-instant-mode)");
     }
+
+    // --priority rule=num: override rule priorities
+    for value in &cli_args.priority {
+        let priority = parse_priority(value);
+        debug!("CLI: priority {:?} (--priority)", priority);
+        settings.priority.extend(priority);
+    }
 }
 
 /// Parse a colon-separated string into a vector of strings.
@@ -368,8 +578,8 @@ fn parse_bool(value: &str, default: bool) -> bool {
 ///
 /// This is useful for first-time setup.
 pub fn create_default_settings_file() -> Result<PathBuf> {
-    let config_dir = ensure_config_dir()?;
-    let settings_path = config_dir.join("settings.toml");
+    ensure_config_dir()?;
+    let settings_path = get_settings_path();
 
     if !settings_path.exists() {
         let default_settings = Settings::default();
@@ -381,7 +591,11 @@ pub fn create_default_settings_file() -> Result<PathBuf> {
 
 "#;
 
-        fs::write(&settings_path, format!("{}{}", header, toml_content)).with_context(|| {
+        let content = format!(
+            "{}{} = {}\n\n{}",
+            header, VERSION_KEY, SETTINGS_VERSION, toml_content
+        );
+        fs::write(&settings_path, content).with_context(|| {
             format!("Failed to write settings file: {}", settings_path.display())
         })?;
 
@@ -394,6 +608,375 @@ pub fn create_default_settings_file() -> Result<PathBuf> {
     Ok(settings_path)
 }
 
+/// Get the effective value of a single setting.
+///
+/// Returns `Ok(None)` for known settings that have no value (e.g. an unset
+/// `history_limit`), and an error for unknown setting names.
+pub fn get_setting(settings: &Settings, key: &str) -> Result<Option<toml::Value>> {
+    ensure_known_key(key)?;
+
+    let value = toml::Value::try_from(settings).context("Failed to serialize settings")?;
+    Ok(value.get(key).cloned())
+}
+
+/// Validate a setting value and write it to the settings file.
+///
+/// The value is parsed as a TOML literal (`5`, `true`, `["sudo"]`). Bare
+/// words are accepted too, using the same formats as the environment
+/// variables (`sudo:git_push` for lists, `sudo=100` for priorities).
+///
+/// Returns the path of the settings file that was written.
+pub fn set_setting(key: &str, value: &str) -> Result<PathBuf> {
+    ensure_known_key(key)?;
+
+    let settings_path = get_settings_path();
+    set_setting_in_file(&settings_path, key, value)?;
+    Ok(settings_path)
+}
+
+/// Open the settings file in the user's editor.
+///
+/// Uses `$VISUAL` or `$EDITOR`, creating a default settings file first if
+/// none exists. The file is validated after the editor exits.
+pub fn edit_settings_file() -> Result<PathBuf> {
+    let settings_path = create_default_settings_file()?;
+    let editor = get_editor();
+    let parts = shlex::split(&editor).unwrap_or_else(|| vec![editor.clone()]);
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No editor configured"))?;
+
+    debug!("Opening {} with {}", settings_path.display(), editor);
+    let status = std::process::Command::new(program)
+        .args(args)
+        .arg(&settings_path)
+        .status()
+        .with_context(|| format!("Failed to launch editor: {}", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor exited with status: {}", status.code().unwrap_or(-1));
+    }
+
+    load_from_file(&settings_path)?;
+    for diagnostic in validate_settings_file(&settings_path, &|| known_rule_names(&[]))? {
+        warn!("{}", diagnostic);
+    }
+    Ok(settings_path)
+}
+
+/// Get the editor command from `$VISUAL` or `$EDITOR`.
+fn get_editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+/// Return an error if `key` is not a recognized setting.
+fn ensure_known_key(key: &str) -> Result<()> {
+    if Settings::KEYS.contains(&key) {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Unknown setting '{}'. Known settings: {}",
+            key,
+            Settings::KEYS.join(", ")
+        )
+    }
+}
+
+/// Write a single setting to a settings file, preserving the other keys.
+///
+/// The updated file is deserialized before writing, so a value of the
+/// wrong type is rejected and the file is left untouched.
+fn set_setting_in_file(path: &Path, key: &str, value: &str) -> Result<()> {
+    let mut values = toml::Table::new();
+    values.insert(key.to_string(), parse_setting_value(key, value));
+    write_settings_values(path, values)
+        .with_context(|| format!("Invalid value for '{}': {}", key, value))?;
+
+    debug!("Set '{}' in {}", key, path.display());
+    Ok(())
+}
+
+/// Write several settings to a settings file, preserving the other keys.
+///
+/// The merged file is validated before writing, like [`set_setting`]. TOML
+/// files are edited in place, so comments and the order of the keys that
+/// didn't change are kept.
+pub(super) fn write_settings_values(path: &Path, values: toml::Table) -> Result<()> {
+    let mut table = read_settings_table(path)?;
+    table.extend(values);
+
+    toml::Value::Table(table.clone()).try_into::<Settings>()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config directory: {}", parent.display()))?;
+    }
+
+    write_settings_table(path, &table)
+}
+
+/// Write `table` to a settings file in the file's format.
+fn write_settings_table(path: &Path, table: &toml::Table) -> Result<()> {
+    let content = match SettingsFormat::from_path(path) {
+        SettingsFormat::Toml => {
+            let existing = if path.exists() {
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read config file: {}", path.display()))?
+            } else {
+                String::new()
+            };
+            update_toml_document(&existing, table)
+        }
+        format => format.serialize(table),
+    }
+    .context("Failed to serialize settings")?;
+
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write settings file: {}", path.display()))?;
+    Ok(())
+}
+
+/// Update TOML `content` so it holds `table`, rewriting only the top-level
+/// keys whose values changed.
+fn update_toml_document(content: &str, table: &toml::Table) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = content.parse()?;
+    let current: toml::Table = toml::from_str(content)?;
+
+    let removed: Vec<String> = current
+        .keys()
+        .filter(|key| !table.contains_key(*key))
+        .cloned()
+        .collect();
+    for key in removed {
+        document.remove(&key);
+    }
+
+    for (key, value) in table {
+        if current.get(key) == Some(value) {
+            continue;
+        }
+        let mut single = toml::Table::new();
+        single.insert(key.clone(), value.clone());
+        let Some(item) = toml_edit::ser::to_document(&single)?.remove(key) else {
+            continue;
+        };
+        match (document.get_mut(key), item) {
+            // Keep the comments around a plain value that is replaced
+            (Some(toml_edit::Item::Value(existing)), toml_edit::Item::Value(mut value)) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            (_, item) => {
+                document.insert(key, item);
+            }
+        }
+    }
+
+    Ok(document.to_string())
+}
+
+/// Read a settings file as a table in the current layout, whatever its format.
+///
+/// Older layouts are migrated in memory only; the file is upgraded the next
+/// time a setting is written to it, or with `oops config upgrade`. Returns an
+/// empty table if the file doesn't exist.
+fn read_settings_table(path: &Path) -> Result<toml::Table> {
+    let mut table = read_raw_settings_table(path)?;
+    let migrated = migrate_settings_table(&mut table)
+        .with_context(|| format!("Failed to migrate config file: {}", path.display()))?;
+    if migrated {
+        debug!(
+            "Read {} in an older layout; run `oops config upgrade` to update it",
+            path.display()
+        );
+    }
+    Ok(table)
+}
+
+/// Read a settings file as a table, exactly as written.
+fn read_raw_settings_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    SettingsFormat::from_path(path)
+        .parse(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))
+}
+
+/// Upgrade a settings table to [`SETTINGS_VERSION`] and stamp its version.
+///
+/// Returns whether any migration changed the settings themselves. Tables
+/// from a newer version of oops are left as they are, with a warning.
+fn migrate_settings_table(table: &mut toml::Table) -> Result<bool> {
+    let version = match table.get(VERSION_KEY) {
+        None => 0,
+        Some(toml::Value::Integer(version)) => u32::try_from(*version)
+            .map_err(|_| anyhow::anyhow!("Invalid settings version: {}", version))?,
+        Some(other) => anyhow::bail!("Invalid settings version: {}", other),
+    };
+
+    if version > SETTINGS_VERSION {
+        warn!(
+            "Settings file version {} is newer than this oops supports ({}); \
+             some settings may be ignored",
+            version, SETTINGS_VERSION
+        );
+        return Ok(false);
+    }
+
+    let original = table.clone();
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        debug!("Migrating settings from version {}", from);
+        migration(table);
+    }
+    table.remove(VERSION_KEY);
+    let mut unversioned = original;
+    unversioned.remove(VERSION_KEY);
+    let changed = *table != unversioned;
+
+    table.insert(
+        VERSION_KEY.to_string(),
+        toml::Value::Integer(SETTINGS_VERSION.into()),
+    );
+    Ok(changed)
+}
+
+/// Upgrade an older settings file in place, keeping a backup of the original.
+///
+/// Files whose settings don't need migrating are left untouched. TOML files
+/// keep their comments and the order of the keys that didn't change. Returns
+/// the backup path if the file was rewritten.
+///
+/// Only `oops config upgrade` calls this; loading settings never rewrites a
+/// file.
+pub fn upgrade_settings_file(path: &Path) -> Result<Option<PathBuf>> {
+    let mut table = read_raw_settings_table(path)?;
+    let version = table
+        .get(VERSION_KEY)
+        .and_then(toml::Value::as_integer)
+        .unwrap_or(0);
+    if !migrate_settings_table(&mut table)? {
+        return Ok(None);
+    }
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", version));
+    let backup = PathBuf::from(backup);
+    fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up settings file to {}", backup.display()))?;
+
+    write_settings_table(path, &table)?;
+    Ok(Some(backup))
+}
+
+/// Version 0 to 1: `[profiles.<name>]` becomes `[profile.<name>]`, rule
+/// options move from `[rules.<name>]` to `[rule.<name>]`, and list or table
+/// settings written as strings (`rules = "sudo:git_push"`,
+/// `priority = "sudo=100"`) become real lists and tables.
+fn migrate_v0_to_v1(table: &mut toml::Table) {
+    if !table.contains_key(PROFILES_KEY) {
+        if let Some(profiles) = table.remove("profiles") {
+            table.insert(PROFILES_KEY.to_string(), profiles);
+        }
+    }
+
+    migrate_v0_settings(table);
+    if let Some(toml::Value::Table(profiles)) = table.get_mut(PROFILES_KEY) {
+        for (_, profile) in profiles.iter_mut() {
+            if let toml::Value::Table(profile) = profile {
+                migrate_v0_settings(profile);
+            }
+        }
+    }
+}
+
+/// The per-table part of [`migrate_v0_to_v1`], shared with profiles.
+fn migrate_v0_settings(table: &mut toml::Table) {
+    if let Some(toml::Value::Table(options)) = table.get("rules").cloned() {
+        table.remove("rules");
+        let rule_options = table
+            .entry("rule")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(rule_options) = rule_options {
+            for (rule, value) in options {
+                rule_options.entry(rule).or_insert(value);
+            }
+        }
+    }
+
+    let defaults = match toml::Value::try_from(Settings::default()) {
+        Ok(toml::Value::Table(defaults)) => defaults,
+        _ => return,
+    };
+    for (key, value) in table.iter_mut() {
+        let expects_collection = matches!(
+            defaults.get(key),
+            Some(toml::Value::Array(_)) | Some(toml::Value::Table(_))
+        );
+        if let (true, toml::Value::String(raw)) = (expects_collection, &*value) {
+            *value = parse_setting_value(key, raw);
+        }
+    }
+}
+
+/// Parse a user-supplied value for `key` into a TOML value.
+///
+/// Valid TOML literals are used as-is. Anything else is interpreted using
+/// the shape of the setting: lists are colon-separated, `priority` uses
+/// `rule=num` pairs, `env` uses `NAME=value` pairs, everything else is a string.
+pub(super) fn parse_setting_value(key: &str, value: &str) -> toml::Value {
+    if let Ok(table) = format!("value = {}", value).parse::<toml::Table>() {
+        if let Some(parsed) = table.get("value") {
+            return parsed.clone();
+        }
+    }
+
+    match key {
+        "priority" => toml::Value::Table(
+            parse_priority(value)
+                .into_iter()
+                .map(|(rule, priority)| (rule, toml::Value::Integer(priority.into())))
+                .collect(),
+        ),
+        "env" => toml::Value::Table(
+            value
+                .split(':')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(name, val)| {
+                    (
+                        name.trim().to_string(),
+                        toml::Value::String(val.trim().to_string()),
+                    )
+                })
+                .collect(),
+        ),
+        "rules"
+        | "exclude_rules"
+        | "slow_commands"
+        | "excluded_search_path_prefixes"
+        | "preserve_env" => toml::Value::Array(
+            parse_colon_separated(value)
+                .into_iter()
+                .map(toml::Value::String)
+                .collect(),
+        ),
+        _ => toml::Value::String(value.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -413,6 +996,26 @@ mod tests {
         "THEFUCK_EXCLUDED_SEARCH_PATH_PREFIXES",
         "THEFUCK_INSTANT_MODE",
         "THEFUCK_DEBUG",
+        "THEFUCK_ENV",
+        "THEFUCK_PRESERVE_ENV",
+        "OOPS_RULES",
+        "OOPS_EXCLUDE_RULES",
+        "OOPS_PRIORITY",
+        "OOPS_REQUIRE_CONFIRMATION",
+        "OOPS_WAIT_COMMAND",
+        "OOPS_WAIT_SLOW_COMMAND",
+        "OOPS_NO_COLORS",
+        "OOPS_HISTORY_LIMIT",
+        "OOPS_ALTER_HISTORY",
+        "OOPS_NUM_CLOSE_MATCHES",
+        "OOPS_SLOW_COMMANDS",
+        "OOPS_EXCLUDED_SEARCH_PATH_PREFIXES",
+        "OOPS_INSTANT_MODE",
+        "OOPS_DEBUG",
+        "OOPS_ENV",
+        "OOPS_PRESERVE_ENV",
+        "OOPS_REEXECUTE",
+        "TF_PWD",
     ];
 
     fn clear_env_vars() {
@@ -421,6 +1024,23 @@ mod tests {
         }
     }
 
+    /// CLI arguments with every flag unset.
+    fn test_cli() -> Cli {
+        Cli {
+            alias: false,
+            yes: false,
+            repeat: false,
+            debug: false,
+            instant_mode: false,
+            force_command: None,
+            shell_logger: None,
+            profile: None,
+            priority: Vec::new(),
+            subcommand: None,
+            command: vec![],
+        }
+    }
+
     #[test]
     fn test_parse_colon_separated() {
         assert_eq!(
@@ -451,7 +1071,7 @@ mod tests {
     #[test]
     fn test_parse_priority_invalid() {
         let priority = parse_priority("sudo=abc:git_push=500");
-        assert!(priority.get("sudo").is_none());
+        assert!(!priority.contains_key("sudo"));
         assert_eq!(priority.get("git_push"), Some(&500));
     }
 
@@ -487,6 +1107,12 @@ mod tests {
         assert!(settings_path.ends_with("settings.toml"));
     }
 
+    #[test]
+    fn test_get_cache_dir() {
+        let cache_dir = get_cache_dir();
+        assert!(cache_dir.ends_with("thefuck/cache"));
+    }
+
     #[test]
     fn test_get_rules_dir() {
         let rules_dir = get_rules_dir();
@@ -494,13 +1120,14 @@ mod tests {
     }
 
     #[test]
-    fn test_load_from_env_rules() {
+    fn test_apply_env_overrides_rules() {
         let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
         clear_env_vars();
         // Set environment variable
         env::set_var("THEFUCK_RULES", "sudo:git_push");
 
-        let settings = load_from_env();
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
         assert_eq!(settings.rules, vec!["sudo", "git_push"]);
 
         // Clean up
@@ -508,41 +1135,125 @@ mod tests {
     }
 
     #[test]
-    fn test_load_from_env_debug() {
+    fn test_apply_env_overrides_debug() {
         let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
         clear_env_vars();
         env::set_var("THEFUCK_DEBUG", "true");
 
-        let settings = load_from_env();
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
         assert!(settings.debug);
 
         clear_env_vars();
     }
 
     #[test]
-    fn test_load_from_env_wait_command() {
+    fn test_apply_env_overrides_wait_command() {
         let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
         clear_env_vars();
         env::set_var("THEFUCK_WAIT_COMMAND", "10");
 
-        let settings = load_from_env();
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
         assert_eq!(settings.wait_command, 10);
 
         clear_env_vars();
     }
 
+    #[test]
+    fn test_apply_env_overrides_preserve_env() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        env::set_var("THEFUCK_PRESERVE_ENV", "LC_ALL:PAGER");
+
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
+        assert_eq!(settings.preserve_env, vec!["LC_ALL", "PAGER"]);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_apply_env_overrides_oops_prefix() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        env::set_var("OOPS_RULES", "sudo");
+        env::set_var("OOPS_NO_COLORS", "yes");
+        env::set_var("OOPS_HISTORY_LIMIT", "50");
+        env::set_var("OOPS_ENV", "LANG=C:GIT_TRACE=1");
+        env::set_var("OOPS_REEXECUTE", "never");
+
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
+        assert_eq!(settings.reexecute, crate::config::Reexecute::Never);
+        assert_eq!(settings.rules, vec!["sudo"]);
+        assert!(settings.no_colors);
+        assert_eq!(settings.history_limit, Some(50));
+        assert_eq!(settings.env.get("GIT_TRACE"), Some(&"1".to_string()));
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_apply_env_overrides_oops_prefix_takes_precedence() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        env::set_var("THEFUCK_WAIT_COMMAND", "10");
+        env::set_var("OOPS_WAIT_COMMAND", "20");
+        env::set_var("THEFUCK_PRIORITY", "sudo=5");
+
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
+        assert_eq!(settings.wait_command, 20);
+        assert_eq!(settings.get_rule_priority("sudo", 1000), 5);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_apply_env_overrides_invalid_values_ignored() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        env::set_var("OOPS_WAIT_COMMAND", "soon");
+        env::set_var("OOPS_HISTORY_LIMIT", "lots");
+
+        let mut settings = Settings::default();
+        apply_env_overrides(&mut settings);
+        assert_eq!(settings.wait_command, 3);
+        assert_eq!(settings.history_limit, None);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_env_overrides_file_with_default_value() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_SETTINGS_FILE_NAME),
+            "wait_command = 10\nrequire_confirmation = false\n",
+        )
+        .unwrap();
+        env::set_var("OOPS_WAIT_COMMAND", "3");
+        env::set_var("OOPS_REQUIRE_CONFIRMATION", "true");
+
+        let settings = load_settings_for_dir(&test_cli(), dir.path()).unwrap();
+
+        assert_eq!(settings.wait_command, 3);
+        assert!(settings.require_confirmation);
+
+        clear_env_vars();
+    }
+
     #[test]
     fn test_apply_cli_args() {
         let mut settings = Settings::default();
         let cli = Cli {
-            alias: false,
             yes: true,
-            repeat: false,
             debug: true,
             instant_mode: true,
-            force_command: None,
-            shell_logger: None,
-            command: vec![],
+            ..test_cli()
         };
 
         apply_cli_args(&mut settings, &cli);
@@ -553,25 +1264,493 @@ mod tests {
     }
 
     #[test]
-    fn test_load_settings_with_defaults() {
+    fn test_load_settings_expands_rule_patterns() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        env::set_var("THEFUCK_EXCLUDE_RULES", "git_push*");
+
+        let settings = load_settings_for_dir(&test_cli(), dir.path()).unwrap();
+
+        assert!(settings.exclude_rules.contains(&"git_push".to_string()));
+        assert!(settings
+            .exclude_rules
+            .contains(&"git_push_force".to_string()));
+        assert!(!settings.exclude_rules.iter().any(|rule| rule.contains('*')));
+        assert!(!settings.is_rule_enabled("git_push_force"));
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_priority_precedence() {
         let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
         clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_SETTINGS_FILE_NAME),
+            "[priority]\ngit_push = 50\nsudo = 60\nno_command = 70\n",
+        )
+        .unwrap();
+        env::set_var("THEFUCK_PRIORITY", "sudo=20:no_command=30");
         let cli = Cli {
-            alias: false,
-            yes: false,
-            repeat: false,
-            debug: false,
-            instant_mode: false,
-            force_command: None,
-            shell_logger: None,
-            command: vec![],
+            priority: vec!["no_command=5".to_string()],
+            ..test_cli()
         };
 
-        let settings = load_settings(&cli).unwrap();
+        let settings = load_settings_for_dir(&cli, dir.path()).unwrap();
+
+        assert_eq!(settings.get_rule_priority("git_push", 1000), 50);
+        assert_eq!(settings.get_rule_priority("sudo", 1000), 20);
+        assert_eq!(settings.get_rule_priority("no_command", 1000), 5);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_load_settings_with_defaults() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let settings = load_settings(&test_cli()).unwrap();
 
         // Should have default values
         assert_eq!(settings.rules, vec!["ALL"]);
         assert!(settings.require_confirmation);
         assert_eq!(settings.wait_command, 3);
     }
+
+    #[test]
+    fn test_parse_setting_value_toml_literals() {
+        assert_eq!(
+            parse_setting_value("wait_command", "5"),
+            toml::Value::Integer(5)
+        );
+        assert_eq!(
+            parse_setting_value("no_colors", "true"),
+            toml::Value::Boolean(true)
+        );
+        assert_eq!(
+            parse_setting_value("rules", r#"["sudo", "git_push"]"#),
+            toml::Value::Array(vec!["sudo".into(), "git_push".into()])
+        );
+    }
+
+    #[test]
+    fn test_parse_setting_value_bare_words() {
+        assert_eq!(
+            parse_setting_value("rules", "sudo:git_push"),
+            toml::Value::Array(vec!["sudo".into(), "git_push".into()])
+        );
+
+        let priority = parse_setting_value("priority", "sudo=100");
+        assert_eq!(priority.get("sudo"), Some(&toml::Value::Integer(100)));
+
+        let env = parse_setting_value("env", "PAGER=cat");
+        assert_eq!(env.get("PAGER"), Some(&toml::Value::String("cat".into())));
+    }
+
+    #[test]
+    fn test_get_setting() {
+        let settings = Settings::default();
+        assert_eq!(
+            get_setting(&settings, "wait_command").unwrap(),
+            Some(toml::Value::Integer(3))
+        );
+        assert_eq!(get_setting(&settings, "history_limit").unwrap(), None);
+        assert!(get_setting(&settings, "wait_commands").is_err());
+    }
+
+    #[test]
+    fn test_set_setting_in_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        fs::write(&path, "debug = true\n").unwrap();
+
+        set_setting_in_file(&path, "wait_command", "10").unwrap();
+        set_setting_in_file(&path, "exclude_rules", "sudo:git_push_force").unwrap();
+
+        let settings = load_from_file(&path).unwrap();
+        assert!(settings.debug);
+        assert_eq!(settings.wait_command, 10);
+        assert_eq!(settings.exclude_rules, vec!["sudo", "git_push_force"]);
+    }
+
+    #[test]
+    fn test_set_setting_in_file_keeps_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        let original = format!(
+            "{} = {}\n\n# Give slow builds more time\nwait_command = 3\n\n\
+             # Never guess for these\nexclude_rules = [\"sudo\"]  # too risky\n",
+            VERSION_KEY, SETTINGS_VERSION
+        );
+        fs::write(&path, &original).unwrap();
+
+        set_setting_in_file(&path, "wait_command", "10").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            original.replace("wait_command = 3", "wait_command = 10")
+        );
+    }
+
+    #[test]
+    fn test_set_setting_in_file_rejects_invalid_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        fs::write(&path, "wait_command = 7\n").unwrap();
+
+        assert!(set_setting_in_file(&path, "wait_command", "soon").is_err());
+
+        // The file is left untouched
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.wait_command, 7);
+    }
+
+    #[test]
+    fn test_settings_format_from_path() {
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings.toml")),
+            SettingsFormat::Toml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings.yaml")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings.YML")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings.json")),
+            SettingsFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_load_from_yaml_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.yaml");
+        fs::write(
+            &path,
+            "exclude_rules:\n  - git_push_force\nwait_command: 8\npriority:\n  sudo: 100\n",
+        )
+        .unwrap();
+
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.exclude_rules, vec!["git_push_force"]);
+        assert_eq!(settings.wait_command, 8);
+        assert_eq!(settings.get_rule_priority("sudo", 1000), 100);
+        // Unspecified keys keep their defaults
+        assert!(settings.require_confirmation);
+    }
+
+    #[test]
+    fn test_load_from_json_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{"rules": ["sudo", "git_push"], "no_colors": true, "history_limit": 50}"#,
+        )
+        .unwrap();
+
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.rules, vec!["sudo", "git_push"]);
+        assert!(settings.no_colors);
+        assert_eq!(settings.history_limit, Some(50));
+    }
+
+    #[test]
+    fn test_load_from_json_file_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"wait_command": "soon"}"#).unwrap();
+
+        assert!(load_from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_set_setting_in_file_keeps_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"debug": true}"#).unwrap();
+
+        set_setting_in_file(&path, "wait_command", "4").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["debug"], serde_json::Value::Bool(true));
+        assert_eq!(value["wait_command"], serde_json::json!(4));
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut settings = Settings {
+            require_confirmation: false,
+            ..Default::default()
+        };
+        settings.priority.insert("sudo".to_string(), 100);
+
+        let overrides: toml::Table = toml::from_str(
+            "require_confirmation = true\nexclude_rules = [\"git_push_force\"]\n\
+             [priority]\ngit_push = 50\n",
+        )
+        .unwrap();
+        apply_overrides(&mut settings, &overrides).unwrap();
+
+        // A present key wins even when it restores the default
+        assert!(settings.require_confirmation);
+        assert_eq!(settings.exclude_rules, vec!["git_push_force"]);
+        // Tables are extended
+        assert_eq!(settings.get_rule_priority("sudo", 1000), 100);
+        assert_eq!(settings.get_rule_priority("git_push", 1000), 50);
+    }
+
+    #[test]
+    fn test_apply_overrides_invalid_value() {
+        let mut settings = Settings::default();
+        let overrides: toml::Table = toml::from_str("wait_command = \"soon\"").unwrap();
+        assert!(apply_overrides(&mut settings, &overrides).is_err());
+        assert_eq!(settings.wait_command, 3);
+    }
+
+    #[test]
+    fn test_find_project_settings_files_in_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let sub_dir = repo.join("crates").join("app");
+        fs::create_dir_all(&sub_dir).unwrap();
+        fs::create_dir(repo.join(".git")).unwrap();
+        fs::write(dir.path().join(PROJECT_SETTINGS_FILE_NAME), "").unwrap();
+        fs::write(repo.join(PROJECT_SETTINGS_FILE_NAME), "").unwrap();
+        fs::write(sub_dir.join(PROJECT_SETTINGS_FILE_NAME), "").unwrap();
+
+        let found = find_project_settings_files(&sub_dir);
+
+        // Stops at the repository root, outermost first
+        assert_eq!(
+            found,
+            vec![
+                repo.join(PROJECT_SETTINGS_FILE_NAME),
+                sub_dir.join(PROJECT_SETTINGS_FILE_NAME),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_project_settings_files_outside_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub_dir = dir.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+        fs::write(dir.path().join(PROJECT_SETTINGS_FILE_NAME), "").unwrap();
+
+        assert!(find_project_settings_files(&sub_dir).is_empty());
+        assert_eq!(
+            find_project_settings_files(dir.path()),
+            vec![dir.path().join(PROJECT_SETTINGS_FILE_NAME)]
+        );
+    }
+
+    #[test]
+    fn test_load_settings_for_dir_applies_project_overrides() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_SETTINGS_FILE_NAME),
+            "exclude_rules = [\"git_push_force\"]\n",
+        )
+        .unwrap();
+        env::set_var("THEFUCK_WAIT_COMMAND", "11");
+
+        let settings = load_settings_for_dir(&test_cli(), dir.path()).unwrap();
+
+        assert!(!settings.is_rule_enabled("git_push_force"));
+        // Environment variables still take precedence over project files
+        assert_eq!(settings.wait_command, 11);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_load_settings_uses_command_working_dir() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_SETTINGS_FILE_NAME),
+            "wait_command = 42\n",
+        )
+        .unwrap();
+        env::set_var("TF_PWD", dir.path());
+
+        let settings = load_settings(&test_cli()).unwrap();
+        assert_eq!(settings.wait_command, 42);
+
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_load_corrections_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        fs::write(
+            &path,
+            "[corrections.gti]\nscript = \"^gti (.*)$\"\nreplace = \"git $1\"\n\
+             [corrections.mk]\nscript = \"^make$\"\noutput = \"No rule\"\n\
+             replace = \"make -C build\"\npriority = 10\n",
+        )
+        .unwrap();
+
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.corrections.len(), 2);
+        assert_eq!(settings.corrections["gti"].replace, "git $1");
+        assert_eq!(
+            settings.corrections["mk"].output.as_deref(),
+            Some("No rule")
+        );
+        assert_eq!(settings.corrections["mk"].priority, Some(10));
+    }
+
+    #[test]
+    fn test_load_from_file_with_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        fs::write(
+            &path,
+            "require_confirmation = false\n\
+             [profile.work]\nrequire_confirmation = true\nexclude_rules = [\"sudo\"]\n\
+             [profile.home]\nwait_command = 1\n",
+        )
+        .unwrap();
+
+        let (settings, profiles) = load_from_file_with_profiles(&path).unwrap();
+        assert!(!settings.require_confirmation);
+        assert_eq!(profiles.len(), 2);
+
+        let mut work = settings.clone();
+        apply_overrides(&mut work, profiles["work"].as_table().unwrap()).unwrap();
+        assert!(work.require_confirmation);
+        assert_eq!(work.exclude_rules, vec!["sudo"]);
+        assert_eq!(work.wait_command, 3);
+    }
+
+    #[test]
+    fn test_load_from_file_ignores_profiles_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.yaml");
+        fs::write(&path, "profile:\n  work:\n    wait_command: 30\n").unwrap();
+
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.wait_command, 3);
+    }
+
+    #[test]
+    fn test_migrate_v0_settings_table() {
+        let mut table: toml::Table = "exclude_rules = \"sudo:git_push\"\n\
+             priority = \"sudo=100\"\n\
+             [rules.ls_lah]\nflags = \"-la\"\n\
+             [profiles.work]\nrules = \"sudo\"\n"
+            .parse()
+            .unwrap();
+
+        assert!(migrate_settings_table(&mut table).unwrap());
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(1));
+        assert!(!table.contains_key("profiles"));
+
+        let (settings, profiles) = {
+            let mut table = table.clone();
+            table.remove(VERSION_KEY);
+            let profiles = table.remove(PROFILES_KEY).unwrap();
+            let settings: Settings = toml::Value::Table(table).try_into().unwrap();
+            (settings, profiles)
+        };
+        assert_eq!(settings.exclude_rules, vec!["sudo", "git_push"]);
+        assert_eq!(settings.priority.get("sudo"), Some(&100));
+        assert_eq!(
+            settings.rule_options["ls_lah"]["flags"].as_str(),
+            Some("-la")
+        );
+        assert_eq!(
+            profiles["work"]["rules"],
+            toml::Value::Array(vec!["sudo".into()])
+        );
+    }
+
+    #[test]
+    fn test_migrate_current_settings_table_is_unchanged() {
+        let mut table: toml::Table = "rules = [\"sudo\"]\n".parse().unwrap();
+        assert!(!migrate_settings_table(&mut table).unwrap());
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(1));
+
+        let mut table: toml::Table = "version = 99\nfuture = true\n".parse().unwrap();
+        assert!(!migrate_settings_table(&mut table).unwrap());
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(99));
+
+        let mut table: toml::Table = "version = \"one\"\n".parse().unwrap();
+        assert!(migrate_settings_table(&mut table).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_settings_file_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        let original = "# my settings\ndebug = true  # noisy\nexclude_rules = \"sudo:cd_mkdir\"\n";
+        fs::write(&path, original).unwrap();
+
+        let backup = upgrade_settings_file(&path).unwrap().unwrap();
+        assert_eq!(backup, dir.path().join("settings.toml.v0.bak"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# my settings\ndebug = true  # noisy\n"));
+        let upgraded: toml::Table = content.parse().unwrap();
+        assert_eq!(upgraded[VERSION_KEY].as_integer(), Some(1));
+        let settings = load_from_file(&path).unwrap();
+        assert_eq!(settings.exclude_rules, vec!["sudo", "cd_mkdir"]);
+
+        // Already upgraded
+        assert_eq!(upgrade_settings_file(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_settings_leaves_older_files_alone() {
+        let _env_guard = crate::test_utils::EnvGuard::new(ENV_VARS);
+        clear_env_vars();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROJECT_SETTINGS_FILE_NAME);
+        let original = "exclude_rules = \"sudo:cd_mkdir\"\n";
+        fs::write(&path, original).unwrap();
+
+        let settings = load_settings_for_dir(&test_cli(), dir.path()).unwrap();
+
+        assert_eq!(settings.exclude_rules, vec!["sudo", "cd_mkdir"]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(!dir.path().join(".oops.toml.v0.bak").exists());
+    }
+
+    #[test]
+    fn test_upgrade_settings_file_leaves_current_layout_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.yaml");
+        let original = "# comments survive\nrules:\n  - sudo\n";
+        fs::write(&path, original).unwrap();
+
+        assert_eq!(upgrade_settings_file(&path).unwrap(), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_set_setting_stamps_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        set_setting_in_file(&path, "wait_command", "5").unwrap();
+
+        let table: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(
+            table[VERSION_KEY].as_integer(),
+            Some(SETTINGS_VERSION.into())
+        );
+    }
 }
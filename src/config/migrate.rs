@@ -0,0 +1,571 @@
+//! Import settings from an existing thefuck installation.
+//!
+//! thefuck keeps its settings in `~/.config/thefuck/settings.py` as Python
+//! assignments and reads `THEFUCK_*` environment variables on top of that.
+//! This module translates both into the oops settings file so users can
+//! switch without rewriting their configuration by hand.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use tracing::debug;
+
+use super::loader::{
+    ensure_config_dir, get_config_dir, get_settings_path, parse_setting_value,
+    write_settings_values,
+};
+use super::Settings;
+
+/// Name of the thefuck Python settings file.
+pub const THEFUCK_SETTINGS_FILE_NAME: &str = "settings.py";
+
+/// Prefix of the environment variables read by thefuck.
+const THEFUCK_ENV_PREFIX: &str = "THEFUCK_";
+
+/// thefuck's name for its built-in rule list, equivalent to `ALL` in oops.
+const DEFAULT_RULES: &str = "DEFAULT_RULES";
+
+/// Outcome of a thefuck settings migration.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// Settings file the migrated values were written to.
+    pub settings_path: PathBuf,
+    /// Settings that were translated, in the order they were found.
+    pub migrated: Vec<(String, toml::Value)>,
+    /// Entries that couldn't be mapped, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Migrate thefuck's `settings.py` and `THEFUCK_*` variables to oops.
+///
+/// Environment variables win over `settings.py`, as they do in thefuck.
+/// With `dry_run` the report is built but nothing is written.
+pub fn migrate_thefuck(dry_run: bool) -> Result<MigrationReport> {
+    let settings_py_path = get_config_dir().join(THEFUCK_SETTINGS_FILE_NAME);
+    let settings_py = if settings_py_path.exists() {
+        debug!(
+            "Reading thefuck settings from {}",
+            settings_py_path.display()
+        );
+        Some(fs::read_to_string(&settings_py_path).with_context(|| {
+            format!(
+                "Failed to read thefuck settings: {}",
+                settings_py_path.display()
+            )
+        })?)
+    } else {
+        None
+    };
+
+    let (values, skipped) = collect_settings(settings_py.as_deref(), env::vars());
+    let settings_path = get_settings_path();
+
+    let migrated: Vec<(String, toml::Value)> = values
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    if !dry_run && !values.is_empty() {
+        ensure_config_dir()?;
+        write_settings_values(&settings_path, values).with_context(|| {
+            format!(
+                "Failed to write migrated settings to {}",
+                settings_path.display()
+            )
+        })?;
+    }
+
+    Ok(MigrationReport {
+        settings_path,
+        migrated,
+        skipped,
+    })
+}
+
+/// Translate `settings.py` content and `THEFUCK_*` variables into settings values.
+///
+/// Returns the values that map onto oops settings, and a description of
+/// every entry that was skipped.
+fn collect_settings(
+    settings_py: Option<&str>,
+    env_vars: impl IntoIterator<Item = (String, String)>,
+) -> (toml::Table, Vec<String>) {
+    let mut values = toml::Table::new();
+    let mut skipped = Vec::new();
+
+    if let Some(content) = settings_py {
+        for statement in split_statements(content) {
+            let Some((name, expr)) = parse_assignment(&statement) else {
+                skipped.push(format!(
+                    "settings.py: unsupported statement `{}`",
+                    statement
+                ));
+                continue;
+            };
+
+            if expr == "None" {
+                debug!("Skipping {} = None", name);
+                continue;
+            }
+
+            match parse_python_literal(expr) {
+                Some(value) => add_value(&mut values, &mut skipped, "settings.py", name, value),
+                None => skipped.push(format!(
+                    "settings.py: {}: unsupported value `{}`",
+                    name, expr
+                )),
+            }
+        }
+    }
+
+    let mut env_vars: Vec<(String, String)> = env_vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(THEFUCK_ENV_PREFIX))
+        .collect();
+    env_vars.sort();
+
+    for (name, raw) in env_vars {
+        let key = name[THEFUCK_ENV_PREFIX.len()..].to_lowercase();
+        let value = parse_setting_value(&key, &raw);
+        add_value(&mut values, &mut skipped, &name, &key, value);
+    }
+
+    (values, skipped)
+}
+
+/// Validate a single translated value and add it to `values`.
+fn add_value(
+    values: &mut toml::Table,
+    skipped: &mut Vec<String>,
+    source: &str,
+    key: &str,
+    mut value: toml::Value,
+) {
+    if !Settings::KEYS.contains(&key) {
+        skipped.push(format!("{}: `{}` has no oops equivalent", source, key));
+        return;
+    }
+
+    if key == "rules" {
+        if let toml::Value::Array(rules) = &mut value {
+            for rule in rules.iter_mut() {
+                if rule.as_str() == Some(DEFAULT_RULES) {
+                    *rule = toml::Value::String("ALL".to_string());
+                }
+            }
+        }
+    }
+
+    let mut single = toml::Table::new();
+    single.insert(key.to_string(), value.clone());
+    if let Err(e) = toml::Value::Table(single).try_into::<Settings>() {
+        skipped.push(format!("{}: invalid value for `{}`: {}", source, key, e));
+        return;
+    }
+
+    values.insert(key.to_string(), value);
+}
+
+/// Split Python source into logical statements.
+///
+/// Comments and blank lines are dropped, and statements spanning several
+/// lines inside brackets are joined.
+fn split_statements(content: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+
+    for line in content.lines() {
+        let code = strip_comment(line).trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(code);
+        depth += bracket_depth(code);
+
+        if depth <= 0 {
+            statements.push(std::mem::take(&mut current));
+            depth = 0;
+        }
+    }
+
+    if !current.is_empty() {
+        statements.push(current);
+    }
+
+    statements
+}
+
+/// Remove a trailing `#` comment, ignoring `#` inside string literals.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '#' => return &line[..i],
+                _ => {}
+            },
+        }
+    }
+
+    line
+}
+
+/// Net change in bracket nesting over a line, ignoring string literals.
+fn bracket_depth(code: &str) -> i32 {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for c in code.chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '[' | '(' | '{' => depth += 1,
+                ']' | ')' | '}' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+
+    depth
+}
+
+/// Split `name = expr` into its parts.
+fn parse_assignment(statement: &str) -> Option<(&str, &str)> {
+    let (name, expr) = statement.split_once('=')?;
+    let name = name.trim();
+    let expr = expr.trim();
+
+    let is_identifier = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier || expr.is_empty() || expr.starts_with('=') {
+        return None;
+    }
+
+    Some((name, expr))
+}
+
+/// Parse a Python literal expression into a TOML value.
+///
+/// Supports strings, integers, floats, booleans, lists, tuples, dicts,
+/// `DEFAULT_RULES`, and list concatenation with `+`.
+fn parse_python_literal(expr: &str) -> Option<toml::Value> {
+    let mut parser = LiteralParser {
+        chars: expr.chars().collect(),
+        pos: 0,
+    };
+    let value = parser.parse_sum()?;
+    parser.skip_whitespace();
+    if parser.pos == parser.chars.len() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Recursive descent parser for the subset of Python used in settings.py.
+struct LiteralParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl LiteralParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parse `value (+ value)*`, concatenating lists.
+    fn parse_sum(&mut self) -> Option<toml::Value> {
+        let mut value = self.parse_value()?;
+        while self.eat('+') {
+            let rhs = self.parse_value()?;
+            match (&mut value, rhs) {
+                (toml::Value::Array(lhs), toml::Value::Array(rhs)) => lhs.extend(rhs),
+                _ => return None,
+            }
+        }
+        Some(value)
+    }
+
+    fn parse_value(&mut self) -> Option<toml::Value> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '[' => {
+                self.pos += 1;
+                self.parse_sequence(']').map(toml::Value::Array)
+            }
+            '(' => {
+                self.pos += 1;
+                self.parse_sequence(')').map(toml::Value::Array)
+            }
+            '{' => {
+                self.pos += 1;
+                self.parse_dict()
+            }
+            '\'' | '"' => self.parse_string().map(toml::Value::String),
+            c if c.is_ascii_digit() || c == '-' => self.parse_number(),
+            c if c.is_ascii_alphabetic() || c == '_' => self.parse_name(),
+            _ => None,
+        }
+    }
+
+    fn parse_sequence(&mut self, close: char) -> Option<Vec<toml::Value>> {
+        let mut items = Vec::new();
+        loop {
+            if self.eat(close) {
+                return Some(items);
+            }
+            items.push(self.parse_sum()?);
+            if !self.eat(',') {
+                return self.eat(close).then_some(items);
+            }
+        }
+    }
+
+    fn parse_dict(&mut self) -> Option<toml::Value> {
+        let mut table = toml::Table::new();
+        loop {
+            if self.eat('}') {
+                return Some(toml::Value::Table(table));
+            }
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            if !self.eat(':') {
+                return None;
+            }
+            let value = self.parse_sum()?;
+            table.insert(key, value);
+            if !self.eat(',') {
+                return self.eat('}').then_some(toml::Value::Table(table));
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        let quote = self.peek().filter(|c| *c == '\'' || *c == '"')?;
+        self.pos += 1;
+
+        let mut value = String::new();
+        loop {
+            let c = self.peek()?;
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        other => other,
+                    });
+                }
+                c if c == quote => return Some(value),
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Option<toml::Value> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == '_')
+        {
+            self.pos += 1;
+        }
+
+        let text: String = self.chars[start..self.pos]
+            .iter()
+            .filter(|c| **c != '_')
+            .collect();
+        if let Ok(integer) = text.parse::<i64>() {
+            Some(toml::Value::Integer(integer))
+        } else {
+            text.parse::<f64>().ok().map(toml::Value::Float)
+        }
+    }
+
+    fn parse_name(&mut self) -> Option<toml::Value> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+
+        let name: String = self.chars[start..self.pos].iter().collect();
+        match name.as_str() {
+            "True" => Some(toml::Value::Boolean(true)),
+            "False" => Some(toml::Value::Boolean(false)),
+            DEFAULT_RULES => Some(toml::Value::Array(vec![toml::Value::String(
+                "ALL".to_string(),
+            )])),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_env() -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    #[test]
+    fn test_parse_python_literal_scalars() {
+        assert_eq!(
+            parse_python_literal("True"),
+            Some(toml::Value::Boolean(true))
+        );
+        assert_eq!(parse_python_literal("3"), Some(toml::Value::Integer(3)));
+        assert_eq!(
+            parse_python_literal("'vim'"),
+            Some(toml::Value::String("vim".to_string()))
+        );
+        assert_eq!(parse_python_literal("os.getcwd()"), None);
+    }
+
+    #[test]
+    fn test_parse_python_literal_collections() {
+        let value = parse_python_literal("['sudo', \"git_push\",]").unwrap();
+        assert_eq!(value, toml::Value::try_from(["sudo", "git_push"]).unwrap());
+
+        let value = parse_python_literal("{'no_command': 9999, 'sudo': 100}").unwrap();
+        assert_eq!(value.get("no_command"), Some(&toml::Value::Integer(9999)));
+        assert_eq!(value.get("sudo"), Some(&toml::Value::Integer(100)));
+    }
+
+    #[test]
+    fn test_parse_python_literal_default_rules() {
+        let value = parse_python_literal("DEFAULT_RULES + ['my_rule']").unwrap();
+        assert_eq!(value, toml::Value::try_from(["ALL", "my_rule"]).unwrap());
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let content =
+            "# comment\nrules = [\n    'sudo',  # inline\n    'cd_mkdir',\n]\nwait_command = 3\n";
+        assert_eq!(
+            split_statements(content),
+            vec!["rules = [ 'sudo', 'cd_mkdir', ]", "wait_command = 3"]
+        );
+    }
+
+    #[test]
+    fn test_collect_settings_from_settings_py() {
+        let content = r#"
+rules = ['sudo', 'git_push']
+exclude_rules = ['rm_dir']
+priority = {'sudo': 100}
+wait_command = 5
+require_confirmation = False
+history_limit = None
+"#;
+        let (values, skipped) = collect_settings(Some(content), no_env());
+
+        assert!(skipped.is_empty(), "{:?}", skipped);
+        assert_eq!(values["wait_command"], toml::Value::Integer(5));
+        assert_eq!(values["require_confirmation"], toml::Value::Boolean(false));
+        assert_eq!(values["priority"]["sudo"], toml::Value::Integer(100));
+        assert!(!values.contains_key("history_limit"));
+
+        let settings: Settings = toml::Value::Table(values).try_into().unwrap();
+        assert!(settings.is_rule_enabled("git_push"));
+        assert!(!settings.is_rule_enabled("rm_dir"));
+    }
+
+    #[test]
+    fn test_collect_settings_reports_unmapped() {
+        let content = "import os\nrepeat = True\nwait_command = 'soon'\nno_colors = get()\n";
+        let (values, skipped) = collect_settings(Some(content), no_env());
+
+        assert!(values.is_empty());
+        assert_eq!(skipped.len(), 4, "{:?}", skipped);
+        assert!(skipped[0].contains("import os"));
+        assert!(skipped[1].contains("`repeat` has no oops equivalent"));
+        assert!(skipped[2].contains("invalid value for `wait_command`"));
+        assert!(skipped[3].contains("unsupported value"));
+    }
+
+    #[test]
+    fn test_collect_settings_env_overrides_settings_py() {
+        let env_vars = vec![
+            ("THEFUCK_WAIT_COMMAND".to_string(), "10".to_string()),
+            (
+                "THEFUCK_RULES".to_string(),
+                "DEFAULT_RULES:my_rule".to_string(),
+            ),
+            ("THEFUCK_PRIORITY".to_string(), "sudo=50".to_string()),
+            ("THEFUCK_UNKNOWN".to_string(), "1".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ];
+        let (values, skipped) = collect_settings(Some("wait_command = 5"), env_vars);
+
+        assert_eq!(values["wait_command"], toml::Value::Integer(10));
+        assert_eq!(
+            values["rules"],
+            toml::Value::try_from(["ALL", "my_rule"]).unwrap()
+        );
+        assert_eq!(values["priority"]["sudo"], toml::Value::Integer(50));
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("THEFUCK_UNKNOWN"));
+    }
+
+    #[test]
+    fn test_collect_settings_nothing_found() {
+        let (values, skipped) = collect_settings(None, no_env());
+        assert!(values.is_empty());
+        assert!(skipped.is_empty());
+    }
+}
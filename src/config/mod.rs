@@ -2,38 +2,55 @@
 //!
 //! This module provides the configuration system for oops, including:
 //! - `Settings`: The main configuration struct with all settings
+//! - `SettingsBuilder`: Programmatic construction for library embedders
 //! - `loader`: Functions for loading settings from files and environment
 //!
+//! There is no global settings instance: load settings once and pass
+//! `&Settings` to the functions that need it.
+//!
 //! # Configuration Priority
 //!
 //! Settings are loaded from multiple sources in order of priority (later sources override earlier):
 //! 1. Default values
-//! 2. Settings file (`~/.config/oops/config.toml`)
-//! 3. Environment variables (`THEFUCK_*`)
-//! 4. CLI arguments
+//! 2. Settings file (`settings.toml`, `settings.yaml` or `settings.json` in the config directory)
+//! 3. Project overrides (`.oops.toml` in the working directory, up to the repository root)
+//! 4. Environment variables (`THEFUCK_*`)
+//! 5. CLI arguments
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use oops::config::{load_settings, get_settings, init_settings};
+//! use oops::config::load_settings;
 //! use oops::cli::Cli;
 //!
-//! // Parse CLI arguments
+//! // Parse CLI arguments and load settings from all sources
 //! let cli = Cli::parse();
+//! let settings = load_settings(&cli).expect("Failed to load settings");
 //!
-//! // Initialize global settings
-//! init_settings(&cli).expect("Failed to load settings");
-//!
-//! // Access settings anywhere in the application
-//! let settings = get_settings();
+//! // Pass the settings explicitly
 //! if settings.debug {
 //!     println!("Debug mode enabled");
 //! }
 //! ```
 //!
+//! Embedders that don't want to read files or the environment can build
+//! settings directly:
+//!
+//! ```
+//! use oops::config::Settings;
+//!
+//! let settings = Settings::builder()
+//!     .exclude_rules(["git_push_force"])
+//!     .require_confirmation(false)
+//!     .build();
+//! assert!(!settings.is_rule_enabled("git_push_force"));
+//! ```
+//!
 //! # Environment Variables
 //!
-//! The following environment variables are supported:
+//! The following environment variables are supported. Each one can also be
+//! spelled with an `OOPS_` prefix (`OOPS_RULES`, `OOPS_DEBUG`, ...), which
+//! takes precedence over the `THEFUCK_` spelling:
 //!
 //! | Variable | Type | Description |
 //! |----------|------|-------------|
@@ -47,16 +64,30 @@
 //! | `THEFUCK_HISTORY_LIMIT` | integer | Maximum history entries to search |
 //! | `THEFUCK_ALTER_HISTORY` | true/false | Alter shell history when fixing |
 //! | `THEFUCK_NUM_CLOSE_MATCHES` | integer | Number of suggestions to show |
+//! | `THEFUCK_FUZZY_ALGORITHM` | jaro_winkler/damerau_levenshtein/subsequence | Fuzzy matching metric |
 //! | `THEFUCK_SLOW_COMMANDS` | colon-separated list | Commands with longer timeout |
+//! | `THEFUCK_EXECUTABLES_CACHE_TTL` | integer | Seconds the PATH scan is reused |
 //! | `THEFUCK_INSTANT_MODE` | true/false | Enable instant mode |
+//! | `THEFUCK_OUTPUT_CACHE_TTL` | integer | Seconds re-run output is reused |
+//! | `THEFUCK_MAX_OUTPUT_SIZE` | integer | Output kept per stream when re-running (bytes) |
+//! | `THEFUCK_EXECUTION` | direct/shell | How corrections are executed |
+//! | `THEFUCK_REPEAT_ATTEMPTS` | integer | Maximum corrections run with `--repeat` |
+//! | `THEFUCK_REPEAT_BACKOFF` | integer | Delay before the first repeat (milliseconds) |
+//! | `THEFUCK_PRESERVE_ENV` | colon-separated list | Variables not overridden by `env` when re-running |
 //! | `THEFUCK_DEBUG` | true/false | Enable debug output |
 
 mod loader;
+mod migrate;
 mod settings;
+mod validate;
 
 // Re-export main types and functions
 pub use loader::{
-    create_default_settings_file, ensure_config_dir, ensure_rules_dir, get_config_dir,
-    get_rules_dir, get_settings, get_settings_path, init_settings, load_settings, SETTINGS,
+    clear_cache, create_default_settings_file, edit_settings_file, ensure_config_dir,
+    ensure_rules_dir, find_project_settings_files, get_cache_dir, get_config_dir, get_rules_dir,
+    get_setting, get_settings_path, load_settings, load_settings_for_dir, set_setting,
+    upgrade_settings_file, PROJECT_SETTINGS_FILE_NAME, SETTINGS_VERSION,
 };
-pub use settings::Settings;
+pub use migrate::{migrate_thefuck, MigrationReport, THEFUCK_SETTINGS_FILE_NAME};
+pub use settings::{CorrectionConfig, Execution, Reexecute, Settings, SettingsBuilder};
+pub use validate::{validate_settings_file, Diagnostic};
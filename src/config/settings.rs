@@ -4,20 +4,24 @@
 //! options for oops. Settings can be loaded from:
 //! 1. Default values
 //! 2. Configuration file (~/.config/oops/config.toml)
-//! 3. Environment variables (THEFUCK_* for backward compatibility)
+//! 3. Environment variables (OOPS_*, or THEFUCK_* for backward compatibility)
 //! 4. CLI arguments
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+
+use crate::utils::fuzzy::Algorithm;
 
 /// Main settings structure for oops configuration.
 ///
 /// Settings are loaded in order of priority (later sources override earlier):
 /// 1. Default values
 /// 2. Settings file (~/.config/oops/config.toml)
-/// 3. Environment variables (THEFUCK_* for backward compatibility)
+/// 3. Environment variables (OOPS_*, or THEFUCK_* for backward compatibility)
 /// 4. CLI arguments
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct Settings {
     /// List of enabled rules. Use ["ALL"] to enable all rules,
@@ -62,16 +66,66 @@ pub struct Settings {
     /// Default: 3
     pub num_close_matches: usize,
 
+    /// Similarity metric for fuzzy matching, unless a rule picks its own.
+    /// Default: "jaro_winkler"
+    pub fuzzy_algorithm: Algorithm,
+
     /// Path prefixes to exclude when searching for executables.
     pub excluded_search_path_prefixes: Vec<String>,
 
-    /// Extra environment variables to set when running commands.
+    /// How long (in seconds) the scan of executables in PATH is reused
+    /// between runs, while PATH and its directories are unchanged.
+    /// 0 disables the cache.
+    /// Default: 86400 seconds (a day)
+    pub executables_cache_ttl: u64,
+
+    /// Extra environment variables to set when re-running commands.
     pub env: HashMap<String, String>,
 
+    /// Environment variables that keep the user's value when re-running
+    /// commands, even if `env` sets them.
+    pub preserve_env: Vec<String>,
+
+    /// User-defined corrections, keyed by rule name.
+    /// Each entry becomes a lightweight regex rule.
+    pub corrections: BTreeMap<String, CorrectionConfig>,
+
+    /// Rule-specific options from `[rule.<name>]` sections, keyed by rule name.
+    #[serde(rename = "rule")]
+    #[schemars(with = "BTreeMap<String, BTreeMap<String, serde_json::Value>>")]
+    pub rule_options: BTreeMap<String, toml::Table>,
+
     /// Enable experimental instant mode.
     /// This mode pre-executes commands to reduce latency.
     pub instant_mode: bool,
 
+    /// Whether oops may re-run the failed command to capture its output.
+    /// Default: "always"
+    pub reexecute: Reexecute,
+
+    /// How long (in seconds) the output of a re-run command is reused
+    /// when oops runs again for the same command. 0 disables the cache.
+    /// Default: 60 seconds
+    pub output_cache_ttl: u64,
+
+    /// Maximum output (in bytes) kept per stream when re-running a command.
+    /// Longer output keeps its start and end. 0 means unlimited.
+    /// Default: 1 MiB
+    pub max_output_size: usize,
+
+    /// How corrected commands are executed.
+    /// Default: "direct"
+    pub execution: Execution,
+
+    /// Maximum number of corrections run in a row with `--repeat`.
+    /// Default: 3
+    pub repeat_attempts: u32,
+
+    /// Delay (in milliseconds) before the first repeated attempt, doubled
+    /// after each further attempt.
+    /// Default: 500 milliseconds
+    pub repeat_backoff: u64,
+
     /// Enable debug output.
     pub debug: bool,
 }
@@ -96,20 +150,105 @@ impl Default for Settings {
                 "vagrant".to_string(),
             ],
             num_close_matches: 3,
+            fuzzy_algorithm: Algorithm::JaroWinkler,
             excluded_search_path_prefixes: Vec::new(),
+            executables_cache_ttl: 86400,
             env: HashMap::new(),
+            preserve_env: Vec::new(),
+            corrections: BTreeMap::new(),
+            rule_options: BTreeMap::new(),
             instant_mode: false,
+            reexecute: Reexecute::Always,
+            output_cache_ttl: 60,
+            max_output_size: crate::output::DEFAULT_OUTPUT_LIMIT,
+            execution: Execution::Direct,
+            repeat_attempts: 3,
+            repeat_backoff: 500,
             debug: false,
         }
     }
 }
 
 impl Settings {
+    /// Names of all recognized settings keys, in declaration order.
+    pub const KEYS: &'static [&'static str] = &[
+        "rules",
+        "exclude_rules",
+        "require_confirmation",
+        "wait_command",
+        "wait_slow_command",
+        "no_colors",
+        "priority",
+        "history_limit",
+        "alter_history",
+        "slow_commands",
+        "num_close_matches",
+        "fuzzy_algorithm",
+        "excluded_search_path_prefixes",
+        "executables_cache_ttl",
+        "env",
+        "preserve_env",
+        "corrections",
+        "rule",
+        "instant_mode",
+        "reexecute",
+        "output_cache_ttl",
+        "max_output_size",
+        "execution",
+        "repeat_attempts",
+        "repeat_backoff",
+        "debug",
+    ];
+
     /// Create a new Settings with default values.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// JSON Schema describing the settings file, for editor validation and completion.
+    ///
+    /// Unknown keys are rejected, and the `[profile.<name>]` sections are
+    /// described as nested settings tables.
+    pub fn json_schema() -> serde_json::Value {
+        let schema = schemars::schema_for!(Settings);
+        let mut value = serde_json::to_value(schema).unwrap_or_default();
+
+        if let Some(root) = value.as_object_mut() {
+            root.insert("additionalProperties".to_string(), false.into());
+            if let Some(properties) = root
+                .get_mut("properties")
+                .and_then(serde_json::Value::as_object_mut)
+            {
+                properties.insert(
+                    "profile".to_string(),
+                    serde_json::json!({
+                        "description": "Named profiles, selected with --profile or OOPS_PROFILE.",
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#" }
+                    }),
+                );
+                properties.insert(
+                    "version".to_string(),
+                    serde_json::json!({
+                        "description": "Layout version of the settings file; older files are upgraded automatically.",
+                        "type": "integer",
+                        "minimum": 0
+                    }),
+                );
+            }
+        }
+
+        value
+    }
+
+    /// Start building settings programmatically, starting from the defaults.
+    ///
+    /// This is intended for embedders of the library crate that want to
+    /// configure oops without reading settings files or the environment.
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+
     /// Check if a specific rule is enabled.
     ///
     /// A rule is enabled if:
@@ -125,6 +264,16 @@ impl Settings {
         self.rules.contains(&"ALL".to_string()) || self.rules.contains(&rule_name.to_string())
     }
 
+    /// Expand glob patterns in `rules` and `exclude_rules`.
+    ///
+    /// Entries containing `*` or `?` (e.g. `git_*`, `*_force`) are replaced by
+    /// the matching names from `available`, in registry order. "ALL" and
+    /// plain names are kept as-is; patterns matching nothing are dropped.
+    pub fn expand_rule_patterns(&mut self, available: &[&str]) {
+        self.rules = expand_patterns(&self.rules, available);
+        self.exclude_rules = expand_patterns(&self.exclude_rules, available);
+    }
+
     /// Get the priority for a specific rule.
     ///
     /// Returns the custom priority if set, otherwise the default (1000).
@@ -137,8 +286,14 @@ impl Settings {
 
     /// Check if a command is considered "slow".
     ///
-    /// Slow commands get longer timeouts.
+    /// Slow commands get longer timeouts. A command is slow if it starts with
+    /// an entry of `slow_commands` (also after `sudo`, `env` or `time`), or
+    /// if its program path ends with one, like `/usr/bin/gradle`.
     pub fn is_slow_command(&self, command: &str) -> bool {
+        if crate::output::is_slow_command(command, &self.slow_commands) {
+            return true;
+        }
+
         // Get the first word (command name) from the command string
         let cmd_name = command.split_whitespace().next().unwrap_or("");
 
@@ -158,6 +313,31 @@ impl Settings {
         }
     }
 
+    /// Get the environment variables to set when re-running a command.
+    ///
+    /// These are the `env` entries, except for variables in `preserve_env`
+    /// that are set in the current environment.
+    pub fn rerun_env(&self) -> HashMap<String, String> {
+        self.env
+            .iter()
+            .filter(|(name, _)| {
+                !(self.preserve_env.contains(name) && std::env::var_os(name.as_str()).is_some())
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Get the delay before the given repeated attempt of `--repeat`.
+    ///
+    /// The first repeat (`attempt` 1) waits `repeat_backoff` milliseconds,
+    /// and every further one twice as long as the one before.
+    pub fn get_repeat_delay(&self, attempt: u32) -> std::time::Duration {
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        std::time::Duration::from_millis(self.repeat_backoff.saturating_mul(factor))
+    }
+
     /// Merge settings from another Settings instance.
     ///
     /// Values from `other` override values in `self` only if they differ from defaults.
@@ -199,21 +379,370 @@ impl Settings {
         if other.num_close_matches != defaults.num_close_matches {
             self.num_close_matches = other.num_close_matches;
         }
+        if other.fuzzy_algorithm != defaults.fuzzy_algorithm {
+            self.fuzzy_algorithm = other.fuzzy_algorithm;
+        }
         if other.excluded_search_path_prefixes != defaults.excluded_search_path_prefixes {
             self.excluded_search_path_prefixes = other.excluded_search_path_prefixes.clone();
         }
+        if other.executables_cache_ttl != defaults.executables_cache_ttl {
+            self.executables_cache_ttl = other.executables_cache_ttl;
+        }
         if other.env != defaults.env {
             self.env.extend(other.env.clone());
         }
+        if other.preserve_env != defaults.preserve_env {
+            self.preserve_env = other.preserve_env.clone();
+        }
+        if other.corrections != defaults.corrections {
+            self.corrections.extend(other.corrections.clone());
+        }
+        if other.rule_options != defaults.rule_options {
+            self.rule_options.extend(other.rule_options.clone());
+        }
         if other.instant_mode != defaults.instant_mode {
             self.instant_mode = other.instant_mode;
         }
+        if other.reexecute != defaults.reexecute {
+            self.reexecute = other.reexecute;
+        }
+        if other.output_cache_ttl != defaults.output_cache_ttl {
+            self.output_cache_ttl = other.output_cache_ttl;
+        }
+        if other.max_output_size != defaults.max_output_size {
+            self.max_output_size = other.max_output_size;
+        }
+        if other.execution != defaults.execution {
+            self.execution = other.execution;
+        }
+        if other.repeat_attempts != defaults.repeat_attempts {
+            self.repeat_attempts = other.repeat_attempts;
+        }
+        if other.repeat_backoff != defaults.repeat_backoff {
+            self.repeat_backoff = other.repeat_backoff;
+        }
         if other.debug != defaults.debug {
             self.debug = other.debug;
         }
     }
 }
 
+/// Policy for re-running the failed command to capture its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Reexecute {
+    /// Re-run the command when no captured output is available.
+    #[default]
+    Always,
+    /// Never re-run the command. Rules that need output are skipped
+    /// unless the shell integration captured it.
+    Never,
+}
+
+/// How corrected commands are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Execution {
+    /// Run with `sh -c` (`cmd /C` or PowerShell on Windows).
+    #[default]
+    Direct,
+    /// Run in an interactive instance of the user's shell (`$SHELL -ic`),
+    /// so aliases, functions and builtins in corrections work.
+    Shell,
+}
+
+/// A user-defined correction from the `[corrections]` settings table.
+///
+/// ```toml
+/// [corrections.gti]
+/// script = "^gti (.*)$"
+/// replace = "git $1"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CorrectionConfig {
+    /// Regex matched against the failed command.
+    pub script: String,
+
+    /// Optional regex that must also match the command output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+
+    /// Replacement command; `$1` / `${name}` refer to groups in `script`.
+    pub replace: String,
+
+    /// Rule priority (lower = shown first). Default: 1000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+}
+
+/// Expand the glob patterns in a rule list against the available rule names.
+fn expand_patterns(entries: &[String], available: &[&str]) -> Vec<String> {
+    let mut expanded: Vec<String> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        if !is_pattern(entry) {
+            if !expanded.contains(entry) {
+                expanded.push(entry.clone());
+            }
+            continue;
+        }
+
+        let matches: Vec<&str> = available
+            .iter()
+            .copied()
+            .filter(|name| glob_matches(entry, name))
+            .collect();
+        if matches.is_empty() {
+            warn!("Rule pattern '{}' does not match any rule", entry);
+        }
+        for name in matches {
+            if !expanded.iter().any(|existing| existing == name) {
+                expanded.push(name.to_string());
+            }
+        }
+    }
+
+    expanded
+}
+
+/// Whether a rule list entry is a glob pattern.
+pub(super) fn is_pattern(entry: &str) -> bool {
+    entry.contains(['*', '?'])
+}
+
+/// Match `name` against a glob `pattern` supporting `*` and `?`.
+pub(super) fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Builder for [`Settings`], created with [`Settings::builder`].
+///
+/// Every field starts at its default value; only the fields that are set
+/// on the builder differ from [`Settings::default`].
+#[derive(Debug, Clone, Default)]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl SettingsBuilder {
+    /// Set the list of enabled rules.
+    pub fn rules<I, S>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.rules = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the list of excluded rules.
+    pub fn exclude_rules<I, S>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.exclude_rules = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether a fix must be confirmed before it is executed.
+    pub fn require_confirmation(mut self, value: bool) -> Self {
+        self.settings.require_confirmation = value;
+        self
+    }
+
+    /// Set the timeout in seconds for re-running commands.
+    pub fn wait_command(mut self, seconds: u64) -> Self {
+        self.settings.wait_command = seconds;
+        self
+    }
+
+    /// Set the timeout in seconds for slow commands.
+    pub fn wait_slow_command(mut self, seconds: u64) -> Self {
+        self.settings.wait_slow_command = seconds;
+        self
+    }
+
+    /// Set whether colored output is disabled.
+    pub fn no_colors(mut self, value: bool) -> Self {
+        self.settings.no_colors = value;
+        self
+    }
+
+    /// Override the priority of a single rule.
+    pub fn priority(mut self, rule_name: impl Into<String>, priority: i32) -> Self {
+        self.settings.priority.insert(rule_name.into(), priority);
+        self
+    }
+
+    /// Set the maximum number of history entries to consider.
+    pub fn history_limit(mut self, limit: Option<usize>) -> Self {
+        self.settings.history_limit = limit;
+        self
+    }
+
+    /// Set whether corrected commands are added to shell history.
+    pub fn alter_history(mut self, value: bool) -> Self {
+        self.settings.alter_history = value;
+        self
+    }
+
+    /// Set the commands that get the slow-command timeout.
+    pub fn slow_commands<I, S>(mut self, commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.slow_commands = commands.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the number of close matches offered for fuzzy suggestions.
+    pub fn num_close_matches(mut self, count: usize) -> Self {
+        self.settings.num_close_matches = count;
+        self
+    }
+
+    /// Set the similarity metric for fuzzy matching.
+    pub fn fuzzy_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.settings.fuzzy_algorithm = algorithm;
+        self
+    }
+
+    /// Set the path prefixes excluded from executable search.
+    pub fn excluded_search_path_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.excluded_search_path_prefixes =
+            prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set how long in seconds the PATH scan is reused; 0 disables the cache.
+    pub fn executables_cache_ttl(mut self, seconds: u64) -> Self {
+        self.settings.executables_cache_ttl = seconds;
+        self
+    }
+
+    /// Add an environment variable set when re-running commands.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the environment variables that keep the user's value when re-running commands.
+    pub fn preserve_env<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.preserve_env = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add a user-defined correction rule.
+    pub fn correction(mut self, name: impl Into<String>, correction: CorrectionConfig) -> Self {
+        self.settings.corrections.insert(name.into(), correction);
+        self
+    }
+
+    /// Set an option in a rule's `[rule.<name>]` section.
+    pub fn rule_option(
+        mut self,
+        rule_name: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<toml::Value>,
+    ) -> Self {
+        self.settings
+            .rule_options
+            .entry(rule_name.into())
+            .or_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Set whether instant mode is enabled.
+    pub fn instant_mode(mut self, value: bool) -> Self {
+        self.settings.instant_mode = value;
+        self
+    }
+
+    /// Set whether oops may re-run the failed command.
+    pub fn reexecute(mut self, value: Reexecute) -> Self {
+        self.settings.reexecute = value;
+        self
+    }
+
+    /// Set how long in seconds re-run output is reused; 0 disables the cache.
+    pub fn output_cache_ttl(mut self, seconds: u64) -> Self {
+        self.settings.output_cache_ttl = seconds;
+        self
+    }
+
+    /// Set the maximum output in bytes kept per stream of a re-run command.
+    pub fn max_output_size(mut self, bytes: usize) -> Self {
+        self.settings.max_output_size = bytes;
+        self
+    }
+
+    /// Set how corrected commands are executed.
+    pub fn execution(mut self, value: Execution) -> Self {
+        self.settings.execution = value;
+        self
+    }
+
+    /// Set the maximum number of corrections run in a row with `--repeat`.
+    pub fn repeat_attempts(mut self, attempts: u32) -> Self {
+        self.settings.repeat_attempts = attempts;
+        self
+    }
+
+    /// Set the delay in milliseconds before the first repeated attempt.
+    pub fn repeat_backoff(mut self, millis: u64) -> Self {
+        self.settings.repeat_backoff = millis;
+        self
+    }
+
+    /// Set whether debug output is enabled.
+    pub fn debug(mut self, value: bool) -> Self {
+        self.settings.debug = value;
+        self
+    }
+
+    /// Finish building and return the settings.
+    pub fn build(self) -> Settings {
+        self.settings
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,16 +773,20 @@ mod tests {
 
     #[test]
     fn test_is_rule_enabled_excluded() {
-        let mut settings = Settings::default();
-        settings.exclude_rules = vec!["sudo".to_string()];
+        let settings = Settings {
+            exclude_rules: vec!["sudo".to_string()],
+            ..Default::default()
+        };
         assert!(settings.is_rule_enabled("git_push"));
         assert!(!settings.is_rule_enabled("sudo"));
     }
 
     #[test]
     fn test_is_rule_enabled_specific() {
-        let mut settings = Settings::default();
-        settings.rules = vec!["sudo".to_string(), "git_push".to_string()];
+        let settings = Settings {
+            rules: vec!["sudo".to_string(), "git_push".to_string()],
+            ..Default::default()
+        };
         assert!(settings.is_rule_enabled("git_push"));
         assert!(settings.is_rule_enabled("sudo"));
         assert!(!settings.is_rule_enabled("cd_mkdir"));
@@ -275,8 +808,17 @@ mod tests {
         assert!(settings.is_slow_command("./gradlew test"));
         assert!(settings.is_slow_command("lein repl"));
         assert!(settings.is_slow_command("vagrant up"));
+        assert!(settings.is_slow_command("sudo vagrant up"));
+        assert!(settings.is_slow_command("/usr/local/bin/gradle build"));
         assert!(!settings.is_slow_command("git status"));
         assert!(!settings.is_slow_command("ls -la"));
+
+        let settings = Settings::builder()
+            .slow_commands(vec!["mvn".to_string(), "docker build".to_string()])
+            .build();
+        assert!(settings.is_slow_command("mvn package"));
+        assert!(settings.is_slow_command("docker build ."));
+        assert!(!settings.is_slow_command("docker ps"));
     }
 
     #[test]
@@ -286,13 +828,47 @@ mod tests {
         assert_eq!(settings.get_wait_time("gradle build"), 15);
     }
 
+    #[test]
+    fn test_rerun_env() {
+        let _guard = crate::test_utils::EnvGuard::new(&["OOPS_TEST_LANG", "OOPS_TEST_UNSET"]);
+        std::env::set_var("OOPS_TEST_LANG", "de_DE.UTF-8");
+        std::env::remove_var("OOPS_TEST_UNSET");
+
+        let settings = Settings::builder()
+            .env("GIT_PAGER", "cat")
+            .env("OOPS_TEST_LANG", "C")
+            .env("OOPS_TEST_UNSET", "C")
+            .preserve_env(["OOPS_TEST_LANG", "OOPS_TEST_UNSET"])
+            .build();
+        let env = settings.rerun_env();
+        assert_eq!(env.get("GIT_PAGER").map(String::as_str), Some("cat"));
+        // Preserved because the user set it
+        assert_eq!(env.get("OOPS_TEST_LANG"), None);
+        // Nothing to preserve
+        assert_eq!(env.get("OOPS_TEST_UNSET").map(String::as_str), Some("C"));
+    }
+
+    #[test]
+    fn test_get_repeat_delay() {
+        let settings = Settings::builder().repeat_backoff(100).build();
+        assert_eq!(settings.get_repeat_delay(1).as_millis(), 100);
+        assert_eq!(settings.get_repeat_delay(2).as_millis(), 200);
+        assert_eq!(settings.get_repeat_delay(4).as_millis(), 800);
+        assert_eq!(
+            settings.get_repeat_delay(100),
+            std::time::Duration::from_millis(u64::MAX)
+        );
+    }
+
     #[test]
     fn test_merge_settings() {
         let mut base = Settings::default();
-        let mut override_settings = Settings::default();
+        let mut override_settings = Settings {
+            debug: true,
+            wait_command: 5,
+            ..Default::default()
+        };
 
-        override_settings.debug = true;
-        override_settings.wait_command = 5;
         override_settings.priority.insert("sudo".to_string(), 100);
 
         base.merge(&override_settings);
@@ -304,6 +880,25 @@ mod tests {
         assert!(base.require_confirmation);
     }
 
+    #[test]
+    fn test_keys_cover_serialized_fields() {
+        let settings = Settings {
+            history_limit: Some(10),
+            ..Default::default()
+        };
+        let value = toml::Value::try_from(&settings).unwrap();
+        let table = value.as_table().unwrap();
+
+        assert_eq!(table.len(), Settings::KEYS.len());
+        for key in table.keys() {
+            assert!(
+                Settings::KEYS.contains(&key.as_str()),
+                "missing key: {}",
+                key
+            );
+        }
+    }
+
     #[test]
     fn test_serialization() {
         let settings = Settings::default();
@@ -313,4 +908,79 @@ mod tests {
         assert_eq!(settings.rules, parsed.rules);
         assert_eq!(settings.wait_command, parsed.wait_command);
     }
+
+    #[test]
+    fn test_builder_defaults() {
+        let built = Settings::builder().build();
+        let defaults = Settings::default();
+        assert_eq!(built.rules, defaults.rules);
+        assert_eq!(built.wait_command, defaults.wait_command);
+        assert_eq!(built.priority, defaults.priority);
+        assert_eq!(built.env, defaults.env);
+    }
+
+    #[test]
+    fn test_builder_overrides() {
+        let settings = Settings::builder()
+            .rules(["git_push", "sudo"])
+            .exclude_rules(["sudo"])
+            .require_confirmation(false)
+            .wait_command(7)
+            .priority("git_push", 10)
+            .env("LANG", "C")
+            .debug(true)
+            .build();
+
+        assert!(settings.is_rule_enabled("git_push"));
+        assert!(!settings.is_rule_enabled("sudo"));
+        assert!(!settings.require_confirmation);
+        assert_eq!(settings.wait_command, 7);
+        assert_eq!(settings.get_rule_priority("git_push", 1000), 10);
+        assert_eq!(settings.env.get("LANG"), Some(&"C".to_string()));
+        assert!(settings.debug);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("git_*", "git_push"));
+        assert!(glob_matches("*_force", "git_push_force"));
+        assert!(glob_matches("git_?ush", "git_push"));
+        assert!(glob_matches("*", "sudo"));
+        assert!(glob_matches("git_*_force", "git_push_force"));
+        assert!(!glob_matches("git_*", "sudo"));
+        assert!(!glob_matches("*_force", "git_push"));
+    }
+
+    #[test]
+    fn test_expand_rule_patterns() {
+        let available = ["git_push", "git_push_force", "sudo", "rm_force"];
+        let mut settings = Settings::builder()
+            .rules(["ALL", "git_*"])
+            .exclude_rules(["*_force", "sudo", "nothing_*"])
+            .build();
+
+        settings.expand_rule_patterns(&available);
+
+        assert_eq!(settings.rules, vec!["ALL", "git_push", "git_push_force"]);
+        assert_eq!(
+            settings.exclude_rules,
+            vec!["git_push_force", "rm_force", "sudo"]
+        );
+        assert!(settings.is_rule_enabled("git_push"));
+        assert!(!settings.is_rule_enabled("rm_force"));
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = Settings::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+
+        for key in Settings::KEYS {
+            assert!(properties.contains_key(*key), "missing key: {}", key);
+        }
+        assert!(properties.contains_key("profile"));
+        assert!(properties.contains_key("version"));
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(properties["wait_command"]["default"], 3);
+    }
 }
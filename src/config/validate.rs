@@ -0,0 +1,409 @@
+//! Validation of settings files.
+//!
+//! Settings files are deserialized leniently: unknown keys are ignored and
+//! a bad value makes the whole file fail to load. This module checks a file
+//! key by key and reports each problem with its location, so typos like
+//! `exclude_rule = [...]` don't go unnoticed.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::loader::{SettingsFormat, PROFILES_KEY, VERSION_KEY};
+use super::settings::{glob_matches, is_pattern};
+use super::Settings;
+use crate::utils::get_closest;
+
+/// Minimum similarity for "did you mean" suggestions.
+const SUGGESTION_CUTOFF: f64 = 0.8;
+
+/// A problem found in a settings file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// File the problem was found in.
+    pub path: PathBuf,
+    /// 1-based line of the offending key or value, when it can be located.
+    pub line: Option<usize>,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+/// Validate a settings file against the known settings and rule names.
+///
+/// `known_rules` should return every registered rule name. It is only called
+/// if the file refers to rules, since listing them means going through every
+/// rule. Corrections defined in the file itself are accepted as rule names too.
+pub fn validate_settings_file(
+    path: &Path,
+    known_rules: &dyn Fn() -> Vec<String>,
+) -> Result<Vec<Diagnostic>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let table: toml::Table = SettingsFormat::from_path(path)
+        .parse(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+    let mut validator = Validator {
+        path,
+        content: &content,
+        lookup: known_rules,
+        known_rules: None,
+        file_rules: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    validator.validate_table(&table, None);
+
+    let mut diagnostics = validator.diagnostics;
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+    Ok(diagnostics)
+}
+
+/// Collects diagnostics for a single file.
+struct Validator<'a> {
+    path: &'a Path,
+    content: &'a str,
+    lookup: &'a dyn Fn() -> Vec<String>,
+    /// The registered rule names, once looked up.
+    known_rules: Option<Vec<String>>,
+    /// Corrections defined in the file, not yet added to `known_rules`.
+    file_rules: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    /// Validate a settings table; `profile` is set for `[profile.<name>]` sections.
+    fn validate_table(&mut self, table: &toml::Table, profile: Option<&str>) {
+        if let Some(toml::Value::Table(corrections)) = table.get("corrections") {
+            self.file_rules.extend(corrections.keys().cloned());
+        }
+
+        for (key, value) in table {
+            if key == PROFILES_KEY && profile.is_none() {
+                self.validate_profiles(value);
+                continue;
+            }
+            if key == VERSION_KEY && profile.is_none() {
+                if value.as_integer().is_none_or(|version| version < 0) {
+                    self.report(
+                        key,
+                        None,
+                        "`version` must be a non-negative integer".to_string(),
+                    );
+                }
+                continue;
+            }
+
+            if !Settings::KEYS.contains(&key.as_str()) {
+                let keys: Vec<String> = Settings::KEYS.iter().map(|k| k.to_string()).collect();
+                let hint = match get_closest(key, &keys, SUGGESTION_CUTOFF, false) {
+                    Some(closest) => format!(" (did you mean `{}`?)", closest),
+                    None => String::new(),
+                };
+                self.report(key, profile, format!("unknown setting `{}`{}", key, hint));
+                continue;
+            }
+
+            let mut single = toml::Table::new();
+            single.insert(key.clone(), value.clone());
+            if let Err(e) = toml::Value::Table(single).try_into::<Settings>() {
+                let reason = e.to_string();
+                let reason = reason.lines().next().unwrap_or_default().trim();
+                self.report(
+                    key,
+                    profile,
+                    format!("invalid value for `{}`: {}", key, reason),
+                );
+                continue;
+            }
+
+            match (key.as_str(), value) {
+                ("rules" | "exclude_rules", toml::Value::Array(entries)) => {
+                    for entry in entries.iter().filter_map(toml::Value::as_str) {
+                        self.check_rule_name(key, entry, profile);
+                    }
+                }
+                ("priority" | "rule", toml::Value::Table(rules)) => {
+                    for rule in rules.keys() {
+                        self.check_rule_name(key, rule, profile);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn validate_profiles(&mut self, value: &toml::Value) {
+        let Some(profiles) = value.as_table() else {
+            self.report(PROFILES_KEY, None, "`profile` must be a table".to_string());
+            return;
+        };
+
+        for (name, profile) in profiles {
+            match profile.as_table() {
+                Some(profile) => self.validate_table(profile, Some(name)),
+                None => self.report(name, None, format!("profile `{}` must be a table", name)),
+            }
+        }
+    }
+
+    /// Report rule names (or glob patterns) that don't match any known rule.
+    fn check_rule_name(&mut self, key: &str, rule: &str, profile: Option<&str>) {
+        if rule == "ALL" {
+            return;
+        }
+
+        let lookup = self.lookup;
+        let known_rules = self.known_rules.get_or_insert_with(lookup);
+        known_rules.append(&mut self.file_rules);
+
+        let known = if is_pattern(rule) {
+            known_rules.iter().any(|name| glob_matches(rule, name))
+        } else {
+            known_rules.iter().any(|name| name == rule)
+        };
+        if known {
+            return;
+        }
+
+        let hint = if is_pattern(rule) {
+            String::new()
+        } else {
+            match get_closest(rule, known_rules, SUGGESTION_CUTOFF, false) {
+                Some(closest) => format!(" (did you mean `{}`?)", closest),
+                None => String::new(),
+            }
+        };
+        let message = format!("unknown rule `{}` in `{}`{}", rule, key, hint);
+        let line = find_value_line(self.content, rule);
+        self.push(line, profile, message);
+    }
+
+    /// Report a problem located at `key`.
+    fn report(&mut self, key: &str, profile: Option<&str>, message: String) {
+        let line = find_key_line(self.content, key);
+        self.push(line, profile, message);
+    }
+
+    fn push(&mut self, line: Option<usize>, profile: Option<&str>, message: String) {
+        let message = match profile {
+            Some(name) => format!("profile `{}`: {}", name, message),
+            None => message,
+        };
+        self.diagnostics.push(Diagnostic {
+            path: self.path.to_path_buf(),
+            line,
+            message,
+        });
+    }
+}
+
+/// Find the 1-based line on which `key` is assigned (TOML, YAML or JSON).
+fn find_key_line(content: &str, key: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            let rest = line
+                .strip_prefix('"')
+                .and_then(|l| l.strip_prefix(key))
+                .and_then(|l| l.strip_prefix('"'))
+                .or_else(|| line.strip_prefix(key));
+            rest.is_some_and(|rest| {
+                let rest = rest.trim_start();
+                rest.starts_with('=') || rest.starts_with(':')
+            }) || line == format!("[{}]", key)
+                || line.ends_with(&format!(".{}]", key))
+        })
+        .map(|index| index + 1)
+}
+
+/// Find the 1-based line on which `value` appears as a string or list item.
+fn find_value_line(content: &str, value: &str) -> Option<usize> {
+    let quoted = [format!("\"{}\"", value), format!("'{}'", value)];
+    content
+        .lines()
+        .position(|line| {
+            let trimmed = line.trim_start();
+            quoted.iter().any(|q| line.contains(q.as_str()))
+                || trimmed.strip_prefix("- ").map(str::trim) == Some(value)
+                || trimmed
+                    .strip_prefix(value)
+                    .is_some_and(|rest| rest.trim_start().starts_with(['=', ':']))
+        })
+        .map(|index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known_rules() -> Vec<String> {
+        ["sudo", "git_push", "git_push_force", "cd_mkdir"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn validate(name: &str, content: &str) -> Vec<Diagnostic> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        validate_settings_file(&path, &known_rules).unwrap()
+    }
+
+    #[test]
+    fn test_valid_file_has_no_diagnostics() {
+        let diagnostics = validate(
+            "settings.toml",
+            "version = 1\nrules = [\"ALL\"]\nexclude_rules = [\"git_*\"]\nwait_command = 5\n\
+             [priority]\nsudo = 10\n",
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_unknown_key_with_suggestion() {
+        let diagnostics = validate(
+            "settings.toml",
+            "wait_command = 5\nexclude_rule = [\"sudo\"]\n",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(
+            diagnostics[0].message,
+            "unknown setting `exclude_rule` (did you mean `exclude_rules`?)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_number() {
+        let diagnostics = validate("settings.toml", "\nwait_command = -1\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0]
+            .message
+            .starts_with("invalid value for `wait_command`"));
+    }
+
+    #[test]
+    fn test_unknown_rule_names() {
+        let diagnostics = validate(
+            "settings.toml",
+            "rules = [\"sudo\", \"git_psuh\"]\nexclude_rules = [\"docker_*\"]\n\
+             [priority]\nno_such_rule = 5\n",
+        );
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(diagnostics.len(), 3, "{:?}", messages);
+        assert!(diagnostics[0]
+            .message
+            .contains("unknown rule `git_psuh` in `rules` (did you mean `git_push`?)"));
+        assert_eq!(diagnostics[0].line, Some(1));
+        assert!(diagnostics[1]
+            .message
+            .contains("`docker_*` in `exclude_rules`"));
+        assert!(diagnostics[2]
+            .message
+            .contains("`no_such_rule` in `priority`"));
+        assert_eq!(diagnostics[2].line, Some(4));
+    }
+
+    #[test]
+    fn test_rule_sections_must_name_known_rules() {
+        let diagnostics = validate(
+            "settings.toml",
+            "[rule.git_push_force]\nforce_with_lease = false\n[rule.sduo]\nx = 1\n",
+        );
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("unknown rule `sduo` in `rule`"));
+    }
+
+    #[test]
+    fn test_corrections_are_known_rules() {
+        let diagnostics = validate(
+            "settings.toml",
+            "exclude_rules = [\"gti\"]\n[corrections.gti]\nscript = \"^gti\"\nreplace = \"git\"\n",
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_rules_only_looked_up_when_named() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+        let lookups = std::cell::Cell::new(0);
+        let known_rules = || {
+            lookups.set(lookups.get() + 1);
+            known_rules()
+        };
+
+        fs::write(&path, "wait_command = 5\n").unwrap();
+        assert!(validate_settings_file(&path, &known_rules)
+            .unwrap()
+            .is_empty());
+        assert_eq!(lookups.get(), 0);
+
+        fs::write(
+            &path,
+            "rules = [\"sudo\", \"cd_mkdir\"]\n[priority]\nsudo = 1\n",
+        )
+        .unwrap();
+        assert!(validate_settings_file(&path, &known_rules)
+            .unwrap()
+            .is_empty());
+        assert_eq!(lookups.get(), 1);
+    }
+
+    #[test]
+    fn test_profiles_are_validated() {
+        let diagnostics = validate(
+            "settings.toml",
+            "[profile.work]\nrequire_confirmaton = true\n",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0]
+            .message
+            .starts_with("profile `work`: unknown setting `require_confirmaton`"));
+    }
+
+    #[test]
+    fn test_yaml_and_json_lines() {
+        let diagnostics = validate(
+            "settings.yaml",
+            "rules:\n  - sudo\n  - sduo\nwait_comand: 3\n",
+        );
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[1].line, Some(4));
+
+        let diagnostics = validate("settings.json", "{\n  \"debugg\": true\n}\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_display() {
+        let diagnostic = Diagnostic {
+            path: PathBuf::from("settings.toml"),
+            line: Some(3),
+            message: "unknown setting `x`".to_string(),
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "settings.toml:3: unknown setting `x`"
+        );
+    }
+}
@@ -7,8 +7,10 @@ use std::process::{Command as ProcessCommand, Stdio};
 /// Represents a command that was executed and potentially failed.
 ///
 /// The `Command` struct stores the original script and its output (combined
-/// stderr and stdout). It also lazily parses the script into parts for
-/// efficient rule matching.
+/// stderr and stdout). The output is normalized for matching: ANSI escapes
+/// are stripped, CRLF becomes LF and carriage-return progress lines are
+/// collapsed; the output as captured is kept in [`raw_output`](Self::raw_output).
+/// It also lazily parses the script into parts for efficient rule matching.
 ///
 /// # Example
 ///
@@ -23,8 +25,12 @@ use std::process::{Command as ProcessCommand, Stdio};
 pub struct Command {
     /// The raw command string as entered by the user.
     pub script: String,
-    /// Combined stderr + stdout from command execution.
+    /// Combined stderr + stdout from command execution, normalized for matching.
     pub output: String,
+    /// The output as captured, before normalization.
+    raw_output: String,
+    /// Whether part of the output was dropped when it was captured.
+    truncated: bool,
     /// Lazily parsed script parts (shell-split).
     script_parts: OnceCell<Vec<String>>,
 }
@@ -45,13 +51,38 @@ impl Command {
     /// let cmd = Command::new("apt install vim", "Permission denied");
     /// ```
     pub fn new(script: impl Into<String>, output: impl Into<String>) -> Self {
+        let raw_output = output.into();
         Self {
             script: script.into(),
-            output: output.into(),
+            output: crate::utils::normalize_output(&raw_output),
+            raw_output,
+            truncated: false,
             script_parts: OnceCell::new(),
         }
     }
 
+    /// Returns the Command marked as having truncated output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oops::core::Command;
+    ///
+    /// let cmd = Command::new("make", "error: build failed").with_truncated(true);
+    /// assert!(cmd.is_truncated());
+    /// ```
+    pub fn with_truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
+    }
+
+    /// Whether part of the output was dropped because it was too long.
+    ///
+    /// Only the start and the end of such output are available.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
     /// Returns a new Command with the script updated but output preserved.
     ///
     /// This is useful when a rule needs to modify the command script
@@ -71,10 +102,27 @@ impl Command {
         Self {
             script: script.into(),
             output: self.output.clone(),
+            raw_output: self.raw_output.clone(),
+            truncated: self.truncated,
             script_parts: OnceCell::new(),
         }
     }
 
+    /// Returns the output as captured, with ANSI escapes and carriage returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oops::core::Command;
+    ///
+    /// let cmd = Command::new("cargo build", "\x1b[31merror\x1b[0m[E0425]");
+    /// assert_eq!(cmd.output, "error[E0425]");
+    /// assert_eq!(cmd.raw_output(), "\x1b[31merror\x1b[0m[E0425]");
+    /// ```
+    pub fn raw_output(&self) -> &str {
+        &self.raw_output
+    }
+
     /// Returns the script split into parts using shell lexing rules.
     ///
     /// The parts are cached after the first call for efficiency.
@@ -144,21 +192,17 @@ impl Command {
             .collect::<Vec<_>>()
             .join(" ");
 
-        // Determine which shell to use
-        let (shell, shell_arg) = if cfg!(windows) {
-            // On Windows, try to use the shell from TF_SHELL or default to cmd
-            match std::env::var("TF_SHELL").as_deref() {
-                Ok("powershell") | Ok("pwsh") => ("powershell", "-Command"),
-                _ => ("cmd", "/C"),
-            }
+        let mut command = if cfg!(windows) {
+            // cmd and PowerShell need their own quoting
+            crate::output::shell_command(&script)
         } else {
             // On Unix, use sh
-            ("sh", "-c")
+            let mut command = ProcessCommand::new("sh");
+            command.arg("-c").arg(&script);
+            command
         };
 
-        let output = ProcessCommand::new(shell)
-            .arg(shell_arg)
-            .arg(&script)
+        let output = command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .output()
@@ -189,6 +233,25 @@ mod tests {
         assert_eq!(cmd.output, "On branch master");
     }
 
+    #[test]
+    fn test_output_is_normalized() {
+        let cmd = Command::new("npm run build", "\x1b[31merror\x1b[39m Command failed.\r\n");
+        assert!(cmd.output.contains("error Command"));
+        assert_eq!(cmd.output, "error Command failed.\n");
+        assert!(cmd.raw_output().contains("\x1b[31m"));
+
+        let updated = cmd.with_script("npm run build:prod");
+        assert_eq!(updated.raw_output(), cmd.raw_output());
+    }
+
+    #[test]
+    fn test_truncated_is_kept_with_script() {
+        let cmd = Command::new("make", "error").with_truncated(true);
+        assert!(cmd.is_truncated());
+        assert!(cmd.with_script("make -j4").is_truncated());
+        assert!(!Command::new("make", "error").is_truncated());
+    }
+
     #[test]
     fn test_script_parts_simple() {
         let cmd = Command::new("git commit -m message", "");
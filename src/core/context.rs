@@ -0,0 +1,140 @@
+//! Context passed to rules while matching a command.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::config::Settings;
+
+/// Per-rule view of the settings, handed to [`Rule`](crate::core::Rule)
+/// methods that take a context.
+///
+/// Besides the global settings, it exposes the rule's own options from its
+/// `[rule.<name>]` settings section and the directory the failed command was
+/// run in.
+///
+/// # Example
+///
+/// ```
+/// use oops::config::Settings;
+/// use oops::core::RuleContext;
+///
+/// let settings = Settings::builder()
+///     .rule_option("ls_lah", "flags", "-la")
+///     .build();
+/// let ctx = RuleContext::new(&settings, "ls_lah");
+/// assert_eq!(ctx.option::<String>("flags").as_deref(), Some("-la"));
+/// assert_eq!(ctx.option::<bool>("missing"), None);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    settings: &'a Settings,
+    rule_name: &'a str,
+    options: Option<&'a toml::Table>,
+    cwd: &'a Path,
+}
+
+impl<'a> RuleContext<'a> {
+    /// Create the context for the rule named `rule_name`.
+    pub fn new(settings: &'a Settings, rule_name: &'a str) -> Self {
+        Self {
+            settings,
+            rule_name,
+            options: settings.rule_options.get(rule_name),
+            cwd: Path::new("."),
+        }
+    }
+
+    /// Set the directory the failed command was run in.
+    pub fn with_cwd(self, cwd: &'a Path) -> Self {
+        Self { cwd, ..self }
+    }
+
+    /// The application settings.
+    pub fn settings(&self) -> &'a Settings {
+        self.settings
+    }
+
+    /// The directory the failed command was run in.
+    ///
+    /// Rules that look at files (`gradlew`, `package.json`, ...) should
+    /// resolve them against this directory rather than the current one.
+    pub fn cwd(&self) -> &'a Path {
+        self.cwd
+    }
+
+    /// Read an option from the rule's `[rule.<name>]` section.
+    ///
+    /// Returns `None` if the option is not set, or if it has the wrong type
+    /// (with a warning).
+    pub fn option<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.options?.get(key)?.clone();
+        match value.try_into() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!(
+                    "Invalid option '{}' for rule '{}': {}",
+                    key, self.rule_name, e
+                );
+                None
+            }
+        }
+    }
+}
+
+impl Default for RuleContext<'static> {
+    fn default() -> Self {
+        static DEFAULT_SETTINGS: once_cell::sync::Lazy<Settings> =
+            once_cell::sync::Lazy::new(Settings::default);
+        Self {
+            settings: &DEFAULT_SETTINGS,
+            rule_name: "",
+            options: None,
+            cwd: Path::new("."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_missing_section() {
+        let settings = Settings::default();
+        let ctx = RuleContext::new(&settings, "git_push_force");
+        assert_eq!(ctx.option::<bool>("force_with_lease"), None);
+    }
+
+    #[test]
+    fn test_option_typed() {
+        let settings = Settings::builder()
+            .rule_option("man", "sections", vec![1, 8])
+            .rule_option("git_push_force", "force_with_lease", false)
+            .build();
+
+        let ctx = RuleContext::new(&settings, "man");
+        assert_eq!(ctx.option::<Vec<u32>>("sections"), Some(vec![1, 8]));
+
+        let ctx = RuleContext::new(&settings, "git_push_force");
+        assert_eq!(ctx.option::<bool>("force_with_lease"), Some(false));
+        // Wrong type is ignored
+        assert_eq!(ctx.option::<String>("force_with_lease"), None);
+    }
+
+    #[test]
+    fn test_default_context() {
+        let ctx = RuleContext::default();
+        assert!(ctx.settings().require_confirmation);
+        assert_eq!(ctx.option::<bool>("anything"), None);
+        assert_eq!(ctx.cwd(), Path::new("."));
+    }
+
+    #[test]
+    fn test_with_cwd() {
+        let settings = Settings::default();
+        let ctx = RuleContext::new(&settings, "gradle_wrapper").with_cwd(Path::new("/tmp/app"));
+        assert_eq!(ctx.cwd(), Path::new("/tmp/app"));
+    }
+}
@@ -1,10 +1,10 @@
 //! CorrectedCommand type representing a suggested command correction.
 
-use crate::config::Settings;
+use crate::config::{Execution, Settings};
 use crate::core::Command;
 use anyhow::Result;
 use std::fmt;
-use std::process::{Command as ProcessCommand, Stdio};
+use std::process::{Command as ProcessCommand, ExitStatus, Stdio};
 use std::sync::Arc;
 
 /// Type alias for side effect functions.
@@ -98,8 +98,9 @@ impl CorrectedCommand {
 
     /// Runs the corrected command and any associated side effects.
     ///
-    /// This method executes the corrected command through the shell and,
-    /// if successful and a side effect is registered, runs the side effect.
+    /// This method executes the corrected command through the shell, with
+    /// its stdin, stdout and stderr connected to the terminal, and, if
+    /// successful and a side effect is registered, runs the side effect.
     ///
     /// # Arguments
     ///
@@ -108,7 +109,8 @@ impl CorrectedCommand {
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure of the command execution.
+    /// The exit code of the corrected command. On Unix, a command killed by
+    /// a signal reports `128 + signal`, as shells do.
     ///
     /// # Example
     ///
@@ -119,43 +121,76 @@ impl CorrectedCommand {
     /// let correction = CorrectedCommand::new("sudo apt install vim", 1000);
     ///
     /// // In a real scenario, you'd have actual settings
-    /// // correction.run(&old_cmd, &settings)?;
+    /// // let exit_code = correction.run(&old_cmd, &settings)?;
     /// ```
-    pub fn run(&self, old_cmd: &Command, _settings: &Settings) -> Result<()> {
-        // Determine which shell to use
-        let (shell, shell_arg) = if cfg!(windows) {
-            match std::env::var("TF_SHELL").as_deref() {
-                Ok("powershell") | Ok("pwsh") => ("powershell", "-Command"),
-                _ => ("cmd", "/C"),
-            }
-        } else {
-            ("sh", "-c")
-        };
-
-        // Execute the corrected command
-        let status = ProcessCommand::new(shell)
-            .arg(shell_arg)
-            .arg(&self.script)
+    pub fn run(&self, old_cmd: &Command, settings: &Settings) -> Result<i32> {
+        // Connect the terminal directly so prompts and progress bars work
+        let status = self
+            .process_command(settings)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .status()?;
 
+        self.finish(old_cmd, status)
+    }
+
+    /// Runs the corrected command like [`run`](Self::run), also capturing its output.
+    ///
+    /// The output is still shown as it is produced, but since stdout and
+    /// stderr are pipes rather than the terminal, progress bars and colors
+    /// may be disabled by the command. Only use this when the output is
+    /// needed, e.g. to correct the command again with `--repeat`.
+    ///
+    /// Returns the exit code, as for [`run`](Self::run), and the merged stdout
+    /// and stderr output, so a new failure can be corrected. The side effect
+    /// only runs on success.
+    pub fn run_capturing(&self, old_cmd: &Command, settings: &Settings) -> Result<(i32, String)> {
+        let mut command = self.process_command(settings);
+        command.stdin(Stdio::inherit());
+        let (status, output) = crate::output::execute_tee(&mut command)?;
+        let exit_code = self.finish(old_cmd, status)?;
+        Ok((exit_code, output))
+    }
+
+    /// Build the invocation for the corrected script per the `execution` setting.
+    fn process_command(&self, settings: &Settings) -> ProcessCommand {
+        match settings.execution {
+            Execution::Direct => self.shell_command(),
+            Execution::Shell => {
+                let shell = crate::shells::detect_shell();
+                let (program, args) = shell.interactive_command(&self.script);
+                let mut command = ProcessCommand::new(program);
+                command.args(args).current_dir(crate::output::working_dir());
+                command
+            }
+        }
+    }
+
+    /// Build the shell invocation for the corrected script.
+    fn shell_command(&self) -> ProcessCommand {
+        // On Windows, run in cmd or PowerShell with their own quoting rules
+        if cfg!(windows) {
+            return crate::output::shell_command(&self.script);
+        }
+
+        let mut command = ProcessCommand::new("sh");
+        command
+            .arg("-c")
+            .arg(&self.script)
+            .current_dir(crate::output::working_dir());
+        command
+    }
+
+    /// Run the side effect after a successful run, and return the exit code.
+    fn finish(&self, old_cmd: &Command, status: ExitStatus) -> Result<i32> {
         // Run side effect if present and command succeeded
         if status.success() {
             if let Some(ref side_effect) = self.side_effect {
                 side_effect(old_cmd, &self.script)?;
             }
         }
-
-        if status.success() {
-            Ok(())
-        } else {
-            anyhow::bail!(
-                "Command exited with status: {}",
-                status.code().unwrap_or(-1)
-            )
-        }
+        Ok(exit_code(status))
     }
 
     /// Runs the corrected command without waiting for completion.
@@ -163,18 +198,7 @@ impl CorrectedCommand {
     /// This is useful for commands that should run in the background
     /// or when you don't need to wait for the result.
     pub fn run_detached(&self) -> Result<()> {
-        let (shell, shell_arg) = if cfg!(windows) {
-            match std::env::var("TF_SHELL").as_deref() {
-                Ok("powershell") | Ok("pwsh") => ("powershell", "-Command"),
-                _ => ("cmd", "/C"),
-            }
-        } else {
-            ("sh", "-c")
-        };
-
-        ProcessCommand::new(shell)
-            .arg(shell_arg)
-            .arg(&self.script)
+        self.shell_command()
             .stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::null())
@@ -184,6 +208,18 @@ impl CorrectedCommand {
     }
 }
 
+/// Convert an exit status to the code a shell would report for it.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
 impl fmt::Debug for CorrectedCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CorrectedCommand")
@@ -256,7 +292,7 @@ mod tests {
         let cmd2 = CorrectedCommand::new("bbb", 500);
         let cmd3 = CorrectedCommand::new("ccc", 1000);
 
-        let mut commands = vec![cmd1.clone(), cmd2.clone(), cmd3.clone()];
+        let mut commands = [cmd1.clone(), cmd2.clone(), cmd3.clone()];
         commands.sort();
 
         assert_eq!(commands[0].script, "bbb"); // Lowest priority value first
@@ -281,4 +317,84 @@ mod tests {
         assert!(debug_str.contains("test"));
         assert!(debug_str.contains("1000"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_returns_exit_code() {
+        let old_cmd = Command::new("false", "");
+        let settings = Settings::default();
+        assert_eq!(
+            CorrectedCommand::new("true", 0)
+                .run(&old_cmd, &settings)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            CorrectedCommand::new("exit 7", 0)
+                .run(&old_cmd, &settings)
+                .unwrap(),
+            7
+        );
+        assert_eq!(
+            CorrectedCommand::new("kill -9 $$", 0)
+                .run(&old_cmd, &settings)
+                .unwrap(),
+            137
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_capturing() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        let side_effect: SideEffect = Arc::new(move |_, _| {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        let old_cmd = Command::new("ehco hi", "ehco: command not found");
+        let settings = Settings::default();
+
+        let failing =
+            CorrectedCommand::with_side_effect("echo hi; exit 2", 1000, side_effect.clone());
+        let (exit_code, output) = failing.run_capturing(&old_cmd, &settings).unwrap();
+        assert_eq!(exit_code, 2);
+        assert_eq!(output, "hi\n");
+        assert!(!ran.load(Ordering::SeqCst));
+
+        let passing = CorrectedCommand::with_side_effect("echo hi", 1000, side_effect);
+        let (exit_code, _) = passing.run_capturing(&old_cmd, &settings).unwrap();
+        assert_eq!(exit_code, 0);
+        assert!(ran.load(Ordering::SeqCst));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_execution_loads_user_functions() {
+        let _guard = crate::test_utils::EnvGuard::new(&["HOME", "SHELL", "TF_SHELL"]);
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(
+            home.path().join(".bashrc"),
+            "greet() { echo \"hello $1\"; }\n",
+        )
+        .unwrap();
+        std::env::set_var("HOME", home.path());
+        std::env::set_var("TF_SHELL", "bash");
+        std::env::remove_var("SHELL");
+
+        let old_cmd = Command::new("gret you", "gret: command not found");
+        let correction = CorrectedCommand::new("greet you", 1000);
+
+        let settings = Settings::builder().execution(Execution::Shell).build();
+        let (exit_code, output) = correction.run_capturing(&old_cmd, &settings).unwrap();
+        assert_eq!(exit_code, 0);
+        assert!(output.contains("hello you"), "{}", output);
+
+        let (exit_code, _) = correction
+            .run_capturing(&old_cmd, &Settings::default())
+            .unwrap();
+        assert_eq!(exit_code, 127);
+    }
 }
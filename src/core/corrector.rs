@@ -3,31 +3,52 @@
 //! This module provides the core functionality for matching rules against
 //! failed commands and generating a sorted list of corrections.
 
-use crate::config::Settings;
+use crate::config::{get_rules_dir, Settings};
 use crate::core::corrected::CorrectedCommand;
 use crate::core::rule::Rule;
-use crate::core::Command;
+use crate::core::{Command, RuleContext};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace};
 
-/// Returns a list of all available rules.
+/// Returns a list of all available rules, ordered by effective priority.
 ///
-/// This function creates instances of all built-in rules. In the future,
-/// this will also include rules loaded from plugins or configuration.
+/// This function creates instances of all built-in rules, plus the
+/// user-defined rules from the `[corrections]` settings table and the rules
+/// directory (see [`crate::rules::drop_in`]). Priorities
+/// from the `[priority]` settings table (or `THEFUCK_PRIORITY` / `--priority`)
+/// override each rule's own priority; rules with equal priority keep their
+/// registration order.
 ///
 /// # Example
 ///
 /// ```
+/// use oops::config::Settings;
 /// use oops::core::get_rules;
 ///
-/// let rules = get_rules();
+/// let rules = get_rules(&Settings::new());
 /// println!("Available rules: {}", rules.len());
 /// for rule in &rules {
 ///     println!("  - {}", rule.name());
 /// }
 /// ```
-pub fn get_rules() -> Vec<Box<dyn Rule>> {
-    crate::rules::get_all_rules()
+pub fn get_rules(settings: &Settings) -> Vec<Box<dyn Rule>> {
+    let mut rules = crate::rules::get_all_rules();
+    rules.extend(crate::rules::corrections::all_rules(settings));
+
+    // Drop-in rules replace built-in rules with the same name
+    let timeout = Duration::from_secs(settings.wait_command);
+    let drop_ins = crate::rules::drop_in::all_rules(&get_rules_dir(), timeout);
+    rules.retain(|rule| !drop_ins.iter().any(|d| d.name() == rule.name()));
+    rules.extend(drop_ins);
+
+    sort_rules_by_priority(&mut rules, settings);
+    rules
+}
+
+/// Stable-sorts rules by their priority after settings overrides.
+fn sort_rules_by_priority(rules: &mut [Box<dyn Rule>], settings: &Settings) {
+    rules.sort_by_key(|rule| settings.get_rule_priority(rule.name(), rule.priority()));
 }
 
 /// Generates corrected commands for a failed command by matching against all rules.
@@ -60,8 +81,12 @@ pub fn get_rules() -> Vec<Box<dyn Rule>> {
 /// }
 /// ```
 pub fn get_corrected_commands(command: &Command, settings: &Settings) -> Vec<CorrectedCommand> {
-    let rules = get_rules();
+    let rules = get_rules(settings);
     let mut corrections = Vec::new();
+    let cwd = crate::output::working_dir();
+
+    // Lookups shelled out to by rules are shared within this fix only
+    crate::utils::cache::clear_command_outputs();
 
     debug!(
         "Matching {} rules against command: {:?}",
@@ -93,8 +118,10 @@ pub fn get_corrected_commands(command: &Command, settings: &Settings) -> Vec<Cor
             continue;
         }
 
+        let ctx = RuleContext::new(settings, rule.name()).with_cwd(&cwd);
+
         // Check if rule matches
-        if !rule.is_match(command) {
+        if !rule.is_match_with_context(command, &ctx) {
             trace!("Rule '{}' does not match", rule.name());
             continue;
         }
@@ -102,7 +129,7 @@ pub fn get_corrected_commands(command: &Command, settings: &Settings) -> Vec<Cor
         debug!("Rule '{}' matches!", rule.name());
 
         // Get corrections from this rule
-        let new_commands = rule.get_new_command(command);
+        let new_commands = rule.get_new_command_with_context(command, &ctx);
         // Apply priority override from settings if configured
         let priority = settings.get_rule_priority(rule.name(), rule.priority());
 
@@ -215,7 +242,7 @@ pub fn get_best_correction(command: &Command, settings: &Settings) -> Option<Cor
 ///
 /// The corrections from that rule, or an empty vector if not found or not matching.
 pub fn match_rule(command: &Command, rule_name: &str) -> Vec<CorrectedCommand> {
-    let rules = get_rules();
+    let rules = crate::rules::get_all_rules();
 
     for rule in rules {
         if rule.name() == rule_name && rule.is_match(command) {
@@ -259,13 +286,47 @@ mod tests {
         }
     }
 
+    fn mock_rule(name: &str, priority: i32) -> Box<dyn Rule> {
+        Box::new(MockRule {
+            name: name.to_string(),
+            matches: true,
+            corrections: vec![format!("{}_fix", name)],
+            priority,
+        })
+    }
+
     #[test]
     fn test_get_rules_returns_vec() {
-        let rules = get_rules();
+        let rules = get_rules(&Settings::new());
         // Should return empty vec for now, will have rules when implemented
         assert!(rules.is_empty() || !rules.is_empty());
     }
 
+    #[test]
+    fn test_get_rules_sorted_by_priority() {
+        let rules = get_rules(&Settings::new());
+        assert!(rules.windows(2).all(|w| w[0].priority() <= w[1].priority()));
+    }
+
+    #[test]
+    fn test_sort_rules_by_priority_uses_overrides() {
+        let mut rules = vec![
+            mock_rule("first", 1000),
+            mock_rule("second", 500),
+            mock_rule("third", 1000),
+        ];
+        let settings = Settings::builder().priority("third", 10).build();
+        sort_rules_by_priority(&mut rules, &settings);
+
+        let names: Vec<&str> = rules.iter().map(|rule| rule.name()).collect();
+        assert_eq!(names, vec!["third", "second", "first"]);
+        assert!(rules[0].is_match(&Command::new("cmd", "")));
+        assert_eq!(
+            rules[0].get_new_command(&Command::new("cmd", "")),
+            vec!["third_fix"]
+        );
+    }
+
     #[test]
     fn test_get_corrected_commands_empty() {
         let cmd = Command::new("test", "error");
@@ -278,7 +339,7 @@ mod tests {
     #[test]
     fn test_corrections_are_sorted() {
         // Test that the sorting works correctly
-        let mut corrections = vec![
+        let mut corrections = [
             CorrectedCommand::new("cmd_c", 1500),
             CorrectedCommand::new("cmd_a", 500),
             CorrectedCommand::new("cmd_b", 1000),
@@ -311,6 +372,26 @@ mod tests {
         assert!(best.is_none());
     }
 
+    #[test]
+    fn test_get_corrected_commands_user_correction() {
+        let settings = Settings::builder()
+            .correction(
+                "gti",
+                crate::config::CorrectionConfig {
+                    script: "^gti (.*)$".to_string(),
+                    output: None,
+                    replace: "git $1".to_string(),
+                    priority: Some(1),
+                },
+            )
+            .build();
+        let cmd = Command::new("gti status", "gti: command not found");
+
+        let best = get_best_correction(&cmd, &settings).unwrap();
+        assert_eq!(best.script, "git status");
+        assert_eq!(best.priority, 1);
+    }
+
     #[test]
     fn test_match_rule_not_found() {
         let cmd = Command::new("test", "error");
@@ -0,0 +1,158 @@
+//! Shell history handed to oops by the shell integration.
+//!
+//! The shell functions generated by `oops --alias` export the last few
+//! history entries in `TF_HISTORY`. Rules and the fix flow read it through
+//! this module, so `history_limit` and the filtering of oops' own
+//! invocations apply everywhere.
+
+use crate::config::Settings;
+use crate::utils::history::command_stats;
+
+/// Names oops is commonly invoked as, besides the configured `TF_ALIAS`.
+const OOPS_NAMES: &[&str] = &["oops", "fuck", "thefuck"];
+
+/// Read the history exported by the shell integration, oldest first.
+///
+/// Uses `TF_HISTORY`, or `THEFUCK_HISTORY` for backward compatibility.
+/// Blank lines are dropped.
+pub fn read_history() -> Vec<String> {
+    std::env::var("TF_HISTORY")
+        .or_else(|_| std::env::var("THEFUCK_HISTORY"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a history entry is an invocation of oops itself.
+///
+/// # Example
+///
+/// ```
+/// use oops::core::is_oops_invocation;
+///
+/// assert!(is_oops_invocation("fuck --yes"));
+/// assert!(!is_oops_invocation("git push"));
+/// ```
+pub fn is_oops_invocation(entry: &str) -> bool {
+    let Some(program) = entry.split_whitespace().next() else {
+        return false;
+    };
+    OOPS_NAMES.contains(&program)
+        || std::env::var("TF_ALIAS").is_ok_and(|alias| !alias.is_empty() && alias == program)
+}
+
+/// The part of `history` oops should look at, oldest first.
+///
+/// Invocations of oops itself are skipped, then only the last
+/// `history_limit` commands are kept (all of them when unset). The last
+/// command is always kept, even with a limit of zero.
+///
+/// # Example
+///
+/// ```
+/// use oops::config::Settings;
+/// use oops::core::recent_commands;
+///
+/// let history: Vec<String> = ["ls", "git psuh", "fuck"].map(String::from).to_vec();
+/// let settings = Settings::builder().history_limit(Some(1)).build();
+/// assert_eq!(recent_commands(&history, &settings), vec!["git psuh"]);
+/// ```
+pub fn recent_commands(history: &[String], settings: &Settings) -> Vec<String> {
+    let mut commands: Vec<String> = history
+        .iter()
+        .filter(|entry| !is_oops_invocation(entry))
+        .cloned()
+        .collect();
+    if let Some(limit) = settings.history_limit {
+        // Always keep the last command, which is the one being fixed
+        let start = commands.len().saturating_sub(limit.max(1));
+        commands.drain(..start);
+    }
+    commands
+}
+
+/// The history from the environment, limited and filtered per `settings`.
+pub fn get_history(settings: &Settings) -> Vec<String> {
+    recent_commands(&read_history(), settings)
+}
+
+/// Unique `commands`, most frequently used first.
+///
+/// Ties go to the most recently used command. `commands` is oldest first,
+/// as returned by [`recent_commands`].
+pub fn by_frequency(commands: &[String]) -> Vec<String> {
+    command_stats(commands, None)
+        .into_iter()
+        .map(|stats| stats.command)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvGuard;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_read_history() {
+        let _guard = EnvGuard::new(&["TF_HISTORY", "THEFUCK_HISTORY"]);
+        std::env::remove_var("TF_HISTORY");
+        std::env::set_var("THEFUCK_HISTORY", "ls\n\n  git status \n");
+        assert_eq!(read_history(), strings(&["ls", "git status"]));
+
+        std::env::set_var("TF_HISTORY", "cd /tmp");
+        assert_eq!(read_history(), strings(&["cd /tmp"]));
+    }
+
+    #[test]
+    fn test_is_oops_invocation() {
+        let _guard = EnvGuard::new(&["TF_ALIAS"]);
+        std::env::set_var("TF_ALIAS", "please");
+        assert!(is_oops_invocation("oops"));
+        assert!(is_oops_invocation("thefuck --yes"));
+        assert!(is_oops_invocation("please"));
+        assert!(!is_oops_invocation("oopsie"));
+        assert!(!is_oops_invocation("echo oops"));
+        assert!(!is_oops_invocation(""));
+    }
+
+    #[test]
+    fn test_recent_commands_honors_history_limit() {
+        let _guard = EnvGuard::new(&["TF_ALIAS"]);
+        std::env::remove_var("TF_ALIAS");
+        let history = strings(&["make", "ls", "fuck", "git psuh", "oops"]);
+
+        let settings = Settings::default();
+        assert_eq!(
+            recent_commands(&history, &settings),
+            strings(&["make", "ls", "git psuh"])
+        );
+
+        let settings = Settings::builder().history_limit(Some(2)).build();
+        assert_eq!(
+            recent_commands(&history, &settings),
+            strings(&["ls", "git psuh"])
+        );
+
+        // The command being fixed is never dropped
+        for limit in [0, 1] {
+            let settings = Settings::builder().history_limit(Some(limit)).build();
+            assert_eq!(recent_commands(&history, &settings), strings(&["git psuh"]));
+        }
+    }
+
+    #[test]
+    fn test_by_frequency() {
+        let commands = strings(&["ls", "make", "git status", "make", "ls", "cargo test"]);
+        assert_eq!(
+            by_frequency(&commands),
+            strings(&["ls", "make", "cargo test", "git status"])
+        );
+    }
+}
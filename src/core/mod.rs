@@ -3,32 +3,75 @@
 //! This module contains the fundamental types used throughout the application:
 //! - [`Command`] - Represents a failed command with its output
 //! - [`Rule`] - Trait for correction rules
+//! - [`RuleContext`] - Settings and rule-specific options handed to rules
+//! - History helpers that apply `history_limit` and skip oops' own invocations
 //! - [`CorrectedCommand`] - A suggested correction for a failed command
 //! - Corrector functions for matching rules and generating corrections
 
 mod command;
+mod context;
 mod corrected;
 mod corrector;
+mod history;
 mod rule;
 
 pub use command::Command;
+pub use context::RuleContext;
 pub use corrected::{CorrectedCommand, SideEffect};
 pub use corrector::{get_best_correction, get_corrected_commands, get_rules, match_rule};
+pub use history::{by_frequency, get_history, is_oops_invocation, read_history, recent_commands};
 pub use rule::{for_app, is_app, ForAppRule, Rule};
 
+use crate::config::{Reexecute, Settings};
+use crate::output::{OutputCache, RerunOutput};
 use anyhow::Result;
+use std::io::IsTerminal;
 
 /// Options for the fix command operation.
 #[derive(Debug, Clone, Default)]
 pub struct FixOptions {
     /// Automatically confirm the first suggestion without prompting.
     pub yes: bool,
-    /// Keep running the corrected command until it succeeds.
+    /// Correct a failing correction again, up to `repeat_attempts` times.
     pub repeat: bool,
     /// Enable instant mode for faster corrections.
     pub instant_mode: bool,
 }
 
+/// Exit code of `oops` when no correction was found.
+pub const EXIT_NO_CORRECTION: i32 = 3;
+
+/// Exit code of `oops` when the user aborted the selection (as for Ctrl+C).
+pub const EXIT_ABORTED: i32 = 130;
+
+/// What [`fix_command`] ended up doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixOutcome {
+    /// A correction was run and exited with this code.
+    Executed(i32),
+    /// A correction was printed for the shell to run, without running it.
+    Printed,
+    /// No rule produced a correction.
+    NoCorrection,
+    /// The user aborted the selection.
+    Aborted,
+}
+
+impl FixOutcome {
+    /// The exit code `oops` should exit with.
+    ///
+    /// That is the correction's own exit code when one was run, `0` when it
+    /// was only printed, [`EXIT_NO_CORRECTION`] or [`EXIT_ABORTED`] otherwise.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FixOutcome::Executed(code) => code,
+            FixOutcome::Printed => 0,
+            FixOutcome::NoCorrection => EXIT_NO_CORRECTION,
+            FixOutcome::Aborted => EXIT_ABORTED,
+        }
+    }
+}
+
 /// Main entry point for fixing a failed command.
 ///
 /// This function is called when the user runs `oops` to fix their last command.
@@ -39,76 +82,337 @@ pub struct FixOptions {
 ///
 /// * `command_str` - Optional command string. If None, will be loaded from history.
 /// * `options` - Options controlling the fix behavior.
+/// * `settings` - Application settings used for rule filtering and execution.
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success, or an error if the fix operation fails.
-pub fn fix_command(command_str: Option<&str>, options: &FixOptions) -> Result<()> {
+/// Returns what was done, see [`FixOutcome::exit_code`], or an error if the
+/// fix operation fails.
+pub fn fix_command(
+    command_str: Option<&str>,
+    options: &FixOptions,
+    settings: &Settings,
+) -> Result<FixOutcome> {
     use tracing::debug;
 
-    // Get settings
-    let settings = crate::config::get_settings();
+    crate::utils::fuzzy::set_default_algorithm(settings.fuzzy_algorithm);
+    if settings.executables_cache_ttl > 0 {
+        crate::utils::executables::set_executables_cache(
+            &crate::config::get_cache_dir(),
+            std::time::Duration::from_secs(settings.executables_cache_ttl),
+        );
+    }
 
     // Get the command to fix
-    let timeout = std::time::Duration::from_secs(settings.wait_command as u64);
-
     let command = if let Some(cmd_str) = command_str {
         debug!("Using provided command: {}", cmd_str);
-        // Re-execute the command to get its output
-        let output = crate::output::get_output(cmd_str, timeout).unwrap_or_default();
-        debug!("Got output: {}", output);
-        Command::new(cmd_str, output)
+        let rerun = command_output(cmd_str, settings);
+        debug!("Got output: {}", rerun.output);
+        Command::new(cmd_str, rerun.output).with_truncated(rerun.truncated)
     } else {
-        // Try to get command from environment (set by shell integration)
-        let script = std::env::var("TF_HISTORY")
-            .or_else(|_| std::env::var("THEFUCK_HISTORY"))
-            .unwrap_or_default();
-
-        if script.is_empty() {
+        // Use the most recent history entry that isn't oops itself
+        let Some(script) = get_history(settings).pop() else {
             anyhow::bail!("No command to fix. Set up shell integration or provide a command.");
-        }
+        };
 
         debug!("Got command from history: {}", script);
-        // Re-execute the command to get its output
-        let output = crate::output::get_output(&script, timeout).unwrap_or_default();
-        debug!("Got output: {}", output);
-        Command::new(script, output)
+        // Output captured by the shell saves re-running the command
+        let rerun = match crate::output::captured_output(&script) {
+            Some(output) => {
+                debug!("Using output captured by the shell");
+                RerunOutput {
+                    output,
+                    ..Default::default()
+                }
+            }
+            None => command_output(&script, settings),
+        };
+        debug!("Got output: {}", rerun.output);
+        Command::new(script, rerun.output).with_truncated(rerun.truncated)
     };
 
     // Get corrections
-    let corrections = get_corrected_commands(&command, &settings);
+    let corrections = get_corrected_commands(&command, settings);
 
     if corrections.is_empty() {
         println!("No corrections available for: {}", command.script);
-        return Ok(());
+        return Ok(FixOutcome::NoCorrection);
     }
 
     debug!("Found {} corrections", corrections.len());
 
     // If --yes flag is set, run the first correction automatically
     if options.yes {
-        let correction = &corrections[0];
-        println!("{}", correction.script);
+        println!("{}", corrections[0].script);
 
-        if !options.instant_mode {
-            correction.run(&command, &settings)?;
+        if options.instant_mode {
+            return Ok(FixOutcome::Printed);
         }
-        return Ok(());
+        return run_correction(&corrections[0], &command, options, settings);
+    }
+
+    // Let the user pick a correction when there is a terminal to ask on
+    if settings.require_confirmation && std::io::stdout().is_terminal() {
+        let mut selector = crate::ui::CommandSelector::new(corrections);
+        return match selector.select() {
+            Some(correction) => run_correction(correction, &command, options, settings),
+            None => {
+                debug!("Selection aborted");
+                Ok(FixOutcome::Aborted)
+            }
+        };
     }
 
-    // Otherwise, use the UI to let the user select a correction
-    // For now, just print the corrections
     println!("Suggestions:");
     for (i, correction) in corrections.iter().enumerate() {
         println!("  {}: {}", i + 1, correction.script);
     }
 
-    // In a full implementation, we'd use the UI module for interactive selection
-    // For now, just run the first correction
-    if !corrections.is_empty() {
-        let correction = &corrections[0];
-        correction.run(&command, &settings)?;
+    // Without a terminal, run the first correction
+    run_correction(&corrections[0], &command, options, settings)
+}
+
+/// Run the chosen correction, correcting it again with `--repeat`.
+///
+/// With `--repeat`, a failing correction is re-run through the corrector
+/// with its new output, as the next failure may need a different rule. The
+/// first of the new corrections is run after the `repeat_backoff` delay,
+/// until one succeeds, no rule matches any more, or `repeat_attempts`
+/// corrections have been run.
+fn run_correction(
+    correction: &CorrectedCommand,
+    command: &Command,
+    options: &FixOptions,
+    settings: &Settings,
+) -> Result<FixOutcome> {
+    use tracing::debug;
+
+    if !options.repeat {
+        return Ok(FixOutcome::Executed(correction.run(command, settings)?));
+    }
+
+    let mut correction = correction.clone();
+    let mut command = command.clone();
+    let mut attempt = 1;
+    loop {
+        let (exit_code, output) = correction.run_capturing(&command, settings)?;
+        if exit_code == 0 || attempt >= settings.repeat_attempts {
+            return Ok(FixOutcome::Executed(exit_code));
+        }
+
+        command = Command::new(correction.script.clone(), output);
+        let Some(next) = get_corrected_commands(&command, settings)
+            .into_iter()
+            .next()
+        else {
+            debug!("No further corrections for: {}", command.script);
+            return Ok(FixOutcome::Executed(exit_code));
+        };
+
+        let delay = settings.get_repeat_delay(attempt);
+        debug!("Attempt {} failed, retrying in {:?}", attempt, delay);
+        std::thread::sleep(delay);
+
+        println!("{}", next.script);
+        correction = next;
+        attempt += 1;
+    }
+}
+
+/// Get the output of the failed command, re-executing it if allowed.
+///
+/// Commands in `slow_commands` get `wait_slow_command` seconds to finish,
+/// others `wait_command`. With `reexecute = "never"` the command is not run
+/// again and the output is empty, so rules that require output are skipped
+/// by the corrector. At most `max_output_size` bytes of each stream are kept.
+///
+/// The command runs with the variables from `env`, except those listed in
+/// `preserve_env`. Output of a command re-run less than `output_cache_ttl` seconds ago in
+/// the same directory is reused.
+fn command_output(script: &str, settings: &Settings) -> RerunOutput {
+    let cache = (settings.output_cache_ttl > 0).then(|| {
+        OutputCache::new(
+            &crate::config::get_cache_dir(),
+            std::time::Duration::from_secs(settings.output_cache_ttl),
+        )
+    });
+    cached_command_output(script, settings, cache.as_ref())
+}
+
+/// [`command_output`] with an explicit cache.
+fn cached_command_output(
+    script: &str,
+    settings: &Settings,
+    cache: Option<&OutputCache>,
+) -> RerunOutput {
+    use tracing::debug;
+
+    if settings.reexecute == Reexecute::Never {
+        debug!("Re-execution disabled, not running: {}", script);
+        return RerunOutput::default();
     }
 
-    Ok(())
+    let cwd = crate::output::working_dir();
+    if let Some(rerun) = cache.and_then(|cache| cache.get(script, &cwd)) {
+        debug!("Using cached output of: {}", script);
+        return rerun;
+    }
+
+    let timeout = std::time::Duration::from_secs(settings.get_wait_time(script));
+    debug!("Re-running with a {:?} timeout: {}", timeout, script);
+    let rerun = crate::output::get_output_limited(
+        script,
+        timeout,
+        settings.max_output_size,
+        &settings.rerun_env(),
+    )
+    .unwrap_or_default();
+    if rerun.truncated {
+        debug!(
+            "Output truncated to {} bytes per stream",
+            settings.max_output_size
+        );
+    }
+
+    if let Some(cache) = cache {
+        if let Err(e) = cache.put(script, &cwd, &rerun) {
+            debug!("Failed to cache output: {:#}", e);
+        }
+    }
+    rerun
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_never_reexecutes() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ran");
+        let script = format!("touch {}", marker.display());
+        let settings = Settings::builder().reexecute(Reexecute::Never).build();
+
+        assert_eq!(command_output(&script, &settings).output, "");
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_slow_commands_get_longer_timeout() {
+        let script = "sleep 1; echo done";
+        let settings = Settings::builder()
+            .wait_command(0)
+            .wait_slow_command(10)
+            .build();
+        assert_eq!(cached_command_output(script, &settings, None).output, "");
+
+        let settings = Settings::builder()
+            .wait_command(0)
+            .wait_slow_command(10)
+            .slow_commands(vec!["sleep".to_string()])
+            .build();
+        assert_eq!(
+            cached_command_output(script, &settings, None).output.trim(),
+            "done"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_reuses_cached_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = OutputCache::new(dir.path(), std::time::Duration::from_secs(60));
+        let counter = dir.path().join("runs");
+        let script = format!("echo x >> {0}; wc -l < {0}", counter.display());
+        let settings = Settings::default();
+
+        let first = cached_command_output(&script, &settings, Some(&cache));
+        let second = cached_command_output(&script, &settings, Some(&cache));
+        assert_eq!(first.output.trim(), "1");
+        assert_eq!(second, first);
+        assert_eq!(first.exit_code, Some(0));
+
+        // Without the cache the command runs again
+        let third = cached_command_output(&script, &settings, None);
+        assert_eq!(third.output.trim(), "2");
+    }
+
+    #[test]
+    fn test_fix_outcome_exit_code() {
+        assert_eq!(FixOutcome::Executed(0).exit_code(), 0);
+        assert_eq!(FixOutcome::Executed(42).exit_code(), 42);
+        assert_eq!(FixOutcome::Printed.exit_code(), 0);
+        assert_eq!(FixOutcome::NoCorrection.exit_code(), EXIT_NO_CORRECTION);
+        assert_eq!(FixOutcome::Aborted.exit_code(), EXIT_ABORTED);
+    }
+
+    #[cfg(unix)]
+    fn repeat_settings(attempts: u32) -> Settings {
+        Settings::builder()
+            .rules(["retry"])
+            .correction(
+                "retry",
+                crate::config::CorrectionConfig {
+                    script: "^exit 4$".to_string(),
+                    output: None,
+                    replace: "exit 0".to_string(),
+                    priority: None,
+                },
+            )
+            .repeat_attempts(attempts)
+            .repeat_backoff(0)
+            .build()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repeat_corrects_failing_correction() {
+        let command = Command::new("exti 4", "");
+        let correction = CorrectedCommand::new("exit 4", 1000);
+        let repeat = FixOptions {
+            repeat: true,
+            ..Default::default()
+        };
+
+        let settings = repeat_settings(3);
+        let outcome = run_correction(&correction, &command, &repeat, &settings).unwrap();
+        assert_eq!(outcome, FixOutcome::Executed(0));
+
+        // Without --repeat, or without attempts left, the failure is reported
+        let outcome =
+            run_correction(&correction, &command, &FixOptions::default(), &settings).unwrap();
+        assert_eq!(outcome, FixOutcome::Executed(4));
+
+        let settings = repeat_settings(1);
+        let outcome = run_correction(&correction, &command, &repeat, &settings).unwrap();
+        assert_eq!(outcome, FixOutcome::Executed(4));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repeat_stops_without_further_corrections() {
+        let command = Command::new("exti 5", "");
+        let correction = CorrectedCommand::new("exit 5", 1000);
+        let repeat = FixOptions {
+            repeat: true,
+            ..Default::default()
+        };
+
+        let outcome = run_correction(&correction, &command, &repeat, &repeat_settings(3)).unwrap();
+        assert_eq!(outcome, FixOutcome::Executed(5));
+    }
+
+    #[test]
+    fn test_never_reexecute_skips_rules_requiring_output() {
+        let settings = Settings::builder().reexecute(Reexecute::Never).build();
+        let rerun = command_output("apt install vim", &settings);
+        let command = Command::new("apt install vim", rerun.output);
+
+        let corrections = get_corrected_commands(&command, &settings);
+        assert!(!corrections
+            .iter()
+            .any(|c| c.script == "sudo apt install vim"));
+    }
 }
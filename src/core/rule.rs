@@ -1,6 +1,6 @@
 //! Rule trait and helper functions for command correction rules.
 
-use crate::core::Command;
+use crate::core::{Command, RuleContext};
 use anyhow::Result;
 use std::marker::PhantomData;
 
@@ -76,6 +76,24 @@ pub trait Rule: Send + Sync {
     /// Returns an empty vector if no corrections can be generated.
     fn get_new_command(&self, command: &Command) -> Vec<String>;
 
+    /// Checks if this rule matches, with access to settings and rule options.
+    ///
+    /// This is what the corrector calls. The default delegates to
+    /// [`is_match`](Rule::is_match); override it for rules whose matching
+    /// depends on their `[rule.<name>]` options.
+    fn is_match_with_context(&self, command: &Command, _ctx: &RuleContext) -> bool {
+        self.is_match(command)
+    }
+
+    /// Returns corrections, with access to settings and rule options.
+    ///
+    /// This is what the corrector calls. The default delegates to
+    /// [`get_new_command`](Rule::get_new_command); override it for rules
+    /// whose corrections depend on their `[rule.<name>]` options.
+    fn get_new_command_with_context(&self, command: &Command, _ctx: &RuleContext) -> Vec<String> {
+        self.get_new_command(command)
+    }
+
     /// Performs any side effects needed after the corrected command runs.
     ///
     /// Some rules need to perform additional actions after the corrected
@@ -207,6 +225,15 @@ impl<R: Rule> Rule for ForAppRule<R> {
         self.inner.get_new_command(command)
     }
 
+    fn is_match_with_context(&self, command: &Command, ctx: &RuleContext) -> bool {
+        let app_refs: Vec<&str> = self.app_names.iter().map(|s| s.as_str()).collect();
+        is_app(command, &app_refs) && self.inner.is_match_with_context(command, ctx)
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        self.inner.get_new_command_with_context(command, ctx)
+    }
+
     fn side_effect(&self, old_cmd: &Command, new_script: &str) -> Result<()> {
         self.inner.side_effect(old_cmd, new_script)
     }
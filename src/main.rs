@@ -7,8 +7,8 @@ use anyhow::Result;
 use tracing::debug;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
-use oops::cli::Cli;
-use oops::{core, shells};
+use oops::cli::{CacheAction, Cli, Commands, ConfigAction};
+use oops::{config, core, shells};
 
 fn main() -> Result<()> {
     // Parse CLI arguments first to check for debug flag
@@ -20,15 +20,27 @@ fn main() -> Result<()> {
     debug!("oops starting with args: {:?}", cli);
 
     // Dispatch to appropriate command
-    if cli.alias {
+    if let Some(Commands::Config { ref action }) = cli.subcommand {
+        // Settings management
+        handle_config(&cli, action)?;
+    } else if let Some(Commands::Cache { ref action }) = cli.subcommand {
+        // Cached data management
+        handle_cache(action)?;
+    } else if let Some(Commands::MigrateThefuck { dry_run }) = cli.subcommand {
+        // Import thefuck settings
+        handle_migrate_thefuck(dry_run)?;
+    } else if cli.alias {
         // Generate shell alias
         handle_alias()?;
     } else if let Some(ref logger_file) = cli.shell_logger {
         // Shell logger mode (internal use)
         handle_shell_logger(logger_file)?;
     } else {
-        // Default: fix command
-        handle_fix_command(&cli)?;
+        // Default: fix command, exiting with the correction's exit code
+        let exit_code = handle_fix_command(&cli)?;
+        if exit_code != 0 {
+            std::process::exit(exit_code);
+        }
     }
 
     Ok(())
@@ -60,10 +72,106 @@ fn handle_shell_logger(logger_file: &str) -> Result<()> {
     shells::run_shell_logger(logger_file)
 }
 
-/// Handle the default fix command mode.
-fn handle_fix_command(cli: &Cli) -> Result<()> {
+/// Handle the `config` subcommand.
+fn handle_config(cli: &Cli, action: &ConfigAction) -> Result<()> {
+    debug!("Config subcommand: {:?}", action);
+
+    match action {
+        ConfigAction::Get { key } => {
+            let settings = config::load_settings(cli)?;
+            match config::get_setting(&settings, key)? {
+                Some(toml::Value::String(value)) => println!("{}", value),
+                Some(value) => println!("{}", value),
+                None => eprintln!("{} is not set", key),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let path = config::set_setting(key, value)?;
+            println!("Set {} in {}", key, path.display());
+        }
+        ConfigAction::List => {
+            let settings = config::load_settings(cli)?;
+            print!("{}", toml::to_string_pretty(&settings)?);
+        }
+        ConfigAction::Edit => {
+            let path = config::edit_settings_file()?;
+            println!("Settings file is valid: {}", path.display());
+        }
+        ConfigAction::Schema => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&config::Settings::json_schema())?
+            );
+        }
+        ConfigAction::Upgrade => {
+            let path = config::get_settings_path();
+            match config::upgrade_settings_file(&path)? {
+                Some(backup) => println!(
+                    "Upgraded {} to settings version {} (backup: {})",
+                    path.display(),
+                    config::SETTINGS_VERSION,
+                    backup.display()
+                ),
+                None => println!("{} is up to date", path.display()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the `cache` subcommand.
+fn handle_cache(action: &CacheAction) -> Result<()> {
+    debug!("Cache subcommand: {:?}", action);
+
+    match action {
+        CacheAction::Clear => {
+            let dir = config::clear_cache()?;
+            println!("Cleared cache in {}", dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the `migrate-thefuck` subcommand.
+fn handle_migrate_thefuck(dry_run: bool) -> Result<()> {
+    debug!("Migrating thefuck settings (dry run: {})", dry_run);
+
+    let report = config::migrate_thefuck(dry_run)?;
+
+    if report.migrated.is_empty() {
+        println!("No thefuck settings found to migrate");
+    } else {
+        let verb = if dry_run { "Would migrate" } else { "Migrated" };
+        println!(
+            "{} {} setting(s) into {}:",
+            verb,
+            report.migrated.len(),
+            report.settings_path.display()
+        );
+        for (key, value) in &report.migrated {
+            println!("  {} = {}", key, value);
+        }
+    }
+
+    if !report.skipped.is_empty() {
+        println!("Could not migrate:");
+        for reason in &report.skipped {
+            println!("  {}", reason);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the default fix command mode, returning the exit code.
+fn handle_fix_command(cli: &Cli) -> Result<i32> {
     debug!("Fix command mode");
 
+    // Load settings from files, project overrides, env and CLI
+    let settings = config::load_settings(cli)?;
+
     // If force-command is specified, use that instead of detecting
     let command = if let Some(ref forced) = cli.force_command {
         debug!("Using forced command: {}", forced);
@@ -79,5 +187,7 @@ fn handle_fix_command(cli: &Cli) -> Result<()> {
         instant_mode: cli.instant_mode,
     };
 
-    core::fix_command(command.as_deref(), &options)
+    let outcome = core::fix_command(command.as_deref(), &options, &settings)?;
+    debug!("Fix outcome: {:?}", outcome);
+    Ok(outcome.exit_code())
 }
@@ -0,0 +1,191 @@
+//! Short-lived cache of re-run output.
+//!
+//! When the user runs oops, aborts the selection and runs it again for the
+//! same failed command, the command doesn't have to be re-run: its output
+//! and exit code are kept in `output.json` in the cache directory for
+//! `output_cache_ttl` seconds, keyed by the script and the directory it ran in.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::RerunOutput;
+
+/// Name of the cache file in the cache directory.
+const CACHE_FILE_NAME: &str = "output.json";
+
+/// Maximum number of commands kept in the cache.
+const MAX_ENTRIES: usize = 16;
+
+/// A cached re-run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    output: String,
+    truncated: bool,
+    exit_code: Option<i32>,
+    /// When the command was re-run, in milliseconds since the Unix epoch.
+    created: u64,
+}
+
+/// Re-run output cached in a directory.
+#[derive(Debug, Clone)]
+pub struct OutputCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl OutputCache {
+    /// The cache in `dir`, with entries expiring after `ttl`.
+    pub fn new(dir: &Path, ttl: Duration) -> Self {
+        Self {
+            path: dir.join(CACHE_FILE_NAME),
+            ttl,
+        }
+    }
+
+    /// Get the cached output of `script` run in `cwd`, if it hasn't expired.
+    pub fn get(&self, script: &str, cwd: &Path) -> Option<RerunOutput> {
+        let entry = self.load().remove(&key(script, cwd))?;
+        if !self.is_fresh(&entry, now()) {
+            return None;
+        }
+        Some(RerunOutput {
+            output: entry.output,
+            truncated: entry.truncated,
+            exit_code: entry.exit_code,
+        })
+    }
+
+    /// Cache the output of `script` run in `cwd`, dropping expired entries.
+    pub fn put(&self, script: &str, cwd: &Path, rerun: &RerunOutput) -> Result<()> {
+        let now = now();
+        let mut entries = self.load();
+        entries.retain(|_, entry| self.is_fresh(entry, now));
+        entries.insert(
+            key(script, cwd),
+            Entry {
+                output: rerun.output.clone(),
+                truncated: rerun.truncated,
+                exit_code: rerun.exit_code,
+                created: now,
+            },
+        );
+
+        // Keep the most recent entries
+        while entries.len() > MAX_ENTRIES {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+        let content = serde_json::to_string(&entries)?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write cache file: {}", self.path.display()))
+    }
+
+    fn load(&self) -> BTreeMap<String, Entry> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return BTreeMap::new();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug!("Ignoring invalid cache file {}: {}", self.path.display(), e);
+            BTreeMap::new()
+        })
+    }
+
+    fn is_fresh(&self, entry: &Entry, now: u64) -> bool {
+        u128::from(now.saturating_sub(entry.created)) < self.ttl.as_millis()
+    }
+}
+
+fn key(script: &str, cwd: &Path) -> String {
+    format!("{}\n{}", cwd.display(), script)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rerun(output: &str) -> RerunOutput {
+        RerunOutput {
+            output: output.to_string(),
+            truncated: false,
+            exit_code: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_put_then_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = OutputCache::new(&dir.path().join("cache"), Duration::from_secs(60));
+        let cwd = Path::new("/src/project");
+
+        assert_eq!(cache.get("git psuh", cwd), None);
+        cache
+            .put("git psuh", cwd, &rerun("git: 'psuh' is not a git command"))
+            .unwrap();
+
+        assert_eq!(
+            cache.get("git psuh", cwd),
+            Some(rerun("git: 'psuh' is not a git command"))
+        );
+        // Same command in another directory
+        assert_eq!(cache.get("git psuh", Path::new("/tmp")), None);
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = OutputCache::new(dir.path(), Duration::ZERO);
+        let cwd = Path::new("/");
+
+        cache
+            .put("make", cwd, &rerun("No rule to make target"))
+            .unwrap();
+        assert_eq!(cache.get("make", cwd), None);
+    }
+
+    #[test]
+    fn test_keeps_most_recent_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = OutputCache::new(dir.path(), Duration::from_secs(60));
+        let cwd = Path::new("/");
+
+        for i in 0..MAX_ENTRIES + 4 {
+            cache.put(&format!("cmd{}", i), cwd, &rerun("")).unwrap();
+        }
+        assert_eq!(cache.load().len(), MAX_ENTRIES);
+    }
+
+    #[test]
+    fn test_invalid_cache_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CACHE_FILE_NAME), "not json").unwrap();
+        let cache = OutputCache::new(dir.path(), Duration::from_secs(60));
+
+        assert_eq!(cache.get("ls", Path::new("/")), None);
+        cache.put("ls", Path::new("/"), &rerun("")).unwrap();
+        assert!(cache.get("ls", Path::new("/")).is_some());
+    }
+}
@@ -0,0 +1,182 @@
+//! Output captured by the shell integration.
+//!
+//! When the shell already recorded the output of the failed command (instant
+//! mode, or piping through the shell logger), it can hand it to oops so the
+//! command doesn't have to be run a second time:
+//!
+//! - `TF_OUTPUT_FILE`: path to a file holding the output, for output that is
+//!   too large or too binary for an environment variable
+//! - `TF_OUTPUT`: the output itself
+//! - `THEFUCK_OUTPUT_LOG`: the log written by the instant mode shell logger,
+//!   from which the output of the command is picked out
+//!
+//! The file takes precedence over the variable, and both over the log.
+
+use std::env;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+
+use tracing::debug;
+
+/// Environment variable naming a file with the captured output.
+pub const OUTPUT_FILE_ENV: &str = "TF_OUTPUT_FILE";
+
+/// Environment variable holding the captured output.
+pub const OUTPUT_ENV: &str = "TF_OUTPUT";
+
+/// Environment variable naming the instant mode shell logger's log.
+pub const OUTPUT_LOG_ENV: &str = "THEFUCK_OUTPUT_LOG";
+
+/// Mark the instant mode prompt writes before each command line.
+const USER_COMMAND_MARK: &str = "\x1b[9999;H";
+
+/// How much of the end of the log is searched for the command.
+const LOG_SIZE_IN_BYTES: u64 = 1024 * 1024;
+
+/// Get the output of `script` captured by the shell, if any.
+///
+/// An unreadable `TF_OUTPUT_FILE` is ignored in favor of `TF_OUTPUT`, and the
+/// log is only searched when neither is set. Invalid UTF-8 is replaced, as for
+/// re-executed commands.
+///
+/// # Example
+///
+/// ```no_run
+/// use oops::output::captured_output;
+///
+/// match captured_output("git push") {
+///     Some(output) => println!("Captured: {}", output),
+///     None => println!("Nothing captured, the command has to be re-run"),
+/// }
+/// ```
+pub fn captured_output(script: &str) -> Option<String> {
+    if let Some(path) = env::var_os(OUTPUT_FILE_ENV).filter(|path| !path.is_empty()) {
+        match fs::read(&path) {
+            Ok(bytes) => return Some(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => debug!(
+                "Failed to read captured output from {}: {}",
+                path.to_string_lossy(),
+                e
+            ),
+        }
+    }
+
+    if let Some(output) = env::var_os(OUTPUT_ENV) {
+        return Some(output.to_string_lossy().into_owned());
+    }
+
+    let path = env::var_os(OUTPUT_LOG_ENV).filter(|path| !path.is_empty())?;
+    match read_log_tail(&path) {
+        Ok(log) => output_from_log(&log, script),
+        Err(e) => {
+            debug!(
+                "Failed to read shell logger log {}: {}",
+                path.to_string_lossy(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Read the last `LOG_SIZE_IN_BYTES` of the shell logger's log.
+fn read_log_tail(path: &std::ffi::OsStr) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(LOG_SIZE_IN_BYTES)))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Pick the output of the latest call of `script` out of the shell log.
+///
+/// Every prompt starts with the user command mark, so the log splits into
+/// one chunk per command: the command line, then what it printed. The
+/// latest chunk whose command line has every word of `script` is used.
+fn output_from_log(log: &str, script: &str) -> Option<String> {
+    let parts = shlex::split(script)
+        .unwrap_or_else(|| script.split_whitespace().map(String::from).collect());
+    let log = log.replace("\r\n", "\n");
+
+    let chunks: Vec<&str> = log.split(USER_COMMAND_MARK).skip(1).collect();
+    chunks.into_iter().rev().find_map(|chunk| {
+        let (line, output) = chunk.split_once('\n').unwrap_or((chunk, ""));
+        parts
+            .iter()
+            .all(|part| line.contains(part.as_str()))
+            .then(|| output.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvGuard;
+
+    #[test]
+    fn test_no_captured_output() {
+        let _guard = EnvGuard::new(&[OUTPUT_FILE_ENV, OUTPUT_ENV, OUTPUT_LOG_ENV]);
+        env::remove_var(OUTPUT_FILE_ENV);
+        env::remove_var(OUTPUT_ENV);
+        env::remove_var(OUTPUT_LOG_ENV);
+        assert_eq!(captured_output("ls"), None);
+    }
+
+    #[test]
+    fn test_captured_output_from_env() {
+        let _guard = EnvGuard::new(&[OUTPUT_FILE_ENV, OUTPUT_ENV, OUTPUT_LOG_ENV]);
+        env::remove_var(OUTPUT_FILE_ENV);
+        env::set_var(OUTPUT_ENV, "error: failed to push");
+        assert_eq!(
+            captured_output("git push").as_deref(),
+            Some("error: failed to push")
+        );
+    }
+
+    #[test]
+    fn test_captured_output_file_wins() {
+        let _guard = EnvGuard::new(&[OUTPUT_FILE_ENV, OUTPUT_ENV, OUTPUT_LOG_ENV]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output");
+        fs::write(&path, b"from file\xff").unwrap();
+
+        env::set_var(OUTPUT_FILE_ENV, &path);
+        env::set_var(OUTPUT_ENV, "from env");
+        assert_eq!(
+            captured_output("git push").as_deref(),
+            Some("from file\u{fffd}")
+        );
+
+        // A missing file falls back to the variable
+        env::set_var(OUTPUT_FILE_ENV, dir.path().join("missing"));
+        assert_eq!(captured_output("git push").as_deref(), Some("from env"));
+    }
+
+    #[test]
+    fn test_captured_output_from_log() {
+        let _guard = EnvGuard::new(&[OUTPUT_FILE_ENV, OUTPUT_ENV, OUTPUT_LOG_ENV]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        let mark = format!(
+            "{}{}",
+            USER_COMMAND_MARK,
+            "\x08".repeat(USER_COMMAND_MARK.len())
+        );
+        let log = format!(
+            "{m}$ git push\r\nfatal: no upstream\r\n{m}$ ls\r\nREADME\r\n{m}$ oops\r\n",
+            m = mark
+        );
+        fs::write(&path, log).unwrap();
+
+        env::remove_var(OUTPUT_FILE_ENV);
+        env::remove_var(OUTPUT_ENV);
+        env::set_var(OUTPUT_LOG_ENV, &path);
+        assert_eq!(
+            captured_output("git push").as_deref(),
+            Some("fatal: no upstream")
+        );
+        assert_eq!(captured_output("ls").as_deref(), Some("README"));
+        assert_eq!(captured_output("cargo build"), None);
+    }
+}
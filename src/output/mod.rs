@@ -4,13 +4,20 @@
 //!
 //! Handles:
 //! - Running commands and capturing output
+//! - Output captured by the shell integration
+//! - Caching re-run output for a short while
 //! - Executing corrected commands
 //! - Output parsing and formatting
 //! - Timeout handling for slow commands
 
+pub mod cache;
+pub mod captured;
 pub mod rerun;
 
+pub use cache::OutputCache;
+pub use captured::captured_output;
 pub use rerun::{
-    execute_command, execute_interactive, get_output, get_output_with_slow_handling,
-    is_slow_command,
+    execute_command, execute_interactive, execute_tee, get_output, get_output_limited,
+    get_output_with_slow_handling, is_slow_command, output_with_timeout, shell_command,
+    working_dir, RerunOutput, DEFAULT_OUTPUT_LIMIT, WORKING_DIR_ENV,
 };
@@ -2,22 +2,49 @@
 //!
 //! This module provides functionality for re-running commands and capturing
 //! their output, with support for timeouts and slow command handling.
+//!
+//! Commands run in the directory the failed command was run in (see
+//! [`working_dir`]), not wherever oops happens to be started.
+//!
+//! Re-run commands get their own process group (a job object on Windows), so
+//! a timeout kills everything the shell started, not just the shell itself.
 
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::io::Read;
-use std::process::{Command, Stdio};
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 
+/// Environment variable with the directory the failed command was run in,
+/// exported by the shell alias.
+pub const WORKING_DIR_ENV: &str = "TF_PWD";
+
 /// Default timeout multiplier for slow commands
 const SLOW_COMMAND_TIMEOUT_MULTIPLIER: u32 = 15;
 
+/// Default cap on the captured output of each stream, in bytes.
+pub const DEFAULT_OUTPUT_LIMIT: usize = 1024 * 1024;
+
+/// Output of a re-run command.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RerunOutput {
+    /// The merged stdout and stderr output.
+    pub output: String,
+    /// Whether the middle of the output was dropped to stay within the limit.
+    pub truncated: bool,
+    /// The exit code, or `None` if the command was killed or timed out.
+    pub exit_code: Option<i32>,
+}
+
 /// Re-run a command and capture its output.
 ///
 /// Executes the given script in a shell and captures both stdout and stderr,
-/// merging them into a single output string. The process is killed if it
-/// exceeds the specified timeout.
+/// merging them into a single output string. If it exceeds the specified
+/// timeout, the shell and every process it started are killed. Each stream
+/// is capped at [`DEFAULT_OUTPUT_LIMIT`] bytes, see [`get_output_limited`].
 ///
 /// # Arguments
 ///
@@ -39,44 +66,71 @@ const SLOW_COMMAND_TIMEOUT_MULTIPLIER: u32 = 15;
 /// println!("Output: {}", output);
 /// ```
 pub fn get_output(script: &str, timeout: Duration) -> Result<String> {
-    let shell = get_shell();
-    let shell_args = get_shell_args(&shell);
+    get_output_limited(script, timeout, DEFAULT_OUTPUT_LIMIT, &HashMap::new())
+        .map(|rerun| rerun.output)
+}
 
-    let mut child = Command::new(&shell)
-        .args(&shell_args)
-        .arg(script)
+/// Re-run a command with extra `env` variables, keeping at most `limit` bytes per stream.
+///
+/// The variables in `env` are set on top of oops' own environment, e.g.
+/// `PAGER=cat` so a pager can't block the capture, or `LC_ALL=C` for
+/// predictable messages.
+///
+/// Rules look for error lines, which are at the start or the end of the
+/// output, so for longer output the first and last `limit / 2` bytes are
+/// kept and the middle is replaced by a note saying how much was dropped.
+/// A `limit` of 0 keeps everything.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+/// use oops::output::rerun::get_output_limited;
+///
+/// let env = HashMap::from([("LC_ALL".to_string(), "C".to_string())]);
+/// let rerun = get_output_limited("make", Duration::from_secs(5), 64 * 1024, &env).unwrap();
+/// if rerun.truncated {
+///     println!("Only the start and end of the output were kept");
+/// }
+/// ```
+pub fn get_output_limited(
+    script: &str,
+    timeout: Duration,
+    limit: usize,
+    env: &HashMap<String, String>,
+) -> Result<RerunOutput> {
+    let mut command = shell_command(script);
+    command
+        .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .envs(env::vars())
-        .spawn()
+        .envs(env);
+    let mut tree = ProcessTree::spawn(&mut command)
         .with_context(|| format!("Failed to execute command: {}", script))?;
+    let child = &mut tree.child;
 
     let start = Instant::now();
+    let mut exit_code = None;
 
-    // Get handles to stdout and stderr
-    let mut stdout = child.stdout.take().expect("Failed to capture stdout");
-    let mut stderr = child.stderr.take().expect("Failed to capture stderr");
-
-    let mut output = String::new();
-    let mut stdout_buffer = Vec::new();
-    let mut stderr_buffer = Vec::new();
+    // Drain both pipes while the command runs, so it never blocks on a full pipe
+    let stdout = child.stdout.take().expect("Failed to capture stdout");
+    let stderr = child.stderr.take().expect("Failed to capture stderr");
+    let stdout_reader = std::thread::spawn(move || BoundedBuffer::read_all(stdout, limit));
+    let stderr_reader = std::thread::spawn(move || BoundedBuffer::read_all(stderr, limit));
 
-    // Use non-blocking reads with timeout checking
     loop {
         // Check timeout
         if start.elapsed() > timeout {
-            // Kill the process if it's still running
-            let _ = child.kill();
-            let _ = child.wait();
+            // Kill the process and its descendants if still running
+            tree.kill();
             break;
         }
 
         // Try to wait for process completion with a short timeout
-        match child.try_wait() {
-            Ok(Some(_status)) => {
-                // Process finished, read remaining output
-                stdout.read_to_end(&mut stdout_buffer).ok();
-                stderr.read_to_end(&mut stderr_buffer).ok();
+        match tree.child.try_wait() {
+            Ok(Some(status)) => {
+                exit_code = status.code();
                 break;
             }
             Ok(None) => {
@@ -84,24 +138,244 @@ pub fn get_output(script: &str, timeout: Duration) -> Result<String> {
                 std::thread::sleep(Duration::from_millis(10));
             }
             Err(_) => {
-                // Error checking status, try to read what we can
+                // Error checking status, read what we can
                 break;
             }
         }
     }
 
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+    let truncated = stdout.is_truncated() || stderr.is_truncated();
+
     // Merge stdout and stderr
-    if !stdout_buffer.is_empty() {
-        output.push_str(&String::from_utf8_lossy(&stdout_buffer));
-    }
-    if !stderr_buffer.is_empty() {
+    let mut output = stdout.into_string();
+    let stderr = stderr.into_string();
+    if !stderr.is_empty() {
         if !output.is_empty() && !output.ends_with('\n') {
             output.push('\n');
         }
-        output.push_str(&String::from_utf8_lossy(&stderr_buffer));
+        output.push_str(&stderr);
     }
 
-    Ok(output)
+    Ok(RerunOutput {
+        output,
+        truncated,
+        exit_code,
+    })
+}
+
+/// Run `command` to completion, or kill it after `timeout`.
+///
+/// Like [`Command::output`], but the command and everything it starts are
+/// killed if it runs longer than `timeout`, in which case `None` is returned.
+/// Both streams are read while it runs, so it can't block on a full pipe.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::process::Command;
+/// use std::time::Duration;
+/// use oops::output::rerun::output_with_timeout;
+///
+/// let mut command = Command::new("git");
+/// command.args(["branch", "--no-color"]);
+/// if let Some(output) = output_with_timeout(&mut command, Duration::from_secs(5)) {
+///     println!("{}", String::from_utf8_lossy(&output.stdout));
+/// }
+/// ```
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> Option<Output> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut tree = ProcessTree::spawn(command).ok()?;
+
+    let stdout = tree.child.stdout.take()?;
+    let stderr = tree.child.stderr.take()?;
+    let stdout_reader = std::thread::spawn(move || BoundedBuffer::read_all(stdout, 0));
+    let stderr_reader = std::thread::spawn(move || BoundedBuffer::read_all(stderr, 0));
+
+    let start = Instant::now();
+    let status = loop {
+        if start.elapsed() > timeout {
+            tree.kill();
+            break None;
+        }
+
+        match tree.child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(_) => {
+                tree.kill();
+                break None;
+            }
+        }
+    };
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+    Some(Output {
+        status: status?,
+        stdout: stdout.head,
+        stderr: stderr.head,
+    })
+}
+
+/// Output buffer keeping the first and last bytes of a stream past a limit.
+#[derive(Debug, Default)]
+struct BoundedBuffer {
+    limit: usize,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    dropped: usize,
+}
+
+impl BoundedBuffer {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// Read `reader` to the end, keeping at most `limit` bytes.
+    fn read_all(mut reader: impl Read, limit: usize) -> Self {
+        let mut buffer = Self::new(limit);
+        let mut chunk = [0u8; 8192];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => buffer.push(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        buffer
+    }
+
+    fn push(&mut self, mut data: &[u8]) {
+        if self.limit == 0 {
+            self.head.extend_from_slice(data);
+            return;
+        }
+
+        let head_room = (self.limit / 2).saturating_sub(self.head.len());
+        let (head, rest) = data.split_at(head_room.min(data.len()));
+        self.head.extend_from_slice(head);
+        data = rest;
+
+        self.tail.extend(data);
+        let tail_limit = self.limit - self.limit / 2;
+        if self.tail.len() > tail_limit {
+            let excess = self.tail.len() - tail_limit;
+            self.tail.drain(..excess);
+            self.dropped += excess;
+        }
+    }
+
+    fn is_truncated(&self) -> bool {
+        self.dropped > 0
+    }
+
+    fn into_string(self) -> String {
+        let mut output = String::from_utf8_lossy(&self.head).into_owned();
+        if self.is_truncated() {
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&format!("[... {} bytes truncated ...]\n", self.dropped));
+        }
+        let tail: Vec<u8> = self.tail.into();
+        output.push_str(&String::from_utf8_lossy(&tail));
+        output
+    }
+}
+
+/// A child process together with the processes it starts.
+///
+/// On Unix the child leads a new process group; on Windows it is assigned to
+/// a job object. Killing the tree kills every process still in the group.
+struct ProcessTree {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<windows::Win32::Foundation::HANDLE>,
+}
+
+impl ProcessTree {
+    /// Spawn `command` as the root of a new process tree.
+    #[cfg(unix)]
+    fn spawn(command: &mut Command) -> std::io::Result<Self> {
+        use std::os::unix::process::CommandExt;
+
+        let child = command.process_group(0).spawn()?;
+        Ok(Self { child })
+    }
+
+    /// Spawn `command` as the root of a new process tree.
+    #[cfg(windows)]
+    fn spawn(command: &mut Command) -> std::io::Result<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows::core::PCWSTR;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        let child = command.spawn()?;
+
+        // Without a job object only the shell itself can be killed
+        // SAFETY: the job handle is owned by the tree and closed on drop (or
+        // here if it can't be used); the process handle is valid while
+        // `child` is alive.
+        let job = unsafe {
+            match CreateJobObjectW(None, PCWSTR::null()) {
+                Ok(job) => {
+                    let process = HANDLE(child.as_raw_handle() as isize);
+                    if AssignProcessToJobObject(job, process).is_ok() {
+                        Some(job)
+                    } else {
+                        let _ = CloseHandle(job);
+                        None
+                    }
+                }
+                Err(_) => None,
+            }
+        };
+        Ok(Self { child, job })
+    }
+
+    /// Kill the whole tree and reap the child.
+    fn kill(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: killpg only sends a signal. The child is not reaped yet, so
+        // its pid (and process group id) cannot have been reused.
+        unsafe {
+            libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+        }
+
+        #[cfg(windows)]
+        if let Some(job) = self.job {
+            // SAFETY: `job` is a valid job object handle owned by the tree.
+            unsafe {
+                let _ = windows::Win32::System::JobObjects::TerminateJobObject(job, 1);
+            }
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        if let Some(job) = self.job.take() {
+            // SAFETY: `job` is a valid handle that is not used after this.
+            unsafe {
+                let _ = windows::Win32::Foundation::CloseHandle(job);
+            }
+        }
+    }
 }
 
 /// Get extended output with a longer timeout for slow commands.
@@ -208,6 +482,28 @@ pub fn is_slow_command(script: &str, slow_commands: &[String]) -> bool {
     false
 }
 
+/// Get the directory the failed command was run in.
+///
+/// Uses `TF_PWD` when it names an existing directory, so commands behave the
+/// same even if oops is started elsewhere (e.g. from a keybinding). Falls
+/// back to the current directory.
+///
+/// # Example
+///
+/// ```no_run
+/// use oops::output::working_dir;
+///
+/// println!("Re-running in {}", working_dir().display());
+/// ```
+pub fn working_dir() -> PathBuf {
+    if let Some(dir) = env::var_os(WORKING_DIR_ENV).map(PathBuf::from) {
+        if dir.is_dir() {
+            return dir;
+        }
+    }
+    env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
 /// Get the current shell to use for command execution.
 ///
 /// Uses the TF_SHELL environment variable if set, otherwise falls back
@@ -241,6 +537,76 @@ fn get_shell() -> String {
     "/bin/sh".to_string()
 }
 
+/// Build the shell invocation running `script` in the [`working_dir`].
+///
+/// On Unix the script is passed to `TF_SHELL` or `$SHELL` with `-c`. On
+/// Windows it runs in PowerShell when that is the shell (`TF_SHELL` or
+/// `COMSPEC`), and in `cmd.exe` otherwise:
+///
+/// - `cmd` gets the script verbatim as `/D /S /C "<script>"`, as the usual
+///   argument quoting doesn't apply to it; a leading `./program` becomes
+///   `.\program`, which cmd resolves to `program.bat`, `.cmd`, `.exe` or
+///   any other `PATHEXT` extension
+/// - PowerShell gets a leading `&` when the script starts with a quoted
+///   program path, which it would otherwise print as a string
+///
+/// # Example
+///
+/// ```no_run
+/// use oops::output::rerun::shell_command;
+///
+/// let status = shell_command("git status").status().unwrap();
+/// println!("Exited with {}", status);
+/// ```
+pub fn shell_command(script: &str) -> Command {
+    let shell = get_shell();
+    let mut command = Command::new(&shell);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let shell_lower = shell.to_lowercase();
+        if shell_lower.contains("powershell") || shell_lower.contains("pwsh") {
+            command
+                .args(get_shell_args(&shell))
+                .arg(powershell_script(script));
+        } else if shell_lower.contains("cmd") {
+            command
+                .raw_arg("/D /S /C")
+                .raw_arg(format!("\"{}\"", cmd_script(script)));
+        } else {
+            // e.g. Git Bash
+            command.args(get_shell_args(&shell)).arg(script);
+        }
+    }
+
+    #[cfg(not(windows))]
+    command.args(get_shell_args(&shell)).arg(script);
+
+    command.current_dir(working_dir());
+    command
+}
+
+/// Adapt a script for `cmd /S /C`, which runs it as typed at the prompt.
+#[cfg(any(windows, test))]
+fn cmd_script(script: &str) -> String {
+    match script.strip_prefix("./") {
+        Some(rest) => format!(".\\{}", rest),
+        None => script.to_string(),
+    }
+}
+
+/// Adapt a script for `powershell -Command`.
+#[cfg(any(windows, test))]
+fn powershell_script(script: &str) -> String {
+    if script.starts_with('"') || script.starts_with('\'') {
+        format!("& {}", script)
+    } else {
+        script.to_string()
+    }
+}
+
 /// Get shell arguments for executing a command.
 fn get_shell_args(shell: &str) -> Vec<&'static str> {
     let shell_lower = shell.to_lowercase();
@@ -267,20 +633,70 @@ fn get_shell_args(shell: &str) -> Vec<&'static str> {
 ///
 /// `true` if the command exited with status 0, `false` otherwise
 pub fn execute_command(script: &str) -> bool {
-    let shell = get_shell();
-    let shell_args = get_shell_args(&shell);
-
-    match Command::new(&shell)
-        .args(&shell_args)
-        .arg(script)
-        .envs(env::vars())
-        .status()
-    {
+    match shell_command(script).envs(env::vars()).status() {
         Ok(status) => status.success(),
         Err(_) => false,
     }
 }
 
+/// Run a prepared command, streaming its output to the terminal while also
+/// capturing it.
+///
+/// stdout and stderr are forwarded to oops' own stdout and stderr as they
+/// arrive, and merged in arrival order into the returned output. The caller
+/// configures stdin.
+///
+/// # Returns
+///
+/// * `Ok((ExitStatus, String))` - The exit status and the merged output
+/// * `Err` - If the command fails to start
+pub fn execute_tee(command: &mut Command) -> Result<(std::process::ExitStatus, String)> {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute command")?;
+
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let forward = |mut pipe: Box<dyn Read + Send>, mut sink: Box<dyn Write + Send>| {
+        let captured = Arc::clone(&captured);
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            while let Ok(read) = pipe.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                let _ = sink.write_all(&buffer[..read]);
+                let _ = sink.flush();
+                captured
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .extend_from_slice(&buffer[..read]);
+            }
+        })
+    };
+
+    let mut threads = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        threads.push(forward(Box::new(stdout), Box::new(std::io::stdout())));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        threads.push(forward(Box::new(stderr), Box::new(std::io::stderr())));
+    }
+
+    let status = child.wait().context("Failed to wait for command")?;
+    for thread in threads {
+        let _ = thread.join();
+    }
+
+    let output =
+        String::from_utf8_lossy(&captured.lock().unwrap_or_else(|e| e.into_inner())).into_owned();
+    Ok((status, output))
+}
+
 /// Execute a command with inherited stdio (for interactive execution).
 ///
 /// This runs the command with stdin, stdout, and stderr connected to the
@@ -295,12 +711,7 @@ pub fn execute_command(script: &str) -> bool {
 /// * `Ok(i32)` - The exit code of the command
 /// * `Err` - If the command fails to execute
 pub fn execute_interactive(script: &str) -> Result<i32> {
-    let shell = get_shell();
-    let shell_args = get_shell_args(&shell);
-
-    let status = Command::new(&shell)
-        .args(&shell_args)
-        .arg(script)
+    let status = shell_command(script)
         .envs(env::vars())
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
@@ -389,6 +800,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cmd_script() {
+        assert_eq!(cmd_script("./gradlew build"), ".\\gradlew build");
+        assert_eq!(cmd_script("git status"), "git status");
+        assert_eq!(
+            cmd_script(r#"echo "a & b" && dir"#),
+            r#"echo "a & b" && dir"#
+        );
+    }
+
+    #[test]
+    fn test_powershell_script() {
+        assert_eq!(
+            powershell_script(r#""C:\Program Files\Git\bin\git.exe" status"#),
+            r#"& "C:\Program Files\Git\bin\git.exe" status"#
+        );
+        assert_eq!(powershell_script("git status"), "git status");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_command_unix() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        env::set_var("TF_SHELL", "/bin/sh");
+
+        let command = shell_command("echo hi");
+        assert_eq!(command.get_program(), "/bin/sh");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["-c", "echo hi"]);
+    }
+
     #[test]
     fn test_get_output_simple_command() {
         // This test runs an actual command, so it's platform-specific
@@ -404,4 +846,150 @@ mod tests {
             assert!(output.contains("hello"));
         }
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_get_output_timeout_kills_process_tree() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        env::set_var("TF_SHELL", "/bin/sh");
+
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+
+        let start = Instant::now();
+        get_output(&script, Duration::from_millis(500)).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        // The grandchild is gone, or a zombie waiting to be reaped by init
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let alive = std::fs::read_to_string(&stat)
+                .map(|stat| !stat.contains(") Z "))
+                .unwrap_or(false);
+            if !alive {
+                break;
+            }
+            assert!(Instant::now() < deadline, "sleep survived the timeout");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_timeout() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo out; echo err >&2; exit 3"]);
+        let output = output_with_timeout(&mut command, Duration::from_secs(5)).unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+        assert_eq!(output.status.code(), Some(3));
+
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let start = Instant::now();
+        assert!(output_with_timeout(&mut command, Duration::from_millis(200)).is_none());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_working_dir() {
+        let _guard = crate::test_utils::EnvGuard::new(&[WORKING_DIR_ENV]);
+        let dir = tempfile::tempdir().unwrap();
+
+        env::set_var(WORKING_DIR_ENV, dir.path());
+        assert_eq!(working_dir(), dir.path());
+
+        env::set_var(WORKING_DIR_ENV, dir.path().join("missing"));
+        assert_eq!(working_dir(), env::current_dir().unwrap());
+
+        env::remove_var(WORKING_DIR_ENV);
+        assert_eq!(working_dir(), env::current_dir().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_output_runs_in_working_dir() {
+        let _guard = crate::test_utils::EnvGuard::new(&[WORKING_DIR_ENV, "TF_SHELL"]);
+        env::set_var("TF_SHELL", "/bin/sh");
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("marker"), "").unwrap();
+
+        env::set_var(WORKING_DIR_ENV, dir.path());
+        let output = get_output("ls", Duration::from_secs(5)).unwrap();
+        assert_eq!(output.trim(), "marker");
+    }
+
+    #[test]
+    fn test_bounded_buffer() {
+        let mut buffer = BoundedBuffer::new(8);
+        buffer.push(b"abc");
+        assert!(!buffer.is_truncated());
+
+        buffer.push(b"defghijklmn");
+        buffer.push(b"op");
+        assert!(buffer.is_truncated());
+        assert_eq!(
+            buffer.into_string(),
+            "abcd\n[... 8 bytes truncated ...]\nmnop"
+        );
+
+        let mut unlimited = BoundedBuffer::new(0);
+        unlimited.push(&[b'x'; 100]);
+        assert!(!unlimited.is_truncated());
+        assert_eq!(unlimited.into_string().len(), 100);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_output_limited_keeps_head_and_tail() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        env::set_var("TF_SHELL", "/bin/sh");
+
+        let script = "echo first; seq 1 200000; echo 'error: last line' >&2";
+        let rerun =
+            get_output_limited(script, Duration::from_secs(10), 1024, &HashMap::new()).unwrap();
+        assert!(rerun.truncated);
+        assert!(rerun.output.starts_with("first\n"));
+        assert!(rerun.output.contains("bytes truncated"));
+        assert!(rerun.output.ends_with("error: last line\n"));
+        assert!(rerun.output.len() < 2 * 1024 + 100);
+
+        let rerun = get_output_limited(
+            "echo short; exit 2",
+            Duration::from_secs(5),
+            1024,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert!(!rerun.truncated);
+        assert_eq!(rerun.output, "short\n");
+        assert_eq!(rerun.exit_code, Some(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_output_limited_sets_env() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        env::set_var("TF_SHELL", "/bin/sh");
+
+        let env = HashMap::from([("GIT_PAGER".to_string(), "cat".to_string())]);
+        let rerun =
+            get_output_limited("echo $GIT_PAGER", Duration::from_secs(5), 1024, &env).unwrap();
+        assert_eq!(rerun.output, "cat\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_tee_captures_both_streams() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo out; echo err >&2; exit 3"]);
+
+        let (status, output) = execute_tee(&mut command).unwrap();
+        assert_eq!(status.code(), Some(3));
+        assert!(output.contains("out\n"));
+        assert!(output.contains("err\n"));
+    }
 }
@@ -8,7 +8,7 @@
 //! - [`CdCs`] - Fixes "cs" typo to "cd" (common due to keyboard proximity)
 
 use crate::core::{is_app, Command, Rule};
-use crate::utils::get_close_matches;
+use crate::utils::{get_close_matches, shell_quote};
 #[cfg(test)]
 use regex::Regex;
 use std::fs;
@@ -133,6 +133,7 @@ impl Rule for CdMkdir {
 
         // Create mkdir -p command followed by cd
         // Use && for command chaining
+        let dir_path = shell_quote(&dir_path);
         vec![format!("mkdir -p {} && cd {}", dir_path, dir_path)]
     }
 
@@ -303,9 +304,10 @@ impl Rule for CdCorrection {
             .into_iter()
             .map(|correct_name| {
                 if let Some(parent) = &search_dir {
-                    format!("cd {}", parent.join(&correct_name).display())
+                    let path = parent.join(&correct_name);
+                    format!("cd {}", shell_quote(&path.to_string_lossy()))
                 } else {
-                    format!("cd {}", correct_name)
+                    format!("cd {}", shell_quote(&correct_name))
                 }
             })
             .collect()
@@ -575,7 +577,7 @@ mod tests {
             // Just verify the function doesn't panic
             let dirs = CdCorrection::get_directories();
             // Directories may or may not exist depending on test environment
-            assert!(dirs.len() >= 0);
+            let _ = dirs;
         }
     }
 
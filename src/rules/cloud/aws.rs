@@ -0,0 +1,347 @@
+//! AWS CLI rules.
+//!
+//! Contains rules for:
+//! - [`AwsCli`] - Fix invalid commands using awscli's "maybe you meant" suggestions
+//! - [`AwsMissingRegion`] - Add the configured region when none is given
+//! - [`AwsSsoExpired`] - Log in again when the SSO session has expired
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::replace_argument;
+
+// =============================================================================
+// Invalid Choice Rule
+// =============================================================================
+
+/// Rule that fixes AWS CLI command errors.
+///
+/// AWS CLI provides helpful suggestions when a command is invalid.
+/// This rule extracts those suggestions and offers them as corrections.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::AwsCli;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = AwsCli;
+/// let cmd = Command::new(
+///     "aws dynamdb describe-table",
+///     "usage: aws [options] <command> <subcommand>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb"
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwsCli;
+
+impl AwsCli {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for AwsCli {
+    fn name(&self) -> &str {
+        "aws_cli"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["aws"]) {
+            return false;
+        }
+        cmd.output.contains("usage:") && cmd.output.contains("maybe you meant:")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        // Pattern to extract the invalid choice: (?<=Invalid choice: ')(.*)(?=', maybe you meant:)
+        let invalid_choice_re = regex!(r"Invalid choice: '([^']*)', maybe you meant:");
+        // Pattern to extract options: ^\s*\*\s(.*)
+        let options_re = regex!(r"(?m)^\s*\*\s+(.+)$");
+
+        let mistake = match invalid_choice_re.captures(&cmd.output) {
+            Some(caps) => caps.get(1).map(|m| m.as_str().to_string()),
+            None => None,
+        };
+
+        let mistake = match mistake {
+            Some(m) => m,
+            None => return vec![],
+        };
+
+        let options: Vec<String> = options_re
+            .captures_iter(&cmd.output)
+            .filter_map(|caps| caps.get(1).map(|m| m.as_str().trim().to_string()))
+            .collect();
+
+        options
+            .into_iter()
+            .map(|opt| replace_argument(&cmd.script, &mistake, &opt))
+            .collect()
+    }
+
+    fn priority(&self) -> i32 {
+        1000
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Missing Region Rule
+// =============================================================================
+
+/// Rule that adds a region when awscli has none.
+///
+/// The region comes from `AWS_REGION` or `AWS_DEFAULT_REGION`, or from
+/// `aws configure get region` (which reads the profile's config).
+///
+/// # Example
+///
+/// ```text
+/// $ aws ec2 describe-instances
+/// You must specify a region. You can also configure your region by running "aws configure".
+///
+/// $ oops
+/// aws ec2 describe-instances --region eu-west-1
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwsMissingRegion;
+
+impl AwsMissingRegion {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the region from the environment or the AWS config.
+    fn get_region() -> Option<String> {
+        ["AWS_REGION", "AWS_DEFAULT_REGION"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|region| !region.trim().is_empty())
+            .or_else(|| {
+                let output = command_output("aws", &["configure", "get", "region"])?;
+                let region = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                (!region.is_empty()).then_some(region)
+            })
+    }
+}
+
+impl Rule for AwsMissingRegion {
+    fn name(&self) -> &str {
+        "aws_missing_region"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["aws"])
+            && cmd.output.contains("You must specify a region")
+            && !cmd.script.contains("--region")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::get_region() {
+            Some(region) => vec![format!("{} --region {}", cmd.script, region)],
+            None => vec![],
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// SSO Expired Rule
+// =============================================================================
+
+/// Rule that logs in again when the SSO session has expired.
+///
+/// The `--profile` given to the command is passed on to `aws sso login`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::AwsSsoExpired;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = AwsSsoExpired::new();
+/// let cmd = Command::new(
+///     "aws s3 ls --profile dev",
+///     "Error when retrieving token from sso: Token has expired and refresh failed",
+/// );
+/// assert_eq!(
+///     rule.get_new_command(&cmd),
+///     vec!["aws sso login --profile dev && aws s3 ls --profile dev"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwsSsoExpired;
+
+impl AwsSsoExpired {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the profile passed with `--profile`.
+    fn get_profile(cmd: &Command) -> Option<String> {
+        let parts = cmd.script_parts();
+        parts.iter().enumerate().find_map(|(i, part)| {
+            if part == "--profile" {
+                parts.get(i + 1).cloned()
+            } else {
+                part.strip_prefix("--profile=").map(str::to_string)
+            }
+        })
+    }
+}
+
+impl Rule for AwsSsoExpired {
+    fn name(&self) -> &str {
+        "aws_sso_expired"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["aws"]) {
+            return false;
+        }
+
+        let re = regex!(
+            r"Error when retrieving token from sso: Token has expired|SSO session associated with this profile has expired|aws sso login"
+        );
+        re.is_match(&cmd.output)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let login = match Self::get_profile(cmd) {
+            Some(profile) => format!("aws sso login --profile {}", profile),
+            None => "aws sso login".to_string(),
+        };
+        vec![format!("{} && {}", login, cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod aws_cli {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = AwsCli::new();
+            assert_eq!(rule.name(), "aws_cli");
+        }
+
+        #[test]
+        fn test_matches_invalid_choice() {
+            let rule = AwsCli::new();
+            let cmd = Command::new(
+                "aws dynamdb describe-table",
+                "usage: aws [options] <command>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_command() {
+            let rule = AwsCli::new();
+            let cmd = Command::new("aws dynamodb describe-table", "Table details...");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_command() {
+            let rule = AwsCli::new();
+            let cmd = Command::new("gcloud compute instances list", "usage: maybe you meant:");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = AwsCli::new();
+            let cmd = Command::new(
+                "aws dynamdb describe-table",
+                "usage: aws [options] <command>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb",
+            );
+            let fixes = rule.get_new_command(&cmd);
+            assert!(!fixes.is_empty());
+            assert!(fixes[0].contains("dynamodb"));
+        }
+    }
+    mod aws_missing_region {
+        use super::*;
+
+        const OUTPUT: &str = "You must specify a region. You can also configure your region \
+                              by running \"aws configure\".";
+
+        #[test]
+        fn test_name() {
+            let rule = AwsMissingRegion::new();
+            assert_eq!(rule.name(), "aws_missing_region");
+        }
+
+        #[test]
+        fn test_matches() {
+            let rule = AwsMissingRegion::new();
+            let cmd = Command::new("aws ec2 describe-instances", OUTPUT);
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_region_given() {
+            let rule = AwsMissingRegion::new();
+            let cmd = Command::new("aws ec2 describe-instances --region ''", OUTPUT);
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod aws_sso_expired {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = AwsSsoExpired::new();
+            assert_eq!(rule.name(), "aws_sso_expired");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = AwsSsoExpired::new();
+            let cmd = Command::new(
+                "aws s3 ls",
+                "The SSO session associated with this profile has expired or is otherwise \
+                 invalid. To refresh this SSO session run aws sso login with the corresponding \
+                 profile.",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["aws sso login && aws s3 ls"]
+            );
+        }
+
+        #[test]
+        fn test_get_profile_equals() {
+            let cmd = Command::new("aws s3 ls --profile=prod", "");
+            assert_eq!(AwsSsoExpired::get_profile(&cmd), Some("prod".to_string()));
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = AwsSsoExpired::new();
+            let cmd = Command::new("aws s3 ls", "An error occurred (AccessDenied)");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! Azure CLI rules.
+//!
+//! Contains rules for:
+//! - [`AzCli`] - Fix misspelled command groups and commands using az's suggestions
+//! - [`AzLoginRequired`] - Log in first when az has no account
+//! - [`AzExtensionMissing`] - Install the extension a command needs
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::replace_argument;
+
+// =============================================================================
+// Misspelled Command Rule
+// =============================================================================
+
+/// Rule that fixes Azure CLI command errors.
+///
+/// Azure CLI provides suggestions when a command is not found in a command
+/// group, either as "the most similar choice(s)" or as "Did you mean".
+/// This rule extracts those suggestions and offers them as corrections.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::AzCli;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = AzCli;
+/// let cmd = Command::new(
+///     "az resoure list",
+///     "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource"
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AzCli;
+
+impl AzCli {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the choices listed after "The most similar choice(s)".
+    fn extract_choices(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.starts_with("The most similar choice"))
+            .skip(1)
+            .take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .collect()
+    }
+
+    /// Extract the misspelled command and az's "Did you mean" suggestion,
+    /// without the leading `az`.
+    fn extract_did_you_mean(output: &str) -> Option<(String, String)> {
+        let mistake = regex!(r"'([^']+)' is misspelled or not recognized").captures(output)?;
+        let fixed = regex!(r"Did you mean '(?:az )?([^']+)'").captures(output)?;
+        Some((mistake[1].to_string(), fixed[1].to_string()))
+    }
+}
+
+impl Rule for AzCli {
+    fn name(&self) -> &str {
+        "az_cli"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["az"]) {
+            return false;
+        }
+        (cmd.output.contains("is not in the") && cmd.output.contains("command group"))
+            || Self::extract_did_you_mean(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if let Some((mistake, fixed)) = Self::extract_did_you_mean(&cmd.output) {
+            return vec![cmd.script.replacen(&mistake, &fixed, 1)];
+        }
+
+        // Pattern to extract the invalid command
+        let invalid_choice_re = regex!(r"'([^']*)' is not in the '.*' command group");
+
+        let mistake = match invalid_choice_re.captures(&cmd.output) {
+            Some(caps) => caps.get(1).map(|m| m.as_str().to_string()),
+            None => None,
+        };
+
+        let mistake = match mistake {
+            Some(m) => m,
+            None => return vec![],
+        };
+
+        Self::extract_choices(&cmd.output)
+            .into_iter()
+            .map(|opt| replace_argument(&cmd.script, &mistake, &opt))
+            .collect()
+    }
+
+    fn priority(&self) -> i32 {
+        1000
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Login Required Rule
+// =============================================================================
+
+/// Rule that logs in first when az has no account.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::AzLoginRequired;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = AzLoginRequired::new();
+/// let cmd = Command::new("az group list", "ERROR: Please run 'az login' to setup account.");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["az login && az group list"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AzLoginRequired;
+
+impl AzLoginRequired {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for AzLoginRequired {
+    fn name(&self) -> &str {
+        "az_login_required"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["az"])
+            && cmd.script_parts().get(1).map(String::as_str) != Some("login")
+            && (cmd.output.contains("Please run 'az login'")
+                || cmd.output.contains("run `az login`"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![format!("az login && {}", cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Extension Missing Rule
+// =============================================================================
+
+/// Rule that installs the extension a command needs.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::AzExtensionMissing;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = AzExtensionMissing::new();
+/// let cmd = Command::new(
+///     "az ssh vm -n myvm",
+///     "ERROR: The command requires the extension ssh. Unable to prompt for extension install \
+///      confirmation as no tty available.",
+/// );
+/// assert_eq!(
+///     rule.get_new_command(&cmd),
+///     vec!["az extension add --name ssh && az ssh vm -n myvm"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AzExtensionMissing;
+
+impl AzExtensionMissing {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the name of the missing extension.
+    fn extract_extension(output: &str) -> Option<String> {
+        let re = regex!(r"requires the extension ([\w-]+)");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for AzExtensionMissing {
+    fn name(&self) -> &str {
+        "az_extension_missing"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["az"]) && Self::extract_extension(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::extract_extension(&cmd.output) {
+            Some(extension) => vec![format!(
+                "az extension add --name {} && {}",
+                extension, cmd.script
+            )],
+            None => vec![],
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod az_cli {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = AzCli::new();
+            assert_eq!(rule.name(), "az_cli");
+        }
+
+        #[test]
+        fn test_matches_not_in_command_group() {
+            let rule = AzCli::new();
+            let cmd = Command::new(
+                "az resoure list",
+                "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_command() {
+            let rule = AzCli::new();
+            let cmd = Command::new("az resource list", "Resources listed");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = AzCli::new();
+            let cmd = Command::new(
+                "az resoure list",
+                "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource",
+            );
+            let fixes = rule.get_new_command(&cmd);
+            assert!(!fixes.is_empty());
+            assert!(fixes[0].contains("resource"));
+        }
+    }
+
+    mod az_cli_suggestions {
+        use super::*;
+
+        #[test]
+        fn test_get_new_command_multiple_choices() {
+            let rule = AzCli::new();
+            let cmd = Command::new(
+                "az vm lis -g rg",
+                "az: 'lis' is not in the 'az vm' command group. See 'az vm --help'.\n\n\
+                 The most similar choices to 'lis':\n    list\n    list-sizes\n",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["az vm list -g rg", "az vm list-sizes -g rg"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_did_you_mean() {
+            let rule = AzCli::new();
+            let cmd = Command::new(
+                "az storage acount list",
+                "ERROR: 'storage acount' is misspelled or not recognized by the system.\n\n\
+                 Did you mean 'az storage account' ?",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd), vec!["az storage account list"]);
+        }
+    }
+
+    mod az_login_required {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = AzLoginRequired::new();
+            assert_eq!(rule.name(), "az_login_required");
+        }
+
+        #[test]
+        fn test_matches() {
+            let rule = AzLoginRequired::new();
+            let cmd = Command::new(
+                "az account show",
+                "ERROR: Please run 'az login' to setup account.",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_login_itself() {
+            let rule = AzLoginRequired::new();
+            let cmd = Command::new("az login", "ERROR: Please run 'az login' to setup account.");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod az_extension_missing {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = AzExtensionMissing::new();
+            assert_eq!(rule.name(), "az_extension_missing");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = AzExtensionMissing::new();
+            let cmd = Command::new(
+                "az devops project list",
+                "The command requires the extension azure-devops. Do you want to install it now?",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["az extension add --name azure-devops && az devops project list"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = AzExtensionMissing::new();
+            let cmd = Command::new("az group list", "ERROR: (AuthorizationFailed)");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! fly.io rules (`fly`, `flyctl`).
+//!
+//! Contains rules for:
+//! - [`FlyUnknownCommand`] - Fix unknown commands
+//! - [`FlyNoApp`] - Pass `-a <app>` when there is no app to act on
+//! - [`FlyLoginRequired`] - Log in first when flyctl has no access token
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// Top-level flyctl commands, for fuzzy matching.
+const FLY_COMMANDS: &[&str] = &[
+    "apps",
+    "auth",
+    "certs",
+    "config",
+    "console",
+    "consul",
+    "dashboard",
+    "deploy",
+    "dig",
+    "docs",
+    "extensions",
+    "image",
+    "ips",
+    "launch",
+    "logs",
+    "machine",
+    "open",
+    "orgs",
+    "platform",
+    "postgres",
+    "proxy",
+    "redis",
+    "regions",
+    "releases",
+    "scale",
+    "secrets",
+    "services",
+    "sftp",
+    "ssh",
+    "status",
+    "storage",
+    "tokens",
+    "version",
+    "volumes",
+    "wireguard",
+];
+
+// =============================================================================
+// Unknown Command Rule
+// =============================================================================
+
+/// Rule that fixes unknown flyctl commands.
+///
+/// Uses flyctl's "Did you mean this?" list when it has one, and
+/// fuzzy-matches the known commands otherwise.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::FlyUnknownCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlyUnknownCommand::new();
+/// let cmd = Command::new(
+///     "fly deplyo --remote-only",
+///     "Error: unknown command \"deplyo\" for \"flyctl\"\n\nDid you mean this?\n\tdeploy\n",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["fly deploy --remote-only"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlyUnknownCommand;
+
+impl FlyUnknownCommand {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the unknown command.
+    fn extract_unknown(output: &str) -> Option<String> {
+        let re = regex!(r#"unknown command "([^"]+)" for "(?:fly|flyctl)"#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the commands listed after "Did you mean this?".
+    fn extract_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean this?"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for FlyUnknownCommand {
+    fn name(&self) -> &str {
+        "fly_unknown_command"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["fly", "flyctl"]) && Self::extract_unknown(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(unknown) = Self::extract_unknown(&cmd.output) else {
+            return vec![];
+        };
+
+        let mut suggestions = Self::extract_suggestions(&cmd.output);
+        if suggestions.is_empty() {
+            let commands: Vec<String> = FLY_COMMANDS.iter().map(|s| s.to_string()).collect();
+            suggestions = get_close_matches(&unknown, &commands, 3, 0.6);
+        }
+
+        suggestions
+            .iter()
+            .map(|fixed| replace_argument(&cmd.script, &unknown, fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// No App Rule
+// =============================================================================
+
+/// Rule that passes `-a <app>` when flyctl has no app to act on.
+///
+/// Outside a directory with a `fly.toml`, flyctl needs the app on the
+/// command line. This rule suggests each app from `fly apps list`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::FlyNoApp;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlyNoApp::new();
+/// let cmd = Command::new(
+///     "fly logs",
+///     "Error: the config for your app is missing an app name, add an app field to the \
+///      fly.toml file or specify with the -a flag",
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlyNoApp;
+
+impl FlyNoApp {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse app names from the `fly apps list` table, skipping its header.
+    fn parse_apps(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.trim_start().starts_with("NAME"))
+            .skip(1)
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Get the apps of the logged in user.
+    fn get_apps(program: &str) -> Vec<String> {
+        match command_output(program, &["apps", "list"]) {
+            Some(output) => Self::parse_apps(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for FlyNoApp {
+    fn name(&self) -> &str {
+        "fly_no_app"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["fly", "flyctl"]) {
+            return false;
+        }
+
+        cmd.output.contains("No app specified")
+            || cmd.output.contains("missing an app name")
+            || cmd.output.contains("Could not find App")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let program = cmd.script_parts()[0].as_str();
+        Self::get_apps(program)
+            .iter()
+            .map(|app| format!("{} -a {}", cmd.script, app))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Login Required Rule
+// =============================================================================
+
+/// Rule that logs in first when flyctl has no access token.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::FlyLoginRequired;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlyLoginRequired::new();
+/// let cmd = Command::new(
+///     "flyctl status",
+///     "Error: no access token available. Please login with 'flyctl auth login'",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["flyctl auth login && flyctl status"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlyLoginRequired;
+
+impl FlyLoginRequired {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for FlyLoginRequired {
+    fn name(&self) -> &str {
+        "fly_login_required"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["fly", "flyctl"]) {
+            return false;
+        }
+
+        let parts = cmd.script_parts();
+        if parts.get(1).map(String::as_str) == Some("auth") {
+            return false;
+        }
+
+        cmd.output.contains("no access token available") || cmd.output.contains("not logged in")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![format!(
+            "{} auth login && {}",
+            cmd.script_parts()[0],
+            cmd.script
+        )]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod fly_unknown_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlyUnknownCommand::new();
+            assert_eq!(rule.name(), "fly_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_fuzzy() {
+            let rule = FlyUnknownCommand::new();
+            let cmd = Command::new(
+                "flyctl secerts list",
+                "Error: unknown command \"secerts\" for \"flyctl\"",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd)[0], "flyctl secrets list");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = FlyUnknownCommand::new();
+            let cmd = Command::new(
+                "pulumi deplyo",
+                "error: unknown command \"deplyo\" for \"pulumi\"",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod fly_no_app {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlyNoApp::new();
+            assert_eq!(rule.name(), "fly_no_app");
+        }
+
+        #[test]
+        fn test_matches_no_app() {
+            let rule = FlyNoApp::new();
+            let cmd = Command::new("fly status", "Error: No app specified");
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_parse_apps() {
+            let output = "NAME          OWNER    STATUS     LATEST DEPLOY\n\
+                          my-api        personal deployed   1h ago\n\
+                          my-web        personal suspended\n";
+            assert_eq!(FlyNoApp::parse_apps(output), vec!["my-api", "my-web"]);
+        }
+    }
+
+    mod fly_login_required {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlyLoginRequired::new();
+            assert_eq!(rule.name(), "fly_login_required");
+        }
+
+        #[test]
+        fn test_get_new_command_fly() {
+            let rule = FlyLoginRequired::new();
+            let cmd = Command::new(
+                "fly deploy",
+                "Error: no access token available. Please login with 'flyctl auth login'",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["fly auth login && fly deploy"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_auth_itself() {
+            let rule = FlyLoginRequired::new();
+            let cmd = Command::new(
+                "fly auth whoami",
+                "Error: no access token available. Please login with 'flyctl auth login'",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
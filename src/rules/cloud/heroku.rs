@@ -0,0 +1,381 @@
+//! Heroku rules.
+//!
+//! Contains rules for:
+//! - [`HerokuMultipleApps`] - Pick an app when heroku doesn't know which one
+//! - [`HerokuNotCommand`] - Fix unknown commands using heroku's suggestions
+//! - [`HerokuLoginRequired`] - Log in first when heroku has no credentials
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::replace_argument;
+
+// =============================================================================
+// Heroku Multiple Apps Rule
+// =============================================================================
+
+/// Rule that fixes Heroku commands when multiple apps are available.
+///
+/// When a Heroku command fails because multiple apps are configured, or no
+/// app is specified at all, this rule suggests the command with the `--app`
+/// flag for each app listed in the error, or else from `heroku apps`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::HerokuMultipleApps;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = HerokuMultipleApps;
+/// let cmd = Command::new(
+///     "heroku logs",
+///     "Multiple apps in folder and target app is not specified.\n\nSpecify app with --app APP.\n\nAvailable apps:\nmy-app-staging (git remote: staging)\nmy-app-production (git remote: production)\n\nhttps://devcenter.heroku.com/articles/multiple-environments"
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HerokuMultipleApps;
+
+impl HerokuMultipleApps {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse app names from `heroku apps` output, which lists them under
+    /// `===` headers, with the region after apps outside the US.
+    fn parse_apps(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("==="))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Get the apps of the logged in user.
+    fn get_apps() -> Vec<String> {
+        match command_output("heroku", &["apps"]) {
+            Some(output) => Self::parse_apps(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for HerokuMultipleApps {
+    fn name(&self) -> &str {
+        "heroku_multiple_apps"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["heroku"]) {
+            return false;
+        }
+        cmd.output
+            .contains("https://devcenter.heroku.com/articles/multiple-environments")
+            || cmd.output.contains("No app specified")
+            || (cmd.output.contains("Missing required flag") && cmd.output.contains("--app"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        // Pattern to extract app names: (\S+) \([^)]*\)
+        // Using \S+ to match non-whitespace characters (including newlines won't be matched)
+        let apps_re = regex!(r"(\S+) \([^)]*\)");
+
+        let mut apps: Vec<String> = apps_re
+            .captures_iter(&cmd.output)
+            .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+            .collect();
+
+        // The error only lists apps when the folder has several git remotes
+        if apps.is_empty() {
+            apps = Self::get_apps();
+        }
+
+        apps.into_iter()
+            .map(|app| format!("{} --app {}", cmd.script, app))
+            .collect()
+    }
+
+    fn priority(&self) -> i32 {
+        1000
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Heroku Not Command Rule
+// =============================================================================
+
+/// Rule that fixes unknown Heroku commands.
+///
+/// When a Heroku command is not recognized, Heroku suggests the correct command,
+/// either as `Run heroku _ to run ...` or, in newer versions, as `Did you mean
+/// ...?`. This rule extracts that suggestion.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::HerokuNotCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = HerokuNotCommand;
+/// let cmd = Command::new(
+///     "heroku lgs",
+///     "lgs is not a heroku command.\nRun heroku _ to run heroku logs."
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HerokuNotCommand;
+
+impl HerokuNotCommand {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the unknown command and the one heroku suggests instead.
+    fn extract_did_you_mean(output: &str) -> Option<(String, String)> {
+        let broken = regex!(r"(\S+) is not a heroku command").captures(output)?;
+        let fixed = regex!(r"Did you mean (\S+?)\?").captures(output)?;
+        Some((broken[1].to_string(), fixed[1].to_string()))
+    }
+}
+
+impl Rule for HerokuNotCommand {
+    fn name(&self) -> &str {
+        "heroku_not_command"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["heroku"]) {
+            return false;
+        }
+        cmd.output.contains("Run heroku _ to run")
+            || Self::extract_did_you_mean(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if let Some((broken, fixed)) = Self::extract_did_you_mean(&cmd.output) {
+            return vec![replace_argument(&cmd.script, &broken, &fixed)];
+        }
+
+        // Pattern to extract the suggested command: Run heroku _ to run ([^.]+)
+        let suggestion_re = regex!(r"Run heroku _ to run ([^.]+)");
+
+        if let Some(caps) = suggestion_re.captures(&cmd.output) {
+            if let Some(suggestion) = caps.get(1) {
+                return vec![format!("heroku {}", suggestion.as_str().trim())];
+            }
+        }
+        vec![]
+    }
+
+    fn priority(&self) -> i32 {
+        1000
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Heroku Login Required Rule
+// =============================================================================
+
+/// Rule that logs in first when heroku has no credentials.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::HerokuLoginRequired;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = HerokuLoginRequired::new();
+/// let cmd = Command::new("heroku apps", " ›   Error: Invalid credentials provided.");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["heroku login && heroku apps"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HerokuLoginRequired;
+
+impl HerokuLoginRequired {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for HerokuLoginRequired {
+    fn name(&self) -> &str {
+        "heroku_login_required"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["heroku"])
+            || cmd.script_parts().get(1).map(String::as_str) == Some("login")
+        {
+            return false;
+        }
+
+        cmd.output.contains("Invalid credentials provided")
+            || cmd.output.contains("Error: not logged in")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![format!("heroku login && {}", cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod heroku_multiple_apps {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = HerokuMultipleApps::new();
+            assert_eq!(rule.name(), "heroku_multiple_apps");
+        }
+
+        #[test]
+        fn test_matches_multiple_apps() {
+            let rule = HerokuMultipleApps::new();
+            let cmd = Command::new(
+                "heroku logs",
+                "my-app-staging (staging)\nmy-app-production (production)\nhttps://devcenter.heroku.com/articles/multiple-environments",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_single_app() {
+            let rule = HerokuMultipleApps::new();
+            let cmd = Command::new("heroku logs", "Log output...");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = HerokuMultipleApps::new();
+            let cmd = Command::new(
+                "heroku logs",
+                "my-app-staging (staging)\nmy-app-production (production)\nhttps://devcenter.heroku.com/articles/multiple-environments",
+            );
+            let fixes = rule.get_new_command(&cmd);
+            assert_eq!(fixes.len(), 2);
+            assert!(
+                fixes[0].contains("--app my-app-staging"),
+                "Expected '--app my-app-staging' in fix[0], got: {:?}",
+                fixes
+            );
+            assert!(
+                fixes[1].contains("--app my-app-production"),
+                "Expected '--app my-app-production' in fix[1], got: {:?}",
+                fixes
+            );
+        }
+    }
+
+    mod heroku_not_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = HerokuNotCommand::new();
+            assert_eq!(rule.name(), "heroku_not_command");
+        }
+
+        #[test]
+        fn test_matches_not_command() {
+            let rule = HerokuNotCommand::new();
+            let cmd = Command::new("heroku lgs", "Run heroku _ to run heroku logs.");
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_command() {
+            let rule = HerokuNotCommand::new();
+            let cmd = Command::new("heroku logs", "Log output...");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = HerokuNotCommand::new();
+            let cmd = Command::new("heroku lgs", "Run heroku _ to run heroku logs.");
+            let fixes = rule.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["heroku heroku logs"]);
+        }
+    }
+
+    mod heroku_modern {
+        use super::*;
+
+        #[test]
+        fn test_not_command_did_you_mean() {
+            let rule = HerokuNotCommand::new();
+            let cmd = Command::new(
+                "heroku cofig:set KEY=1",
+                " ›   Warning: cofig:set is not a heroku command.\nDid you mean config:set? [y/n]: ",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd), vec!["heroku config:set KEY=1"]);
+        }
+
+        #[test]
+        fn test_multiple_apps_missing_flag() {
+            let rule = HerokuMultipleApps::new();
+            let cmd = Command::new(
+                "heroku logs",
+                " ›   Error: Missing required flag:\n ›     -a, --app APP  app to run command against",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_parse_apps() {
+            let output = "=== me@example.com Apps\nmy-api\nmy-web (eu)\n\n=== Collaborated Apps\nshared-app\n";
+            assert_eq!(
+                HerokuMultipleApps::parse_apps(output),
+                vec!["my-api", "my-web", "shared-app"]
+            );
+        }
+    }
+
+    mod heroku_login_required {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = HerokuLoginRequired::new();
+            assert_eq!(rule.name(), "heroku_login_required");
+        }
+
+        #[test]
+        fn test_matches_not_logged_in() {
+            let rule = HerokuLoginRequired::new();
+            let cmd = Command::new("heroku ps", " ›   Error: not logged in");
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_login_itself() {
+            let rule = HerokuLoginRequired::new();
+            let cmd = Command::new("heroku login", " ›   Error: Invalid credentials provided.");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,801 @@
+//! kubectl rules.
+//!
+//! Contains rules for:
+//! - [`KubectlUnknownCommand`] - Fix unknown commands using kubectl's suggestions
+//! - [`KubectlUnknownResource`] - Fix misspelled resource types
+//! - [`KubectlNamespace`] - Find the namespace of a resource that wasn't found
+//! - [`KubectlNoContext`] - Pick a context when none is set
+//! - [`KubectlStaleContext`] - Switch away from a context whose cluster is gone
+//! - [`KubectlApplyFile`] - Add the forgotten `-f` to `kubectl apply`
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// Resource types and their short names, for fuzzy matching.
+const KUBECTL_RESOURCES: &[&str] = &[
+    "pods",
+    "pod",
+    "po",
+    "services",
+    "service",
+    "svc",
+    "deployments",
+    "deployment",
+    "deploy",
+    "replicasets",
+    "replicaset",
+    "rs",
+    "statefulsets",
+    "statefulset",
+    "sts",
+    "daemonsets",
+    "daemonset",
+    "ds",
+    "jobs",
+    "job",
+    "cronjobs",
+    "cronjob",
+    "cj",
+    "configmaps",
+    "configmap",
+    "cm",
+    "secrets",
+    "secret",
+    "ingresses",
+    "ingress",
+    "ing",
+    "namespaces",
+    "namespace",
+    "ns",
+    "nodes",
+    "node",
+    "no",
+    "persistentvolumes",
+    "pv",
+    "persistentvolumeclaims",
+    "pvc",
+    "serviceaccounts",
+    "sa",
+    "events",
+    "ev",
+    "endpoints",
+    "ep",
+    "roles",
+    "rolebindings",
+    "clusterroles",
+    "clusterrolebindings",
+    "networkpolicies",
+    "netpol",
+    "storageclasses",
+    "sc",
+    "horizontalpodautoscalers",
+    "hpa",
+    "poddisruptionbudgets",
+    "pdb",
+    "customresourcedefinitions",
+    "crd",
+];
+
+/// Run kubectl with `args` and return its stdout lines.
+fn kubectl_lines(args: &[&str]) -> Vec<String> {
+    match command_output("kubectl", args) {
+        Some(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => vec![],
+    }
+}
+
+// =============================================================================
+// Unknown Command Rule
+// =============================================================================
+
+/// Rule that fixes unknown kubectl commands using kubectl's suggestions.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::KubectlUnknownCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = KubectlUnknownCommand::new();
+/// let cmd = Command::new(
+///     "kubectl gte pods",
+///     "error: unknown command \"gte\" for \"kubectl\"\n\nDid you mean this?\n\tget\n",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["kubectl get pods"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlUnknownCommand;
+
+impl KubectlUnknownCommand {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the unknown command.
+    fn extract_unknown(output: &str) -> Option<String> {
+        let re = regex!(r#"unknown command "([^"]+)" for "kubectl"#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the commands listed after "Did you mean this?".
+    fn extract_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean this?"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for KubectlUnknownCommand {
+    fn name(&self) -> &str {
+        "kubectl_unknown_command"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["kubectl"])
+            && Self::extract_unknown(&cmd.output).is_some()
+            && cmd.output.contains("Did you mean this?")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(unknown) = Self::extract_unknown(&cmd.output) else {
+            return vec![];
+        };
+
+        Self::extract_suggestions(&cmd.output)
+            .iter()
+            .map(|fixed| replace_argument(&cmd.script, &unknown, fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Unknown Resource Rule
+// =============================================================================
+
+/// Rule that fixes misspelled resource types.
+///
+/// kubectl doesn't suggest anything for these, so the type is matched
+/// against the built-in resource types and their short names.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::KubectlUnknownResource;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = KubectlUnknownResource::new();
+/// let cmd = Command::new(
+///     "kubectl get deploymnets",
+///     "error: the server doesn't have a resource type \"deploymnets\"",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd)[0], "kubectl get deployments");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlUnknownResource;
+
+impl KubectlUnknownResource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the unknown resource type.
+    fn extract_resource(output: &str) -> Option<String> {
+        let re = regex!(r#"the server doesn't have a resource type "([^"]+)""#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for KubectlUnknownResource {
+    fn name(&self) -> &str {
+        "kubectl_unknown_resource"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["kubectl"]) && Self::extract_resource(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(resource) = Self::extract_resource(&cmd.output) else {
+            return vec![];
+        };
+
+        let resources: Vec<String> = KUBECTL_RESOURCES.iter().map(|s| s.to_string()).collect();
+        get_close_matches(&resource, &resources, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&cmd.script, &resource, &fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Namespace Rule
+// =============================================================================
+
+/// Rule that finds the namespace of a resource that wasn't found.
+///
+/// When a resource isn't in the current namespace, this rule looks it up
+/// across all namespaces and suggests `-n <namespace>` for each one that
+/// has it. A misspelled `-n` namespace is matched against `kubectl get ns`.
+///
+/// # Example
+///
+/// ```text
+/// $ kubectl logs web-0
+/// Error from server (NotFound): pods "web-0" not found
+///
+/// $ oops
+/// kubectl logs web-0 -n shop
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlNamespace;
+
+impl KubectlNamespace {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the kind and name of the resource that wasn't found.
+    fn extract_not_found(output: &str) -> Option<(String, String)> {
+        let re = regex!(r#"\(NotFound\): (\S+) "([^"]+)" not found"#);
+        let caps = re.captures(output)?;
+        Some((caps[1].to_string(), caps[2].to_string()))
+    }
+
+    /// Check if the command already picks a namespace.
+    fn has_namespace(cmd: &Command) -> bool {
+        cmd.script_parts().iter().any(|p| {
+            p == "-n"
+                || p == "-A"
+                || p == "--all-namespaces"
+                || p.starts_with("--namespace")
+                || (p.starts_with("-n") && p.len() > 2)
+        })
+    }
+
+    /// Find the namespaces with a `kind` resource called `name`, from
+    /// `kubectl get <kind> -A --no-headers` rows.
+    fn parse_namespaces(rows: &[String], name: &str) -> Vec<String> {
+        rows.iter()
+            .filter_map(|row| {
+                let mut columns = row.split_whitespace();
+                let namespace = columns.next()?;
+                (columns.next()? == name).then(|| namespace.to_string())
+            })
+            .collect()
+    }
+}
+
+impl Rule for KubectlNamespace {
+    fn name(&self) -> &str {
+        "kubectl_namespace"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["kubectl"]) {
+            return false;
+        }
+
+        match Self::extract_not_found(&cmd.output) {
+            Some((kind, _)) if kind == "namespaces" => true,
+            Some(_) => !Self::has_namespace(cmd),
+            None => false,
+        }
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some((kind, name)) = Self::extract_not_found(&cmd.output) else {
+            return vec![];
+        };
+
+        if kind == "namespaces" {
+            let namespaces: Vec<String> = kubectl_lines(&["get", "ns", "-o", "name"])
+                .iter()
+                .map(|ns| ns.trim_start_matches("namespace/").to_string())
+                .collect();
+            return get_close_matches(&name, &namespaces, 3, 0.6)
+                .into_iter()
+                .map(|fixed| replace_argument(&cmd.script, &name, &fixed))
+                .collect();
+        }
+
+        let rows = kubectl_lines(&["get", &kind, "-A", "--no-headers"]);
+        Self::parse_namespaces(&rows, &name)
+            .iter()
+            .map(|ns| format!("{} -n {}", cmd.script, ns))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// No Context Rule
+// =============================================================================
+
+/// Rule that picks a context when none is set.
+///
+/// Suggests `kubectl config use-context` for each context in the kubeconfig.
+///
+/// # Example
+///
+/// ```text
+/// $ kubectl get pods
+/// error: current-context is not set
+///
+/// $ oops
+/// kubectl config use-context minikube && kubectl get pods
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlNoContext;
+
+impl KubectlNoContext {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for KubectlNoContext {
+    fn name(&self) -> &str {
+        "kubectl_no_context"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["kubectl"]) && cmd.output.contains("current-context is not set")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        kubectl_lines(&["config", "get-contexts", "-o", "name"])
+            .iter()
+            .map(|context| format!("kubectl config use-context {} && {}", context, cmd.script))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Stale Context Rule
+// =============================================================================
+
+/// Rule that switches away from a context whose cluster is gone.
+///
+/// Handles a `--context` that doesn't exist, by replacing it with the
+/// closest known context, and a refused connection to the current
+/// context's server, usually left behind by a deleted local cluster, by
+/// switching to another context.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::KubectlStaleContext;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = KubectlStaleContext::new();
+/// let cmd = Command::new(
+///     "kubectl get pods",
+///     "The connection to the server 127.0.0.1:38671 was refused - did you specify the right host or port?",
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlStaleContext;
+
+impl KubectlStaleContext {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the `--context` kubectl couldn't find.
+    fn extract_missing(output: &str) -> Option<String> {
+        let re = regex!(r"context was not found for specified context: (\S+)");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    fn is_refused(output: &str) -> bool {
+        regex!(r"The connection to the server \S+ was refused").is_match(output)
+            || (output.contains("Unable to connect to the server")
+                && output.contains("connection refused"))
+    }
+
+    /// Build the fixes, given the known contexts and the current one.
+    fn context_fixes(cmd: &Command, contexts: &[String], current: Option<&str>) -> Vec<String> {
+        if let Some(missing) = Self::extract_missing(&cmd.output) {
+            let mut closest = get_close_matches(&missing, contexts, 3, 0.6);
+            for context in contexts {
+                if !closest.contains(context) {
+                    closest.push(context.clone());
+                }
+            }
+            return closest
+                .iter()
+                .map(|context| replace_argument(&cmd.script, &missing, context))
+                .collect();
+        }
+
+        contexts
+            .iter()
+            .filter(|context| Some(context.as_str()) != current)
+            .map(|context| format!("kubectl config use-context {} && {}", context, cmd.script))
+            .collect()
+    }
+}
+
+impl Rule for KubectlStaleContext {
+    fn name(&self) -> &str {
+        "kubectl_stale_context"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["kubectl"])
+            && (Self::extract_missing(&cmd.output).is_some() || Self::is_refused(&cmd.output))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let contexts = kubectl_lines(&["config", "get-contexts", "-o", "name"]);
+        let current = kubectl_lines(&["config", "current-context"]);
+        Self::context_fixes(cmd, &contexts, current.first().map(String::as_str))
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Apply File Rule
+// =============================================================================
+
+/// Rule that adds the forgotten `-f` before a manifest.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::KubectlApplyFile;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = KubectlApplyFile::new();
+/// let cmd = Command::new("kubectl apply deploy.yaml", "error: Unexpected args: [deploy.yaml]");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["kubectl apply -f deploy.yaml"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubectlApplyFile;
+
+impl KubectlApplyFile {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Check if an argument looks like a manifest file, directory or URL.
+    fn is_manifest(arg: &str) -> bool {
+        [".yaml", ".yml", ".json"]
+            .iter()
+            .any(|ext| arg.ends_with(ext))
+            || arg.ends_with('/')
+            || arg.starts_with("http://")
+            || arg.starts_with("https://")
+    }
+
+    /// Find the manifest argument after the subcommand.
+    fn find_manifest(cmd: &Command) -> Option<String> {
+        cmd.script_parts()
+            .iter()
+            .skip(2)
+            .find(|p| Self::is_manifest(p))
+            .cloned()
+    }
+}
+
+impl Rule for KubectlApplyFile {
+    fn name(&self) -> &str {
+        "kubectl_apply_file"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["kubectl"]) {
+            return false;
+        }
+
+        let parts = cmd.script_parts();
+        let is_apply = matches!(
+            parts.get(1).map(String::as_str),
+            Some("apply" | "create" | "replace")
+        );
+
+        is_apply
+            && (cmd.output.contains("Unexpected args")
+                || cmd.output.contains("must specify one of -f and -k"))
+            && Self::find_manifest(cmd).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::find_manifest(cmd) {
+            Some(manifest) => vec![replace_argument(
+                &cmd.script,
+                &manifest,
+                &format!("-f {}", manifest),
+            )],
+            None => vec![],
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod kubectl_unknown_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlUnknownCommand::new();
+            assert_eq!(rule.name(), "kubectl_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_multiple() {
+            let rule = KubectlUnknownCommand::new();
+            let cmd = Command::new(
+                "kubectl lgos web-0",
+                "error: unknown command \"lgos\" for \"kubectl\"\n\n\
+                 Did you mean this?\n\tlogs\n\tlabel\n\n\
+                 Run 'kubectl --help' for usage.",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["kubectl logs web-0", "kubectl label web-0"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_without_suggestion() {
+            let rule = KubectlUnknownCommand::new();
+            let cmd = Command::new(
+                "kubectl zzz",
+                "error: unknown command \"zzz\" for \"kubectl\"",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod kubectl_unknown_resource {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlUnknownResource::new();
+            assert_eq!(rule.name(), "kubectl_unknown_resource");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = KubectlUnknownResource::new();
+            let cmd = Command::new(
+                "kubectl get pdos -A",
+                "error: the server doesn't have a resource type \"pdos\"",
+            );
+            assert!(rule.is_match(&cmd));
+            assert!(rule
+                .get_new_command(&cmd)
+                .contains(&"kubectl get pods -A".to_string()));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = KubectlUnknownResource::new();
+            let cmd = Command::new(
+                "oc get pdos",
+                "error: the server doesn't have a resource type \"pdos\"",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod kubectl_namespace {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlNamespace::new();
+            assert_eq!(rule.name(), "kubectl_namespace");
+        }
+
+        #[test]
+        fn test_matches_not_found() {
+            let rule = KubectlNamespace::new();
+            let cmd = Command::new(
+                "kubectl logs web-0",
+                "Error from server (NotFound): pods \"web-0\" not found",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_matches_missing_namespace() {
+            let rule = KubectlNamespace::new();
+            let cmd = Command::new(
+                "kubectl get pods -n stagin",
+                "Error from server (NotFound): namespaces \"stagin\" not found",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_namespace_given() {
+            let rule = KubectlNamespace::new();
+            let cmd = Command::new(
+                "kubectl logs web-0 -n shop",
+                "Error from server (NotFound): pods \"web-0\" not found",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_parse_namespaces() {
+            let rows = vec![
+                "shop    web-0   1/1   Running   0   2d".to_string(),
+                "blog    web-1   1/1   Running   0   2d".to_string(),
+                "stage   web-0   1/1   Running   0   1h".to_string(),
+            ];
+            assert_eq!(
+                KubectlNamespace::parse_namespaces(&rows, "web-0"),
+                vec!["shop", "stage"]
+            );
+        }
+    }
+
+    mod kubectl_no_context {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlNoContext::new();
+            assert_eq!(rule.name(), "kubectl_no_context");
+        }
+
+        #[test]
+        fn test_matches() {
+            let rule = KubectlNoContext::new();
+            let cmd = Command::new("kubectl get pods", "error: current-context is not set");
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match() {
+            let rule = KubectlNoContext::new();
+            let cmd = Command::new(
+                "kubectl get pods",
+                "No resources found in default namespace.",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod kubectl_stale_context {
+        use super::*;
+
+        fn contexts() -> Vec<String> {
+            vec!["kind-dev".to_string(), "minikube".to_string()]
+        }
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlStaleContext::new();
+            assert_eq!(rule.name(), "kubectl_stale_context");
+        }
+
+        #[test]
+        fn test_context_fixes_refused() {
+            let cmd = Command::new(
+                "kubectl get pods",
+                "The connection to the server 127.0.0.1:38671 was refused - did you specify \
+                 the right host or port?",
+            );
+            assert_eq!(
+                KubectlStaleContext::context_fixes(&cmd, &contexts(), Some("kind-dev")),
+                vec!["kubectl config use-context minikube && kubectl get pods"]
+            );
+        }
+
+        #[test]
+        fn test_context_fixes_missing_context() {
+            let cmd = Command::new(
+                "kubectl --context kind-dve get pods",
+                "error: context was not found for specified context: kind-dve",
+            );
+            let rule = KubectlStaleContext::new();
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                KubectlStaleContext::context_fixes(&cmd, &contexts(), None)[0],
+                "kubectl --context kind-dev get pods"
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = KubectlStaleContext::new();
+            let cmd = Command::new(
+                "kubectl get pods",
+                "No resources found in default namespace.",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod kubectl_apply_file {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KubectlApplyFile::new();
+            assert_eq!(rule.name(), "kubectl_apply_file");
+        }
+
+        #[test]
+        fn test_get_new_command_directory() {
+            let rule = KubectlApplyFile::new();
+            let cmd = Command::new(
+                "kubectl apply k8s/ --dry-run=client",
+                "error: Unexpected args: [k8s/]",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["kubectl apply -f k8s/ --dry-run=client"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_subcommand() {
+            let rule = KubectlApplyFile::new();
+            let cmd = Command::new("kubectl get deploy.yaml", "error: Unexpected args");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_without_manifest() {
+            let rule = KubectlApplyFile::new();
+            let cmd = Command::new("kubectl apply", "error: must specify one of -f and -k");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Local Kubernetes cluster rules (minikube, kind).
+//!
+//! Contains rules for:
+//! - [`MinikubeNotRunning`] - Start the cluster before using it
+//! - [`KindClusterNotFound`] - Create the cluster a kind command needs
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+
+// =============================================================================
+// minikube Not Running Rule
+// =============================================================================
+
+/// Rule that starts minikube when its cluster isn't running.
+///
+/// Uses the start command minikube suggests, which includes the profile.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::MinikubeNotRunning;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = MinikubeNotRunning::new();
+/// let cmd = Command::new(
+///     "minikube dashboard",
+///     "The control-plane node minikube host is not running: state=Stopped\n\
+///      To start a cluster, run: \"minikube start\"",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["minikube start && minikube dashboard"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinikubeNotRunning;
+
+impl MinikubeNotRunning {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the start command minikube suggests.
+    fn extract_start(output: &str) -> Option<String> {
+        let re = regex!(r#"To start a cluster, run: "(minikube start[^"]*)""#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for MinikubeNotRunning {
+    fn name(&self) -> &str {
+        "minikube_not_running"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["minikube"])
+            || cmd.script_parts().get(1).map(String::as_str) == Some("start")
+        {
+            return false;
+        }
+
+        Self::extract_start(&cmd.output).is_some() || cmd.output.contains("host is not running")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let start =
+            Self::extract_start(&cmd.output).unwrap_or_else(|| "minikube start".to_string());
+        vec![format!("{} && {}", start, cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// kind Cluster Not Found Rule
+// =============================================================================
+
+/// Rule that creates the kind cluster a command needs.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::KindClusterNotFound;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = KindClusterNotFound::new();
+/// let cmd = Command::new(
+///     "kind load docker-image app:dev --name dev",
+///     "ERROR: no nodes found for cluster \"dev\"",
+/// );
+/// assert_eq!(
+///     rule.get_new_command(&cmd),
+///     vec!["kind create cluster --name dev && kind load docker-image app:dev --name dev"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KindClusterNotFound;
+
+impl KindClusterNotFound {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the name of the missing cluster.
+    fn extract_cluster(output: &str) -> Option<String> {
+        let re = regex!(
+            r#"no nodes found for cluster "([^"]+)"|could not locate any control plane nodes for cluster named '([^']+)'"#
+        );
+        let caps = re.captures(output)?;
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for KindClusterNotFound {
+    fn name(&self) -> &str {
+        "kind_cluster_not_found"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["kind"]) && Self::extract_cluster(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::extract_cluster(&cmd.output) {
+            // "kind" is the default name, so needs no flag
+            Some(cluster) if cluster == "kind" => {
+                vec![format!("kind create cluster && {}", cmd.script)]
+            }
+            Some(cluster) => vec![format!(
+                "kind create cluster --name {} && {}",
+                cluster, cmd.script
+            )],
+            None => vec![],
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod minikube_not_running {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = MinikubeNotRunning::new();
+            assert_eq!(rule.name(), "minikube_not_running");
+        }
+
+        #[test]
+        fn test_get_new_command_profile() {
+            let rule = MinikubeNotRunning::new();
+            let cmd = Command::new(
+                "minikube -p dev service web",
+                "* Profile \"dev\" not found. Run \"minikube profile list\" to view all \
+                 profiles.\n  To start a cluster, run: \"minikube start -p dev\"",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["minikube start -p dev && minikube -p dev service web"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_start_itself() {
+            let rule = MinikubeNotRunning::new();
+            let cmd = Command::new(
+                "minikube start",
+                "To start a cluster, run: \"minikube start\"",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod kind_cluster_not_found {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = KindClusterNotFound::new();
+            assert_eq!(rule.name(), "kind_cluster_not_found");
+        }
+
+        #[test]
+        fn test_get_new_command_default_cluster() {
+            let rule = KindClusterNotFound::new();
+            let cmd = Command::new(
+                "kind get kubeconfig",
+                "ERROR: could not locate any control plane nodes for cluster named 'kind'",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["kind create cluster && kind get kubeconfig"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = KindClusterNotFound::new();
+            let cmd = Command::new("kind create cluster", "ERROR: node(s) already exist");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
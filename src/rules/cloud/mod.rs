@@ -1,314 +1,54 @@
-//! Cloud and network rules (AWS, Azure, Heroku, SSH, Tsuru, etc.)
+//! Cloud and network rules (AWS, Azure, Heroku, fly.io, SSH, Tsuru, etc.)
 //!
 //! This module contains rules for cloud services and network-related commands:
 //!
-//! - [`AwsCli`] - AWS CLI command fixes
-//! - [`AzCli`] - Azure CLI command fixes
-//! - [`HerokuMultipleApps`] - Fix heroku multiple apps error
-//! - [`HerokuNotCommand`] - Fix unknown heroku commands
+//! - AWS CLI rules (`aws.rs`): [`AwsCli`], [`AwsMissingRegion`], [`AwsSsoExpired`]
+//! - Azure CLI rules (`az.rs`): [`AzCli`], [`AzLoginRequired`], [`AzExtensionMissing`]
+//! - Heroku rules (`heroku.rs`): [`HerokuMultipleApps`], [`HerokuNotCommand`],
+//!   [`HerokuLoginRequired`]
+//! - fly.io rules (`fly.rs`): [`FlyUnknownCommand`], [`FlyNoApp`], [`FlyLoginRequired`]
 //! - [`SshKnownHosts`] - Handle SSH known_hosts issues
+//! - [`SshPermissionDenied`] - Install or load a key when public key auth fails
 //! - [`Whois`] - Fix whois command errors
 //! - [`PortAlreadyInUse`] - Suggest killing process on port
 //! - [`TsuruLogin`] - Tsuru login suggestions
 //! - [`TsuruNotCommand`] - Tsuru command fixes
 //! - [`HostsCli`] - Hosts CLI fixes
+//! - kubectl rules (`kubectl.rs`): [`KubectlUnknownCommand`], [`KubectlUnknownResource`],
+//!   [`KubectlNamespace`], [`KubectlNoContext`], [`KubectlStaleContext`], [`KubectlApplyFile`]
+//! - Local cluster rules (`local_cluster.rs`): [`MinikubeNotRunning`], [`KindClusterNotFound`]
+//! - Pulumi rules (`pulumi.rs`): [`PulumiUnknownCommand`], [`PulumiNoStack`],
+//!   [`PulumiLoginRequired`]
+//! - File transfer rules (`transfer.rs`): [`ScpDirectory`], [`RsyncSkippingDirectory`],
+//!   [`RsyncPath`]
+
+pub mod aws;
+pub mod az;
+pub mod fly;
+pub mod heroku;
+pub mod kubectl;
+pub mod local_cluster;
+pub mod pulumi;
+pub mod transfer;
+
+pub use aws::{AwsCli, AwsMissingRegion, AwsSsoExpired};
+pub use az::{AzCli, AzExtensionMissing, AzLoginRequired};
+pub use fly::{FlyLoginRequired, FlyNoApp, FlyUnknownCommand};
+pub use heroku::{HerokuLoginRequired, HerokuMultipleApps, HerokuNotCommand};
+pub use kubectl::{
+    KubectlApplyFile, KubectlNamespace, KubectlNoContext, KubectlStaleContext,
+    KubectlUnknownCommand, KubectlUnknownResource,
+};
+pub use local_cluster::{KindClusterNotFound, MinikubeNotRunning};
+pub use pulumi::{PulumiLoginRequired, PulumiNoStack, PulumiUnknownCommand};
+pub use transfer::{RsyncPath, RsyncSkippingDirectory, ScpDirectory};
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::shells::detect_shell;
+use crate::utils::cache::command_output;
+use crate::utils::regex_cache;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
-
-// =============================================================================
-// AWS CLI Rule
-// =============================================================================
-
-/// Rule that fixes AWS CLI command errors.
-///
-/// AWS CLI provides helpful suggestions when a command is invalid.
-/// This rule extracts those suggestions and offers them as corrections.
-///
-/// # Example
-///
-/// ```
-/// use oops::rules::cloud::AwsCli;
-/// use oops::core::{Command, Rule};
-///
-/// let rule = AwsCli;
-/// let cmd = Command::new(
-///     "aws dynamdb describe-table",
-///     "usage: aws [options] <command> <subcommand>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb"
-/// );
-/// assert!(rule.is_match(&cmd));
-/// ```
-#[derive(Debug, Clone, Copy, Default)]
-pub struct AwsCli;
-
-impl AwsCli {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-impl Rule for AwsCli {
-    fn name(&self) -> &str {
-        "aws_cli"
-    }
-
-    fn is_match(&self, cmd: &Command) -> bool {
-        if !is_app(cmd, &["aws"]) {
-            return false;
-        }
-        cmd.output.contains("usage:") && cmd.output.contains("maybe you meant:")
-    }
-
-    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        // Pattern to extract the invalid choice: (?<=Invalid choice: ')(.*)(?=', maybe you meant:)
-        let invalid_choice_re = Regex::new(r"Invalid choice: '([^']*)', maybe you meant:").unwrap();
-        // Pattern to extract options: ^\s*\*\s(.*)
-        let options_re = Regex::new(r"(?m)^\s*\*\s+(.+)$").unwrap();
-
-        let mistake = match invalid_choice_re.captures(&cmd.output) {
-            Some(caps) => caps.get(1).map(|m| m.as_str().to_string()),
-            None => None,
-        };
-
-        let mistake = match mistake {
-            Some(m) => m,
-            None => return vec![],
-        };
-
-        let options: Vec<String> = options_re
-            .captures_iter(&cmd.output)
-            .filter_map(|caps| caps.get(1).map(|m| m.as_str().trim().to_string()))
-            .collect();
-
-        options
-            .into_iter()
-            .map(|opt| replace_argument(&cmd.script, &mistake, &opt))
-            .collect()
-    }
-
-    fn priority(&self) -> i32 {
-        1000
-    }
-
-    fn requires_output(&self) -> bool {
-        true
-    }
-}
-
-// =============================================================================
-// Azure CLI Rule
-// =============================================================================
-
-/// Rule that fixes Azure CLI command errors.
-///
-/// Azure CLI provides suggestions when a command is not found in a command group.
-/// This rule extracts those suggestions and offers them as corrections.
-///
-/// # Example
-///
-/// ```
-/// use oops::rules::cloud::AzCli;
-/// use oops::core::{Command, Rule};
-///
-/// let rule = AzCli;
-/// let cmd = Command::new(
-///     "az resoure list",
-///     "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource"
-/// );
-/// assert!(rule.is_match(&cmd));
-/// ```
-#[derive(Debug, Clone, Copy, Default)]
-pub struct AzCli;
-
-impl AzCli {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-impl Rule for AzCli {
-    fn name(&self) -> &str {
-        "az_cli"
-    }
-
-    fn is_match(&self, cmd: &Command) -> bool {
-        if !is_app(cmd, &["az"]) {
-            return false;
-        }
-        cmd.output.contains("is not in the") && cmd.output.contains("command group")
-    }
-
-    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        // Pattern to extract the invalid command
-        let invalid_choice_re = Regex::new(r"'([^']*)' is not in the '.*' command group").unwrap();
-        // Pattern to extract the suggestion
-        let options_re =
-            Regex::new(r"(?m)^The most similar choice to '[^']*' is:\n\s*(.+)$").unwrap();
-
-        let mistake = match invalid_choice_re.captures(&cmd.output) {
-            Some(caps) => caps.get(1).map(|m| m.as_str().to_string()),
-            None => None,
-        };
-
-        let mistake = match mistake {
-            Some(m) => m,
-            None => return vec![],
-        };
-
-        let options: Vec<String> = options_re
-            .captures_iter(&cmd.output)
-            .filter_map(|caps| caps.get(1).map(|m| m.as_str().trim().to_string()))
-            .collect();
-
-        options
-            .into_iter()
-            .map(|opt| replace_argument(&cmd.script, &mistake, &opt))
-            .collect()
-    }
-
-    fn priority(&self) -> i32 {
-        1000
-    }
-
-    fn requires_output(&self) -> bool {
-        true
-    }
-}
-
-// =============================================================================
-// Heroku Multiple Apps Rule
-// =============================================================================
-
-/// Rule that fixes Heroku commands when multiple apps are available.
-///
-/// When a Heroku command fails because multiple apps are configured,
-/// this rule extracts the available apps and suggests the command with
-/// the `--app` flag.
-///
-/// # Example
-///
-/// ```
-/// use oops::rules::cloud::HerokuMultipleApps;
-/// use oops::core::{Command, Rule};
-///
-/// let rule = HerokuMultipleApps;
-/// let cmd = Command::new(
-///     "heroku logs",
-///     "Multiple apps in folder and target app is not specified.\n\nSpecify app with --app APP.\n\nAvailable apps:\nmy-app-staging (git remote: staging)\nmy-app-production (git remote: production)\n\nhttps://devcenter.heroku.com/articles/multiple-environments"
-/// );
-/// assert!(rule.is_match(&cmd));
-/// ```
-#[derive(Debug, Clone, Copy, Default)]
-pub struct HerokuMultipleApps;
-
-impl HerokuMultipleApps {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-impl Rule for HerokuMultipleApps {
-    fn name(&self) -> &str {
-        "heroku_multiple_apps"
-    }
-
-    fn is_match(&self, cmd: &Command) -> bool {
-        if !is_app(cmd, &["heroku"]) {
-            return false;
-        }
-        cmd.output
-            .contains("https://devcenter.heroku.com/articles/multiple-environments")
-    }
-
-    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        // Pattern to extract app names: (\S+) \([^)]*\)
-        // Using \S+ to match non-whitespace characters (including newlines won't be matched)
-        let apps_re = Regex::new(r"(\S+) \([^)]*\)").unwrap();
-
-        let apps: Vec<String> = apps_re
-            .captures_iter(&cmd.output)
-            .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
-            .collect();
-
-        apps.into_iter()
-            .map(|app| format!("{} --app {}", cmd.script, app))
-            .collect()
-    }
-
-    fn priority(&self) -> i32 {
-        1000
-    }
-
-    fn requires_output(&self) -> bool {
-        true
-    }
-}
-
-// =============================================================================
-// Heroku Not Command Rule
-// =============================================================================
-
-/// Rule that fixes unknown Heroku commands.
-///
-/// When a Heroku command is not recognized, Heroku suggests the correct command.
-/// This rule extracts that suggestion.
-///
-/// # Example
-///
-/// ```
-/// use oops::rules::cloud::HerokuNotCommand;
-/// use oops::core::{Command, Rule};
-///
-/// let rule = HerokuNotCommand;
-/// let cmd = Command::new(
-///     "heroku lgs",
-///     "lgs is not a heroku command.\nRun heroku _ to run heroku logs."
-/// );
-/// assert!(rule.is_match(&cmd));
-/// ```
-#[derive(Debug, Clone, Copy, Default)]
-pub struct HerokuNotCommand;
-
-impl HerokuNotCommand {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-impl Rule for HerokuNotCommand {
-    fn name(&self) -> &str {
-        "heroku_not_command"
-    }
-
-    fn is_match(&self, cmd: &Command) -> bool {
-        if !is_app(cmd, &["heroku"]) {
-            return false;
-        }
-        cmd.output.contains("Run heroku _ to run")
-    }
-
-    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        // Pattern to extract the suggested command: Run heroku _ to run ([^.]+)
-        let suggestion_re = Regex::new(r"Run heroku _ to run ([^.]+)").unwrap();
-
-        if let Some(caps) = suggestion_re.captures(&cmd.output) {
-            if let Some(suggestion) = caps.get(1) {
-                return vec![format!("heroku {}", suggestion.as_str().trim())];
-            }
-        }
-        vec![]
-    }
-
-    fn priority(&self) -> i32 {
-        1000
-    }
-
-    fn requires_output(&self) -> bool {
-        true
-    }
-}
 
 // =============================================================================
 // SSH Known Hosts Rule
@@ -363,7 +103,7 @@ impl Rule for SshKnownHosts {
         ];
 
         patterns.iter().any(|pattern| {
-            if let Ok(re) = Regex::new(pattern) {
+            if let Ok(re) = regex_cache::get(pattern) {
                 re.is_match(&cmd.output)
             } else {
                 false
@@ -380,8 +120,7 @@ impl Rule for SshKnownHosts {
     fn side_effect(&self, old_cmd: &Command, _new_script: &str) -> anyhow::Result<()> {
         // Pattern to find offending key entries: Offending key in ([^:]+):(\d+)
         let offending_re =
-            Regex::new(r"(?:Offending (?:key for IP|\S+ key)|Matching host key) in ([^:]+):(\d+)")
-                .unwrap();
+            regex!(r"(?:Offending (?:key for IP|\S+ key)|Matching host key) in ([^:]+):(\d+)");
 
         for caps in offending_re.captures_iter(&old_cmd.output) {
             let filepath = caps.get(1).map(|m| m.as_str()).unwrap_or("");
@@ -426,6 +165,138 @@ impl Rule for SshKnownHosts {
     }
 }
 
+// =============================================================================
+// SSH Permission Denied Rule
+// =============================================================================
+
+/// ssh options that take a value, so aren't the destination.
+const SSH_VALUE_OPTIONS: &[&str] = &[
+    "-B", "-b", "-c", "-D", "-E", "-e", "-F", "-I", "-i", "-J", "-L", "-l", "-m", "-O", "-o", "-p",
+    "-Q", "-R", "-S", "-W", "-w",
+];
+
+/// Rule that fixes SSH public key authentication failures.
+///
+/// Suggests `ssh-add` when the agent has no identities, and copying the
+/// key to the host with `ssh-copy-id`, using the identity file and port
+/// from the original command.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::SshPermissionDenied;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = SshPermissionDenied::new();
+/// let cmd = Command::new(
+///     "ssh -i ~/.ssh/deploy deploy@example.com",
+///     "deploy@example.com: Permission denied (publickey,password).",
+/// );
+/// let fixes = rule.get_new_command(&cmd);
+/// assert!(fixes
+///     .last()
+///     .unwrap()
+///     .starts_with("ssh-copy-id -i ~/.ssh/deploy deploy@example.com && "));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SshPermissionDenied;
+
+impl SshPermissionDenied {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the value of an option, given either as `-i key` or `-ikey`.
+    fn option_value(parts: &[String], option: &str) -> Option<String> {
+        parts.iter().enumerate().skip(1).find_map(|(i, part)| {
+            if part == option {
+                parts.get(i + 1).cloned()
+            } else {
+                part.strip_prefix(option)
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+            }
+        })
+    }
+
+    /// Get the destination, adding the user from `-l` if it has none.
+    fn destination(parts: &[String]) -> Option<String> {
+        let mut args = parts.iter().skip(1);
+        let host = loop {
+            let arg = args.next()?;
+            if SSH_VALUE_OPTIONS.contains(&arg.as_str()) {
+                args.next();
+            } else if !arg.starts_with('-') {
+                break arg.clone();
+            }
+        };
+
+        match Self::option_value(parts, "-l") {
+            Some(user) if !host.contains('@') => Some(format!("{}@{}", user, host)),
+            _ => Some(host),
+        }
+    }
+
+    /// Get the identity file from the command, or from ssh's output.
+    fn identity_file(cmd: &Command, parts: &[String]) -> Option<String> {
+        Self::option_value(parts, "-i").or_else(|| {
+            let re = regex!(r"[Ii]dentity file (\S+) not accessible");
+            re.captures(&cmd.output)
+                .and_then(|caps| caps.get(1))
+                .map(|m| m.as_str().to_string())
+        })
+    }
+
+    /// Check whether the SSH agent is running without any keys loaded.
+    fn agent_has_no_identities(output: &str) -> bool {
+        if output.contains("The agent has no identities") {
+            return true;
+        }
+
+        // `ssh-add -l` exits with 1 when the agent has no identities, and 2
+        // when there is no agent at all
+        command_output("ssh-add", &["-l"])
+            .map(|output| output.status.code() == Some(1))
+            .unwrap_or(false)
+    }
+}
+
+impl Rule for SshPermissionDenied {
+    fn name(&self) -> &str {
+        "ssh_permission_denied"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["ssh"]) && regex!(r"Permission denied \([^)]*publickey").is_match(&cmd.output)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let parts = cmd.script_parts();
+        let mut fixes = Vec::new();
+
+        if Self::agent_has_no_identities(&cmd.output) {
+            fixes.push(format!("ssh-add && {}", cmd.script));
+        }
+
+        if let Some(destination) = Self::destination(parts) {
+            let mut copy_id = String::from("ssh-copy-id");
+            if let Some(identity) = Self::identity_file(cmd, parts) {
+                copy_id.push_str(&format!(" -i {}", identity));
+            }
+            if let Some(port) = Self::option_value(parts, "-p") {
+                copy_id.push_str(&format!(" -p {}", port));
+            }
+            fixes.push(format!("{} {} && {}", copy_id, destination, cmd.script));
+        }
+
+        fixes
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
 // =============================================================================
 // Whois Rule
 // =============================================================================
@@ -564,7 +435,7 @@ impl PortAlreadyInUse {
         ];
 
         for pattern in &patterns {
-            if let Ok(re) = Regex::new(pattern) {
+            if let Ok(re) = regex_cache::get(pattern) {
                 if let Some(caps) = re.captures(output) {
                     if let Some(port_match) = caps.name("port") {
                         if let Ok(port) = port_match.as_str().parse() {
@@ -579,15 +450,10 @@ impl PortAlreadyInUse {
 
     /// Get the PID of the process using the given port using lsof.
     fn get_pid_by_port(port: u16) -> Option<u32> {
-        use std::process::Command as ProcessCommand;
-
         // Check if lsof is available
         crate::utils::which("lsof".to_string())?;
 
-        let output = ProcessCommand::new("lsof")
-            .args(["-i", &format!(":{}", port)])
-            .output()
-            .ok()?;
+        let output = crate::utils::cache::command_output("lsof", &["-i", &format!(":{}", port)])?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let lines: Vec<&str> = stdout.lines().collect();
@@ -743,7 +609,7 @@ impl Rule for TsuruNotCommand {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the broken command: tsuru: "([^"]*)" is not a tsuru command
-        let broken_cmd_re = Regex::new(r#"tsuru: "([^"]*)" is not a tsuru command"#).unwrap();
+        let broken_cmd_re = regex!(r#"tsuru: "([^"]*)" is not a tsuru command"#);
 
         let broken_cmd = match broken_cmd_re.captures(&cmd.output) {
             Some(caps) => caps.get(1).map(|m| m.as_str().to_string()),
@@ -840,7 +706,7 @@ impl Rule for HostsCli {
         }
 
         // Extract misspelled command from error: Error: No such command "..."
-        let error_re = Regex::new(r#"Error: No such command "([^"]*)""#).unwrap();
+        let error_re = regex!(r#"Error: No such command "([^"]*)""#);
 
         if let Some(caps) = error_re.captures(&cmd.output) {
             if let Some(misspelled) = caps.get(1).map(|m| m.as_str()) {
@@ -876,15 +742,38 @@ impl Rule for HostsCli {
 pub fn all_rules() -> Vec<Box<dyn Rule>> {
     vec![
         Box::new(AwsCli::new()),
+        Box::new(AwsMissingRegion::new()),
+        Box::new(AwsSsoExpired::new()),
         Box::new(AzCli::new()),
+        Box::new(AzLoginRequired::new()),
+        Box::new(AzExtensionMissing::new()),
         Box::new(HerokuMultipleApps::new()),
         Box::new(HerokuNotCommand::new()),
+        Box::new(HerokuLoginRequired::new()),
+        Box::new(FlyUnknownCommand::new()),
+        Box::new(FlyNoApp::new()),
+        Box::new(FlyLoginRequired::new()),
         Box::new(SshKnownHosts::new()),
+        Box::new(SshPermissionDenied::new()),
         Box::new(Whois::new()),
         Box::new(PortAlreadyInUse::new()),
         Box::new(TsuruLogin::new()),
         Box::new(TsuruNotCommand::new()),
         Box::new(HostsCli::new()),
+        Box::new(KubectlUnknownCommand::new()),
+        Box::new(KubectlUnknownResource::new()),
+        Box::new(KubectlNamespace::new()),
+        Box::new(KubectlNoContext::new()),
+        Box::new(KubectlStaleContext::new()),
+        Box::new(KubectlApplyFile::new()),
+        Box::new(MinikubeNotRunning::new()),
+        Box::new(KindClusterNotFound::new()),
+        Box::new(PulumiUnknownCommand::new()),
+        Box::new(PulumiNoStack::new()),
+        Box::new(PulumiLoginRequired::new()),
+        Box::new(ScpDirectory::new()),
+        Box::new(RsyncSkippingDirectory::new()),
+        Box::new(RsyncPath::new()),
     ]
 }
 
@@ -896,223 +785,124 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
 mod tests {
     use super::*;
 
-    mod aws_cli {
+    mod ssh_known_hosts {
         use super::*;
 
         #[test]
         fn test_name() {
-            let rule = AwsCli::new();
-            assert_eq!(rule.name(), "aws_cli");
+            let rule = SshKnownHosts::new();
+            assert_eq!(rule.name(), "ssh_known_hosts");
         }
 
         #[test]
-        fn test_matches_invalid_choice() {
-            let rule = AwsCli::new();
+        fn test_matches_host_changed() {
+            let rule = SshKnownHosts::new();
             let cmd = Command::new(
-                "aws dynamdb describe-table",
-                "usage: aws [options] <command>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb",
+                "ssh user@host",
+                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
             );
             assert!(rule.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_command() {
-            let rule = AwsCli::new();
-            let cmd = Command::new("aws dynamodb describe-table", "Table details...");
-            assert!(!rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_other_command() {
-            let rule = AwsCli::new();
-            let cmd = Command::new("gcloud compute instances list", "usage: maybe you meant:");
-            assert!(!rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_get_new_command() {
-            let rule = AwsCli::new();
-            let cmd = Command::new(
-                "aws dynamdb describe-table",
-                "usage: aws [options] <command>\nInvalid choice: 'dynamdb', maybe you meant:\n\n\t* dynamodb",
-            );
-            let fixes = rule.get_new_command(&cmd);
-            assert!(!fixes.is_empty());
-            assert!(fixes[0].contains("dynamodb"));
-        }
-    }
-
-    mod az_cli {
-        use super::*;
-
-        #[test]
-        fn test_name() {
-            let rule = AzCli::new();
-            assert_eq!(rule.name(), "az_cli");
+        fn test_matches_dns_spoofing() {
+            let rule = SshKnownHosts::new();
+            let cmd = Command::new("ssh user@host", "WARNING: POSSIBLE DNS SPOOFING DETECTED!");
+            assert!(rule.is_match(&cmd));
         }
 
         #[test]
-        fn test_matches_not_in_command_group() {
-            let rule = AzCli::new();
+        fn test_matches_scp() {
+            let rule = SshKnownHosts::new();
             let cmd = Command::new(
-                "az resoure list",
-                "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource",
+                "scp file user@host:/path",
+                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
             );
             assert!(rule.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_command() {
-            let rule = AzCli::new();
-            let cmd = Command::new("az resource list", "Resources listed");
+        fn test_no_match_successful() {
+            let rule = SshKnownHosts::new();
+            let cmd = Command::new("ssh user@host", "Welcome to host");
             assert!(!rule.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
-            let rule = AzCli::new();
+            let rule = SshKnownHosts::new();
             let cmd = Command::new(
-                "az resoure list",
-                "az: 'resoure' is not in the 'az' command group.\nThe most similar choice to 'resoure' is:\n    resource",
+                "ssh user@host",
+                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
             );
             let fixes = rule.get_new_command(&cmd);
-            assert!(!fixes.is_empty());
-            assert!(fixes[0].contains("resource"));
+            assert_eq!(fixes, vec!["ssh user@host"]);
         }
     }
 
-    mod heroku_multiple_apps {
+    mod ssh_permission_denied {
         use super::*;
 
+        const OUTPUT: &str = "deploy@example.com: Permission denied (publickey,password).";
+
         #[test]
         fn test_name() {
-            let rule = HerokuMultipleApps::new();
-            assert_eq!(rule.name(), "heroku_multiple_apps");
+            let rule = SshPermissionDenied::new();
+            assert_eq!(rule.name(), "ssh_permission_denied");
         }
 
         #[test]
-        fn test_matches_multiple_apps() {
-            let rule = HerokuMultipleApps::new();
-            let cmd = Command::new(
-                "heroku logs",
-                "my-app-staging (staging)\nmy-app-production (production)\nhttps://devcenter.heroku.com/articles/multiple-environments",
-            );
+        fn test_matches_publickey() {
+            let rule = SshPermissionDenied::new();
+            let cmd = Command::new("ssh deploy@example.com", OUTPUT);
             assert!(rule.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_single_app() {
-            let rule = HerokuMultipleApps::new();
-            let cmd = Command::new("heroku logs", "Log output...");
-            assert!(!rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_get_new_command() {
-            let rule = HerokuMultipleApps::new();
+        fn test_no_match_other_error() {
+            let rule = SshPermissionDenied::new();
             let cmd = Command::new(
-                "heroku logs",
-                "my-app-staging (staging)\nmy-app-production (production)\nhttps://devcenter.heroku.com/articles/multiple-environments",
-            );
-            let fixes = rule.get_new_command(&cmd);
-            assert_eq!(fixes.len(), 2);
-            assert!(
-                fixes[0].contains("--app my-app-staging"),
-                "Expected '--app my-app-staging' in fix[0], got: {:?}",
-                fixes
+                "ssh deploy@example.com",
+                "ssh: connect to host example.com port 22: Connection refused",
             );
-            assert!(
-                fixes[1].contains("--app my-app-production"),
-                "Expected '--app my-app-production' in fix[1], got: {:?}",
-                fixes
-            );
-        }
-    }
-
-    mod heroku_not_command {
-        use super::*;
-
-        #[test]
-        fn test_name() {
-            let rule = HerokuNotCommand::new();
-            assert_eq!(rule.name(), "heroku_not_command");
-        }
-
-        #[test]
-        fn test_matches_not_command() {
-            let rule = HerokuNotCommand::new();
-            let cmd = Command::new("heroku lgs", "Run heroku _ to run heroku logs.");
-            assert!(rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_valid_command() {
-            let rule = HerokuNotCommand::new();
-            let cmd = Command::new("heroku logs", "Log output...");
             assert!(!rule.is_match(&cmd));
         }
 
         #[test]
-        fn test_get_new_command() {
-            let rule = HerokuNotCommand::new();
-            let cmd = Command::new("heroku lgs", "Run heroku _ to run heroku logs.");
+        fn test_get_new_command_port_and_user() {
+            let rule = SshPermissionDenied::new();
+            let cmd = Command::new("ssh -p 2222 -l deploy example.com uptime", OUTPUT);
             let fixes = rule.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["heroku heroku logs"]);
-        }
-    }
-
-    mod ssh_known_hosts {
-        use super::*;
-
-        #[test]
-        fn test_name() {
-            let rule = SshKnownHosts::new();
-            assert_eq!(rule.name(), "ssh_known_hosts");
-        }
-
-        #[test]
-        fn test_matches_host_changed() {
-            let rule = SshKnownHosts::new();
-            let cmd = Command::new(
-                "ssh user@host",
-                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
-            );
-            assert!(rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_matches_dns_spoofing() {
-            let rule = SshKnownHosts::new();
-            let cmd = Command::new("ssh user@host", "WARNING: POSSIBLE DNS SPOOFING DETECTED!");
-            assert!(rule.is_match(&cmd));
+            assert!(fixes.contains(
+                &"ssh-copy-id -p 2222 deploy@example.com && ssh -p 2222 -l deploy example.com uptime"
+                    .to_string()
+            ));
         }
 
         #[test]
-        fn test_matches_scp() {
-            let rule = SshKnownHosts::new();
+        fn test_get_new_command_identity_from_output() {
+            let rule = SshPermissionDenied::new();
             let cmd = Command::new(
-                "scp file user@host:/path",
-                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
+                "ssh deploy@example.com",
+                "Warning: Identity file ~/.ssh/deploy not accessible: No such file or directory.\n\
+                 deploy@example.com: Permission denied (publickey).",
             );
-            assert!(rule.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_successful() {
-            let rule = SshKnownHosts::new();
-            let cmd = Command::new("ssh user@host", "Welcome to host");
-            assert!(!rule.is_match(&cmd));
+            let fixes = rule.get_new_command(&cmd);
+            assert!(fixes.contains(
+                &"ssh-copy-id -i ~/.ssh/deploy deploy@example.com && ssh deploy@example.com"
+                    .to_string()
+            ));
         }
 
         #[test]
-        fn test_get_new_command() {
-            let rule = SshKnownHosts::new();
+        fn test_get_new_command_agent_empty() {
+            let rule = SshPermissionDenied::new();
             let cmd = Command::new(
-                "ssh user@host",
-                "WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!",
+                "ssh deploy@example.com",
+                "The agent has no identities.\ndeploy@example.com: Permission denied (publickey).",
             );
             let fixes = rule.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["ssh user@host"]);
+            assert_eq!(fixes[0], "ssh-add && ssh deploy@example.com");
         }
     }
 
@@ -1350,7 +1140,7 @@ mod tests {
         #[test]
         fn test_all_rules_not_empty() {
             let rules = all_rules();
-            assert_eq!(rules.len(), 10);
+            assert_eq!(rules.len(), 33);
         }
 
         #[test]
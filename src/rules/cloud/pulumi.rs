@@ -0,0 +1,364 @@
+//! Pulumi rules.
+//!
+//! Contains rules for:
+//! - [`PulumiUnknownCommand`] - Fix unknown commands
+//! - [`PulumiNoStack`] - Select a stack when none is selected
+//! - [`PulumiLoginRequired`] - Log in first when pulumi has no credentials
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// Top-level pulumi commands, for fuzzy matching.
+const PULUMI_COMMANDS: &[&str] = &[
+    "about", "cancel", "config", "console", "convert", "destroy", "import", "install", "login",
+    "logout", "new", "org", "package", "plugin", "policy", "preview", "refresh", "schema", "stack",
+    "state", "up", "version", "watch", "whoami",
+];
+
+// =============================================================================
+// Unknown Command Rule
+// =============================================================================
+
+/// Rule that fixes unknown pulumi commands.
+///
+/// Uses pulumi's "Did you mean this?" list when it has one, and
+/// fuzzy-matches the known commands otherwise.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::PulumiUnknownCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = PulumiUnknownCommand::new();
+/// let cmd = Command::new(
+///     "pulumi previw --diff",
+///     "error: unknown command \"previw\" for \"pulumi\"\n\nDid you mean this?\n\tpreview\n",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["pulumi preview --diff"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PulumiUnknownCommand;
+
+impl PulumiUnknownCommand {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the unknown command.
+    fn extract_unknown(output: &str) -> Option<String> {
+        let re = regex!(r#"unknown command "([^"]+)" for "pulumi"#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the commands listed after "Did you mean this?".
+    fn extract_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean this?"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for PulumiUnknownCommand {
+    fn name(&self) -> &str {
+        "pulumi_unknown_command"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["pulumi"]) && Self::extract_unknown(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(unknown) = Self::extract_unknown(&cmd.output) else {
+            return vec![];
+        };
+
+        let mut suggestions = Self::extract_suggestions(&cmd.output);
+        if suggestions.is_empty() {
+            let commands: Vec<String> = PULUMI_COMMANDS.iter().map(|s| s.to_string()).collect();
+            suggestions = get_close_matches(&unknown, &commands, 3, 0.6);
+        }
+
+        suggestions
+            .iter()
+            .map(|fixed| replace_argument(&cmd.script, &unknown, fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// No Stack Selected Rule
+// =============================================================================
+
+/// Rule that selects a stack when none is selected.
+///
+/// Suggests selecting each stack pulumi lists in the error, falling back to
+/// the interactive `pulumi stack select`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::PulumiNoStack;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = PulumiNoStack::new();
+/// let cmd = Command::new(
+///     "pulumi up",
+///     "error: no stack selected; please use `pulumi stack select` or `pulumi stack init` \
+///      to choose one",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["pulumi stack select && pulumi up"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PulumiNoStack;
+
+impl PulumiNoStack {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract the stacks listed in the error, either after "Available
+    /// stacks:" on one line or one per line below it.
+    fn extract_stacks(output: &str) -> Vec<String> {
+        let mut lines = output
+            .lines()
+            .skip_while(|line| !line.to_lowercase().contains("available stacks"));
+
+        let Some(header) = lines.next() else {
+            return vec![];
+        };
+
+        let inline = header
+            .split_once(':')
+            .map(|(_, rest)| rest.trim())
+            .unwrap_or("");
+        if !inline.is_empty() {
+            return inline
+                .split([',', ' '])
+                .map(|stack| stack.trim())
+                .filter(|stack| !stack.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        lines
+            .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for PulumiNoStack {
+    fn name(&self) -> &str {
+        "pulumi_no_stack"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["pulumi"]) && cmd.output.contains("no stack selected")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let stacks = Self::extract_stacks(&cmd.output);
+        if stacks.is_empty() {
+            return vec![format!("pulumi stack select && {}", cmd.script)];
+        }
+
+        stacks
+            .iter()
+            .map(|stack| format!("pulumi stack select {} && {}", stack, cmd.script))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Login Required Rule
+// =============================================================================
+
+/// Rule that logs in first when pulumi has no credentials.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::PulumiLoginRequired;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = PulumiLoginRequired::new();
+/// let cmd = Command::new(
+///     "pulumi stack ls",
+///     "error: getting user info from https://api.pulumi.com: [401] Unauthorized: \
+///      No credentials provided or are invalid.",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["pulumi login && pulumi stack ls"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PulumiLoginRequired;
+
+impl PulumiLoginRequired {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for PulumiLoginRequired {
+    fn name(&self) -> &str {
+        "pulumi_login_required"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["pulumi"])
+            || cmd.script_parts().get(1).map(String::as_str) == Some("login")
+        {
+            return false;
+        }
+
+        cmd.output
+            .contains("No credentials provided or are invalid")
+            || cmd
+                .output
+                .contains("PULUMI_ACCESS_TOKEN must be set for login")
+            || cmd.output.contains("please run `pulumi login`")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![format!("pulumi login && {}", cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod pulumi_unknown_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = PulumiUnknownCommand::new();
+            assert_eq!(rule.name(), "pulumi_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_fuzzy() {
+            let rule = PulumiUnknownCommand::new();
+            let cmd = Command::new(
+                "pulumi refersh",
+                "error: unknown command \"refersh\" for \"pulumi\"\n\
+                 Run 'pulumi --help' for usage.",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd)[0], "pulumi refresh");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = PulumiUnknownCommand::new();
+            let cmd = Command::new(
+                "kubectl upp",
+                "error: unknown command \"upp\" for \"kubectl\"",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod pulumi_no_stack {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = PulumiNoStack::new();
+            assert_eq!(rule.name(), "pulumi_no_stack");
+        }
+
+        #[test]
+        fn test_get_new_command_inline_stacks() {
+            let rule = PulumiNoStack::new();
+            let cmd = Command::new(
+                "pulumi preview",
+                "error: no stack selected; please use `pulumi stack select` or \
+                 `pulumi stack init` to choose one\nAvailable stacks: dev, prod",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec![
+                    "pulumi stack select dev && pulumi preview",
+                    "pulumi stack select prod && pulumi preview"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_extract_stacks_listed() {
+            let output = "error: no stack selected\nAvailable stacks:\n  - myorg/dev\n  \
+                          - myorg/staging\n\n";
+            assert_eq!(
+                PulumiNoStack::extract_stacks(output),
+                vec!["myorg/dev", "myorg/staging"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = PulumiNoStack::new();
+            let cmd = Command::new("pulumi up", "error: update failed");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod pulumi_login_required {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = PulumiLoginRequired::new();
+            assert_eq!(rule.name(), "pulumi_login_required");
+        }
+
+        #[test]
+        fn test_matches_missing_token() {
+            let rule = PulumiLoginRequired::new();
+            let cmd = Command::new(
+                "pulumi up --yes",
+                "error: PULUMI_ACCESS_TOKEN must be set for login during non-interactive \
+                 CLI sessions",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_login_itself() {
+            let rule = PulumiLoginRequired::new();
+            let cmd = Command::new(
+                "pulumi login",
+                "error: PULUMI_ACCESS_TOKEN must be set for login during non-interactive \
+                 CLI sessions",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! File transfer rules (scp, rsync).
+//!
+//! Contains rules for:
+//! - [`ScpDirectory`] - Add `-r` when copying a directory with scp
+//! - [`RsyncSkippingDirectory`] - Add `-a` when rsync skips a directory
+//! - [`RsyncPath`] - Fix rsync destinations that aren't directories or don't exist
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::regex_cache;
+
+/// Get the last argument that isn't an option, which is the destination.
+fn destination(cmd: &Command) -> Option<String> {
+    cmd.script_parts()
+        .iter()
+        .skip(1)
+        .rev()
+        .find(|part| !part.starts_with('-'))
+        .cloned()
+}
+
+/// Insert a flag right after the program name.
+fn insert_flag(script: &str, program: &str, flag: &str) -> String {
+    match regex_cache::get(&format!(r"^{}\b", regex::escape(program))) {
+        Ok(re) => re
+            .replace(script, format!("{} {}", program, flag).as_str())
+            .to_string(),
+        Err(_) => script.to_string(),
+    }
+}
+
+// =============================================================================
+// scp Directory Rule
+// =============================================================================
+
+/// Rule that adds `-r` when copying a directory with scp.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::ScpDirectory;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = ScpDirectory::new();
+/// let cmd = Command::new("scp logs user@host:/tmp", "scp: logs: not a regular file");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["scp -r logs user@host:/tmp"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScpDirectory;
+
+impl ScpDirectory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for ScpDirectory {
+    fn name(&self) -> &str {
+        "scp_directory"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["scp"]) && cmd.output.contains("not a regular file")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![insert_flag(&cmd.script, "scp", "-r")]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// rsync Skipping Directory Rule
+// =============================================================================
+
+/// Rule that adds `-a` when rsync skips a directory.
+///
+/// Without `-r` (or `-a`, which implies it) rsync only copies files, and
+/// skips any directory it is given.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::RsyncSkippingDirectory;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = RsyncSkippingDirectory::new();
+/// let cmd = Command::new("rsync src backup/", "skipping directory src");
+/// assert_eq!(
+///     rule.get_new_command(&cmd),
+///     vec!["rsync -a src backup/", "rsync -r src backup/"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RsyncSkippingDirectory;
+
+impl RsyncSkippingDirectory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Rule for RsyncSkippingDirectory {
+    fn name(&self) -> &str {
+        "rsync_skipping_directory"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["rsync"]) && cmd.output.contains("skipping directory")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![
+            insert_flag(&cmd.script, "rsync", "-a"),
+            insert_flag(&cmd.script, "rsync", "-r"),
+        ]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// rsync Path Rule
+// =============================================================================
+
+/// Rule that fixes rsync destinations.
+///
+/// Handles the trailing slash pitfalls:
+/// - `failed: Not a directory` - the destination is a file, so drop the
+///   trailing slash that made rsync treat it as a directory
+/// - `mkdir "..." failed: No such file or directory` - the destination's
+///   parents don't exist, so add `--mkpath`
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::cloud::RsyncPath;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = RsyncPath::new();
+/// let cmd = Command::new(
+///     "rsync -a notes.txt host:backup/notes.txt/",
+///     "rsync: [Receiver] change_dir#3 \"/home/user/backup/notes.txt\" failed: Not a directory (20)",
+/// );
+/// assert_eq!(
+///     rule.get_new_command(&cmd),
+///     vec!["rsync -a notes.txt host:backup/notes.txt"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RsyncPath;
+
+impl RsyncPath {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn is_not_a_directory(output: &str) -> bool {
+        output.contains("failed: Not a directory")
+    }
+
+    fn is_missing_parent(output: &str) -> bool {
+        regex!(r#"mkdir "[^"]+" failed: No such file or directory"#).is_match(output)
+    }
+}
+
+impl Rule for RsyncPath {
+    fn name(&self) -> &str {
+        "rsync_path"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["rsync"]) {
+            return false;
+        }
+
+        Self::is_missing_parent(&cmd.output)
+            || (Self::is_not_a_directory(&cmd.output)
+                && destination(cmd).is_some_and(|dest| dest.ends_with('/')))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if Self::is_missing_parent(&cmd.output) {
+            return vec![insert_flag(&cmd.script, "rsync", "--mkpath")];
+        }
+
+        let Some(dest) = destination(cmd) else {
+            return vec![];
+        };
+        let fixed = dest.trim_end_matches('/');
+        if fixed.is_empty() || fixed.ends_with(':') {
+            return vec![];
+        }
+
+        match cmd.script.rfind(dest.as_str()) {
+            Some(pos) => vec![format!(
+                "{}{}{}",
+                &cmd.script[..pos],
+                fixed,
+                &cmd.script[pos + dest.len()..]
+            )],
+            None => vec![],
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod scp_directory {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = ScpDirectory::new();
+            assert_eq!(rule.name(), "scp_directory");
+        }
+
+        #[test]
+        fn test_matches_directory() {
+            let rule = ScpDirectory::new();
+            let cmd = Command::new(
+                "scp -P 2222 site host:/var/www",
+                "scp: site: not a regular file",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["scp -r -P 2222 site host:/var/www"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = ScpDirectory::new();
+            let cmd = Command::new("scp file host:/tmp", "ssh: Could not resolve hostname host");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod rsync_skipping_directory {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = RsyncSkippingDirectory::new();
+            assert_eq!(rule.name(), "rsync_skipping_directory");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = RsyncSkippingDirectory::new();
+            let cmd = Command::new("scp src backup/", "skipping directory src");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod rsync_path {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = RsyncPath::new();
+            assert_eq!(rule.name(), "rsync_path");
+        }
+
+        #[test]
+        fn test_get_new_command_missing_parent() {
+            let rule = RsyncPath::new();
+            let cmd = Command::new(
+                "rsync -av dist/ host:/srv/app/releases/v2/",
+                "rsync: [Receiver] mkdir \"/srv/app/releases/v2\" failed: No such file or \
+                 directory (2)",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["rsync --mkpath -av dist/ host:/srv/app/releases/v2/"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_not_a_directory_without_slash() {
+            let rule = RsyncPath::new();
+            let cmd = Command::new(
+                "rsync a.txt b.txt host:notes.txt",
+                "rsync: [Receiver] change_dir#3 \"/home/user/notes.txt\" failed: Not a \
+                 directory (20)",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+}
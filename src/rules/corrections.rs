@@ -0,0 +1,189 @@
+//! User-defined corrections from the `[corrections]` settings table.
+//!
+//! Each entry is turned into a lightweight regex rule at startup, as a
+//! middle ground between the built-in rules and writing a plugin:
+//!
+//! ```toml
+//! [corrections.gti]
+//! script = "^gti (.*)$"
+//! replace = "git $1"
+//!
+//! [corrections.make_jobs]
+//! script = "^make$"
+//! output = "No rule to make target"
+//! replace = "make -C build"
+//! priority = 100
+//! ```
+
+use regex::Regex;
+use tracing::warn;
+
+use crate::config::{CorrectionConfig, Settings};
+use crate::core::{Command, Rule};
+
+/// Default priority of user-defined corrections.
+const DEFAULT_PRIORITY: i32 = 1000;
+
+/// A rule built from a `[corrections.<name>]` settings entry.
+///
+/// # Example
+///
+/// ```
+/// use oops::config::CorrectionConfig;
+/// use oops::core::{Command, Rule};
+/// use oops::rules::corrections::UserCorrection;
+///
+/// let config = CorrectionConfig {
+///     script: "^gti (.*)$".to_string(),
+///     output: None,
+///     replace: "git $1".to_string(),
+///     priority: None,
+/// };
+/// let rule = UserCorrection::new("gti", &config).unwrap();
+/// let cmd = Command::new("gti status", "gti: command not found");
+/// assert!(rule.is_match(&cmd));
+/// assert_eq!(rule.get_new_command(&cmd), vec!["git status"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UserCorrection {
+    name: String,
+    script: Regex,
+    output: Option<Regex>,
+    replace: String,
+    priority: i32,
+}
+
+impl UserCorrection {
+    /// Compile a correction, failing if either pattern is not a valid regex.
+    pub fn new(name: &str, config: &CorrectionConfig) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.to_string(),
+            script: Regex::new(&config.script)?,
+            output: config.output.as_deref().map(Regex::new).transpose()?,
+            replace: config.replace.clone(),
+            priority: config.priority.unwrap_or(DEFAULT_PRIORITY),
+        })
+    }
+}
+
+impl Rule for UserCorrection {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn requires_output(&self) -> bool {
+        self.output.is_some()
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        self.script.is_match(&command.script)
+            && self
+                .output
+                .as_ref()
+                .is_none_or(|output| output.is_match(&command.output))
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let Some(captures) = self.script.captures(&command.script) else {
+            return vec![];
+        };
+
+        let mut new_command = String::new();
+        captures.expand(&self.replace, &mut new_command);
+        vec![new_command]
+    }
+}
+
+/// Build the rules for all user-defined corrections in `settings`.
+///
+/// Entries with an invalid regex are skipped with a warning.
+pub fn all_rules(settings: &Settings) -> Vec<Box<dyn Rule>> {
+    settings
+        .corrections
+        .iter()
+        .filter_map(|(name, config)| match UserCorrection::new(name, config) {
+            Ok(rule) => Some(Box::new(rule) as Box<dyn Rule>),
+            Err(e) => {
+                warn!("Invalid correction '{}': {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(script: &str, output: Option<&str>, replace: &str) -> CorrectionConfig {
+        CorrectionConfig {
+            script: script.to_string(),
+            output: output.map(str::to_string),
+            replace: replace.to_string(),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn test_script_only() {
+        let rule = UserCorrection::new("gti", &config("^gti (.*)$", None, "git $1")).unwrap();
+        assert!(!rule.requires_output());
+        assert!(rule.is_match(&Command::new("gti push", "")));
+        assert!(!rule.is_match(&Command::new("git push", "")));
+        assert_eq!(
+            rule.get_new_command(&Command::new("gti push origin", "")),
+            vec!["git push origin"]
+        );
+    }
+
+    #[test]
+    fn test_output_pattern() {
+        let rule = UserCorrection::new(
+            "make_build",
+            &config("^make$", Some("No rule to make target"), "make -C build"),
+        )
+        .unwrap();
+        assert!(rule.requires_output());
+        assert!(rule.is_match(&Command::new(
+            "make",
+            "make: *** No rule to make target 'all'.  Stop."
+        )));
+        assert!(!rule.is_match(&Command::new("make", "make: Nothing to be done")));
+    }
+
+    #[test]
+    fn test_named_groups() {
+        let rule = UserCorrection::new(
+            "kubectl_ns",
+            &config(r"^k (?P<rest>.*)$", None, "kubectl ${rest}"),
+        )
+        .unwrap();
+        assert_eq!(
+            rule.get_new_command(&Command::new("k get pods", "")),
+            vec!["kubectl get pods"]
+        );
+    }
+
+    #[test]
+    fn test_priority() {
+        let mut cfg = config("^x$", None, "y");
+        assert_eq!(UserCorrection::new("x", &cfg).unwrap().priority(), 1000);
+        cfg.priority = Some(50);
+        assert_eq!(UserCorrection::new("x", &cfg).unwrap().priority(), 50);
+    }
+
+    #[test]
+    fn test_all_rules_skips_invalid() {
+        let settings = Settings::builder()
+            .correction("good", config("^a$", None, "b"))
+            .correction("bad", config("(unclosed", None, "b"))
+            .build();
+        let rules = all_rules(&settings);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name(), "good");
+    }
+}
@@ -1,8 +1,8 @@
 //! Development tool rules (Go, Java, Maven, Gradle, Terraform, etc.)
 //!
 //! Contains rules for:
-//! - Go: `go_run`, `go_unknown_command`
-//! - Gradle: `gradle_no_task`, `gradle_wrapper`
+//! - Go: `go_run`, `go_unknown_command`, `go_missing_module`, `go_mod_init`
+//! - Gradle: `gradle_no_task`, `gradle_wrapper`, `gradle_unsupported_java`, `gradle_daemon`
 //! - Java: `java`, `javac`
 //! - Maven: `mvn_no_command`, `mvn_unknown_lifecycle_phase`
 //! - PHP Composer: `composer_not_command`
@@ -10,11 +10,21 @@
 //! - Python Fabric: `fab_command_not_found`
 //! - Node.js: `grunt_task_not_found`, `gulp_not_task`
 //! - Clojure: `lein_not_task`
-//! - Terraform: `terraform_init`, `terraform_no_command`
-
-use crate::core::{is_app, Command, Rule};
-use crate::utils::{get_close_matches, get_closest, replace_argument};
-use regex::Regex;
+//! - Terraform: `terraform_init`, `terraform_no_command`, `terraform_workspace_missing`,
+//!   `terraform_state_lock`
+//! - Deno: `deno_permission`, `deno_unknown_command`, `deno_run_extension`
+//! - Rustup: `rustup_toolchain_not_installed`, `rustup_unknown_component`, `rustup_target_add`
+//! - Make: `make_no_target`
+//! - CMake: `cmake_no_build_dir`, `cmake_cache_mismatch`
+//! - Just: `just_unknown_recipe`, `just_no_justfile`
+//! - Xcode and Swift: `xcodebuild_scheme_not_found`, `xcrun_missing_tools`,
+//!   `swift_unknown_subcommand`
+
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, get_closest, replace_argument, shell_quote};
+use std::path::Path;
 
 // ============================================================================
 // Go Rules
@@ -108,6 +118,112 @@ impl Rule for GoUnknownCommand {
     }
 }
 
+/// Rule to fetch a package no module in `go.mod` provides.
+///
+/// Matches errors like:
+/// - `no required module provides package github.com/google/uuid; to add it:`
+/// - `missing go.sum entry for module providing package golang.org/x/sync/errgroup`
+///
+/// Suggests `go get` for the package, then `go mod tidy`, which fetches
+/// every missing import at once.
+///
+/// # Example
+///
+/// ```text
+/// > go run .
+/// main.go:6:2: no required module provides package github.com/google/uuid; to add it:
+///     go get github.com/google/uuid
+///
+/// > oops
+/// go get github.com/google/uuid && go run .
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GoMissingModule;
+
+impl GoMissingModule {
+    /// Extract the package no module provides.
+    fn get_package(output: &str) -> Option<String> {
+        let re = regex!(r"no required module provides package ([^\s;]+)");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for GoMissingModule {
+    fn name(&self) -> &str {
+        "go_missing_module"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["go"]) {
+            return false;
+        }
+
+        Self::get_package(&command.output).is_some()
+            || command.output.contains("missing go.sum entry")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let mut fixes = Vec::new();
+        if let Some(package) = Self::get_package(&command.output) {
+            fixes.push(format!("go get {} && {}", package, command.script));
+        }
+        fixes.push(format!("go mod tidy && {}", command.script));
+        fixes
+    }
+}
+
+/// Rule to create a `go.mod` when there is none.
+///
+/// Matches errors like:
+/// - `go: go.mod file not found in current directory or any parent directory`
+///
+/// Names the module after the working directory.
+///
+/// # Example
+///
+/// ```text
+/// > go build
+/// go: go.mod file not found in current directory or any parent directory; see 'go help modules'
+///
+/// > oops
+/// go mod init hello && go build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GoModInit;
+
+impl GoModInit {
+    /// Get a module path from a directory name, which can't contain spaces.
+    fn module_name(dir: &Path) -> String {
+        dir.file_name()
+            .map(|name| name.to_string_lossy().to_lowercase().replace(' ', "-"))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "example.com/app".to_string())
+    }
+}
+
+impl Rule for GoModInit {
+    fn name(&self) -> &str {
+        "go_mod_init"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        is_app(command, &["go"]) && command.output.contains("go.mod file not found")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        vec![format!(
+            "go mod init {} && {}",
+            Self::module_name(ctx.cwd()),
+            command.script
+        )]
+    }
+}
+
 // ============================================================================
 // Gradle Rules
 // ============================================================================
@@ -161,7 +277,7 @@ impl GradleNoTask {
 
     /// Extract the wrong task name from Gradle output.
     fn get_wrong_task(output: &str) -> Option<String> {
-        let re = Regex::new(r"Task '([^']*)' (?:is ambiguous|not found)").ok()?;
+        let re = regex!(r"Task '([^']*)' (?:is ambiguous|not found)");
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -217,6 +333,10 @@ impl Rule for GradleWrapper {
     }
 
     fn is_match(&self, command: &Command) -> bool {
+        self.is_match_with_context(command, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, command: &Command, ctx: &RuleContext) -> bool {
         let parts = command.script_parts();
         if parts.is_empty() || parts[0] != "gradle" {
             return false;
@@ -227,8 +347,7 @@ impl Rule for GradleWrapper {
         (output_lower.contains("not found")
             || output_lower.contains("not recognized")
             || output_lower.contains("is not recognized"))
-            && (std::path::Path::new("gradlew").exists()
-                || std::path::Path::new("gradlew.bat").exists())
+            && (ctx.cwd().join("gradlew").exists() || ctx.cwd().join("gradlew.bat").exists())
     }
 
     fn get_new_command(&self, command: &Command) -> Vec<String> {
@@ -253,6 +372,173 @@ impl Rule for GradleWrapper {
     }
 }
 
+/// Oldest Gradle release that runs on each Java version.
+const GRADLE_FOR_JAVA: &[(u32, &str)] = &[
+    (16, "7.0"),
+    (17, "7.3"),
+    (18, "7.5"),
+    (19, "7.6"),
+    (20, "8.3"),
+    (21, "8.5"),
+    (22, "8.8"),
+    (23, "8.10"),
+    (24, "8.14"),
+];
+
+/// Rule to fix Gradle running on a JDK newer than it supports.
+///
+/// Matches errors like:
+/// - `Unsupported class file major version 65`
+///
+/// Suggests upgrading the wrapper to a Gradle release that supports the
+/// JDK, and running with an older JDK from `JAVA_HOME`.
+///
+/// # Example
+///
+/// ```text
+/// > ./gradlew build
+/// BUG! exception in phase 'semantic analysis' in source unit '_BuildScript_'
+/// Unsupported class file major version 65
+///
+/// > oops
+/// ./gradlew wrapper --gradle-version 8.5 && ./gradlew build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GradleUnsupportedJava;
+
+impl GradleUnsupportedJava {
+    /// Extract the Java version from the class file major version.
+    fn get_java_version(output: &str) -> Option<u32> {
+        let re = regex!(r"Unsupported class file major version (\d+)");
+        let major: u32 = re.captures(output)?.get(1)?.as_str().parse().ok()?;
+        major.checked_sub(44)
+    }
+
+    /// Find installed JDKs, with the Java version parsed from their
+    /// directory names (e.g. `java-17-openjdk-amd64`, `temurin-17.jdk`).
+    fn installed_jdks() -> Vec<(u32, String)> {
+        let roots = [
+            ("/usr/lib/jvm", ""),
+            ("/Library/Java/JavaVirtualMachines", "/Contents/Home"),
+        ];
+        let re = regex!(r"(?:^|[-_])(?:1\.)?(\d{1,2})(?:[-_.]|$)");
+        let mut jdks = Vec::new();
+        for (root, suffix) in roots {
+            let Ok(entries) = std::fs::read_dir(root) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let version = re
+                    .captures(&name)
+                    .and_then(|caps| caps[1].parse::<u32>().ok());
+                if let Some(version) = version {
+                    jdks.push((version, format!("{}/{}{}", root, name, suffix)));
+                }
+            }
+        }
+        jdks
+    }
+
+    /// Build the fixes for a JDK of `java` version, given the installed JDKs.
+    fn java_fixes(command: &Command, java: u32, jdks: &[(u32, String)]) -> Vec<String> {
+        let mut fixes = Vec::new();
+        let program = &command.script_parts()[0];
+
+        if program.contains("gradlew") {
+            if let Some((_, gradle)) = GRADLE_FOR_JAVA.iter().find(|(v, _)| *v == java) {
+                fixes.push(format!(
+                    "{} wrapper --gradle-version {} && {}",
+                    program, gradle, command.script
+                ));
+            }
+        }
+
+        // The newest JDK older than the one in use
+        if let Some((_, home)) = jdks
+            .iter()
+            .filter(|(version, _)| *version < java)
+            .max_by_key(|(version, _)| *version)
+        {
+            fixes.push(format!("JAVA_HOME={} {}", home, command.script));
+        }
+
+        fixes
+    }
+}
+
+impl Rule for GradleUnsupportedJava {
+    fn name(&self) -> &str {
+        "gradle_unsupported_java"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["gradle", "gradlew", "gradlew.bat"]) {
+            return false;
+        }
+
+        Self::get_java_version(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_java_version(&command.output) {
+            Some(java) => Self::java_fixes(command, java, &Self::installed_jdks()),
+            None => vec![],
+        }
+    }
+}
+
+/// Rule to restart the Gradle daemon when it crashed or hung.
+///
+/// Matches errors like:
+/// - `Gradle build daemon disappeared unexpectedly`
+/// - `Timeout waiting to connect to the Gradle daemon.`
+///
+/// Suggests stopping the daemons before running the command again, and
+/// running without a daemon as a fallback.
+///
+/// # Example
+///
+/// ```text
+/// > gradle build
+/// FAILURE: Build failed with an exception.
+/// * What went wrong:
+/// Gradle build daemon disappeared unexpectedly (it may have been killed or may have crashed)
+///
+/// > oops
+/// gradle --stop && gradle build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GradleDaemon;
+
+impl Rule for GradleDaemon {
+    fn name(&self) -> &str {
+        "gradle_daemon"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["gradle", "gradlew", "gradlew.bat"]) {
+            return false;
+        }
+
+        let output = &command.output;
+        output.contains("daemon disappeared unexpectedly")
+            || output.contains("the daemon has disappeared")
+            || output.contains("Could not connect to the Gradle daemon")
+            || output.contains("Timeout waiting to connect to the Gradle daemon")
+            || output.contains("Gradle Daemon crashed")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let program = &command.script_parts()[0];
+        let mut fixes = vec![format!("{} --stop && {}", program, command.script)];
+        if !command.script_parts().iter().any(|p| p == "--no-daemon") {
+            fixes.push(format!("{} --no-daemon", command.script));
+        }
+        fixes
+    }
+}
+
 // ============================================================================
 // Java Rules
 // ============================================================================
@@ -392,14 +678,14 @@ pub struct MvnUnknownLifecyclePhase;
 impl MvnUnknownLifecyclePhase {
     /// Extract the failed lifecycle phase from Maven output.
     fn get_failed_lifecycle(output: &str) -> Option<String> {
-        let re = Regex::new(r#"\[ERROR\] Unknown lifecycle phase "([^"]+)""#).ok()?;
+        let re = regex!(r#"\[ERROR\] Unknown lifecycle phase "([^"]+)""#);
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
 
     /// Extract available lifecycle phases from Maven output.
     fn get_available_lifecycles(output: &str) -> Option<Vec<String>> {
-        let re = Regex::new(r"Available lifecycle phases are: ([^>]+) -> \[Help 1\]").ok()?;
+        let re = regex!(r"Available lifecycle phases are: ([^>]+) -> \[Help 1\]");
         let caps = re.captures(output)?;
         let phases_str = caps.get(1)?.as_str();
         Some(
@@ -468,7 +754,7 @@ pub struct ComposerNotCommand;
 impl ComposerNotCommand {
     /// Extract the broken command from Composer output.
     fn get_broken_command(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Command "([^"]*)" is not defined"#).ok()?;
+        let re = regex!(r#"Command "([^"]*)" is not defined"#);
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -476,7 +762,7 @@ impl ComposerNotCommand {
     /// Extract the suggested command from Composer output.
     fn get_suggested_command(output: &str) -> Option<String> {
         // Try "Did you mean this?" first
-        let re1 = Regex::new(r"Did you mean this\?[^\n]*\n\s*([^\n]*)").ok()?;
+        let re1 = regex!(r"Did you mean this\?[^\n]*\n\s*([^\n]*)");
         if let Some(caps) = re1.captures(output) {
             if let Some(m) = caps.get(1) {
                 return Some(m.as_str().trim().to_string());
@@ -484,7 +770,7 @@ impl ComposerNotCommand {
         }
 
         // Try "Did you mean one of these?" next
-        let re2 = Regex::new(r"Did you mean one of these\?[^\n]*\n\s*([^\n]*)").ok()?;
+        let re2 = regex!(r"Did you mean one of these\?[^\n]*\n\s*([^\n]*)");
         if let Some(caps) = re2.captures(output) {
             if let Some(m) = caps.get(1) {
                 return Some(m.as_str().trim().to_string());
@@ -725,7 +1011,7 @@ impl GruntTaskNotFound {
 
     /// Extract the misspelled task from Grunt output.
     fn get_wrong_task(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Warning: Task "([^"]*)" not found"#).ok()?;
+        let re = regex!(r#"Warning: Task "([^"]*)" not found"#);
         let caps = re.captures(output)?;
         let task = caps.get(1)?.as_str();
         // Handle task:target format - just get the task name
@@ -794,7 +1080,7 @@ impl GulpNotTask {
 
     /// Extract the wrong task from Gulp output.
     fn get_wrong_task(output: &str) -> Option<String> {
-        let re = Regex::new(r"Task '(\w+)' is not in your gulpfile").ok()?;
+        let re = regex!(r"Task '(\w+)' is not in your gulpfile");
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -852,7 +1138,7 @@ pub struct LeinNotTask;
 impl LeinNotTask {
     /// Extract the broken task from Leiningen output.
     fn get_broken_task(output: &str) -> Option<String> {
-        let re = Regex::new(r"'([^']*)' is not a task").ok()?;
+        let re = regex!(r"'([^']*)' is not a task");
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -974,14 +1260,14 @@ pub struct TerraformNoCommand;
 impl TerraformNoCommand {
     /// Extract the mistaken command from Terraform output.
     fn get_mistake(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Terraform has no command named "([^"]+)""#).ok()?;
+        let re = regex!(r#"Terraform has no command named "([^"]+)""#);
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
 
     /// Extract the suggested fix from Terraform output.
     fn get_fix(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Did you mean "([^"]+)"\?"#).ok()?;
+        let re = regex!(r#"Did you mean "([^"]+)"\?"#);
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -1015,686 +1301,2719 @@ impl Rule for TerraformNoCommand {
     }
 }
 
-// ============================================================================
-// All Rules Function
-// ============================================================================
-
-/// Returns all development tool rules as boxed trait objects.
+/// Rule to fix selecting a Terraform workspace that doesn't exist.
 ///
-/// This function creates instances of all devtools rules
-/// for registration with the rule system.
-pub fn all_rules() -> Vec<Box<dyn Rule>> {
-    vec![
-        // Go rules
-        Box::new(GoRun),
-        Box::new(GoUnknownCommand),
-        // Gradle rules
-        Box::new(GradleNoTask),
-        Box::new(GradleWrapper),
-        // Java rules
-        Box::new(Java),
-        Box::new(Javac),
-        // Maven rules
-        Box::new(MvnNoCommand),
-        Box::new(MvnUnknownLifecyclePhase),
-        // PHP Composer
-        Box::new(ComposerNotCommand),
-        // C++
-        Box::new(Cpp11),
-        // Python Fabric
-        Box::new(FabCommandNotFound),
-        // Node.js
-        Box::new(GruntTaskNotFound),
-        Box::new(GulpNotTask),
-        // Clojure
-        Box::new(LeinNotTask),
-        // Terraform
-        Box::new(TerraformInit),
-        Box::new(TerraformNoCommand),
-    ]
-}
-
-// ============================================================================
-// Tests
-// ============================================================================
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Matches errors like:
+/// - `Workspace "stagng" doesn't exist.`
+///
+/// Suggests selecting the closest existing workspace from
+/// `terraform workspace list`, then creating the workspace.
+///
+/// # Example
+///
+/// ```text
+/// > terraform workspace select stagng
+/// Workspace "stagng" doesn't exist.
+///
+/// You can create this workspace with the "new" subcommand
+/// or include the "-or-create" flag with the "select" subcommand.
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerraformWorkspaceMissing;
 
-    // ------------------------------------------------------------------------
-    // Go Rules Tests
-    // ------------------------------------------------------------------------
+impl TerraformWorkspaceMissing {
+    /// Extract the missing workspace from Terraform output.
+    fn get_workspace(output: &str) -> Option<String> {
+        let re = regex!(r#"Workspace "([^"]+)" doesn't exist"#);
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
 
-    mod go_run_tests {
-        use super::*;
+    /// Parse workspace names from `terraform workspace list` output, where
+    /// the current workspace is marked with `*`.
+    fn parse_workspaces(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .map(|line| line.trim().trim_start_matches('*').trim())
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
 
-        #[test]
-        fn test_name() {
-            assert_eq!(GoRun.name(), "go_run");
+    /// Get the workspaces of the current configuration.
+    fn get_workspaces() -> Vec<String> {
+        match command_output("terraform", &["workspace", "list"]) {
+            Some(output) => Self::parse_workspaces(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
         }
+    }
+}
 
-        #[test]
-        fn test_matches_go_run_without_extension() {
-            let cmd = Command::new("go run foo", "go run: no go files listed");
-            assert!(GoRun.is_match(&cmd));
-        }
+impl Rule for TerraformWorkspaceMissing {
+    fn name(&self) -> &str {
+        "terraform_workspace_missing"
+    }
 
-        #[test]
-        fn test_no_match_with_extension() {
-            let cmd = Command::new("go run foo.go", "");
-            assert!(!GoRun.is_match(&cmd));
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["terraform"]) {
+            return false;
         }
 
-        #[test]
-        fn test_no_match_other_go_command() {
-            let cmd = Command::new("go build foo", "");
-            assert!(!GoRun.is_match(&cmd));
-        }
+        Self::get_workspace(&command.output).is_some()
+    }
 
-        #[test]
-        fn test_get_new_command() {
-            let cmd = Command::new("go run foo", "go run: no go files listed");
-            let fixes = GoRun.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["go run foo.go"]);
-        }
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let workspace = match Self::get_workspace(&command.output) {
+            Some(w) => w,
+            None => return vec![],
+        };
 
-        #[test]
-        fn test_requires_no_output() {
-            assert!(!GoRun.requires_output());
-        }
+        let mut fixes: Vec<String> = get_close_matches(&workspace, &Self::get_workspaces(), 3, 0.6)
+            .into_iter()
+            .filter(|fixed| *fixed != workspace)
+            .map(|fixed| format!("terraform workspace select {}", fixed))
+            .collect();
+        fixes.push(format!("terraform workspace new {}", workspace));
+        fixes
     }
+}
 
-    mod go_unknown_command_tests {
-        use super::*;
+/// Rule to release a stale Terraform state lock.
+///
+/// Matches errors like:
+/// - `Error acquiring the state lock`
+///
+/// Suggests `terraform force-unlock` with the lock ID from the output. The
+/// original command isn't re-run afterwards. This is destructive if another
+/// run still holds the lock, so it's disabled by default.
+///
+/// # Example
+///
+/// ```text
+/// > terraform apply
+/// Error: Error acquiring the state lock
+///
+/// Lock Info:
+///   ID:        9db590f1-b6fe-c5f2-2678-8804f089deba
+///
+/// > oops
+/// terraform force-unlock 9db590f1-b6fe-c5f2-2678-8804f089deba
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerraformStateLock;
 
-        #[test]
-        fn test_name() {
-            assert_eq!(GoUnknownCommand.name(), "go_unknown_command");
-        }
+impl TerraformStateLock {
+    /// Extract the lock ID from the "Lock Info" block.
+    fn get_lock_id(output: &str) -> Option<String> {
+        let re = regex!(r"(?m)^\s*ID:\s+(\S+)");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+}
 
-        #[test]
+impl Rule for TerraformStateLock {
+    fn name(&self) -> &str {
+        "terraform_state_lock"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["terraform"]) {
+            return false;
+        }
+
+        command.output.contains("Error acquiring the state lock")
+            && Self::get_lock_id(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_lock_id(&command.output) {
+            Some(id) => vec![format!("terraform force-unlock {}", id)],
+            None => vec![],
+        }
+    }
+
+    fn priority(&self) -> i32 {
+        // Lower priority since the lock may still belong to a running apply
+        1100
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Deno Rules
+// ============================================================================
+
+/// Deno subcommands that run a script, so take permission flags.
+const DENO_RUN_COMMANDS: &[&str] = &["run", "test", "bench", "serve", "compile", "install"];
+
+/// Rule to add the `--allow-*` flag Deno asks for when a permission is denied.
+///
+/// Matches errors like:
+/// - `Requires net access to "example.com", run again with the --allow-net flag`
+///
+/// # Example
+///
+/// ```text
+/// > deno run main.ts
+/// error: Uncaught (in promise) NotCapable: Requires net access to "example.com:443",
+/// run again with the --allow-net flag
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenoPermission;
+
+impl DenoPermission {
+    /// Extract the flag Deno suggests.
+    fn get_flag(output: &str) -> Option<&str> {
+        regex!(r"run again with the (--allow-[a-z-]+) flag")
+            .captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+    }
+}
+
+impl Rule for DenoPermission {
+    fn name(&self) -> &str {
+        "deno_permission"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["deno"]) {
+            return false;
+        }
+
+        Self::get_flag(&command.output).is_some_and(|flag| !command.script.contains(flag))
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let Some(flag) = Self::get_flag(&command.output) else {
+            return vec![];
+        };
+
+        // Flags after the script are passed to the script, so add it before
+        let parts = command.script_parts();
+        let after = match parts.get(1) {
+            Some(sub) if DENO_RUN_COMMANDS.contains(&sub.as_str()) => sub.as_str(),
+            _ => parts[0].as_str(),
+        };
+        vec![replace_argument(
+            &command.script,
+            after,
+            &format!("{} {}", after, flag),
+        )]
+    }
+}
+
+/// Rule to suggest correct Deno subcommands when an unknown one is used.
+///
+/// Matches errors like:
+/// - `error: unrecognized subcommand 'tset'`
+///
+/// # Example
+///
+/// ```text
+/// > deno tset
+/// error: unrecognized subcommand 'tset'
+///
+///   tip: a similar subcommand exists: 'test'
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenoUnknownCommand;
+
+impl DenoUnknownCommand {
+    /// Deno subcommands for fuzzy matching.
+    const DENO_COMMANDS: &'static [&'static str] = &[
+        "add",
+        "bench",
+        "check",
+        "clean",
+        "compile",
+        "completions",
+        "coverage",
+        "doc",
+        "eval",
+        "fmt",
+        "info",
+        "init",
+        "install",
+        "jupyter",
+        "lint",
+        "lsp",
+        "outdated",
+        "publish",
+        "remove",
+        "repl",
+        "run",
+        "serve",
+        "task",
+        "test",
+        "types",
+        "uninstall",
+        "upgrade",
+    ];
+}
+
+impl Rule for DenoUnknownCommand {
+    fn name(&self) -> &str {
+        "deno_unknown_command"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["deno"]) {
+            return false;
+        }
+
+        command.output.contains("unrecognized subcommand")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let Some(broken) = regex!(r"unrecognized subcommand '([^']+)'")
+            .captures(&command.output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+        else {
+            return vec![];
+        };
+
+        // Prefer Deno's own suggestion
+        if let Some(tip) = regex!(r"a similar subcommand exists: '([^']+)'")
+            .captures(&command.output)
+            .and_then(|caps| caps.get(1))
+        {
+            return vec![replace_argument(&command.script, broken, tip.as_str())];
+        }
+
+        let deno_commands: Vec<String> =
+            Self::DENO_COMMANDS.iter().map(|s| s.to_string()).collect();
+        get_close_matches(broken, &deno_commands, 3, 0.6)
+            .into_iter()
+            .map(|fix| replace_argument(&command.script, broken, &fix))
+            .collect()
+    }
+}
+
+/// Rule to add the extension to a script `deno run` can't find.
+///
+/// Matches errors like:
+/// - `deno run main` (should be `deno run main.ts`)
+///
+/// # Example
+///
+/// ```text
+/// > deno run main
+/// error: Module not found "file:///home/me/app/main".
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenoRunExtension;
+
+impl DenoRunExtension {
+    /// Script extensions Deno runs, most likely first.
+    const EXTENSIONS: &'static [&'static str] = &["ts", "js", "tsx", "jsx", "mts", "mjs"];
+
+    /// Get the script argument of `deno run`, if it has no extension.
+    fn get_script(command: &Command) -> Option<String> {
+        let parts = command.script_parts();
+        if parts.get(1).map(String::as_str) != Some("run") {
+            return None;
+        }
+        parts[2..]
+            .iter()
+            .find(|part| !part.starts_with('-'))
+            .filter(|script| Path::new(script.as_str()).extension().is_none())
+            .cloned()
+    }
+}
+
+impl Rule for DenoRunExtension {
+    fn name(&self) -> &str {
+        "deno_run_extension"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["deno"]) {
+            return false;
+        }
+
+        command.output.contains("Module not found") && Self::get_script(command).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let Some(script) = Self::get_script(command) else {
+            return vec![];
+        };
+
+        let existing: Vec<&str> = Self::EXTENSIONS
+            .iter()
+            .copied()
+            .filter(|ext| Path::new(&script).with_extension(ext).exists())
+            .collect();
+        let extensions = if existing.is_empty() {
+            &Self::EXTENSIONS[..1]
+        } else {
+            &existing[..]
+        };
+
+        extensions
+            .iter()
+            .map(|ext| replace_argument(&command.script, &script, &format!("{}.{}", script, ext)))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Rustup Rules
+// ============================================================================
+
+/// Rule to install a toolchain that isn't installed yet.
+///
+/// Matches errors like:
+/// - `error: toolchain 'nightly-x86_64-unknown-linux-gnu' is not installed`
+///
+/// # Example
+///
+/// ```text
+/// > cargo +nightly build
+/// error: toolchain 'nightly-x86_64-unknown-linux-gnu' is not installed
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustupToolchainNotInstalled;
+
+impl RustupToolchainNotInstalled {
+    /// Extract the missing toolchain from the error.
+    fn get_toolchain(output: &str) -> Option<String> {
+        let re = regex!(r"toolchain '([^']+)' is not installed");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for RustupToolchainNotInstalled {
+    fn name(&self) -> &str {
+        "rustup_toolchain_not_installed"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo", "rustc", "rustup", "rustdoc", "rustfmt"]) {
+            return false;
+        }
+
+        Self::get_toolchain(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_toolchain(&command.output) {
+            Some(toolchain) => vec![format!(
+                "rustup toolchain install {} && {}",
+                toolchain, command.script
+            )],
+            None => vec![],
+        }
+    }
+}
+
+/// Rule to fix a misspelled component name in `rustup component add`.
+///
+/// Matches errors like:
+/// - `error: toolchain 'stable-x86_64-unknown-linux-gnu' does not contain component 'clipy'`
+///
+/// Suggests the closest components from `rustup component list`.
+///
+/// # Example
+///
+/// ```text
+/// > rustup component add clipy
+/// error: toolchain 'stable-x86_64-unknown-linux-gnu' does not contain component 'clipy'
+/// for target 'x86_64-unknown-linux-gnu'
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustupUnknownComponent;
+
+impl RustupUnknownComponent {
+    /// Extract the unknown component from the error.
+    fn get_component(output: &str) -> Option<String> {
+        let re = regex!(r"does not contain component '([^']+)'");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Parse `rustup component list` output into the names
+    /// `rustup component add` takes, without the target suffix.
+    fn parse_components(output: &str) -> Vec<String> {
+        let target_suffix = regex!(
+            r"-(?:x86_64|aarch64|i686|i586|arm|armv7|thumbv7neon|riscv64gc|powerpc64le|s390x|wasm32)-.*$"
+        );
+        output
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|name| target_suffix.replace(name, "").to_string())
+            .collect()
+    }
+
+    /// List the components of the active toolchain.
+    fn get_components() -> Vec<String> {
+        match command_output("rustup", &["component", "list"]) {
+            Some(output) => Self::parse_components(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for RustupUnknownComponent {
+    fn name(&self) -> &str {
+        "rustup_unknown_component"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["rustup"]) {
+            return false;
+        }
+
+        command.script.contains("component") && Self::get_component(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let component = match Self::get_component(&command.output) {
+            Some(c) => c,
+            None => return vec![],
+        };
+
+        get_close_matches(&component, &Self::get_components(), 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &component, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to add a compilation target that isn't installed.
+///
+/// Matches errors like:
+/// - ``the `wasm32-unknown-unknown` target may not be installed``
+///
+/// # Example
+///
+/// ```text
+/// > cargo build --target wasm32-unknown-unknown
+/// error[E0463]: can't find crate for `core`
+///   = note: the `wasm32-unknown-unknown` target may not be installed
+///   = help: consider downloading the target with `rustup target add wasm32-unknown-unknown`
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustupTargetAdd;
+
+impl RustupTargetAdd {
+    /// Extract the missing target from the error.
+    fn get_target(output: &str) -> Option<String> {
+        let re = regex!(r"the `([^`]+)` target may not be installed");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for RustupTargetAdd {
+    fn name(&self) -> &str {
+        "rustup_target_add"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo", "rustc"]) {
+            return false;
+        }
+
+        Self::get_target(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_target(&command.output) {
+            Some(target) => vec![format!(
+                "rustup target add {} && {}",
+                target, command.script
+            )],
+            None => vec![],
+        }
+    }
+}
+
+// ============================================================================
+// Make Rules
+// ============================================================================
+
+/// Makefile names GNU make looks for, in order.
+const MAKEFILE_NAMES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+/// Rule to fix a misspelled make target, or run make where the Makefile is.
+///
+/// Matches errors like:
+/// - `make: *** No rule to make target 'instal'.  Stop.`
+/// - `make: *** No targets specified and no makefile found.  Stop.`
+///
+/// Fuzzy-matches the target against the ones defined in the Makefile in
+/// the working directory. Without a Makefile there, suggests `make -C`
+/// for each subdirectory that has one.
+///
+/// # Example
+///
+/// ```text
+/// > make instal
+/// make: *** No rule to make target 'instal'.  Stop.
+///
+/// > oops
+/// make install
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MakeNoTarget;
+
+impl MakeNoTarget {
+    /// Extract the target make has no rule for.
+    fn get_target(output: &str) -> Option<String> {
+        let re = regex!(r"No rule to make target [`']([^']+)'");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Find the Makefile in `dir`.
+    fn find_makefile(dir: &Path) -> Option<std::path::PathBuf> {
+        MAKEFILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Parse the explicit targets defined in a Makefile, skipping special
+    /// targets, pattern rules and variable assignments.
+    fn parse_targets(makefile: &str) -> Vec<String> {
+        let mut targets: Vec<String> = Vec::new();
+        for line in makefile.lines() {
+            if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+                continue;
+            }
+            let Some((names, rest)) = line.split_once(':') else {
+                continue;
+            };
+            if rest.starts_with('=') || names.contains('=') {
+                continue;
+            }
+            for name in names.split_whitespace() {
+                let is_special = name.starts_with('.') || name.contains(['%', '$']);
+                if !is_special && !targets.iter().any(|t| t == name) {
+                    targets.push(name.to_string());
+                }
+            }
+        }
+        targets
+    }
+
+    /// Get the subdirectories of `dir` that have a Makefile.
+    fn subdirs_with_makefile(dir: &Path) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return vec![];
+        };
+        let mut subdirs: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir() && Self::find_makefile(&entry.path()).is_some())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        subdirs.sort();
+        subdirs
+    }
+}
+
+impl Rule for MakeNoTarget {
+    fn name(&self) -> &str {
+        "make_no_target"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["make", "gmake"]) {
+            return false;
+        }
+
+        Self::get_target(&command.output).is_some() || command.output.contains("no makefile found")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        let cwd = ctx.cwd();
+        let Some(makefile) = Self::find_makefile(cwd) else {
+            let program = &command.script_parts()[0];
+            return Self::subdirs_with_makefile(cwd)
+                .iter()
+                .map(|dir| {
+                    replace_argument(&command.script, program, &format!("{} -C {}", program, dir))
+                })
+                .collect();
+        };
+
+        let Some(target) = Self::get_target(&command.output) else {
+            return vec![];
+        };
+        let targets = match std::fs::read_to_string(makefile) {
+            Ok(content) => Self::parse_targets(&content),
+            Err(_) => return vec![],
+        };
+
+        get_close_matches(&target, &targets, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &target, &fixed))
+            .collect()
+    }
+}
+
+// ============================================================================
+// CMake Rules
+// ============================================================================
+
+/// Rule to configure a CMake build directory before building.
+///
+/// Matches:
+/// - `cmake --build` failing with `Error: could not load cache`
+/// - `make` finding no Makefile in a directory with a `CMakeLists.txt`
+///
+/// # Example
+///
+/// ```text
+/// > cmake --build .
+/// Error: could not load cache
+///
+/// > oops
+/// cmake -S . -B build && cmake --build build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CmakeNoBuildDir;
+
+impl CmakeNoBuildDir {
+    /// Get the directory passed to `cmake --build`.
+    fn get_build_dir(parts: &[String]) -> Option<String> {
+        let pos = parts.iter().position(|p| p == "--build")?;
+        parts.get(pos + 1).cloned()
+    }
+}
+
+impl Rule for CmakeNoBuildDir {
+    fn name(&self) -> &str {
+        "cmake_no_build_dir"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        self.is_match_with_context(command, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, command: &Command, ctx: &RuleContext) -> bool {
+        if is_app(command, &["cmake"]) {
+            return command.script_parts().iter().any(|p| p == "--build")
+                && (command.output.contains("could not load cache")
+                    || command.output.contains("is not a directory"));
+        }
+
+        is_app(command, &["make"])
+            && command.output.contains("no makefile found")
+            && ctx.cwd().join("CMakeLists.txt").exists()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let parts = command.script_parts();
+        match Self::get_build_dir(parts) {
+            // Building in place means the build tree would be the source tree
+            Some(dir) if dir != "." => {
+                vec![format!("cmake -S . -B {} && {}", dir, command.script)]
+            }
+            Some(dir) => vec![format!(
+                "cmake -S . -B build && {}",
+                replace_argument(&command.script, &dir, "build")
+            )],
+            None => vec!["cmake -S . -B build && cmake --build build".to_string()],
+        }
+    }
+}
+
+/// Rule to delete a CMake cache created for another directory.
+///
+/// Matches errors like:
+/// - `The current CMakeCache.txt directory ... is different than the directory ... where CMakeCache.txt was created`
+/// - `The source ".../CMakeLists.txt" does not match the source ... used to generate cache`
+///
+/// This deletes the configured cache, so it's disabled by default.
+///
+/// # Example
+///
+/// ```text
+/// > cmake -S . -B build
+/// CMake Error: The current CMakeCache.txt directory /src/app/build/CMakeCache.txt is
+/// different than the directory /old/app/build where CMakeCache.txt was created.
+///
+/// > oops
+/// rm -rf build/CMakeCache.txt build/CMakeFiles && cmake -S . -B build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CmakeCacheMismatch;
+
+impl CmakeCacheMismatch {
+    /// Get the build directory the command used.
+    fn get_build_dir(parts: &[String]) -> String {
+        parts
+            .iter()
+            .enumerate()
+            .find_map(|(i, part)| {
+                if part == "-B" || part == "--build" {
+                    parts.get(i + 1).cloned()
+                } else {
+                    part.strip_prefix("-B")
+                        .filter(|dir| !dir.is_empty())
+                        .map(str::to_string)
+                }
+            })
+            .unwrap_or_else(|| ".".to_string())
+    }
+}
+
+impl Rule for CmakeCacheMismatch {
+    fn name(&self) -> &str {
+        "cmake_cache_mismatch"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cmake"]) {
+            return false;
+        }
+
+        let output = &command.output;
+        (output.contains("CMakeCache.txt directory") && output.contains("is different than"))
+            || (output.contains("does not match the source")
+                && output.contains("used to generate cache"))
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let dir = Self::get_build_dir(command.script_parts());
+        let cache = if dir == "." {
+            "CMakeCache.txt CMakeFiles".to_string()
+        } else {
+            format!(
+                "{} {}",
+                shell_quote(&format!("{}/CMakeCache.txt", dir)),
+                shell_quote(&format!("{}/CMakeFiles", dir))
+            )
+        };
+        vec![format!("rm -rf {} && {}", cache, command.script)]
+    }
+
+    fn priority(&self) -> i32 {
+        // Lower priority since this deletes the configured cache
+        1100
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Just Rules
+// ============================================================================
+
+/// Justfile names just looks for.
+const JUSTFILE_NAMES: &[&str] = &["justfile", "Justfile", ".justfile"];
+
+/// Rule to fix a misspelled just recipe.
+///
+/// Matches errors like:
+/// - ``error: Justfile does not contain recipe `buidl`.``
+///
+/// Uses just's own suggestion when it gives one, and fuzzy-matches the
+/// recipes from `just --summary` otherwise.
+///
+/// # Example
+///
+/// ```text
+/// > just buidl
+/// error: Justfile does not contain recipe `buidl`.
+/// Did you mean `build`?
+///
+/// > oops
+/// just build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JustUnknownRecipe;
+
+impl JustUnknownRecipe {
+    /// Extract the recipe just couldn't find.
+    fn get_recipe(output: &str) -> Option<String> {
+        let re = regex!(r"(?i)justfile does not contain recipe [`']([^`']+)[`']");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract just's suggestion.
+    fn get_suggestion(output: &str) -> Option<String> {
+        let re = regex!(r"Did you mean [`']([^`']+)[`']\?");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Get the recipes of the justfile from `just --summary`.
+    fn get_recipes() -> Vec<String> {
+        match command_output("just", &["--summary"]) {
+            Some(output) => String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for JustUnknownRecipe {
+    fn name(&self) -> &str {
+        "just_unknown_recipe"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        is_app(command, &["just"]) && Self::get_recipe(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let recipe = match Self::get_recipe(&command.output) {
+            Some(r) => r,
+            None => return vec![],
+        };
+
+        let fixes = match Self::get_suggestion(&command.output) {
+            Some(suggestion) => vec![suggestion],
+            None => get_close_matches(&recipe, &Self::get_recipes(), 3, 0.6),
+        };
+        fixes
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &recipe, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to list the recipes of a justfile in a subdirectory.
+///
+/// Matches errors like:
+/// - `error: No justfile found`
+///
+/// just looks for a justfile in the working directory and its parents, so
+/// this suggests `just --list` in each subdirectory that has one.
+///
+/// # Example
+///
+/// ```text
+/// > just build
+/// error: No justfile found
+///
+/// > oops
+/// cd backend && just --list
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JustNoJustfile;
+
+impl Rule for JustNoJustfile {
+    fn name(&self) -> &str {
+        "just_no_justfile"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        is_app(command, &["just"]) && command.output.contains("No justfile found")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, _command: &Command, ctx: &RuleContext) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(ctx.cwd()) else {
+            return vec![];
+        };
+        let mut dirs: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let path = entry.path();
+                path.is_dir() && JUSTFILE_NAMES.iter().any(|name| path.join(name).is_file())
+            })
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        dirs.sort();
+
+        dirs.iter()
+            .map(|dir| format!("cd {} && just --list", dir))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Xcode and Swift Rules
+// ============================================================================
+
+/// Rule to fix a misspelled `xcodebuild -scheme`.
+///
+/// Matches errors like:
+/// - `xcodebuild: error: The project named "MyApp" does not contain a scheme named "MyAp".`
+///
+/// Fuzzy-matches the scheme against the ones `xcodebuild -list` reports
+/// for the same project or workspace.
+///
+/// # Example
+///
+/// ```text
+/// > xcodebuild -scheme MyAp build
+/// xcodebuild: error: The project named "MyApp" does not contain a scheme named "MyAp".
+///
+/// > oops
+/// xcodebuild -scheme MyApp build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XcodebuildSchemeNotFound;
+
+impl XcodebuildSchemeNotFound {
+    /// Extract the scheme xcodebuild couldn't find.
+    fn get_scheme(output: &str) -> Option<String> {
+        let re = regex!(r#"does not contain a scheme named "([^"]+)""#);
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Parse the schemes from `xcodebuild -list` output.
+    fn parse_schemes(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| line.trim() != "Schemes:")
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// List the schemes of the project or workspace the command used.
+    fn get_schemes(parts: &[String]) -> Vec<String> {
+        let mut args = vec!["-list"];
+        for flag in ["-project", "-workspace"] {
+            if let Some(pos) = parts.iter().position(|p| p == flag) {
+                if let Some(value) = parts.get(pos + 1) {
+                    args.push(flag);
+                    args.push(value);
+                }
+            }
+        }
+        match command_output("xcodebuild", &args) {
+            Some(output) => Self::parse_schemes(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for XcodebuildSchemeNotFound {
+    fn name(&self) -> &str {
+        "xcodebuild_scheme_not_found"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        is_app(command, &["xcodebuild"]) && Self::get_scheme(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let scheme = match Self::get_scheme(&command.output) {
+            Some(s) => s,
+            None => return vec![],
+        };
+
+        get_close_matches(&scheme, &Self::get_schemes(command.script_parts()), 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &scheme, &fixed))
+            .collect()
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        // Xcode only runs on macOS
+        cfg!(target_os = "macos")
+    }
+}
+
+/// Rule to install the Xcode command line tools when xcrun can't find them.
+///
+/// Matches errors like:
+/// - `xcrun: error: invalid active developer path (/Library/Developer/CommandLineTools)`
+/// - `xcrun: error: unable to find utility "simctl", not a developer tool or in PATH`
+///
+/// Any command that goes through xcrun can fail this way, `git` included
+/// after a macOS upgrade. When Xcode itself is installed, also suggests
+/// selecting it, since some utilities only ship with Xcode.
+///
+/// # Example
+///
+/// ```text
+/// > git status
+/// xcrun: error: invalid active developer path (/Library/Developer/CommandLineTools),
+/// missing xcrun at: /Library/Developer/CommandLineTools/usr/bin/xcrun
+///
+/// > oops
+/// xcode-select --install && git status
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XcrunMissingTools;
+
+impl XcrunMissingTools {
+    /// Where Xcode is installed from the App Store.
+    const XCODE_DEVELOPER_DIR: &'static str = "/Applications/Xcode.app/Contents/Developer";
+}
+
+impl Rule for XcrunMissingTools {
+    fn name(&self) -> &str {
+        "xcrun_missing_tools"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        command
+            .output
+            .contains("xcrun: error: invalid active developer path")
+            || command
+                .output
+                .contains("xcrun: error: unable to find utility")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let mut fixes = vec![format!("xcode-select --install && {}", command.script)];
+        if command.output.contains("unable to find utility")
+            && Path::new(Self::XCODE_DEVELOPER_DIR).exists()
+        {
+            fixes.push(format!(
+                "sudo xcode-select --switch {} && {}",
+                Self::XCODE_DEVELOPER_DIR,
+                command.script
+            ));
+        }
+        fixes
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        // xcrun only exists on macOS
+        cfg!(target_os = "macos")
+    }
+}
+
+/// Rule to fix unknown Swift Package Manager subcommands.
+///
+/// Matches errors like:
+/// - `error: unable to invoke subcommand: /usr/bin/swift-biuld (No such file or directory)`
+/// - `error: Unknown subcommand or plugin name 'reslove'`
+///
+/// # Example
+///
+/// ```text
+/// > swift package reslove
+/// error: Unknown subcommand or plugin name 'reslove'
+///
+/// > oops
+/// swift package resolve
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwiftUnknownSubcommand;
+
+impl SwiftUnknownSubcommand {
+    /// Subcommands of `swift`.
+    const SWIFT_COMMANDS: &'static [&'static str] =
+        &["build", "package", "repl", "run", "sdk", "test"];
+
+    /// Subcommands of `swift package`.
+    const PACKAGE_COMMANDS: &'static [&'static str] = &[
+        "add-dependency",
+        "add-product",
+        "add-target",
+        "archive-source",
+        "clean",
+        "compute-checksum",
+        "config",
+        "describe",
+        "diagnose-api-breaking-changes",
+        "dump-package",
+        "dump-symbol-graph",
+        "edit",
+        "init",
+        "plugin",
+        "purge-cache",
+        "reset",
+        "resolve",
+        "show-dependencies",
+        "tools-version",
+        "unedit",
+        "update",
+    ];
+
+    /// Extract the unknown subcommand, and the known ones to match it with.
+    fn get_broken(output: &str) -> Option<(String, &'static [&'static str])> {
+        let re = regex!(r"unable to invoke subcommand: \S*swift-(\S+)");
+        if let Some(caps) = re.captures(output) {
+            return Some((caps[1].to_string(), Self::SWIFT_COMMANDS));
+        }
+
+        let re = regex!(r"Unknown subcommand or plugin name [‘'`]([^’'`]+)[’'`]");
+        let caps = re.captures(output)?;
+        Some((caps[1].to_string(), Self::PACKAGE_COMMANDS))
+    }
+}
+
+impl Rule for SwiftUnknownSubcommand {
+    fn name(&self) -> &str {
+        "swift_unknown_subcommand"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        is_app(command, &["swift"]) && Self::get_broken(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let (broken, known) = match Self::get_broken(&command.output) {
+            Some(b) => b,
+            None => return vec![],
+        };
+
+        let known: Vec<String> = known.iter().map(|s| s.to_string()).collect();
+        get_close_matches(&broken, &known, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &broken, &fixed))
+            .collect()
+    }
+}
+
+// ============================================================================
+// All Rules Function
+// ============================================================================
+
+/// Returns all development tool rules as boxed trait objects.
+///
+/// This function creates instances of all devtools rules
+/// for registration with the rule system.
+pub fn all_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // Go rules
+        Box::new(GoRun),
+        Box::new(GoUnknownCommand),
+        Box::new(GoMissingModule),
+        Box::new(GoModInit),
+        // Gradle rules
+        Box::new(GradleNoTask),
+        Box::new(GradleWrapper),
+        Box::new(GradleUnsupportedJava),
+        Box::new(GradleDaemon),
+        // Java rules
+        Box::new(Java),
+        Box::new(Javac),
+        // Maven rules
+        Box::new(MvnNoCommand),
+        Box::new(MvnUnknownLifecyclePhase),
+        // PHP Composer
+        Box::new(ComposerNotCommand),
+        // C++
+        Box::new(Cpp11),
+        // Python Fabric
+        Box::new(FabCommandNotFound),
+        // Node.js
+        Box::new(GruntTaskNotFound),
+        Box::new(GulpNotTask),
+        // Clojure
+        Box::new(LeinNotTask),
+        // Terraform
+        Box::new(TerraformInit),
+        Box::new(TerraformNoCommand),
+        Box::new(TerraformWorkspaceMissing),
+        Box::new(TerraformStateLock),
+        // Deno
+        Box::new(DenoPermission),
+        Box::new(DenoUnknownCommand),
+        Box::new(DenoRunExtension),
+        // Rustup
+        Box::new(RustupToolchainNotInstalled),
+        Box::new(RustupUnknownComponent),
+        Box::new(RustupTargetAdd),
+        // Make
+        Box::new(MakeNoTarget),
+        // CMake
+        Box::new(CmakeNoBuildDir),
+        Box::new(CmakeCacheMismatch),
+        // Just
+        Box::new(JustUnknownRecipe),
+        Box::new(JustNoJustfile),
+        // Xcode and Swift
+        Box::new(XcodebuildSchemeNotFound),
+        Box::new(XcrunMissingTools),
+        Box::new(SwiftUnknownSubcommand),
+    ]
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ------------------------------------------------------------------------
+    // Go Rules Tests
+    // ------------------------------------------------------------------------
+
+    mod go_run_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GoRun.name(), "go_run");
+        }
+
+        #[test]
+        fn test_matches_go_run_without_extension() {
+            let cmd = Command::new("go run foo", "go run: no go files listed");
+            assert!(GoRun.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_with_extension() {
+            let cmd = Command::new("go run foo.go", "");
+            assert!(!GoRun.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_go_command() {
+            let cmd = Command::new("go build foo", "");
+            assert!(!GoRun.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("go run foo", "go run: no go files listed");
+            let fixes = GoRun.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["go run foo.go"]);
+        }
+
+        #[test]
+        fn test_requires_no_output() {
+            assert!(!GoRun.requires_output());
+        }
+    }
+
+    mod go_unknown_command_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GoUnknownCommand.name(), "go_unknown_command");
+        }
+
+        #[test]
         fn test_matches_unknown_command() {
             let cmd = Command::new("go buidl", "go buidl: unknown command");
             assert!(GoUnknownCommand.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_command() {
-            let cmd = Command::new("go build", "");
-            assert!(!GoUnknownCommand.is_match(&cmd));
+        fn test_no_match_valid_command() {
+            let cmd = Command::new("go build", "");
+            assert!(!GoUnknownCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("npm buidl", "unknown command");
+            assert!(!GoUnknownCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("go buidl", "go buidl: unknown command");
+            let fixes = GoUnknownCommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["go build"]);
+        }
+    }
+
+    mod go_missing_module_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GoMissingModule.name(), "go_missing_module");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "go run .",
+                "main.go:6:2: no required module provides package github.com/google/uuid; to \
+                 add it:\n\tgo get github.com/google/uuid",
+            );
+            assert!(GoMissingModule.is_match(&cmd));
+            assert_eq!(
+                GoMissingModule.get_new_command(&cmd),
+                vec![
+                    "go get github.com/google/uuid && go run .",
+                    "go mod tidy && go run ."
+                ]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_missing_go_sum() {
+            let cmd = Command::new(
+                "go build ./...",
+                "main.go:4:2: missing go.sum entry for module providing package \
+                 golang.org/x/sync/errgroup (imported by app); to add:\n\t\
+                 go get app",
+            );
+            assert!(GoMissingModule.is_match(&cmd));
+            assert_eq!(
+                GoMissingModule.get_new_command(&cmd),
+                vec!["go mod tidy && go build ./..."]
+            );
+        }
+    }
+
+    mod go_mod_init_tests {
+        use super::*;
+
+        const OUTPUT: &str = "go: go.mod file not found in current directory or any parent \
+                              directory; see 'go help modules'";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GoModInit.name(), "go_mod_init");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("gofmt -l .", OUTPUT);
+            assert!(!GoModInit.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command_uses_directory() {
+            let dir = tempfile::tempdir().unwrap();
+            let project = dir.path().join("Hello World");
+            std::fs::create_dir(&project).unwrap();
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "go_mod_init").with_cwd(&project);
+
+            let cmd = Command::new("go build", OUTPUT);
+            assert!(GoModInit.is_match(&cmd));
+            assert_eq!(
+                GoModInit.get_new_command_with_context(&cmd, &ctx),
+                vec!["go mod init hello-world && go build"]
+            );
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Gradle Rules Tests
+    // ------------------------------------------------------------------------
+
+    mod gradle_no_task_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GradleNoTask.name(), "gradle_no_task");
+        }
+
+        #[test]
+        fn test_matches_task_not_found() {
+            let cmd = Command::new("gradle complie", "Task 'complie' not found in root project");
+            assert!(GradleNoTask.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_matches_task_ambiguous() {
+            let cmd = Command::new("gradle test", "Task 'test' is ambiguous in root project");
+            assert!(GradleNoTask.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_task() {
+            let cmd = Command::new("gradle build", "BUILD SUCCESSFUL");
+            assert!(!GradleNoTask.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("gradle complie", "Task 'complie' not found in root project");
+            let fixes = GradleNoTask.get_new_command(&cmd);
+            // Should suggest compile-related tasks
+            assert!(!fixes.is_empty());
+        }
+    }
+
+    mod gradle_wrapper_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GradleWrapper.name(), "gradle_wrapper");
+        }
+
+        #[test]
+        fn test_no_match_gradle_found() {
+            let cmd = Command::new("gradle build", "BUILD SUCCESSFUL");
+            assert!(!GradleWrapper.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("maven build", "command not found");
+            assert!(!GradleWrapper.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_match_wrapper_in_working_dir() {
+            let dir = tempfile::tempdir().unwrap();
+            let cmd = Command::new("gradle build", "gradle: command not found");
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "gradle_wrapper").with_cwd(dir.path());
+            assert!(!GradleWrapper.is_match_with_context(&cmd, &ctx));
+
+            std::fs::write(dir.path().join("gradlew"), "").unwrap();
+            assert!(GradleWrapper.is_match_with_context(&cmd, &ctx));
+        }
+    }
+
+    mod gradle_unsupported_java_tests {
+        use super::*;
+
+        const OUTPUT: &str = "BUG! exception in phase 'semantic analysis' in source unit \
+            '_BuildScript_' Unsupported class file major version 65";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GradleUnsupportedJava.name(), "gradle_unsupported_java");
+        }
+
+        #[test]
+        fn test_get_java_version() {
+            assert_eq!(GradleUnsupportedJava::get_java_version(OUTPUT), Some(21));
+        }
+
+        #[test]
+        fn test_java_fixes() {
+            let cmd = Command::new("./gradlew build", OUTPUT);
+            assert!(GradleUnsupportedJava.is_match(&cmd));
+            let jdks = vec![
+                (11, "/usr/lib/jvm/java-11-openjdk-amd64".to_string()),
+                (17, "/usr/lib/jvm/java-17-openjdk-amd64".to_string()),
+                (21, "/usr/lib/jvm/java-21-openjdk-amd64".to_string()),
+            ];
+            assert_eq!(
+                GradleUnsupportedJava::java_fixes(&cmd, 21, &jdks),
+                vec![
+                    "./gradlew wrapper --gradle-version 8.5 && ./gradlew build",
+                    "JAVA_HOME=/usr/lib/jvm/java-17-openjdk-amd64 ./gradlew build"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let cmd = Command::new(
+                "gradle build",
+                "Compilation failed; see the compiler error output",
+            );
+            assert!(!GradleUnsupportedJava.is_match(&cmd));
+        }
+    }
+
+    mod gradle_daemon_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GradleDaemon.name(), "gradle_daemon");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "./gradlew assemble",
+                "Gradle build daemon disappeared unexpectedly (it may have been killed or may \
+                 have crashed)",
+            );
+            assert!(GradleDaemon.is_match(&cmd));
+            assert_eq!(
+                GradleDaemon.get_new_command(&cmd),
+                vec![
+                    "./gradlew --stop && ./gradlew assemble",
+                    "./gradlew assemble --no-daemon"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_no_daemon_already() {
+            let cmd = Command::new(
+                "gradle test --no-daemon",
+                "Timeout waiting to connect to the Gradle daemon.",
+            );
+            assert_eq!(
+                GradleDaemon.get_new_command(&cmd),
+                vec!["gradle --stop && gradle test --no-daemon"]
+            );
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Java Rules Tests
+    // ------------------------------------------------------------------------
+
+    mod java_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(Java.name(), "java");
+        }
+
+        #[test]
+        fn test_matches_java_extension() {
+            let cmd = Command::new(
+                "java foo.java",
+                "Error: Could not find or load main class foo.java",
+            );
+            assert!(Java.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_without_extension() {
+            let cmd = Command::new("java foo", "");
+            assert!(!Java.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("javac foo.java", "");
+            assert!(!Java.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "java foo.java",
+                "Error: Could not find or load main class foo.java",
+            );
+            let fixes = Java.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["java foo"]);
+        }
+
+        #[test]
+        fn test_requires_no_output() {
+            assert!(!Java.requires_output());
+        }
+    }
+
+    mod javac_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(Javac.name(), "javac");
+        }
+
+        #[test]
+        fn test_matches_without_extension() {
+            let cmd = Command::new("javac foo", "error: Class names, 'foo', are only accepted");
+            assert!(Javac.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_with_extension() {
+            let cmd = Command::new("javac foo.java", "");
+            assert!(!Javac.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("java foo", "");
+            assert!(!Javac.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("javac foo", "error: Class names, 'foo', are only accepted");
+            let fixes = Javac.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["javac foo.java"]);
+        }
+
+        #[test]
+        fn test_requires_no_output() {
+            assert!(!Javac.requires_output());
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Maven Rules Tests
+    // ------------------------------------------------------------------------
+
+    mod mvn_no_command_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(MvnNoCommand.name(), "mvn_no_command");
+        }
+
+        #[test]
+        fn test_matches_no_goals() {
+            let cmd = Command::new(
+                "mvn",
+                "[ERROR] No goals have been specified for this build.",
+            );
+            assert!(MvnNoCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_with_goals() {
+            let cmd = Command::new("mvn clean install", "BUILD SUCCESS");
+            assert!(!MvnNoCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "mvn",
+                "[ERROR] No goals have been specified for this build.",
+            );
+            let fixes = MvnNoCommand.get_new_command(&cmd);
+            assert_eq!(fixes.len(), 2);
+            assert!(fixes.contains(&"mvn clean package".to_string()));
+            assert!(fixes.contains(&"mvn clean install".to_string()));
+        }
+    }
+
+    mod mvn_unknown_lifecycle_phase_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(
+                MvnUnknownLifecyclePhase.name(),
+                "mvn_unknown_lifecycle_phase"
+            );
+        }
+
+        #[test]
+        fn test_matches_unknown_phase() {
+            let cmd = Command::new(
+                "mvn comiple",
+                r#"[ERROR] Unknown lifecycle phase "comiple". You must specify a valid lifecycle phase or a goal in the format...
+Available lifecycle phases are: validate, initialize, compile -> [Help 1]"#,
+            );
+            assert!(MvnUnknownLifecyclePhase.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_phase() {
+            let cmd = Command::new("mvn compile", "BUILD SUCCESS");
+            assert!(!MvnUnknownLifecyclePhase.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "mvn comiple",
+                r#"[ERROR] Unknown lifecycle phase "comiple". You must specify a valid lifecycle phase or a goal...
+Available lifecycle phases are: validate, initialize, compile -> [Help 1]"#,
+            );
+            let fixes = MvnUnknownLifecyclePhase.get_new_command(&cmd);
+            assert!(!fixes.is_empty());
+            assert!(fixes.iter().any(|f| f.contains("compile")));
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Composer Tests
+    // ------------------------------------------------------------------------
+
+    mod composer_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(ComposerNotCommand.name(), "composer_not_command");
+        }
+
+        #[test]
+        fn test_matches_did_you_mean() {
+            let cmd = Command::new(
+                "composer instal",
+                r#"Command "instal" is not defined.
+Did you mean this?
+    install"#,
+            );
+            assert!(ComposerNotCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_matches_install_to_require() {
+            let cmd = Command::new("composer install package", "Did you mean composer require?");
+            assert!(ComposerNotCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_command() {
+            let cmd = Command::new("composer install", "Installing dependencies");
+            assert!(!ComposerNotCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command_did_you_mean() {
+            let cmd = Command::new(
+                "composer instal",
+                r#"Command "instal" is not defined.
+Did you mean this?
+    install"#,
+            );
+            let fixes = ComposerNotCommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["composer install"]);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // C++ Tests
+    // ------------------------------------------------------------------------
+
+    mod cpp11_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(Cpp11.name(), "cpp11");
+        }
+
+        #[test]
+        fn test_matches_cpp11_required() {
+            let cmd = Command::new(
+                "g++ main.cpp",
+                "This file requires compiler and library support for the ISO C++ 2011 standard",
+            );
+            assert!(Cpp11.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_matches_cpp11_extensions() {
+            let cmd = Command::new(
+                "clang++ main.cpp",
+                "warning: range-based for loop is a C++11 extension [-Wc++11-extensions]",
+            );
+            assert!(Cpp11.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_compilation() {
+            let cmd = Command::new("g++ main.cpp", "");
+            assert!(!Cpp11.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "g++ main.cpp",
+                "This file requires compiler and library support for the ISO C++ 2011 standard",
+            );
+            let fixes = Cpp11.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["g++ main.cpp -std=c++11"]);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Fabric Tests
+    // ------------------------------------------------------------------------
+
+    mod fab_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(FabCommandNotFound.name(), "fab_command_not_found");
+        }
+
+        #[test]
+        fn test_matches_command_not_found() {
+            let cmd = Command::new(
+                "fab deply",
+                r#"Warning: Command(s) not found:
+    deply
+Available commands:
+    deploy"#,
+            );
+            assert!(FabCommandNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_command() {
+            let cmd = Command::new("fab deploy", "Running deploy...");
+            assert!(!FabCommandNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "fab deply",
+                r#"Warning: Command(s) not found:
+    deply
+Available commands:
+    deploy"#,
+            );
+            let fixes = FabCommandNotFound.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["fab deploy"]);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Grunt Tests
+    // ------------------------------------------------------------------------
+
+    mod grunt_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GruntTaskNotFound.name(), "grunt_task_not_found");
+        }
+
+        #[test]
+        fn test_matches_task_not_found() {
+            let cmd = Command::new("grunt biuld", r#"Warning: Task "biuld" not found."#);
+            assert!(GruntTaskNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_task() {
+            let cmd = Command::new("grunt build", "Running build task...");
+            assert!(!GruntTaskNotFound.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_other_tool() {
-            let cmd = Command::new("npm buidl", "unknown command");
-            assert!(!GoUnknownCommand.is_match(&cmd));
+        fn test_get_new_command() {
+            let cmd = Command::new("grunt biuld", r#"Warning: Task "biuld" not found."#);
+            let fixes = GruntTaskNotFound.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["grunt build"]);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Gulp Tests
+    // ------------------------------------------------------------------------
+
+    mod gulp_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(GulpNotTask.name(), "gulp_not_task");
+        }
+
+        #[test]
+        fn test_matches_task_not_found() {
+            let cmd = Command::new("gulp biuld", "Task 'biuld' is not in your gulpfile");
+            assert!(GulpNotTask.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_valid_task() {
+            let cmd = Command::new("gulp build", "Starting 'build'...");
+            assert!(!GulpNotTask.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
-            let cmd = Command::new("go buidl", "go buidl: unknown command");
-            let fixes = GoUnknownCommand.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["go build"]);
+            let cmd = Command::new("gulp biuld", "Task 'biuld' is not in your gulpfile");
+            let fixes = GulpNotTask.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["gulp build"]);
         }
     }
 
     // ------------------------------------------------------------------------
-    // Gradle Rules Tests
+    // Leiningen Tests
     // ------------------------------------------------------------------------
 
-    mod gradle_no_task_tests {
+    mod lein_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(GradleNoTask.name(), "gradle_no_task");
+            assert_eq!(LeinNotTask.name(), "lein_not_task");
         }
 
         #[test]
         fn test_matches_task_not_found() {
-            let cmd = Command::new("gradle complie", "Task 'complie' not found in root project");
-            assert!(GradleNoTask.is_match(&cmd));
+            let cmd = Command::new(
+                "lein repls",
+                r#"'repls' is not a task. See 'lein help'.
+Did you mean this?
+         repl"#,
+            );
+            assert!(LeinNotTask.is_match(&cmd));
         }
 
         #[test]
-        fn test_matches_task_ambiguous() {
-            let cmd = Command::new("gradle test", "Task 'test' is ambiguous in root project");
-            assert!(GradleNoTask.is_match(&cmd));
+        fn test_no_match_valid_task() {
+            let cmd = Command::new("lein repl", "nREPL server started");
+            assert!(!LeinNotTask.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_task() {
-            let cmd = Command::new("gradle build", "BUILD SUCCESSFUL");
-            assert!(!GradleNoTask.is_match(&cmd));
+        fn test_no_match_without_suggestion() {
+            let cmd = Command::new("lein xyz", "'xyz' is not a task. See 'lein help'.");
+            assert!(!LeinNotTask.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
-            let cmd = Command::new("gradle complie", "Task 'complie' not found in root project");
-            let fixes = GradleNoTask.get_new_command(&cmd);
-            // Should suggest compile-related tasks
-            assert!(!fixes.is_empty());
+            let cmd = Command::new(
+                "lein repls",
+                r#"'repls' is not a task. See 'lein help'.
+Did you mean this?
+         repl"#,
+            );
+            let fixes = LeinNotTask.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["lein repl"]);
         }
     }
 
-    mod gradle_wrapper_tests {
+    // ------------------------------------------------------------------------
+    // Terraform Tests
+    // ------------------------------------------------------------------------
+
+    mod terraform_init_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(GradleWrapper.name(), "gradle_wrapper");
+            assert_eq!(TerraformInit.name(), "terraform_init");
         }
 
         #[test]
-        fn test_no_match_gradle_found() {
-            let cmd = Command::new("gradle build", "BUILD SUCCESSFUL");
-            assert!(!GradleWrapper.is_match(&cmd));
+        fn test_matches_module_not_installed() {
+            let cmd = Command::new("terraform plan", "This module is not yet installed");
+            assert!(TerraformInit.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_other_tool() {
-            let cmd = Command::new("maven build", "command not found");
-            assert!(!GradleWrapper.is_match(&cmd));
+        fn test_matches_initialization_required() {
+            let cmd = Command::new("terraform apply", "Error: Initialization required");
+            assert!(TerraformInit.is_match(&cmd));
         }
-    }
 
-    // ------------------------------------------------------------------------
-    // Java Rules Tests
-    // ------------------------------------------------------------------------
+        #[test]
+        fn test_no_match_initialized() {
+            let cmd = Command::new("terraform plan", "Plan: 5 to add, 0 to change");
+            assert!(!TerraformInit.is_match(&cmd));
+        }
 
-    mod java_tests {
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("terraform plan", "This module is not yet installed");
+            let fixes = TerraformInit.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["terraform init && terraform plan"]);
+        }
+    }
+
+    mod terraform_no_command_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(Java.name(), "java");
+            assert_eq!(TerraformNoCommand.name(), "terraform_no_command");
         }
 
         #[test]
-        fn test_matches_java_extension() {
+        fn test_matches_unknown_command() {
             let cmd = Command::new(
-                "java foo.java",
-                "Error: Could not find or load main class foo.java",
+                "terraform plna",
+                r#"Terraform has no command named "plna".
+Did you mean "plan"?"#,
             );
-            assert!(Java.is_match(&cmd));
+            assert!(TerraformNoCommand.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_without_extension() {
-            let cmd = Command::new("java foo", "");
-            assert!(!Java.is_match(&cmd));
+        fn test_no_match_valid_command() {
+            let cmd = Command::new("terraform plan", "Plan: 5 to add");
+            assert!(!TerraformNoCommand.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_other_tool() {
-            let cmd = Command::new("javac foo.java", "");
-            assert!(!Java.is_match(&cmd));
+        fn test_no_match_without_suggestion() {
+            let cmd = Command::new("terraform xyz", r#"Terraform has no command named "xyz"."#);
+            assert!(!TerraformNoCommand.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
             let cmd = Command::new(
-                "java foo.java",
-                "Error: Could not find or load main class foo.java",
+                "terraform plna",
+                r#"Terraform has no command named "plna".
+Did you mean "plan"?"#,
             );
-            let fixes = Java.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["java foo"]);
-        }
-
-        #[test]
-        fn test_requires_no_output() {
-            assert!(!Java.requires_output());
+            let fixes = TerraformNoCommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["terraform plan"]);
         }
     }
 
-    mod javac_tests {
+    mod terraform_workspace_missing_tests {
         use super::*;
 
+        const OUTPUT: &str = "\nWorkspace \"stagng\" doesn't exist.\n\n\
+            You can create this workspace with the \"new\" subcommand\n\
+            or include the \"-or-create\" flag with the \"select\" subcommand.\n";
+
         #[test]
         fn test_name() {
-            assert_eq!(Javac.name(), "javac");
+            assert_eq!(
+                TerraformWorkspaceMissing.name(),
+                "terraform_workspace_missing"
+            );
         }
 
         #[test]
-        fn test_matches_without_extension() {
-            let cmd = Command::new("javac foo", "error: Class names, 'foo', are only accepted");
-            assert!(Javac.is_match(&cmd));
+        fn test_matches_missing_workspace() {
+            let cmd = Command::new("terraform workspace select stagng", OUTPUT);
+            assert!(TerraformWorkspaceMissing.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_with_extension() {
-            let cmd = Command::new("javac foo.java", "");
-            assert!(!Javac.is_match(&cmd));
+        fn test_no_match_other_error() {
+            let cmd = Command::new("terraform plan", "Error: Invalid reference");
+            assert!(!TerraformWorkspaceMissing.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_other_tool() {
-            let cmd = Command::new("java foo", "");
-            assert!(!Javac.is_match(&cmd));
+        fn test_get_new_command_creates_workspace() {
+            let cmd = Command::new("terraform workspace select stagng", OUTPUT);
+            let fixes = TerraformWorkspaceMissing.get_new_command(&cmd);
+            assert_eq!(fixes.last().unwrap(), "terraform workspace new stagng");
+        }
+
+        #[test]
+        fn test_parse_workspaces() {
+            let output = "  default\n* production\n  staging\n\n";
+            assert_eq!(
+                TerraformWorkspaceMissing::parse_workspaces(output),
+                vec!["default", "production", "staging"]
+            );
+        }
+    }
+
+    mod terraform_state_lock_tests {
+        use super::*;
+
+        const OUTPUT: &str = "Error: Error acquiring the state lock\n\n\
+            Error message: ConditionalCheckFailedException: The conditional request failed\n\
+            Lock Info:\n  ID:        9db590f1-b6fe-c5f2-2678-8804f089deba\n  \
+            Path:      my-bucket/terraform.tfstate\n  Operation: OperationTypeApply\n";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(TerraformStateLock.name(), "terraform_state_lock");
+        }
+
+        #[test]
+        fn test_matches_state_lock() {
+            let cmd = Command::new("terraform apply", OUTPUT);
+            assert!(TerraformStateLock.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_without_lock_id() {
+            let cmd = Command::new("terraform apply", "Error: Error acquiring the state lock");
+            assert!(!TerraformStateLock.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
-            let cmd = Command::new("javac foo", "error: Class names, 'foo', are only accepted");
-            let fixes = Javac.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["javac foo.java"]);
+            let cmd = Command::new("terraform apply", OUTPUT);
+            assert_eq!(
+                TerraformStateLock.get_new_command(&cmd),
+                vec!["terraform force-unlock 9db590f1-b6fe-c5f2-2678-8804f089deba"]
+            );
         }
 
         #[test]
-        fn test_requires_no_output() {
-            assert!(!Javac.requires_output());
+        fn test_priority() {
+            assert_eq!(TerraformStateLock.priority(), 1100);
+            assert!(!TerraformStateLock.enabled_by_default());
         }
     }
 
     // ------------------------------------------------------------------------
-    // Maven Rules Tests
+    // Deno Rules Tests
     // ------------------------------------------------------------------------
 
-    mod mvn_no_command_tests {
+    mod deno_permission_tests {
         use super::*;
 
+        const NET_DENIED: &str = "error: Uncaught (in promise) NotCapable: Requires net \
+            access to \"example.com:443\", run again with the --allow-net flag";
+
         #[test]
         fn test_name() {
-            assert_eq!(MvnNoCommand.name(), "mvn_no_command");
+            assert_eq!(DenoPermission.name(), "deno_permission");
         }
 
         #[test]
-        fn test_matches_no_goals() {
-            let cmd = Command::new(
-                "mvn",
-                "[ERROR] No goals have been specified for this build.",
-            );
-            assert!(MvnNoCommand.is_match(&cmd));
+        fn test_matches_permission_denied() {
+            let cmd = Command::new("deno run main.ts", NET_DENIED);
+            assert!(DenoPermission.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_with_goals() {
-            let cmd = Command::new("mvn clean install", "BUILD SUCCESS");
-            assert!(!MvnNoCommand.is_match(&cmd));
+        fn test_no_match_flag_present() {
+            let cmd = Command::new("deno run --allow-net main.ts", NET_DENIED);
+            assert!(!DenoPermission.is_match(&cmd));
         }
 
         #[test]
         fn test_get_new_command() {
+            let cmd = Command::new("deno run main.ts --port 8000", NET_DENIED);
+            let fixes = DenoPermission.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["deno run --allow-net main.ts --port 8000"]);
+        }
+
+        #[test]
+        fn test_get_new_command_without_subcommand() {
             let cmd = Command::new(
-                "mvn",
-                "[ERROR] No goals have been specified for this build.",
+                "deno main.ts",
+                "PermissionDenied: Requires read access to \"./data.json\", \
+                 run again with the --allow-read flag",
             );
-            let fixes = MvnNoCommand.get_new_command(&cmd);
-            assert_eq!(fixes.len(), 2);
-            assert!(fixes.contains(&"mvn clean package".to_string()));
-            assert!(fixes.contains(&"mvn clean install".to_string()));
+            let fixes = DenoPermission.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["deno --allow-read main.ts"]);
         }
     }
 
-    mod mvn_unknown_lifecycle_phase_tests {
+    mod deno_unknown_command_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(
-                MvnUnknownLifecyclePhase.name(),
-                "mvn_unknown_lifecycle_phase"
+            assert_eq!(DenoUnknownCommand.name(), "deno_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_uses_tip() {
+            let cmd = Command::new(
+                "deno tset --watch",
+                "error: unrecognized subcommand 'tset'\n\n  \
+                 tip: a similar subcommand exists: 'test'",
             );
+            assert!(DenoUnknownCommand.is_match(&cmd));
+            let fixes = DenoUnknownCommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["deno test --watch"]);
         }
 
         #[test]
-        fn test_matches_unknown_phase() {
+        fn test_get_new_command_fuzzy() {
+            let cmd = Command::new("deno lnit", "error: unrecognized subcommand 'lnit'");
+            let fixes = DenoUnknownCommand.get_new_command(&cmd);
+            assert!(fixes.contains(&"deno lint".to_string()));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("cargo tset", "error: unrecognized subcommand 'tset'");
+            assert!(!DenoUnknownCommand.is_match(&cmd));
+        }
+    }
+
+    mod deno_run_extension_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(DenoRunExtension.name(), "deno_run_extension");
+        }
+
+        #[test]
+        fn test_get_new_command_default_extension() {
             let cmd = Command::new(
-                "mvn comiple",
-                r#"[ERROR] Unknown lifecycle phase "comiple". You must specify a valid lifecycle phase or a goal in the format...
-Available lifecycle phases are: validate, initialize, compile -> [Help 1]"#,
+                "deno run --allow-net oops_no_such_server",
+                "error: Module not found \"file:///tmp/oops_no_such_server\".",
             );
-            assert!(MvnUnknownLifecyclePhase.is_match(&cmd));
+            assert!(DenoRunExtension.is_match(&cmd));
+            let fixes = DenoRunExtension.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["deno run --allow-net oops_no_such_server.ts"]);
         }
 
         #[test]
-        fn test_no_match_valid_phase() {
-            let cmd = Command::new("mvn compile", "BUILD SUCCESS");
-            assert!(!MvnUnknownLifecyclePhase.is_match(&cmd));
+        fn test_get_new_command_existing_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let script = dir.path().join("server");
+            std::fs::write(script.with_extension("js"), "").unwrap();
+            let script = script.to_str().unwrap();
+
+            let cmd = Command::new(
+                format!("deno run {}", script),
+                format!("error: Module not found \"file://{}\".", script),
+            );
+            let fixes = DenoRunExtension.get_new_command(&cmd);
+            assert_eq!(fixes, vec![format!("deno run {}.js", script)]);
         }
 
         #[test]
-        fn test_get_new_command() {
+        fn test_no_match_with_extension() {
             let cmd = Command::new(
-                "mvn comiple",
-                r#"[ERROR] Unknown lifecycle phase "comiple". You must specify a valid lifecycle phase or a goal...
-Available lifecycle phases are: validate, initialize, compile -> [Help 1]"#,
+                "deno run main.ts",
+                "error: Module not found \"file:///tmp/main.ts\".",
             );
-            let fixes = MvnUnknownLifecyclePhase.get_new_command(&cmd);
-            assert!(!fixes.is_empty());
-            assert!(fixes.iter().any(|f| f.contains("compile")));
+            assert!(!DenoRunExtension.is_match(&cmd));
         }
     }
 
     // ------------------------------------------------------------------------
-    // Composer Tests
+    // Rustup Rules Tests
     // ------------------------------------------------------------------------
 
-    mod composer_tests {
+    mod rustup_toolchain_not_installed_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(ComposerNotCommand.name(), "composer_not_command");
+            assert_eq!(
+                RustupToolchainNotInstalled.name(),
+                "rustup_toolchain_not_installed"
+            );
         }
 
         #[test]
-        fn test_matches_did_you_mean() {
+        fn test_get_new_command() {
             let cmd = Command::new(
-                "composer instal",
-                r#"Command "instal" is not defined.
-Did you mean this?
-    install"#,
+                "cargo +nightly build",
+                "error: toolchain 'nightly-x86_64-unknown-linux-gnu' is not installed",
+            );
+            assert!(RustupToolchainNotInstalled.is_match(&cmd));
+            let fixes = RustupToolchainNotInstalled.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["rustup toolchain install nightly-x86_64-unknown-linux-gnu && cargo +nightly build"]
             );
-            assert!(ComposerNotCommand.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_matches_install_to_require() {
-            let cmd = Command::new("composer install package", "Did you mean composer require?");
-            assert!(ComposerNotCommand.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_valid_command() {
-            let cmd = Command::new("composer install", "Installing dependencies");
-            assert!(!ComposerNotCommand.is_match(&cmd));
         }
 
         #[test]
-        fn test_get_new_command_did_you_mean() {
-            let cmd = Command::new(
-                "composer instal",
-                r#"Command "instal" is not defined.
-Did you mean this?
-    install"#,
-            );
-            let fixes = ComposerNotCommand.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["composer install"]);
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("make", "error: toolchain 'nightly' is not installed");
+            assert!(!RustupToolchainNotInstalled.is_match(&cmd));
         }
     }
 
-    // ------------------------------------------------------------------------
-    // C++ Tests
-    // ------------------------------------------------------------------------
-
-    mod cpp11_tests {
+    mod rustup_unknown_component_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(Cpp11.name(), "cpp11");
+            assert_eq!(RustupUnknownComponent.name(), "rustup_unknown_component");
         }
 
         #[test]
-        fn test_matches_cpp11_required() {
+        fn test_matches_unknown_component() {
             let cmd = Command::new(
-                "g++ main.cpp",
-                "This file requires compiler and library support for the ISO C++ 2011 standard",
+                "rustup component add clipy",
+                "error: toolchain 'stable-x86_64-unknown-linux-gnu' does not contain \
+                 component 'clipy' for target 'x86_64-unknown-linux-gnu'",
             );
-            assert!(Cpp11.is_match(&cmd));
+            assert!(RustupUnknownComponent.is_match(&cmd));
         }
 
         #[test]
-        fn test_matches_cpp11_extensions() {
+        fn test_no_match_installed() {
             let cmd = Command::new(
-                "clang++ main.cpp",
-                "warning: range-based for loop is a C++11 extension [-Wc++11-extensions]",
+                "rustup component add clippy",
+                "info: component 'clippy' for target 'x86_64-unknown-linux-gnu' is up to date",
             );
-            assert!(Cpp11.is_match(&cmd));
+            assert!(!RustupUnknownComponent.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_compilation() {
-            let cmd = Command::new("g++ main.cpp", "");
-            assert!(!Cpp11.is_match(&cmd));
+        fn test_parse_components() {
+            let output = "cargo-x86_64-unknown-linux-gnu (installed)\n\
+                          clippy-x86_64-unknown-linux-gnu (installed)\n\
+                          llvm-tools-x86_64-unknown-linux-gnu\n\
+                          rust-src (installed)\n";
+            assert_eq!(
+                RustupUnknownComponent::parse_components(output),
+                vec!["cargo", "clippy", "llvm-tools", "rust-src"]
+            );
+        }
+    }
+
+    mod rustup_target_add_tests {
+        use super::*;
+
+        const OUTPUT: &str = "error[E0463]: can't find crate for `core`\n  \
+            = note: the `wasm32-unknown-unknown` target may not be installed\n  \
+            = help: consider downloading the target with `rustup target add wasm32-unknown-unknown`";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(RustupTargetAdd.name(), "rustup_target_add");
         }
 
         #[test]
         fn test_get_new_command() {
-            let cmd = Command::new(
-                "g++ main.cpp",
-                "This file requires compiler and library support for the ISO C++ 2011 standard",
+            let cmd = Command::new("cargo build --target wasm32-unknown-unknown", OUTPUT);
+            assert!(RustupTargetAdd.is_match(&cmd));
+            let fixes = RustupTargetAdd.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["rustup target add wasm32-unknown-unknown && cargo build --target wasm32-unknown-unknown"]
             );
-            let fixes = Cpp11.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["g++ main.cpp -std=c++11"]);
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("cargo build", "Finished `dev` profile");
+            assert!(!RustupTargetAdd.is_match(&cmd));
         }
     }
 
     // ------------------------------------------------------------------------
-    // Fabric Tests
+    // Make Rules Tests
     // ------------------------------------------------------------------------
 
-    mod fab_tests {
+    mod make_no_target_tests {
         use super::*;
 
+        const MAKEFILE: &str = "PREFIX ?= /usr/local\nCC := gcc\n\n.PHONY: all install clean\n\n\
+            all: build\n\nbuild: main.o\n\t$(CC) -o app main.o\n\n%.o: %.c\n\t$(CC) -c $<\n\n\
+            install: build\n\tcp app $(PREFIX)/bin\n\nclean:\n\trm -f app *.o\n";
+
         #[test]
         fn test_name() {
-            assert_eq!(FabCommandNotFound.name(), "fab_command_not_found");
+            assert_eq!(MakeNoTarget.name(), "make_no_target");
         }
 
         #[test]
-        fn test_matches_command_not_found() {
+        fn test_matches_no_rule() {
             let cmd = Command::new(
-                "fab deply",
-                r#"Warning: Command(s) not found:
-    deply
-Available commands:
-    deploy"#,
+                "make instal",
+                "make: *** No rule to make target 'instal'.  Stop.",
             );
-            assert!(FabCommandNotFound.is_match(&cmd));
+            assert!(MakeNoTarget.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_command() {
-            let cmd = Command::new("fab deploy", "Running deploy...");
-            assert!(!FabCommandNotFound.is_match(&cmd));
+        fn test_no_match_other_error() {
+            let cmd = Command::new("make", "make: *** [Makefile:4: build] Error 1");
+            assert!(!MakeNoTarget.is_match(&cmd));
         }
 
         #[test]
-        fn test_get_new_command() {
+        fn test_parse_targets() {
+            assert_eq!(
+                MakeNoTarget::parse_targets(MAKEFILE),
+                vec!["all", "build", "install", "clean"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_from_makefile() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("Makefile"), MAKEFILE).unwrap();
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "make_no_target").with_cwd(dir.path());
+
             let cmd = Command::new(
-                "fab deply",
-                r#"Warning: Command(s) not found:
-    deply
-Available commands:
-    deploy"#,
+                "make instal PREFIX=/opt",
+                "make: *** No rule to make target 'instal'.  Stop.",
             );
-            let fixes = FabCommandNotFound.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["fab deploy"]);
+            let fixes = MakeNoTarget.get_new_command_with_context(&cmd, &ctx);
+            assert_eq!(fixes[0], "make install PREFIX=/opt");
+        }
+
+        #[test]
+        fn test_get_new_command_makefile_in_subdir() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("build")).unwrap();
+            std::fs::write(dir.path().join("build").join("Makefile"), MAKEFILE).unwrap();
+            std::fs::create_dir(dir.path().join("docs")).unwrap();
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "make_no_target").with_cwd(dir.path());
+
+            let cmd = Command::new(
+                "make test",
+                "make: *** No rule to make target 'test'.  Stop.",
+            );
+            let fixes = MakeNoTarget.get_new_command_with_context(&cmd, &ctx);
+            assert_eq!(fixes, vec!["make -C build test"]);
         }
     }
 
     // ------------------------------------------------------------------------
-    // Grunt Tests
+    // CMake Rules Tests
     // ------------------------------------------------------------------------
 
-    mod grunt_tests {
+    mod cmake_no_build_dir_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(GruntTaskNotFound.name(), "grunt_task_not_found");
+            assert_eq!(CmakeNoBuildDir.name(), "cmake_no_build_dir");
         }
 
         #[test]
-        fn test_matches_task_not_found() {
-            let cmd = Command::new("grunt biuld", r#"Warning: Task "biuld" not found."#);
-            assert!(GruntTaskNotFound.is_match(&cmd));
+        fn test_get_new_command_in_place() {
+            let cmd = Command::new("cmake --build . --parallel", "Error: could not load cache");
+            assert!(CmakeNoBuildDir.is_match(&cmd));
+            assert_eq!(
+                CmakeNoBuildDir.get_new_command(&cmd),
+                vec!["cmake -S . -B build && cmake --build build --parallel"]
+            );
         }
 
         #[test]
-        fn test_no_match_valid_task() {
-            let cmd = Command::new("grunt build", "Running build task...");
-            assert!(!GruntTaskNotFound.is_match(&cmd));
+        fn test_get_new_command_build_dir() {
+            let cmd = Command::new("cmake --build out", "Error: could not load cache");
+            assert_eq!(
+                CmakeNoBuildDir.get_new_command(&cmd),
+                vec!["cmake -S . -B out && cmake --build out"]
+            );
         }
 
         #[test]
-        fn test_get_new_command() {
-            let cmd = Command::new("grunt biuld", r#"Warning: Task "biuld" not found."#);
-            let fixes = GruntTaskNotFound.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["grunt build"]);
+        fn test_match_make_in_cmake_project() {
+            let dir = tempfile::tempdir().unwrap();
+            let cmd = Command::new(
+                "make",
+                "make: *** No targets specified and no makefile found.  Stop.",
+            );
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "cmake_no_build_dir").with_cwd(dir.path());
+            assert!(!CmakeNoBuildDir.is_match_with_context(&cmd, &ctx));
+
+            std::fs::write(dir.path().join("CMakeLists.txt"), "").unwrap();
+            assert!(CmakeNoBuildDir.is_match_with_context(&cmd, &ctx));
+            assert_eq!(
+                CmakeNoBuildDir.get_new_command(&cmd),
+                vec!["cmake -S . -B build && cmake --build build"]
+            );
         }
     }
 
-    // ------------------------------------------------------------------------
-    // Gulp Tests
-    // ------------------------------------------------------------------------
-
-    mod gulp_tests {
+    mod cmake_cache_mismatch_tests {
         use super::*;
 
+        const OUTPUT: &str = "CMake Error: The current CMakeCache.txt directory \
+            /src/app/build/CMakeCache.txt is different than the directory /old/app/build where \
+            CMakeCache.txt was created. This may result in binaries being created in the wrong \
+            place.";
+
         #[test]
         fn test_name() {
-            assert_eq!(GulpNotTask.name(), "gulp_not_task");
+            assert_eq!(CmakeCacheMismatch.name(), "cmake_cache_mismatch");
         }
 
         #[test]
-        fn test_matches_task_not_found() {
-            let cmd = Command::new("gulp biuld", "Task 'biuld' is not in your gulpfile");
-            assert!(GulpNotTask.is_match(&cmd));
+        fn test_get_new_command() {
+            let cmd = Command::new("cmake -S . -B build", OUTPUT);
+            assert!(CmakeCacheMismatch.is_match(&cmd));
+            assert_eq!(
+                CmakeCacheMismatch.get_new_command(&cmd),
+                vec!["rm -rf build/CMakeCache.txt build/CMakeFiles && cmake -S . -B build"]
+            );
         }
 
         #[test]
-        fn test_no_match_valid_task() {
-            let cmd = Command::new("gulp build", "Starting 'build'...");
-            assert!(!GulpNotTask.is_match(&cmd));
+        fn test_get_new_command_in_place() {
+            let cmd = Command::new(
+                "cmake ..",
+                "CMake Error: The source \"/src/app/CMakeLists.txt\" does not match the source \
+                 \"/old/app/CMakeLists.txt\" used to generate cache.  Re-run cmake with a \
+                 different source directory.",
+            );
+            assert!(CmakeCacheMismatch.is_match(&cmd));
+            assert_eq!(
+                CmakeCacheMismatch.get_new_command(&cmd),
+                vec!["rm -rf CMakeCache.txt CMakeFiles && cmake .."]
+            );
         }
 
         #[test]
-        fn test_get_new_command() {
-            let cmd = Command::new("gulp biuld", "Task 'biuld' is not in your gulpfile");
-            let fixes = GulpNotTask.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["gulp build"]);
+        fn test_get_new_command_quotes_dir() {
+            let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+            std::env::remove_var("TF_SHELL");
+            let cmd = Command::new("cmake -S . -B 'my build'", OUTPUT);
+            assert_eq!(
+                CmakeCacheMismatch.get_new_command(&cmd),
+                vec![
+                    "rm -rf 'my build/CMakeCache.txt' 'my build/CMakeFiles' && \
+                     cmake -S . -B 'my build'"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_priority() {
+            assert_eq!(CmakeCacheMismatch.priority(), 1100);
+            assert!(!CmakeCacheMismatch.enabled_by_default());
         }
     }
 
     // ------------------------------------------------------------------------
-    // Leiningen Tests
+    // Just Rules Tests
     // ------------------------------------------------------------------------
 
-    mod lein_tests {
+    mod just_unknown_recipe_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(LeinNotTask.name(), "lein_not_task");
+            assert_eq!(JustUnknownRecipe.name(), "just_unknown_recipe");
         }
 
         #[test]
-        fn test_matches_task_not_found() {
+        fn test_get_new_command_uses_suggestion() {
             let cmd = Command::new(
-                "lein repls",
-                r#"'repls' is not a task. See 'lein help'.
-Did you mean this?
-         repl"#,
+                "just buidl --release",
+                "error: Justfile does not contain recipe `buidl`.\nDid you mean `build`?",
+            );
+            assert!(JustUnknownRecipe.is_match(&cmd));
+            assert_eq!(
+                JustUnknownRecipe.get_new_command(&cmd),
+                vec!["just build --release"]
             );
-            assert!(LeinNotTask.is_match(&cmd));
         }
 
         #[test]
-        fn test_no_match_valid_task() {
-            let cmd = Command::new("lein repl", "nREPL server started");
-            assert!(!LeinNotTask.is_match(&cmd));
+        fn test_no_match_other_error() {
+            let cmd = Command::new("just build", "error: Recipe `build` failed on line 3");
+            assert!(!JustUnknownRecipe.is_match(&cmd));
         }
+    }
+
+    mod just_no_justfile_tests {
+        use super::*;
 
         #[test]
-        fn test_no_match_without_suggestion() {
-            let cmd = Command::new("lein xyz", "'xyz' is not a task. See 'lein help'.");
-            assert!(!LeinNotTask.is_match(&cmd));
+        fn test_name() {
+            assert_eq!(JustNoJustfile.name(), "just_no_justfile");
         }
 
         #[test]
-        fn test_get_new_command() {
-            let cmd = Command::new(
-                "lein repls",
-                r#"'repls' is not a task. See 'lein help'.
-Did you mean this?
-         repl"#,
+        fn test_get_new_command_subdirs() {
+            let dir = tempfile::tempdir().unwrap();
+            for (sub, file) in [("web", "justfile"), ("api", "Justfile"), ("docs", "README")] {
+                std::fs::create_dir(dir.path().join(sub)).unwrap();
+                std::fs::write(dir.path().join(sub).join(file), "").unwrap();
+            }
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "just_no_justfile").with_cwd(dir.path());
+
+            let cmd = Command::new("just test", "error: No justfile found");
+            assert!(JustNoJustfile.is_match(&cmd));
+            assert_eq!(
+                JustNoJustfile.get_new_command_with_context(&cmd, &ctx),
+                vec!["cd api && just --list", "cd web && just --list"]
             );
-            let fixes = LeinNotTask.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["lein repl"]);
         }
     }
 
     // ------------------------------------------------------------------------
-    // Terraform Tests
+    // Xcode and Swift Rules Tests
     // ------------------------------------------------------------------------
 
-    mod terraform_init_tests {
+    mod xcodebuild_scheme_not_found_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(TerraformInit.name(), "terraform_init");
+            assert_eq!(
+                XcodebuildSchemeNotFound.name(),
+                "xcodebuild_scheme_not_found"
+            );
         }
 
         #[test]
-        fn test_matches_module_not_installed() {
-            let cmd = Command::new("terraform plan", "This module is not yet installed");
-            assert!(TerraformInit.is_match(&cmd));
+        fn test_matches_scheme_not_found() {
+            let cmd = Command::new(
+                "xcodebuild -scheme MyAp build",
+                "xcodebuild: error: The project named \"MyApp\" does not contain a scheme named \
+                 \"MyAp\". The \"-list\" option can be used to find the names of the schemes in \
+                 the project.",
+            );
+            assert!(XcodebuildSchemeNotFound.is_match(&cmd));
         }
 
         #[test]
-        fn test_matches_initialization_required() {
-            let cmd = Command::new("terraform apply", "Error: Initialization required");
-            assert!(TerraformInit.is_match(&cmd));
+        fn test_parse_schemes() {
+            let output = "Information about project \"MyApp\":\n    Targets:\n        MyApp\n        \
+                          MyAppTests\n\n    Build Configurations:\n        Debug\n        Release\n\n    \
+                          Schemes:\n        MyApp\n        MyApp-Staging\n\n";
+            assert_eq!(
+                XcodebuildSchemeNotFound::parse_schemes(output),
+                vec!["MyApp", "MyApp-Staging"]
+            );
         }
+    }
+
+    mod xcrun_missing_tools_tests {
+        use super::*;
 
         #[test]
-        fn test_no_match_initialized() {
-            let cmd = Command::new("terraform plan", "Plan: 5 to add, 0 to change");
-            assert!(!TerraformInit.is_match(&cmd));
+        fn test_name() {
+            assert_eq!(XcrunMissingTools.name(), "xcrun_missing_tools");
         }
 
         #[test]
         fn test_get_new_command() {
-            let cmd = Command::new("terraform plan", "This module is not yet installed");
-            let fixes = TerraformInit.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["terraform init && terraform plan"]);
+            let cmd = Command::new(
+                "git status",
+                "xcrun: error: invalid active developer path (/Library/Developer/CommandLineTools), \
+                 missing xcrun at: /Library/Developer/CommandLineTools/usr/bin/xcrun",
+            );
+            assert!(XcrunMissingTools.is_match(&cmd));
+            assert_eq!(
+                XcrunMissingTools.get_new_command(&cmd),
+                vec!["xcode-select --install && git status"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let cmd = Command::new("git status", "fatal: not a git repository");
+            assert!(!XcrunMissingTools.is_match(&cmd));
         }
     }
 
-    mod terraform_no_command_tests {
+    mod swift_unknown_subcommand_tests {
         use super::*;
 
         #[test]
         fn test_name() {
-            assert_eq!(TerraformNoCommand.name(), "terraform_no_command");
+            assert_eq!(SwiftUnknownSubcommand.name(), "swift_unknown_subcommand");
         }
 
         #[test]
-        fn test_matches_unknown_command() {
+        fn test_get_new_command_swift() {
             let cmd = Command::new(
-                "terraform plna",
-                r#"Terraform has no command named "plna".
-Did you mean "plan"?"#,
+                "swift biuld -c release",
+                "error: unable to invoke subcommand: /usr/bin/swift-biuld (No such file or \
+                 directory)",
+            );
+            assert!(SwiftUnknownSubcommand.is_match(&cmd));
+            assert_eq!(
+                SwiftUnknownSubcommand.get_new_command(&cmd)[0],
+                "swift build -c release"
             );
-            assert!(TerraformNoCommand.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_valid_command() {
-            let cmd = Command::new("terraform plan", "Plan: 5 to add");
-            assert!(!TerraformNoCommand.is_match(&cmd));
-        }
-
-        #[test]
-        fn test_no_match_without_suggestion() {
-            let cmd = Command::new("terraform xyz", r#"Terraform has no command named "xyz"."#);
-            assert!(!TerraformNoCommand.is_match(&cmd));
         }
 
         #[test]
-        fn test_get_new_command() {
+        fn test_get_new_command_package() {
             let cmd = Command::new(
-                "terraform plna",
-                r#"Terraform has no command named "plna".
-Did you mean "plan"?"#,
+                "swift package reslove",
+                "error: Unknown subcommand or plugin name ‘reslove’\nUsage: swift package \
+                 <options> <subcommand>",
+            );
+            assert!(SwiftUnknownSubcommand.is_match(&cmd));
+            assert_eq!(
+                SwiftUnknownSubcommand.get_new_command(&cmd)[0],
+                "swift package resolve"
             );
-            let fixes = TerraformNoCommand.get_new_command(&cmd);
-            assert_eq!(fixes, vec!["terraform plan"]);
         }
     }
 
@@ -1719,7 +4038,7 @@ Did you mean "plan"?"#,
     #[test]
     fn test_all_rules_count() {
         let rules = all_rules();
-        assert_eq!(rules.len(), 16, "Expected 16 devtools rules");
+        assert_eq!(rules.len(), 36, "Expected 36 devtools rules");
     }
 
     #[test]
@@ -5,12 +5,18 @@
 //! - [`DockerImageBeingUsedByContainer`] - Suggests stopping container before removing image
 //! - [`DockerLogin`] - Suggests login when push fails due to authentication
 //! - [`DockerNotCommand`] - Fixes unknown docker commands (typos)
+//! - [`DockerComposeV2`] - Switches between `docker-compose` and `docker compose`
+//! - [`DockerComposeNotCommand`] - Fixes unknown docker compose commands (typos)
+//! - [`DockerDaemonNotRunning`] - Starts the Docker daemon when it isn't running
+//! - [`DockerPermissionDenied`] - Uses sudo when the daemon socket isn't accessible
+//! - [`DockerGroupAdd`] - Adds the user to the `docker` group for socket access
 //! - [`VagrantUp`] - Fixes vagrant up issues
 //! - [`Tmux`] - Fixes ambiguous tmux commands
 
 use crate::core::{is_app, Command, Rule};
-use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, replace_argument, which};
 
 /// Common Docker commands for fuzzy matching.
 const DOCKER_COMMANDS: &[&str] = &[
@@ -214,15 +220,10 @@ impl Rule for DockerNotCommand {
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Try to extract the wrong command from the error message
         // Format: "docker: 'pus' is not a docker command."
-        let wrong_cmd_re = Regex::new(r"docker: '(\w+)' is not a docker command").ok();
-
-        let wrong_command = if let Some(re) = wrong_cmd_re {
-            re.captures(&cmd.output)
-                .and_then(|caps| caps.get(1))
-                .map(|m| m.as_str().to_string())
-        } else {
-            None
-        };
+        let wrong_command = regex!(r"docker: '(\w+)' is not a docker command")
+            .captures(&cmd.output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string());
 
         // If we found the wrong command in the error, use it for matching
         // Otherwise, try to get it from the script parts
@@ -253,6 +254,334 @@ impl Rule for DockerNotCommand {
     }
 }
 
+/// Docker Compose commands for fuzzy matching.
+const DOCKER_COMPOSE_COMMANDS: &[&str] = &[
+    "attach", "build", "config", "cp", "create", "down", "events", "exec", "images", "kill",
+    "logs", "ls", "pause", "port", "ps", "pull", "push", "restart", "rm", "run", "scale", "start",
+    "stats", "stop", "top", "unpause", "up", "version", "wait", "watch",
+];
+
+/// Rule that switches between Compose v1 (`docker-compose`) and v2
+/// (`docker compose`).
+///
+/// When one of them isn't installed but the other is, the command is
+/// rewritten to use the one that is.
+///
+/// # Example
+///
+/// ```text
+/// $ docker-compose up -d
+/// bash: docker-compose: command not found
+///
+/// $ oops
+/// docker compose up -d
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerComposeV2;
+
+impl DockerComposeV2 {
+    /// Check if the `docker compose` plugin is installed.
+    fn has_compose_plugin() -> bool {
+        command_output("docker", &["compose", "version"])
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Check if the standalone `docker-compose` is installed.
+    fn has_standalone_compose() -> bool {
+        which("docker-compose".to_string()).is_some()
+    }
+
+    /// Check if the command is `docker-compose` and the shell couldn't find it.
+    fn is_missing_standalone(cmd: &Command) -> bool {
+        is_app(cmd, &["docker-compose"])
+            && (cmd.output.contains("command not found")
+                || cmd.output.contains("Unknown command")
+                || cmd.output.contains("is not recognized"))
+    }
+
+    /// Check if the command is `docker compose` and docker has no such plugin.
+    fn is_missing_plugin(cmd: &Command) -> bool {
+        is_app(cmd, &["docker"])
+            && cmd.script_parts().get(1).map(String::as_str) == Some("compose")
+            && cmd.output.contains("'compose' is not a docker command")
+    }
+}
+
+impl Rule for DockerComposeV2 {
+    fn name(&self) -> &str {
+        "docker_compose_v2"
+    }
+
+    fn priority(&self) -> i32 {
+        // Ahead of docker_not_command, which would suggest `docker commit`
+        900
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        (Self::is_missing_standalone(cmd) && Self::has_compose_plugin())
+            || (Self::is_missing_plugin(cmd) && Self::has_standalone_compose())
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if Self::is_missing_standalone(cmd) {
+            vec![cmd.script.replacen("docker-compose", "docker compose", 1)]
+        } else if Self::is_missing_plugin(cmd) {
+            vec![cmd.script.replacen("docker compose", "docker-compose", 1)]
+        } else {
+            vec![]
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that fixes unknown docker compose commands (typos).
+///
+/// Uses Compose's own suggestions when it gives some, and fuzzy-matches
+/// the known commands otherwise. Works for both `docker compose` and
+/// `docker-compose`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::docker::DockerComposeNotCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = DockerComposeNotCommand;
+/// let cmd = Command::new("docker compose upp -d", "unknown docker command: \"compose upp\"");
+/// assert_eq!(rule.get_new_command(&cmd)[0], "docker compose up -d");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerComposeNotCommand;
+
+impl DockerComposeNotCommand {
+    /// Extract the unknown compose command from the error output.
+    fn extract_unknown(output: &str) -> Option<String> {
+        let re = regex!(
+            r#"unknown docker command: "compose (\S+)"|unknown command "(\S+)" for "docker compose"|No such command: (\S+)"#
+        );
+        let caps = re.captures(output)?;
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .or_else(|| caps.get(3))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the commands listed after "Did you mean this?".
+    fn extract_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean this?"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Check if the command runs Docker Compose, v1 or v2.
+    fn is_compose(cmd: &Command) -> bool {
+        is_app(cmd, &["docker-compose"])
+            || (is_app(cmd, &["docker"])
+                && cmd.script_parts().get(1).map(String::as_str) == Some("compose"))
+    }
+}
+
+impl Rule for DockerComposeNotCommand {
+    fn name(&self) -> &str {
+        "docker_compose_not_command"
+    }
+
+    fn priority(&self) -> i32 {
+        // Ahead of docker_not_command, which would fix `compose` instead
+        900
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        Self::is_compose(cmd) && Self::extract_unknown(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(unknown) = Self::extract_unknown(&cmd.output) else {
+            return vec![];
+        };
+
+        let mut suggestions = Self::extract_suggestions(&cmd.output);
+        if suggestions.is_empty() {
+            let commands: Vec<String> = DOCKER_COMPOSE_COMMANDS
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            suggestions = get_close_matches(&unknown, &commands, 3, 0.6);
+        }
+
+        suggestions
+            .iter()
+            .map(|fixed| replace_argument(&cmd.script, &unknown, fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that starts the Docker daemon when it isn't running.
+///
+/// The command to start it depends on the platform: systemd (or the
+/// `service` wrapper) on Linux, and Colima or Docker Desktop on macOS.
+///
+/// # Example
+///
+/// ```text
+/// $ docker ps
+/// Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?
+///
+/// $ oops
+/// sudo systemctl start docker && docker ps
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerDaemonNotRunning;
+
+impl DockerDaemonNotRunning {
+    /// Get the commands that start the daemon on this platform.
+    #[cfg(target_os = "linux")]
+    fn start_commands() -> Vec<&'static str> {
+        if which("systemctl".to_string()).is_some() {
+            vec!["sudo systemctl start docker"]
+        } else {
+            vec!["sudo service docker start"]
+        }
+    }
+
+    /// Get the commands that start the daemon on this platform.
+    #[cfg(target_os = "macos")]
+    fn start_commands() -> Vec<&'static str> {
+        if which("colima".to_string()).is_some() {
+            vec!["colima start", "open -a Docker"]
+        } else {
+            vec!["open -a Docker"]
+        }
+    }
+
+    /// Get the commands that start the daemon on this platform.
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    fn start_commands() -> Vec<&'static str> {
+        vec![]
+    }
+}
+
+impl Rule for DockerDaemonNotRunning {
+    fn name(&self) -> &str {
+        "docker_daemon_not_running"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["docker", "docker-compose"])
+            && cmd.output.contains("Cannot connect to the Docker daemon")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        Self::start_commands()
+            .into_iter()
+            .map(|start| format!("{} && {}", start, cmd.script))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Check if the output says the Docker daemon socket isn't accessible.
+fn is_socket_permission_denied(cmd: &Command) -> bool {
+    is_app(cmd, &["docker", "docker-compose"])
+        && cmd
+            .output
+            .contains("permission denied while trying to connect to the Docker daemon socket")
+}
+
+/// Rule that uses sudo when the Docker daemon socket isn't accessible.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::docker::DockerPermissionDenied;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = DockerPermissionDenied;
+/// let cmd = Command::new(
+///     "docker ps",
+///     "permission denied while trying to connect to the Docker daemon socket at unix:///var/run/docker.sock"
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["sudo docker ps"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerPermissionDenied;
+
+impl Rule for DockerPermissionDenied {
+    fn name(&self) -> &str {
+        "docker_permission_denied"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_socket_permission_denied(cmd)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![format!("sudo {}", cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that adds the user to the `docker` group for socket access.
+///
+/// Membership in the group is equivalent to root access, so this comes
+/// after [`DockerPermissionDenied`] and is never the correction run
+/// without choosing it.
+///
+/// # Example
+///
+/// ```text
+/// $ docker ps
+/// permission denied while trying to connect to the Docker daemon socket at unix:///var/run/docker.sock
+///
+/// $ oops
+/// sudo docker ps
+/// sudo usermod -aG docker $USER && newgrp docker
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerGroupAdd;
+
+impl Rule for DockerGroupAdd {
+    fn name(&self) -> &str {
+        "docker_group_add"
+    }
+
+    fn priority(&self) -> i32 {
+        // Behind docker_permission_denied, as this changes the system
+        1100
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_socket_permission_denied(cmd)
+    }
+
+    fn get_new_command(&self, _cmd: &Command) -> Vec<String> {
+        vec!["sudo usermod -aG docker $USER && newgrp docker".to_string()]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
 /// Rule that fixes vagrant up issues.
 ///
 /// When Vagrant suggests running `vagrant up`, this rule provides
@@ -362,25 +691,22 @@ impl Rule for Tmux {
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Parse the tmux error message
         // Format: "ambiguous command: <cmd>, could be: <suggestion1>, <suggestion2>, ..."
-        let re = Regex::new(r"ambiguous command: ([^,]+), could be: (.+)").ok();
-
-        if let Some(re) = re {
-            if let Some(caps) = re.captures(&cmd.output) {
-                let old_cmd = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
-                let suggestions_str = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-
-                // Parse suggestions (comma-separated)
-                let suggestions: Vec<&str> = suggestions_str
-                    .split(',')
-                    .map(|s| s.trim())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-
-                return suggestions
-                    .into_iter()
-                    .map(|suggestion| replace_argument(&cmd.script, old_cmd, suggestion))
-                    .collect();
-            }
+        let re = regex!(r"ambiguous command: ([^,]+), could be: (.+)");
+        if let Some(caps) = re.captures(&cmd.output) {
+            let old_cmd = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
+            let suggestions_str = caps.get(2).map(|m| m.as_str()).unwrap_or("");
+
+            // Parse suggestions (comma-separated)
+            let suggestions: Vec<&str> = suggestions_str
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect();
+
+            return suggestions
+                .into_iter()
+                .map(|suggestion| replace_argument(&cmd.script, old_cmd, suggestion))
+                .collect();
         }
 
         vec![]
@@ -400,6 +726,11 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(DockerImageBeingUsedByContainer),
         Box::new(DockerLogin),
         Box::new(DockerNotCommand),
+        Box::new(DockerComposeV2),
+        Box::new(DockerComposeNotCommand),
+        Box::new(DockerDaemonNotRunning),
+        Box::new(DockerPermissionDenied),
+        Box::new(DockerGroupAdd),
         Box::new(VagrantUp),
         Box::new(Tmux),
     ]
@@ -636,6 +967,185 @@ mod tests {
     }
 
     // VagrantUp tests
+    mod docker_compose_v2 {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = DockerComposeV2;
+            assert_eq!(rule.name(), "docker_compose_v2");
+        }
+
+        #[test]
+        fn test_get_new_command_to_v2() {
+            let rule = DockerComposeV2;
+            let cmd = Command::new(
+                "docker-compose up -d",
+                "bash: docker-compose: command not found",
+            );
+            assert_eq!(rule.get_new_command(&cmd), vec!["docker compose up -d"]);
+        }
+
+        #[test]
+        fn test_get_new_command_to_v1() {
+            let rule = DockerComposeV2;
+            let cmd = Command::new(
+                "docker compose logs -f web",
+                "docker: 'compose' is not a docker command.\nSee 'docker --help'",
+            );
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["docker-compose logs -f web"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let rule = DockerComposeV2;
+            let cmd = Command::new("docker-compose up", "no configuration file provided");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod docker_compose_not_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = DockerComposeNotCommand;
+            assert_eq!(rule.name(), "docker_compose_not_command");
+        }
+
+        #[test]
+        fn test_matches_v2() {
+            let rule = DockerComposeNotCommand;
+            let cmd = Command::new(
+                "docker compose dwon",
+                "unknown docker command: \"compose dwon\"",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd)[0], "docker compose down");
+        }
+
+        #[test]
+        fn test_uses_suggestions() {
+            let rule = DockerComposeNotCommand;
+            let cmd = Command::new(
+                "docker compose strat web",
+                "unknown command \"strat\" for \"docker compose\"\n\nDid you mean this?\n\tstart\n",
+            );
+            assert_eq!(rule.get_new_command(&cmd), vec!["docker compose start web"]);
+        }
+
+        #[test]
+        fn test_matches_v1() {
+            let rule = DockerComposeNotCommand;
+            let cmd = Command::new("docker-compose lgos", "No such command: lgos");
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd)[0], "docker-compose logs");
+        }
+
+        #[test]
+        fn test_no_match_plain_docker() {
+            let rule = DockerComposeNotCommand;
+            let cmd = Command::new("docker lgos", "No such command: lgos");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod docker_daemon_not_running {
+        use super::*;
+
+        const OUTPUT: &str = "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. \
+                              Is the docker daemon running?";
+
+        #[test]
+        fn test_name() {
+            let rule = DockerDaemonNotRunning;
+            assert_eq!(rule.name(), "docker_daemon_not_running");
+        }
+
+        #[test]
+        fn test_matches() {
+            let rule = DockerDaemonNotRunning;
+            let cmd = Command::new("docker ps", OUTPUT);
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = DockerDaemonNotRunning;
+            let cmd = Command::new("podman ps", OUTPUT);
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[cfg(target_os = "linux")]
+        #[test]
+        fn test_get_new_command_linux() {
+            let rule = DockerDaemonNotRunning;
+            let cmd = Command::new("docker ps", OUTPUT);
+            let fixes = rule.get_new_command(&cmd);
+            assert_eq!(fixes.len(), 1);
+            assert!(fixes[0].starts_with("sudo "));
+            assert!(fixes[0].ends_with(" && docker ps"));
+        }
+
+        #[cfg(target_os = "macos")]
+        #[test]
+        fn test_get_new_command_macos() {
+            let rule = DockerDaemonNotRunning;
+            let cmd = Command::new("docker ps", OUTPUT);
+            let fixes = rule.get_new_command(&cmd);
+            assert!(fixes.contains(&"open -a Docker && docker ps".to_string()));
+        }
+    }
+
+    mod docker_permission_denied {
+        use super::*;
+
+        const OUTPUT: &str = "permission denied while trying to connect to the Docker daemon \
+                              socket at unix:///var/run/docker.sock: Get \
+                              \"http://%2Fvar%2Frun%2Fdocker.sock/v1.24/containers/json\": \
+                              dial unix /var/run/docker.sock: connect: permission denied";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(DockerPermissionDenied.name(), "docker_permission_denied");
+            assert_eq!(DockerGroupAdd.name(), "docker_group_add");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("docker compose up", OUTPUT);
+            assert!(DockerPermissionDenied.is_match(&cmd));
+            assert_eq!(
+                DockerPermissionDenied.get_new_command(&cmd),
+                vec!["sudo docker compose up"]
+            );
+        }
+
+        #[test]
+        fn test_group_add_comes_second() {
+            let cmd = Command::new("docker ps", OUTPUT);
+            assert!(DockerGroupAdd.is_match(&cmd));
+            assert_eq!(
+                DockerGroupAdd.get_new_command(&cmd),
+                vec!["sudo usermod -aG docker $USER && newgrp docker"]
+            );
+            assert!(DockerGroupAdd.priority() > DockerPermissionDenied.priority());
+        }
+
+        #[test]
+        fn test_no_match_daemon_not_running() {
+            let cmd = Command::new(
+                "docker ps",
+                "Cannot connect to the Docker daemon at unix:///var/run/docker.sock.",
+            );
+            assert!(!DockerPermissionDenied.is_match(&cmd));
+            assert!(!DockerGroupAdd.is_match(&cmd));
+        }
+    }
+
     mod vagrant_up {
         use super::*;
 
@@ -818,7 +1328,7 @@ mod tests {
         #[test]
         fn test_all_rules_returns_five_rules() {
             let rules = all_rules();
-            assert_eq!(rules.len(), 5);
+            assert_eq!(rules.len(), 10);
         }
 
         #[test]
@@ -0,0 +1,385 @@
+//! Drop-in rules from the rules directory (`~/.config/thefuck/rules/`).
+//!
+//! Two kinds of files are loaded at startup:
+//!
+//! - **Declarative rules**: `<name>.toml` files with the same fields as a
+//!   `[corrections.<name>]` entry:
+//!
+//!   ```toml
+//!   # ~/.config/thefuck/rules/gti.toml
+//!   script = "^gti (.*)$"
+//!   replace = "git $1"
+//!   ```
+//!
+//! - **Scripted rules**: executable files. The script runs with the failed
+//!   command in `OOPS_COMMAND` and its output in `OOPS_OUTPUT`, and prints one
+//!   correction per line. A non-zero exit status or no output means the rule
+//!   doesn't match.
+//!
+//! The rule name is the file name without its extension. Files that fail to
+//! load are reported individually and skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+
+use super::corrections::UserCorrection;
+use crate::config::CorrectionConfig;
+use crate::core::{Command, Rule};
+use crate::output::{output_with_timeout, working_dir};
+
+/// Extension of declarative rule files.
+const DECLARATIVE_EXTENSION: &str = "toml";
+
+/// Environment variable holding the failed command for scripted rules.
+const COMMAND_ENV: &str = "OOPS_COMMAND";
+
+/// Environment variable holding the command output for scripted rules.
+const OUTPUT_ENV: &str = "OOPS_OUTPUT";
+
+/// A file in the rules directory that couldn't be loaded.
+#[derive(Debug)]
+pub struct LoadError {
+    /// The file that failed to load.
+    pub path: PathBuf,
+    /// Why it failed.
+    pub error: anyhow::Error,
+}
+
+/// Load all drop-in rules from `dir`.
+///
+/// Returns the rules that loaded, in file name order, and an error for each
+/// file that didn't. A missing directory yields no rules and no errors.
+/// Scripted rules are killed after `timeout`.
+pub fn load_rules_dir(dir: &Path, timeout: Duration) -> (Vec<Box<dyn Rule>>, Vec<LoadError>) {
+    let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+    let mut errors = Vec::new();
+
+    for path in rule_paths(dir) {
+        match load_rule_file(&path, timeout) {
+            Ok(Some(rule)) => {
+                debug!("Loaded rule '{}' from {}", rule.name(), path.display());
+                rules.push(rule);
+            }
+            Ok(None) => debug!("Ignoring {}", path.display()),
+            Err(error) => errors.push(LoadError { path, error }),
+        }
+    }
+
+    (rules, errors)
+}
+
+/// Names of the drop-in rules in `dir`, in file name order.
+///
+/// The files aren't loaded, so this is cheap, and files that would fail to
+/// load are included.
+pub fn rule_names(dir: &Path) -> Vec<String> {
+    rule_paths(dir)
+        .iter()
+        .filter_map(|path| rule_name(path))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The files in `dir`, sorted by name.
+fn rule_paths(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            debug!("Rules directory {} not loaded: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Get the rule name of a file, or `None` for files that aren't rules.
+fn rule_name(path: &Path) -> Option<&str> {
+    let name = path.file_stem().and_then(|stem| stem.to_str())?;
+    if name.starts_with('.') {
+        return None;
+    }
+    (is_declarative(path) || is_executable(path)).then_some(name)
+}
+
+fn is_declarative(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some(DECLARATIVE_EXTENSION)
+}
+
+/// Load drop-in rules from `dir`, logging a warning for each file that fails.
+pub fn all_rules(dir: &Path, timeout: Duration) -> Vec<Box<dyn Rule>> {
+    let (rules, errors) = load_rules_dir(dir, timeout);
+    for LoadError { path, error } in errors {
+        warn!("Failed to load rule {}: {:#}", path.display(), error);
+    }
+    rules
+}
+
+/// Load a single rule file, returning `None` for files that aren't rules.
+fn load_rule_file(path: &Path, timeout: Duration) -> Result<Option<Box<dyn Rule>>> {
+    let Some(name) = rule_name(path) else {
+        return Ok(None);
+    };
+
+    if is_declarative(path) {
+        let content = fs::read_to_string(path).context("Failed to read rule file")?;
+        let config: CorrectionConfig = toml::from_str(&content).context("Invalid rule file")?;
+        let rule = UserCorrection::new(name, &config).context("Invalid regex")?;
+        return Ok(Some(Box::new(rule)));
+    }
+
+    Ok(Some(Box::new(ScriptRule::new(name, path, timeout))))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref(),
+        Some("exe") | Some("bat") | Some("cmd")
+    )
+}
+
+/// Script and output of a command, with the corrections a script produced for it.
+type CachedRun = ((String, String), Vec<String>);
+
+/// A rule backed by an executable in the rules directory.
+#[derive(Debug)]
+pub struct ScriptRule {
+    name: String,
+    path: PathBuf,
+    timeout: Duration,
+    /// Corrections from the last run, keyed by the command, so `is_match`
+    /// and `get_new_command` run the script only once.
+    last_run: Mutex<Option<CachedRun>>,
+}
+
+impl ScriptRule {
+    /// Create a rule that runs the executable at `path`.
+    pub fn new(name: &str, path: &Path, timeout: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            timeout,
+            last_run: Mutex::new(None),
+        }
+    }
+
+    /// Run the script for `command`, reusing the last result for the same command.
+    fn corrections(&self, command: &Command) -> Vec<String> {
+        let mut last_run = self.last_run.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(((script, output), corrections)) = last_run.as_ref() {
+            if *script == command.script && *output == command.output {
+                return corrections.clone();
+            }
+        }
+
+        let corrections = match self.run(command) {
+            Ok(corrections) => corrections,
+            Err(e) => {
+                warn!("Rule script {} failed: {:#}", self.path.display(), e);
+                Vec::new()
+            }
+        };
+        *last_run = Some((
+            (command.script.clone(), command.output.clone()),
+            corrections.clone(),
+        ));
+        corrections
+    }
+
+    /// Run the script in the command's working directory, killing it and
+    /// anything it started once the timeout passes.
+    fn run(&self, command: &Command) -> Result<Vec<String>> {
+        let mut process = ProcessCommand::new(&self.path);
+        process
+            .env(COMMAND_ENV, &command.script)
+            .env(OUTPUT_ENV, &command.output)
+            .current_dir(working_dir());
+        let output = output_with_timeout(&mut process, self.timeout)
+            .with_context(|| format!("failed to start or timed out after {:?}", self.timeout))?;
+
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+impl Rule for ScriptRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn requires_output(&self) -> bool {
+        false
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        !self.corrections(command).is_empty()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.corrections(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_missing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let (rules, errors) = load_rules_dir(&dir.path().join("missing"), TIMEOUT);
+        assert!(rules.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_declarative_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("gti.toml"),
+            "script = \"^gti (.*)$\"\nreplace = \"git $1\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "notes").unwrap();
+
+        let (rules, errors) = load_rules_dir(dir.path(), TIMEOUT);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name(), "gti");
+
+        let cmd = Command::new("gti status", "");
+        assert!(rules[0].is_match(&cmd));
+        assert_eq!(rules[0].get_new_command(&cmd), vec!["git status"]);
+    }
+
+    #[test]
+    fn test_errors_are_reported_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("broken.toml"), "script = ").unwrap();
+        fs::write(
+            dir.path().join("bad_regex.toml"),
+            "script = \"(\"\nreplace = \"x\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("good.toml"),
+            "script = \"^a$\"\nreplace = \"b\"\n",
+        )
+        .unwrap();
+
+        let (rules, errors) = load_rules_dir(dir.path(), TIMEOUT);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name(), "good");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].path.ends_with("bad_regex.toml"));
+        assert!(errors[1].path.ends_with("broken.toml"));
+
+        // Names are listed without loading the files
+        fs::write(dir.path().join("notes.txt"), "not a rule").unwrap();
+        assert_eq!(rule_names(dir.path()), vec!["bad_regex", "broken", "good"]);
+    }
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scripted_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(
+            dir.path(),
+            "make_build.sh",
+            r#"case "$OOPS_OUTPUT" in *"No rule"*) echo "$OOPS_COMMAND -C build";; *) exit 1;; esac"#,
+        );
+
+        let (rules, errors) = load_rules_dir(dir.path(), TIMEOUT);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(rules[0].name(), "make_build");
+
+        let cmd = Command::new("make", "make: *** No rule to make target");
+        assert!(rules[0].is_match(&cmd));
+        assert_eq!(rules[0].get_new_command(&cmd), vec!["make -C build"]);
+        assert!(!rules[0].is_match(&Command::new("make", "ok")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scripted_rule_large_output() {
+        let dir = tempfile::tempdir().unwrap();
+        // More than fits in a pipe buffer
+        write_script(
+            dir.path(),
+            "chatty",
+            "i=0; while [ $i -lt 20000 ]; do echo \"fix $i\"; i=$((i + 1)); done",
+        );
+
+        let (rules, _) = load_rules_dir(dir.path(), TIMEOUT);
+        let fixes = rules[0].get_new_command(&Command::new("anything", ""));
+        assert_eq!(fixes.len(), 20000);
+        assert_eq!(fixes[19999], "fix 19999");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scripted_rule_runs_in_working_dir() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_PWD"]);
+        let dir = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        write_script(dir.path(), "where", "pwd");
+        std::env::set_var("TF_PWD", project.path());
+
+        let (rules, _) = load_rules_dir(dir.path(), TIMEOUT);
+        assert_eq!(
+            rules[0].get_new_command(&Command::new("anything", "")),
+            vec![project.path().display().to_string()]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scripted_rule_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(dir.path(), "slow", "sleep 5; echo fixed");
+
+        let (rules, _) = load_rules_dir(dir.path(), Duration::from_millis(100));
+        assert!(!rules[0].is_match(&Command::new("anything", "")));
+    }
+}
@@ -5,17 +5,23 @@
 //! - Python: [`PythonExecute`], [`PythonModuleError`]
 //! - Rails: [`RailsMigrationsPending`]
 //! - React Native: [`ReactNativeCommandUnrecognized`]
-//! - NixOS: [`NixosCmdNotFound`]
+//! - NixOS: [`NixosCmdNotFound`], [`NixExperimentalFeatures`], [`NixEnvDeprecated`],
+//!   [`NixAttributeNotFound`]
 //! - Omnienv: [`OmnienvNoSuchCommand`]
+//! - asdf/mise: [`VersionManagerNoVersion`], [`VersionManagerUnknownPlugin`],
+//!   [`VersionManagerReshim`]
 //! - Django South: [`DjangoSouthGhost`], [`DjangoSouthMerge`]
 //! - PHP: [`PhpS`]
 //! - Virtualenv: [`WorkonDoesntExists`]
 //! - Yarn: [`YarnAlias`], [`YarnCommandNotFound`], [`YarnCommandReplaced`], [`YarnHelp`]
 //! - npm: [`NpmRunScript`]
+//! - Flutter: [`FlutterUnknownCommand`], [`FlutterNoPubspec`], [`FlutterPubGet`]
 
-use crate::core::{is_app, Command, Rule};
-use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::regex_cache;
+use crate::utils::{expand_path, get_close_matches, replace_argument};
 use std::path::PathBuf;
 
 // =============================================================================
@@ -94,7 +100,7 @@ pub struct PythonModuleError;
 impl PythonModuleError {
     /// Extract the missing module name from the error output.
     fn extract_module_name(output: &str) -> Option<String> {
-        let re = Regex::new(r"ModuleNotFoundError: No module named '([^']+)'").ok()?;
+        let re = regex!(r"ModuleNotFoundError: No module named '([^']+)'");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -155,7 +161,7 @@ pub struct RailsMigrationsPending;
 impl RailsMigrationsPending {
     /// Extract the migration command from the error output.
     fn extract_migration_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"To resolve this issue, run:\s*\n?\s*(.+?)(?:\n|$)").ok()?;
+        let re = regex!(r"To resolve this issue, run:\s*\n?\s*(.+?)(?:\n|$)");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().trim().to_string())
@@ -237,7 +243,7 @@ const REACT_NATIVE_COMMANDS: &[&str] = &[
 impl ReactNativeCommandUnrecognized {
     /// Extract the unrecognized command from the error output.
     fn extract_bad_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"Unrecognized command '([^']*)'").ok()?;
+        let re = regex!(r"Unrecognized command '([^']*)'");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -305,7 +311,7 @@ pub struct NixosCmdNotFound;
 impl NixosCmdNotFound {
     /// Extract the nix-env install command from the output.
     fn extract_nix_install(output: &str) -> Option<String> {
-        let re = Regex::new(r"nix-env -iA ([^\s]+)").ok()?;
+        let re = regex!(r"nix-env -iA ([^\s]+)");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -348,6 +354,214 @@ impl Rule for NixosCmdNotFound {
     }
 }
 
+/// Rule that enables the experimental features the new `nix` CLI needs.
+///
+/// When `nix` reports that `nix-command` or `flakes` is disabled, this rule
+/// reruns the command with `--extra-experimental-features` for both.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::NixExperimentalFeatures;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = NixExperimentalFeatures;
+/// let cmd = Command::new("nix build .#hello", "error: experimental Nix feature 'flakes' is disabled");
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NixExperimentalFeatures;
+
+impl Rule for NixExperimentalFeatures {
+    fn name(&self) -> &str {
+        "nix_experimental_features"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["nix"]) || cmd.script.contains("experimental-features") {
+            return false;
+        }
+
+        let re = regex!(r"experimental (?:Nix )?feature '(?:nix-command|flakes)' is disabled");
+        re.is_match(&cmd.output)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        // Right after `nix`, so the flag isn't passed on by `nix run ... --`
+        vec![cmd.script.replacen(
+            "nix",
+            "nix --extra-experimental-features 'nix-command flakes'",
+            1,
+        )]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that translates `nix-env` commands to `nix profile`.
+///
+/// A profile created by `nix profile` can't be managed with `nix-env`
+/// anymore, so this rule suggests the `nix profile` equivalent of the
+/// install, uninstall, upgrade or query.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::NixEnvDeprecated;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = NixEnvDeprecated;
+/// let output = "error: profile '/home/user/.local/state/nix/profiles/profile' is incompatible \
+///               with 'nix-env'; please use 'nix profile' instead";
+/// let cmd = Command::new("nix-env -iA nixpkgs.htop", output);
+/// assert_eq!(rule.get_new_command(&cmd), vec!["nix profile install nixpkgs#htop"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NixEnvDeprecated;
+
+impl NixEnvDeprecated {
+    /// Convert an attribute path like `nixpkgs.htop` to a flake reference.
+    fn to_installable(attr: &str) -> String {
+        match attr.split_once('.') {
+            Some((flake, path)) if flake == "nixpkgs" || flake == "nixos" => {
+                format!("nixpkgs#{}", path)
+            }
+            _ => format!("nixpkgs#{}", attr),
+        }
+    }
+}
+
+impl Rule for NixEnvDeprecated {
+    fn name(&self) -> &str {
+        "nix_env_deprecated"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["nix-env"])
+            && (cmd.output.contains("incompatible with 'nix-env'")
+                || cmd.output.contains("please use 'nix profile'"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let parts = cmd.script_parts();
+        let action = match parts.get(1) {
+            Some(a) => a.as_str(),
+            None => return vec![],
+        };
+        let args: Vec<&str> = parts[2..]
+            .iter()
+            .map(String::as_str)
+            .filter(|a| !a.starts_with('-'))
+            .collect();
+
+        let new_command = match action {
+            "-iA" | "--install" | "-i" => {
+                let installables: Vec<String> =
+                    args.iter().map(|a| Self::to_installable(a)).collect();
+                format!("nix profile install {}", installables.join(" "))
+            }
+            "-e" | "--uninstall" => format!("nix profile remove {}", args.join(" ")),
+            "-u" | "--upgrade" if args.is_empty() => "nix profile upgrade --all".to_string(),
+            "-u" | "--upgrade" => format!("nix profile upgrade {}", args.join(" ")),
+            "-q" | "--query" => "nix profile list".to_string(),
+            "--rollback" => "nix profile rollback".to_string(),
+            _ => return vec![],
+        };
+
+        vec![new_command.trim_end().to_string()]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that fixes a misspelled attribute using nix's own suggestions.
+///
+/// When an attribute is missing, nix lists similar ones in a
+/// "Did you mean" hint; this rule suggests each of them.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::NixAttributeNotFound;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = NixAttributeNotFound;
+/// let output = "error: attribute 'htpo' missing\n       Did you mean one of htop or atop?";
+/// let cmd = Command::new("nix run nixpkgs#htpo", output);
+/// assert_eq!(rule.get_new_command(&cmd)[0], "nix run nixpkgs#htop");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NixAttributeNotFound;
+
+impl NixAttributeNotFound {
+    /// Extract the missing attribute from the error.
+    fn get_broken(output: &str) -> Option<String> {
+        let re = regex!(r"attribute '([^']+)' missing");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the attributes suggested by "Did you mean".
+    fn get_suggestions(output: &str) -> Vec<String> {
+        let re = regex!(r"Did you mean (?:one of )?([^?]+)\?");
+        let list = match re.captures(output).and_then(|caps| caps.get(1)) {
+            Some(m) => m.as_str(),
+            None => return vec![],
+        };
+
+        list.split(", ")
+            .flat_map(|item| item.split(" or "))
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for NixAttributeNotFound {
+    fn name(&self) -> &str {
+        "nix_attribute_not_found"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["nix", "nix-env", "nix-shell", "nix-build"])
+            && Self::get_broken(&cmd.output).is_some()
+            && !Self::get_suggestions(&cmd.output).is_empty()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let broken = match Self::get_broken(&cmd.output) {
+            Some(b) => b,
+            None => return vec![],
+        };
+
+        // The attribute ends an installable like `nixpkgs#htpo` or `nixpkgs.htpo`
+        let re = match regex_cache::get(&format!(r"([#.\s]){}(\s|$)", regex::escape(&broken))) {
+            Ok(re) => re,
+            Err(_) => return vec![],
+        };
+        if !re.is_match(&cmd.script) {
+            return vec![];
+        }
+
+        Self::get_suggestions(&cmd.output)
+            .iter()
+            .map(|fixed| {
+                re.replace(&cmd.script, format!("${{1}}{}${{2}}", fixed))
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
 // =============================================================================
 // Omnienv Rules (pyenv, rbenv, nodenv, goenv)
 // =============================================================================
@@ -412,7 +626,7 @@ impl OmnienvNoSuchCommand {
 
     /// Extract the bad command from the error output.
     fn extract_bad_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"env: no such command [`']([^'`]*)'").ok()?;
+        let re = regex!(r"env: no such command [`']([^'`]*)'");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -471,6 +685,226 @@ impl Rule for OmnienvNoSuchCommand {
     }
 }
 
+// =============================================================================
+// Version Manager Rules (asdf, mise)
+// =============================================================================
+
+/// Version managers handled by the rules below.
+const VERSION_MANAGER_APPS: &[&str] = &["asdf", "mise"];
+
+/// Check if asdf or mise is available.
+fn is_version_manager_available() -> bool {
+    VERSION_MANAGER_APPS
+        .iter()
+        .any(|app| crate::utils::which(app.to_string()).is_some())
+}
+
+/// Rule that installs or selects a version when a shim has none.
+///
+/// When a shimmed tool has no version configured, this rule suggests
+/// `mise use <tool>@latest` for mise, and installing the configured
+/// versions (or the latest one) for asdf.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::VersionManagerNoVersion;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = VersionManagerNoVersion;
+/// let cmd = Command::new("node app.js", "mise ERROR No version is set for shim: node");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["mise use node@latest && node app.js"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VersionManagerNoVersion;
+
+impl VersionManagerNoVersion {
+    /// Extract the tool without a version.
+    fn extract_tool(output: &str) -> Option<String> {
+        let re = regex!(r"No version is set for (?:command|shim):? (\S+)");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for VersionManagerNoVersion {
+    fn name(&self) -> &str {
+        "version_manager_no_version"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        is_version_manager_available()
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("No preset version installed")
+            || Self::extract_tool(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        // The versions are configured but not installed yet
+        if cmd.output.contains("No preset version installed") {
+            return vec![format!("asdf install && {}", cmd.script)];
+        }
+
+        let Some(tool) = Self::extract_tool(&cmd.output) else {
+            return vec![];
+        };
+
+        if cmd.output.contains("mise") {
+            vec![format!("mise use {}@latest && {}", tool, cmd.script)]
+        } else {
+            vec![format!(
+                "asdf install {0} latest && asdf set {0} latest && {1}",
+                tool, cmd.script
+            )]
+        }
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that fixes a misspelled plugin or tool name in asdf and mise.
+///
+/// Suggests the closest plugins from `asdf plugin list`, or the closest
+/// tools from `mise registry`.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::VersionManagerUnknownPlugin;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = VersionManagerUnknownPlugin;
+/// let cmd = Command::new("asdf install nodej 20.10.0", "Plugin named nodej not found");
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VersionManagerUnknownPlugin;
+
+impl VersionManagerUnknownPlugin {
+    /// Extract the unknown plugin or tool.
+    fn extract_plugin(output: &str) -> Option<String> {
+        let re = regex!(
+            r"Plugin named (\S+) not found|No such plugin: (\S+)|(\S+) not found in mise tool registry"
+        );
+        let caps = re.captures(output)?;
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .or_else(|| caps.get(3))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Parse the first column of `asdf plugin list` or `mise registry`.
+    fn parse_names(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// List the plugins or tools `app` knows about.
+    fn get_plugins(app: &str) -> Vec<String> {
+        let args: &[&str] = if app == "mise" {
+            &["registry"]
+        } else {
+            &["plugin", "list"]
+        };
+        match command_output(app, args) {
+            Some(output) => Self::parse_names(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for VersionManagerUnknownPlugin {
+    fn name(&self) -> &str {
+        "version_manager_unknown_plugin"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        is_version_manager_available()
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, VERSION_MANAGER_APPS) && Self::extract_plugin(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(plugin) = Self::extract_plugin(&cmd.output) else {
+            return vec![];
+        };
+        let app = if is_app(cmd, &["mise"]) {
+            "mise"
+        } else {
+            "asdf"
+        };
+
+        // mise takes `tool@version`, so the name isn't always a whole argument
+        let Ok(re) = regex_cache::get(&format!(r"\b{}\b", regex::escape(&plugin))) else {
+            return vec![];
+        };
+
+        get_close_matches(&plugin, &Self::get_plugins(app), 3, 0.6)
+            .into_iter()
+            .map(|fixed| re.replace(&cmd.script, fixed.as_str()).to_string())
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that regenerates shims after a global install.
+///
+/// Executables installed with e.g. `npm install -g` aren't on the PATH
+/// until asdf or mise recreates its shims.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::VersionManagerReshim;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = VersionManagerReshim;
+/// let cmd = Command::new("tsc", "asdf: unknown command: tsc. Perhaps you have to reshim?");
+/// assert_eq!(rule.get_new_command(&cmd), vec!["asdf reshim && tsc"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VersionManagerReshim;
+
+impl Rule for VersionManagerReshim {
+    fn name(&self) -> &str {
+        "version_manager_reshim"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        is_version_manager_available()
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("Perhaps you have to reshim") || cmd.output.contains("mise reshim")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let app = if cmd.output.contains("mise reshim") {
+            "mise"
+        } else {
+            "asdf"
+        };
+        vec![format!("{} reshim && {}", app, cmd.script)]
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
 // =============================================================================
 // Django South Rules
 // =============================================================================
@@ -637,20 +1071,18 @@ impl Rule for PhpS {
 ///
 /// let rule = WorkonDoesntExists;
 /// let cmd = Command::new("workon myenv", "");
-/// // Will match if ~/.virtualenvs/myenv doesn't exist
+/// // Will match if $WORKON_HOME/myenv doesn't exist
 /// ```
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WorkonDoesntExists;
 
 impl WorkonDoesntExists {
-    /// Get all available virtualenvs from ~/.virtualenvs.
+    /// Get all available virtualenvs from `$WORKON_HOME`, by default
+    /// ~/.virtualenvs.
     fn get_all_environments() -> Vec<String> {
-        let home = match dirs::home_dir() {
-            Some(h) => h,
-            None => return vec![],
-        };
-
-        let virtualenvs_dir = home.join(".virtualenvs");
+        let workon_home =
+            std::env::var("WORKON_HOME").unwrap_or_else(|_| "~/.virtualenvs".to_string());
+        let virtualenvs_dir = expand_path(&workon_home);
         if !virtualenvs_dir.is_dir() {
             return vec![];
         }
@@ -749,7 +1181,7 @@ pub struct YarnAlias;
 impl YarnAlias {
     /// Extract the suggested command from Yarn's "Did you mean" message.
     fn extract_suggestion(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Did you mean [`"](?:yarn )?([^`"]*)[`"]"#).ok()?;
+        let re = regex!(r#"Did you mean [`"](?:yarn )?([^`"]*)[`"]"#);
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -863,7 +1295,7 @@ const NPM_TO_YARN_COMMANDS: &[(&str, &str)] = &[
 impl YarnCommandNotFound {
     /// Extract the not found command from the error output.
     fn extract_bad_command(output: &str) -> Option<String> {
-        let re = Regex::new(r#"error Command "([^"]*)" not found\."#).ok()?;
+        let re = regex!(r#"error Command "([^"]*)" not found\."#);
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -932,7 +1364,7 @@ pub struct YarnCommandReplaced;
 impl YarnCommandReplaced {
     /// Extract the replacement command from the output.
     fn extract_replacement(output: &str) -> Option<String> {
-        let re = Regex::new(r#"Run "([^"]*)" instead"#).ok()?;
+        let re = regex!(r#"Run "([^"]*)" instead"#);
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -986,7 +1418,7 @@ pub struct YarnHelp;
 impl YarnHelp {
     /// Extract the documentation URL from the output.
     fn extract_url(output: &str) -> Option<String> {
-        let re = Regex::new(r"Visit ([^ ]*) for documentation about this command\.").ok()?;
+        let re = regex!(r"Visit ([^ ]*) for documentation about this command\.");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -1066,26 +1498,23 @@ impl NpmRunScript {
         crate::utils::which("npm".to_string()).is_some()
     }
 
-    /// Get scripts from package.json in the current directory.
+    /// Get scripts from package.json in `dir`.
     /// Returns a cached or computed list of script names.
-    fn get_scripts() -> Vec<String> {
+    fn get_scripts(dir: &std::path::Path) -> Vec<String> {
         // Try to read package.json
-        let package_json = std::path::Path::new("package.json");
+        let package_json = dir.join("package.json");
         if !package_json.exists() {
             return vec![];
         }
 
-        let content = match std::fs::read_to_string(package_json) {
+        let content = match std::fs::read_to_string(&package_json) {
             Ok(c) => c,
             Err(_) => return vec![],
         };
 
         // Parse JSON and extract scripts
         // Using a simple regex-based approach to avoid adding json dependency
-        let re = match Regex::new(r#""scripts"\s*:\s*\{([^}]*)\}"#) {
-            Ok(r) => r,
-            Err(_) => return vec![],
-        };
+        let re = regex!(r#""scripts"\s*:\s*\{([^}]*)\}"#);
 
         let scripts_block = match re.captures(&content) {
             Some(caps) => caps.get(1).map(|m| m.as_str()).unwrap_or(""),
@@ -1093,10 +1522,7 @@ impl NpmRunScript {
         };
 
         // Extract script names
-        let script_re = match Regex::new(r#""([^"]+)"\s*:"#) {
-            Ok(r) => r,
-            Err(_) => return vec![],
-        };
+        let script_re = regex!(r#""([^"]+)"\s*:"#);
 
         script_re
             .captures_iter(scripts_block)
@@ -1119,6 +1545,10 @@ impl Rule for NpmRunScript {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
         if !is_app(cmd, &["npm"]) {
             return false;
         }
@@ -1139,7 +1569,7 @@ impl Rule for NpmRunScript {
         }
 
         // Check if the command is actually a script name
-        let scripts = Self::get_scripts();
+        let scripts = Self::get_scripts(ctx.cwd());
         scripts.contains(&parts[1])
     }
 
@@ -1162,16 +1592,247 @@ impl Rule for NpmRunScript {
 }
 
 // =============================================================================
-// Module Exports
+// Flutter Rules
 // =============================================================================
 
-/// Returns all framework rules as boxed trait objects.
+/// Top-level flutter commands, for fuzzy matching.
+const FLUTTER_COMMANDS: &[&str] = &[
+    "analyze",
+    "assemble",
+    "attach",
+    "build",
+    "channel",
+    "clean",
+    "config",
+    "create",
+    "custom-devices",
+    "devices",
+    "doctor",
+    "downgrade",
+    "drive",
+    "emulators",
+    "gen-l10n",
+    "install",
+    "logs",
+    "precache",
+    "pub",
+    "run",
+    "screenshot",
+    "symbolize",
+    "test",
+    "upgrade",
+];
+
+/// Rule that fixes unknown flutter commands.
 ///
-/// This function creates instances of all framework and language rules
-/// for registration with the rule system.
-pub fn all_rules() -> Vec<Box<dyn Rule>> {
-    vec![
-        // Python rules
+/// Uses the commands flutter lists under "Did you mean one of these?",
+/// and fuzzy-matches the known commands when it lists none.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::FlutterUnknownCommand;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlutterUnknownCommand;
+/// let cmd = Command::new(
+///     "flutter biuld apk",
+///     "Could not find a command named \"biuld\".\n\nDid you mean one of these?\n  build\n",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["flutter build apk"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlutterUnknownCommand;
+
+impl FlutterUnknownCommand {
+    /// Extract the unknown command.
+    fn extract_unknown(output: &str) -> Option<String> {
+        let re = regex!(r#"Could not find a command named "([^"]+)""#);
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the commands listed after "Did you mean".
+    fn extract_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for FlutterUnknownCommand {
+    fn name(&self) -> &str {
+        "flutter_unknown_command"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["flutter"]) && Self::extract_unknown(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(unknown) = Self::extract_unknown(&cmd.output) else {
+            return vec![];
+        };
+
+        let mut suggestions = Self::extract_suggestions(&cmd.output);
+        if suggestions.is_empty() {
+            let commands: Vec<String> = FLUTTER_COMMANDS.iter().map(|s| s.to_string()).collect();
+            suggestions = get_close_matches(&unknown, &commands, 3, 0.6);
+        }
+
+        suggestions
+            .iter()
+            .map(|fixed| replace_argument(&cmd.script, &unknown, fixed))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that runs flutter from the project directory.
+///
+/// When flutter can't find a `pubspec.yaml`, this rule suggests changing
+/// into each subdirectory that has one.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::FlutterNoPubspec;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlutterNoPubspec;
+/// let cmd = Command::new(
+///     "flutter run",
+///     "Error: No pubspec.yaml file found.\nThis command should be run from the root of your \
+///      Flutter project.",
+/// );
+/// assert!(rule.is_match(&cmd));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlutterNoPubspec;
+
+impl Rule for FlutterNoPubspec {
+    fn name(&self) -> &str {
+        "flutter_no_pubspec"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["flutter", "dart"]) && cmd.output.contains("No pubspec.yaml file found")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(ctx.cwd()) else {
+            return vec![];
+        };
+        let mut projects: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().join("pubspec.yaml").is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        projects.sort();
+
+        projects
+            .iter()
+            .map(|project| format!("cd {} && {}", project, cmd.script))
+            .collect()
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+/// Rule that fetches packages before running or building a Flutter app.
+///
+/// When packages haven't been fetched, or the lock file changed since,
+/// this rule suggests `flutter pub get` first. When a package isn't a
+/// dependency at all, it also suggests adding it.
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::frameworks::FlutterPubGet;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = FlutterPubGet;
+/// let cmd = Command::new(
+///     "flutter run",
+///     "Error: No pubspec.lock file found, please run \"flutter pub get\" and try again.",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd), vec!["flutter pub get && flutter run"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlutterPubGet;
+
+impl FlutterPubGet {
+    /// Subcommands that need the packages fetched.
+    const COMMANDS: &'static [&'static str] = &["run", "build", "test", "analyze"];
+
+    /// Extract a package the code imports but flutter couldn't resolve.
+    fn extract_package(output: &str) -> Option<String> {
+        let re = regex!(r"Couldn't resolve the package '([^']+)'");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for FlutterPubGet {
+    fn name(&self) -> &str {
+        "flutter_pub_get"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        if !is_app(cmd, &["flutter"]) {
+            return false;
+        }
+
+        let is_command = cmd
+            .script_parts()
+            .get(1)
+            .is_some_and(|sub| Self::COMMANDS.contains(&sub.as_str()));
+
+        is_command
+            && (cmd.output.contains("flutter pub get")
+                || Self::extract_package(&cmd.output).is_some())
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let mut fixes = vec![format!("flutter pub get && {}", cmd.script)];
+        if let Some(package) = Self::extract_package(&cmd.output) {
+            fixes.push(format!("flutter pub add {} && {}", package, cmd.script));
+        }
+        fixes
+    }
+
+    fn requires_output(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Module Exports
+// =============================================================================
+
+/// Returns all framework rules as boxed trait objects.
+///
+/// This function creates instances of all framework and language rules
+/// for registration with the rule system.
+pub fn all_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // Python rules
         Box::new(PythonExecute),
         Box::new(PythonModuleError),
         // Rails rules
@@ -1180,8 +1841,15 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(ReactNativeCommandUnrecognized),
         // NixOS rules
         Box::new(NixosCmdNotFound),
+        Box::new(NixExperimentalFeatures),
+        Box::new(NixEnvDeprecated),
+        Box::new(NixAttributeNotFound),
         // Omnienv rules
         Box::new(OmnienvNoSuchCommand),
+        // asdf/mise rules
+        Box::new(VersionManagerNoVersion),
+        Box::new(VersionManagerUnknownPlugin),
+        Box::new(VersionManagerReshim),
         // Django South rules
         Box::new(DjangoSouthGhost),
         Box::new(DjangoSouthMerge),
@@ -1196,6 +1864,10 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(YarnHelp),
         // npm rules
         Box::new(NpmRunScript),
+        // Flutter rules
+        Box::new(FlutterUnknownCommand),
+        Box::new(FlutterNoPubspec),
+        Box::new(FlutterPubGet),
     ]
 }
 
@@ -1438,6 +2110,167 @@ mod tests {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // NixExperimentalFeatures tests
+    // -------------------------------------------------------------------------
+
+    mod nix_experimental_features {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = NixExperimentalFeatures;
+            assert_eq!(rule.name(), "nix_experimental_features");
+        }
+
+        #[test]
+        fn test_matches_nix_command() {
+            let rule = NixExperimentalFeatures;
+            let cmd = Command::new(
+                "nix search nixpkgs hello",
+                "error: experimental Nix feature 'nix-command' is disabled; \
+                 add '--extra-experimental-features nix-command' to enable it",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_already_enabled() {
+            let rule = NixExperimentalFeatures;
+            let cmd = Command::new(
+                "nix --extra-experimental-features 'nix-command flakes' build",
+                "error: experimental Nix feature 'flakes' is disabled",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let rule = NixExperimentalFeatures;
+            let cmd = Command::new(
+                "nix run .#app -- --verbose",
+                "error: experimental Nix feature 'flakes' is disabled",
+            );
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec![
+                    "nix --extra-experimental-features 'nix-command flakes' run .#app -- --verbose"
+                ]
+            );
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // NixEnvDeprecated tests
+    // -------------------------------------------------------------------------
+
+    mod nix_env_deprecated {
+        use super::*;
+
+        const OUTPUT: &str = "error: profile '/home/user/.local/state/nix/profiles/profile' \
+                              is incompatible with 'nix-env'; please use 'nix profile' instead";
+
+        #[test]
+        fn test_name() {
+            let rule = NixEnvDeprecated;
+            assert_eq!(rule.name(), "nix_env_deprecated");
+        }
+
+        #[test]
+        fn test_no_match_compatible_profile() {
+            let rule = NixEnvDeprecated;
+            let cmd = Command::new("nix-env -iA nixpkgs.htop", "installing 'htop-3.3.0'");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command_install() {
+            let rule = NixEnvDeprecated;
+            let cmd = Command::new("nix-env -iA nixos.htop nixos.jq", OUTPUT);
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["nix profile install nixpkgs#htop nixpkgs#jq"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_uninstall() {
+            let rule = NixEnvDeprecated;
+            let cmd = Command::new("nix-env -e htop", OUTPUT);
+            assert_eq!(rule.get_new_command(&cmd), vec!["nix profile remove htop"]);
+        }
+
+        #[test]
+        fn test_get_new_command_upgrade_all() {
+            let rule = NixEnvDeprecated;
+            let cmd = Command::new("nix-env -u", OUTPUT);
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["nix profile upgrade --all"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_query() {
+            let rule = NixEnvDeprecated;
+            let cmd = Command::new("nix-env -q", OUTPUT);
+            assert_eq!(rule.get_new_command(&cmd), vec!["nix profile list"]);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // NixAttributeNotFound tests
+    // -------------------------------------------------------------------------
+
+    mod nix_attribute_not_found {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = NixAttributeNotFound;
+            assert_eq!(rule.name(), "nix_attribute_not_found");
+        }
+
+        #[test]
+        fn test_no_match_without_suggestions() {
+            let rule = NixAttributeNotFound;
+            let cmd = Command::new("nix run nixpkgs#zzzz", "error: attribute 'zzzz' missing");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command_flake() {
+            let rule = NixAttributeNotFound;
+            let cmd = Command::new(
+                "nix shell nixpkgs#htpo",
+                "error: attribute 'htpo' missing\n       Did you mean one of htop, btop or atop?",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec![
+                    "nix shell nixpkgs#htop",
+                    "nix shell nixpkgs#btop",
+                    "nix shell nixpkgs#atop"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_attr_path() {
+            let rule = NixAttributeNotFound;
+            let cmd = Command::new(
+                "nix-env -iA nixpkgs.ripgerp",
+                "error: attribute 'ripgerp' missing\n       Did you mean ripgrep?",
+            );
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["nix-env -iA nixpkgs.ripgrep"]
+            );
+        }
+    }
+
     // -------------------------------------------------------------------------
     // OmnienvNoSuchCommand tests
     // -------------------------------------------------------------------------
@@ -1483,6 +2316,154 @@ mod tests {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // VersionManagerNoVersion tests
+    // -------------------------------------------------------------------------
+
+    mod version_manager_no_version {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = VersionManagerNoVersion;
+            assert_eq!(rule.name(), "version_manager_no_version");
+        }
+
+        #[test]
+        fn test_get_new_command_mise() {
+            let rule = VersionManagerNoVersion;
+            let cmd = Command::new(
+                "python -V",
+                "mise ERROR No version is set for shim: python\nSet a global default version",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["mise use python@latest && python -V"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_asdf_not_set() {
+            let rule = VersionManagerNoVersion;
+            let cmd = Command::new("ruby -v", "No version is set for command ruby");
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["asdf install ruby latest && asdf set ruby latest && ruby -v"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_asdf_not_installed() {
+            let rule = VersionManagerNoVersion;
+            let cmd = Command::new(
+                "node -v",
+                "No preset version installed for command node\n\
+                 Please install a version by running one of the following:\n\n\
+                 asdf install nodejs 20.10.0",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd), vec!["asdf install && node -v"]);
+        }
+
+        #[test]
+        fn test_no_match() {
+            let rule = VersionManagerNoVersion;
+            let cmd = Command::new("node -v", "v20.10.0");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // VersionManagerUnknownPlugin tests
+    // -------------------------------------------------------------------------
+
+    mod version_manager_unknown_plugin {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = VersionManagerUnknownPlugin;
+            assert_eq!(rule.name(), "version_manager_unknown_plugin");
+        }
+
+        #[test]
+        fn test_matches_mise() {
+            let rule = VersionManagerUnknownPlugin;
+            let cmd = Command::new(
+                "mise use nodj@20",
+                "mise ERROR nodj not found in mise tool registry",
+            );
+            assert!(rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = VersionManagerUnknownPlugin;
+            let cmd = Command::new("npm install nodej", "Plugin named nodej not found");
+            assert!(!rule.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_extract_plugin() {
+            assert_eq!(
+                VersionManagerUnknownPlugin::extract_plugin("No such plugin: pyhton"),
+                Some("pyhton".to_string())
+            );
+        }
+
+        #[test]
+        fn test_parse_names() {
+            let output = "1password-cli  aqua:1Password/cli\nnode           core:node\n";
+            assert_eq!(
+                VersionManagerUnknownPlugin::parse_names(output),
+                vec!["1password-cli", "node"]
+            );
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // VersionManagerReshim tests
+    // -------------------------------------------------------------------------
+
+    mod version_manager_reshim {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = VersionManagerReshim;
+            assert_eq!(rule.name(), "version_manager_reshim");
+        }
+
+        #[test]
+        fn test_get_new_command_asdf() {
+            let rule = VersionManagerReshim;
+            let cmd = Command::new(
+                "tsc --init",
+                "asdf: unknown command: tsc. Perhaps you have to reshim?",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["asdf reshim && tsc --init"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_mise() {
+            let rule = VersionManagerReshim;
+            let cmd = Command::new("tsc", "mise WARN  tsc not found, try running `mise reshim`");
+            assert_eq!(rule.get_new_command(&cmd), vec!["mise reshim && tsc"]);
+        }
+
+        #[test]
+        fn test_no_match() {
+            let rule = VersionManagerReshim;
+            let cmd = Command::new("tsc", "command not found: tsc");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
     // -------------------------------------------------------------------------
     // DjangoSouthGhost tests
     // -------------------------------------------------------------------------
@@ -1629,6 +2610,23 @@ mod tests {
                 .any(|f| f.contains("mkvirtualenv nonexistent_env_xyz")));
         }
 
+        #[test]
+        fn test_uses_workon_home() {
+            let _guard = crate::test_utils::EnvGuard::new(&["WORKON_HOME"]);
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("project")).unwrap();
+            std::env::set_var("WORKON_HOME", dir.path());
+
+            let rule = WorkonDoesntExists;
+            assert!(!rule.is_match(&Command::new("workon project", "")));
+            let cmd = Command::new("workon projcet", "");
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec!["workon project", "mkvirtualenv projcet"]
+            );
+        }
+
         #[test]
         fn test_requires_output() {
             let rule = WorkonDoesntExists;
@@ -1844,6 +2842,122 @@ mod tests {
             let cmd = Command::new("yarn build", "Usage: npm <command>");
             assert!(!rule.is_match(&cmd));
         }
+
+        #[test]
+        fn test_match_script_in_working_dir() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(
+                dir.path().join("package.json"),
+                r#"{"scripts": {"build": "tsc", "lint": "eslint ."}}"#,
+            )
+            .unwrap();
+
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "npm_run_script").with_cwd(dir.path());
+            let cmd = Command::new("npm build", "Usage: npm <command>");
+            assert!(NpmRunScript.is_match_with_context(&cmd, &ctx));
+            let cmd = Command::new("npm deploy", "Usage: npm <command>");
+            assert!(!NpmRunScript.is_match_with_context(&cmd, &ctx));
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Flutter tests
+    // -------------------------------------------------------------------------
+
+    mod flutter_unknown_command {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlutterUnknownCommand;
+            assert_eq!(rule.name(), "flutter_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_fuzzy() {
+            let rule = FlutterUnknownCommand;
+            let cmd = Command::new(
+                "flutter doctr -v",
+                "Could not find a command named \"doctr\".\n\nRun 'flutter -h' (or 'flutter \
+                 <command> -h') for available flutter commands and options.",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(rule.get_new_command(&cmd)[0], "flutter doctor -v");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let rule = FlutterUnknownCommand;
+            let cmd = Command::new("dart biuld", "Could not find a command named \"biuld\".");
+            assert!(!rule.is_match(&cmd));
+        }
+    }
+
+    mod flutter_no_pubspec {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlutterNoPubspec;
+            assert_eq!(rule.name(), "flutter_no_pubspec");
+        }
+
+        #[test]
+        fn test_get_new_command_projects() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("app")).unwrap();
+            std::fs::write(dir.path().join("app").join("pubspec.yaml"), "name: app").unwrap();
+            std::fs::create_dir(dir.path().join("docs")).unwrap();
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "flutter_no_pubspec").with_cwd(dir.path());
+
+            let rule = FlutterNoPubspec;
+            let cmd = Command::new("flutter pub get", "Error: No pubspec.yaml file found.");
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command_with_context(&cmd, &ctx),
+                vec!["cd app && flutter pub get"]
+            );
+        }
+    }
+
+    mod flutter_pub_get {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            let rule = FlutterPubGet;
+            assert_eq!(rule.name(), "flutter_pub_get");
+        }
+
+        #[test]
+        fn test_get_new_command_unresolved_package() {
+            let rule = FlutterPubGet;
+            let cmd = Command::new(
+                "flutter build apk",
+                "lib/main.dart:2:8: Error: Couldn't resolve the package 'http' in \
+                 'package:http/http.dart'.",
+            );
+            assert!(rule.is_match(&cmd));
+            assert_eq!(
+                rule.get_new_command(&cmd),
+                vec![
+                    "flutter pub get && flutter build apk",
+                    "flutter pub add http && flutter build apk"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_no_match_pub_itself() {
+            let rule = FlutterPubGet;
+            let cmd = Command::new(
+                "flutter pub outdated",
+                "Error: No pubspec.lock file found, please run \"flutter pub get\" and try again.",
+            );
+            assert!(!rule.is_match(&cmd));
+        }
     }
 
     // -------------------------------------------------------------------------
@@ -1864,7 +2978,7 @@ mod tests {
         #[test]
         fn test_all_rules_count() {
             let rules = all_rules();
-            assert_eq!(rules.len(), 15);
+            assert_eq!(rules.len(), 24);
         }
 
         #[test]
@@ -2,10 +2,14 @@
 //!
 //! This module contains rules for fixing common git add issues.
 
-use regex::Regex;
 use std::path::Path;
 
-use super::support::{and_commands, replace_argument, Command, GitSupport, Rule};
+use super::support::{
+    and_commands, get_close_matches, get_files, replace_argument, Command, GitSupport, Rule,
+    RuleContext,
+};
+use crate::regex;
+use crate::utils::shell_quote;
 
 /// Rule for adding untracked/modified files.
 ///
@@ -60,8 +64,7 @@ impl Rule for GitAdd {
 
 /// Extract the missing file from git's error output.
 fn get_missing_file(output: &str) -> Option<String> {
-    let re =
-        Regex::new(r"error: pathspec '([^']*)' did not match any file\(s\) known to git.").unwrap();
+    let re = regex!(r"error: pathspec '([^']*)' did not match any file\(s\) known to git.");
     re.captures(output)
         .and_then(|captures| captures.get(1))
         .map(|m| m.as_str().to_string())
@@ -179,6 +182,79 @@ impl Rule for GitAddAll {
     }
 }
 
+/// Rule for handling a mistyped path in git add or checkout.
+///
+/// Matches when a pathspec didn't match anything and no such file exists,
+/// and suggests the closest tracked or untracked file instead.
+pub struct GitPathspecTypo;
+
+impl GitPathspecTypo {
+    /// Creates a new GitPathspecTypo rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitPathspecTypo)
+    }
+
+    /// Extract the pathspec git couldn't find.
+    fn missing_pathspec(output: &str) -> Option<&str> {
+        regex!(r"error: pathspec '([^']*)' did not match any file\(s\) known to git")
+            .captures(output)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// Build fixes for `missing` from the files git knows about.
+    fn suggestions(cmd: &Command, missing: &str, files: &[String]) -> Vec<String> {
+        let add = cmd.script_parts().get(1).is_some_and(|sub| sub == "add");
+        get_close_matches(missing, files, 3, 0.6)
+            .into_iter()
+            .map(|file| {
+                if add {
+                    format!("git add {}", shell_quote(&file))
+                } else {
+                    format!("git checkout -- {}", shell_quote(&file))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for GitPathspecTypo {
+    fn default() -> Self {
+        GitPathspecTypo
+    }
+}
+
+impl Rule for GitPathspecTypo {
+    fn name(&self) -> &str {
+        "git_pathspec_typo"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        let parts = cmd.script_parts();
+        if !parts
+            .get(1)
+            .is_some_and(|sub| sub == "add" || sub == "checkout")
+        {
+            return false;
+        }
+        Self::missing_pathspec(&cmd.output).is_some_and(|missing| {
+            !ctx.cwd().join(missing).exists()
+                && !Self::suggestions(cmd, missing, &get_files()).is_empty()
+        })
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::missing_pathspec(&cmd.output) {
+            Some(missing) => Self::suggestions(cmd, missing, &get_files()),
+            None => vec![],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,6 +383,55 @@ mod tests {
         assert!(new_commands.contains(&"git add -A".to_string()));
     }
 
+    #[test]
+    fn test_git_pathspec_typo_suggestions() {
+        let files = vec![
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "README.md".to_string(),
+        ];
+        let output = "error: pathspec 'src/mian.rs' did not match any file(s) known to git\n";
+        assert_eq!(
+            GitPathspecTypo::missing_pathspec(output),
+            Some("src/mian.rs")
+        );
+
+        let add = Command::new("git add src/mian.rs", output);
+        assert_eq!(
+            GitPathspecTypo::suggestions(&add, "src/mian.rs", &files)[0],
+            "git add src/main.rs"
+        );
+        let checkout = Command::new("git checkout src/mian.rs", output);
+        assert_eq!(
+            GitPathspecTypo::suggestions(&checkout, "src/mian.rs", &files)[0],
+            "git checkout -- src/main.rs"
+        );
+        assert!(GitPathspecTypo::suggestions(&add, "zzzzzz", &files).is_empty());
+    }
+
+    #[test]
+    fn test_git_pathspec_typo_no_match() {
+        let rule = GitPathspecTypo;
+        assert!(!rule.is_match(&Command::new(
+            "git commit src/mian.rs",
+            "error: pathspec 'src/mian.rs' did not match any file(s) known to git\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git add src/main.rs", "")));
+
+        // A pathspec that exists where the command ran isn't a typo
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("mian.rs"), "").unwrap();
+        let settings = crate::config::Settings::default();
+        let ctx = RuleContext::new(&settings, "git_pathspec_typo").with_cwd(dir.path());
+        assert!(!rule.is_match_with_context(
+            &Command::new(
+                "git add mian.rs",
+                "error: pathspec 'mian.rs' did not match any file(s) known to git\n",
+            ),
+            &ctx
+        ));
+    }
+
     #[test]
     fn test_git_add_wrapped() {
         let rule = GitAddForce::new();
@@ -2,11 +2,11 @@
 //!
 //! This module contains rules for fixing common git branch issues.
 
-use regex::Regex;
-
 use super::support::{
     and_commands, get_branches, get_closest, replace_argument, Command, GitSupport, Rule,
 };
+use crate::regex;
+use crate::utils::shell_quote;
 
 /// Rule for handling branch deletion when not fully merged.
 ///
@@ -107,7 +107,7 @@ impl Rule for GitBranchExists {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the branch name
-        let re = Regex::new(r"fatal: [Aa] branch named '([^']*)' already exists").unwrap();
+        let re = regex!(r"fatal: [Aa] branch named '([^']*)' already exists");
         let branch = match re.captures(&cmd.output) {
             Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or(""),
             None => return vec![],
@@ -163,7 +163,7 @@ impl Rule for GitBranchNotFound {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the branch name that wasn't found
-        let re = Regex::new(r"error: branch '([^']*)' not found").unwrap();
+        let re = regex!(r"error: branch '([^']*)' not found");
         let missing = match re.captures(&cmd.output) {
             Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or(""),
             None => return vec![],
@@ -280,6 +280,101 @@ impl Rule for GitBranchFlagPosition {
     }
 }
 
+/// Check if the command renames a branch with `-m` or `--move`.
+fn is_rename(cmd: &Command) -> bool {
+    let parts = cmd.script_parts();
+    parts.iter().any(|p| p == "branch") && parts.iter().any(|p| p == "-m" || p == "--move")
+}
+
+/// Rule for renaming a branch onto a name that already exists.
+///
+/// Matches when `git branch -m` refuses to overwrite an existing branch,
+/// and suggests `-M` to rename it anyway.
+pub struct GitBranchRenameExists;
+
+impl GitBranchRenameExists {
+    /// Creates a new GitBranchRenameExists rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitBranchRenameExists)
+    }
+}
+
+impl Default for GitBranchRenameExists {
+    fn default() -> Self {
+        GitBranchRenameExists
+    }
+}
+
+impl Rule for GitBranchRenameExists {
+    fn name(&self) -> &str {
+        "git_branch_rename_exists"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_rename(cmd) && regex!(r"[Aa] branch named '[^']*' already exists").is_match(&cmd.output)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let flag = if cmd.script_parts().iter().any(|p| p == "--move") {
+            "--move"
+        } else {
+            "-m"
+        };
+        vec![replace_argument(&cmd.script, flag, "-M")]
+    }
+
+    fn priority(&self) -> i32 {
+        // Higher priority than generic git_branch_exists
+        900
+    }
+}
+
+/// Rule for renaming a branch that is checked out in another worktree.
+///
+/// Matches when git points at the worktree that has the branch checked
+/// out, and runs the rename from that worktree instead.
+pub struct GitBranchRenameWorktree;
+
+impl GitBranchRenameWorktree {
+    /// Creates a new GitBranchRenameWorktree rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitBranchRenameWorktree)
+    }
+
+    /// Extract the path of the worktree that has the branch checked out.
+    fn worktree(output: &str) -> Option<&str> {
+        regex!(r"'[^']+' is (?:already )?(?:checked out|used by worktree) at '([^']+)'")
+            .captures(output)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str())
+    }
+}
+
+impl Default for GitBranchRenameWorktree {
+    fn default() -> Self {
+        GitBranchRenameWorktree
+    }
+}
+
+impl Rule for GitBranchRenameWorktree {
+    fn name(&self) -> &str {
+        "git_branch_rename_worktree"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_rename(cmd) && Self::worktree(&cmd.output).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let (Some(worktree), Some((git, rest))) =
+            (Self::worktree(&cmd.output), cmd.script.split_once(' '))
+        else {
+            return vec![];
+        };
+        vec![format!("{} -C {} {}", git, shell_quote(worktree), rest)]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,7 +420,7 @@ mod tests {
     }
 
     #[test]
-    fn test_git_branch_delete_checked_out_matches_D() {
+    fn test_git_branch_delete_checked_out_matches_capital_d() {
         let rule = GitBranchDeleteCheckedOut;
         let cmd = Command::new(
             "git branch -D feature",
@@ -433,6 +528,50 @@ mod tests {
         assert_eq!(new_commands[0], "git branch -d feature");
     }
 
+    #[test]
+    fn test_git_branch_rename_exists() {
+        let rule = GitBranchRenameExists;
+        let cmd = Command::new(
+            "git branch -m feature",
+            "fatal: a branch named 'feature' already exists\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(rule.get_new_command(&cmd), vec!["git branch -M feature"]);
+
+        let cmd = Command::new(
+            "git branch --move old feature",
+            "fatal: A branch named 'feature' already exists.\n",
+        );
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git branch -M old feature"]
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git checkout -b feature",
+            "fatal: A branch named 'feature' already exists.\n",
+        )));
+    }
+
+    #[test]
+    fn test_git_branch_rename_worktree() {
+        let rule = GitBranchRenameWorktree;
+        let cmd = Command::new(
+            "git branch -m feature feature-v2",
+            "fatal: 'feature' is already used by worktree at '/home/me/src/app-feature'\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git -C /home/me/src/app-feature branch -m feature feature-v2"]
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git checkout feature",
+            "fatal: 'feature' is already checked out at '/home/me/src/app-feature'\n",
+        )));
+    }
+
     #[test]
     fn test_git_branch_delete_wrapped() {
         let rule = GitBranchDelete::new();
@@ -2,11 +2,11 @@
 //!
 //! This module contains rules for fixing common git checkout issues.
 
-use regex::Regex;
-
 use super::support::{
     and_commands, get_branches, get_closest, replace_argument, Command, GitSupport, Rule,
+    RuleContext,
 };
+use crate::regex;
 
 /// Rule for handling wrong branch name in checkout.
 ///
@@ -40,8 +40,7 @@ impl Rule for GitCheckout {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the missing file/branch name from the error
-        let re = Regex::new(r"error: pathspec '([^']*)' did not match any file\(s\) known to git")
-            .unwrap();
+        let re = regex!(r"error: pathspec '([^']*)' did not match any file\(s\) known to git");
         let missing = match re.captures(&cmd.output) {
             Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or(""),
             None => return vec![],
@@ -171,6 +170,234 @@ impl Rule for GitMainMaster {
     }
 }
 
+/// Extract the name from git switch's "fatal: invalid reference: <name>".
+fn invalid_reference(output: &str) -> Option<&str> {
+    regex!(r"fatal: invalid reference: (\S+)")
+        .captures(output)
+        .and_then(|captures| captures.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Rule for handling git switch on a file.
+///
+/// Matches when `git switch` is given a file, which newer git restores with
+/// `git restore` instead.
+pub struct GitSwitchRestore;
+
+impl GitSwitchRestore {
+    /// Creates a new GitSwitchRestore rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitSwitchRestore)
+    }
+}
+
+impl Default for GitSwitchRestore {
+    fn default() -> Self {
+        GitSwitchRestore
+    }
+}
+
+impl Rule for GitSwitchRestore {
+    fn name(&self) -> &str {
+        "git_switch_restore"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        cmd.script.contains("switch")
+            && invalid_reference(&cmd.output).is_some_and(|name| ctx.cwd().join(name).exists())
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![replace_argument(&cmd.script, "switch", "restore")]
+    }
+}
+
+/// Rule for handling git switch to a missing branch.
+///
+/// Matches when `git switch` is given a branch that doesn't exist, and
+/// suggests similar branch names or creating it with `-c`.
+pub struct GitSwitchCreate;
+
+impl GitSwitchCreate {
+    /// Creates a new GitSwitchCreate rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitSwitchCreate)
+    }
+}
+
+impl Default for GitSwitchCreate {
+    fn default() -> Self {
+        GitSwitchCreate
+    }
+}
+
+impl Rule for GitSwitchCreate {
+    fn name(&self) -> &str {
+        "git_switch_create"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        cmd.script.contains("switch")
+            && invalid_reference(&cmd.output).is_some_and(|name| !ctx.cwd().join(name).exists())
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(missing) = invalid_reference(&cmd.output) else {
+            return vec![];
+        };
+
+        let mut new_commands = Vec::new();
+        if let Some(closest_branch) = get_closest(missing, &get_branches(), false) {
+            new_commands.push(replace_argument(&cmd.script, missing, &closest_branch));
+        }
+        new_commands.push(replace_argument(&cmd.script, "switch", "switch -c"));
+        new_commands
+    }
+}
+
+/// Rule for handling git restore on a branch.
+///
+/// Matches when `git restore` is given a branch name, which is switched to
+/// with `git switch`.
+pub struct GitRestoreBranch;
+
+impl GitRestoreBranch {
+    /// Creates a new GitRestoreBranch rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitRestoreBranch)
+    }
+
+    /// Extract the pathspec git restore couldn't find.
+    fn missing_pathspec(output: &str) -> Option<&str> {
+        regex!(r"error: pathspec '([^']*)' did not match any file\(s\) known to git")
+            .captures(output)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str())
+    }
+}
+
+impl Default for GitRestoreBranch {
+    fn default() -> Self {
+        GitRestoreBranch
+    }
+}
+
+impl Rule for GitRestoreBranch {
+    fn name(&self) -> &str {
+        "git_restore_branch"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("restore")
+            && Self::missing_pathspec(&cmd.output)
+                .is_some_and(|name| get_branches().iter().any(|branch| branch == name))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::missing_pathspec(&cmd.output) {
+            Some(branch) => vec![format!("git switch {}", branch)],
+            None => vec![],
+        }
+    }
+}
+
+/// Rule for handling a remote branch that was deleted.
+///
+/// Matches when git can't find a remote ref, usually because the branch
+/// was deleted on the remote but is still known locally, and suggests
+/// pruning stale remote branches first.
+pub struct GitFetchPrune;
+
+impl GitFetchPrune {
+    /// Creates a new GitFetchPrune rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitFetchPrune)
+    }
+}
+
+impl Default for GitFetchPrune {
+    fn default() -> Self {
+        GitFetchPrune
+    }
+}
+
+impl Rule for GitFetchPrune {
+    fn name(&self) -> &str {
+        "git_fetch_prune"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("couldn't find remote ref") && !cmd.script.contains("--prune")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![and_commands("git fetch --prune", &cmd.script)]
+    }
+}
+
+/// Rule for recovering from a detached HEAD.
+///
+/// Matches when a checkout of a commit or tag leaves HEAD detached, and
+/// suggests creating a branch to keep any new work or going back to the
+/// previous branch.
+pub struct GitDetachedHead;
+
+impl GitDetachedHead {
+    /// Creates a new GitDetachedHead rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitDetachedHead)
+    }
+
+    /// Get the commit or tag HEAD was detached at.
+    fn detached_ref(cmd: &Command) -> Option<String> {
+        regex!(r"Note: (?:switching to|checking out) '([^']+)'")
+            .captures(&cmd.output)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str().to_string())
+            .or_else(|| {
+                cmd.script_parts()
+                    .last()
+                    .filter(|part| !part.starts_with('-'))
+                    .cloned()
+            })
+    }
+}
+
+impl Default for GitDetachedHead {
+    fn default() -> Self {
+        GitDetachedHead
+    }
+}
+
+impl Rule for GitDetachedHead {
+    fn name(&self) -> &str {
+        "git_detached_head"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        (cmd.script.contains("checkout") || cmd.script.contains("switch"))
+            && cmd.output.contains("detached HEAD")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let mut new_commands = Vec::new();
+        if let Some(detached) = Self::detached_ref(cmd) {
+            let branch = format!("{}-work", detached.replace('/', "-"));
+            new_commands.push(format!("git switch -c {}", branch));
+        }
+        new_commands.push("git switch -".to_string());
+        new_commands
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +511,129 @@ mod tests {
         assert_eq!(new_commands, vec!["git checkout main"]);
     }
 
+    #[test]
+    fn test_git_switch_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "notes").unwrap();
+        let file = file.to_str().unwrap();
+
+        let rule = GitSwitchRestore;
+        let cmd = Command::new(
+            format!("git switch {}", file),
+            format!("fatal: invalid reference: {}\n", file),
+        );
+        assert!(rule.is_match(&cmd));
+        assert!(!GitSwitchCreate.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec![format!("git restore {}", file)]
+        );
+    }
+
+    #[test]
+    fn test_git_switch_restore_relative_to_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+        let settings = crate::config::Settings::default();
+        let ctx = RuleContext::new(&settings, "git_switch_restore").with_cwd(dir.path());
+
+        let cmd = Command::new(
+            "git switch notes.txt",
+            "fatal: invalid reference: notes.txt\n",
+        );
+        assert!(GitSwitchRestore.is_match_with_context(&cmd, &ctx));
+        assert!(!GitSwitchCreate.is_match_with_context(&cmd, &ctx));
+    }
+
+    #[test]
+    fn test_git_switch_create() {
+        let rule = GitSwitchCreate;
+        let cmd = Command::new(
+            "git switch oops-no-such-branch",
+            "fatal: invalid reference: oops-no-such-branch\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert!(!GitSwitchRestore.is_match(&cmd));
+        let new_commands = rule.get_new_command(&cmd);
+        assert_eq!(
+            new_commands.last().unwrap(),
+            "git switch -c oops-no-such-branch"
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git switch main",
+            "Switched to branch 'main'\n"
+        )));
+    }
+
+    #[test]
+    fn test_git_restore_branch() {
+        let rule = GitRestoreBranch;
+        let cmd = Command::new(
+            "git restore main",
+            "error: pathspec 'main' did not match any file(s) known to git\n",
+        );
+        assert_eq!(rule.get_new_command(&cmd), vec!["git switch main"]);
+
+        // A missing file that isn't a branch either
+        assert!(!rule.is_match(&Command::new(
+            "git restore oops-no-such-file.txt",
+            "error: pathspec 'oops-no-such-file.txt' did not match any file(s) known to git\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git restore README.md", "")));
+    }
+
+    #[test]
+    fn test_git_fetch_prune() {
+        let rule = GitFetchPrune;
+        let cmd = Command::new(
+            "git pull origin feature/login",
+            "fatal: couldn't find remote ref feature/login\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git fetch --prune && git pull origin feature/login"]
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git fetch --prune && git pull origin feature/login",
+            "fatal: couldn't find remote ref feature/login\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git pull", "Already up to date.\n")));
+    }
+
+    #[test]
+    fn test_git_detached_head() {
+        let rule = GitDetachedHead;
+        let cmd = Command::new(
+            "git checkout v1.2.0",
+            "Note: switching to 'v1.2.0'.\n\n\
+             You are in 'detached HEAD' state. You can look around, make experimental\n\
+             changes and commit them, and you can discard any commits you make in this\n\
+             state without impacting any branches by switching back to a branch.\n\n\
+             HEAD is now at 1a2b3c4 Release 1.2.0\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git switch -c v1.2.0-work", "git switch -"]
+        );
+
+        // Older git phrases the note differently
+        let cmd = Command::new(
+            "git checkout 1a2b3c4",
+            "Note: checking out '1a2b3c4'.\n\nYou are in 'detached HEAD' state.\n",
+        );
+        assert_eq!(rule.get_new_command(&cmd)[0], "git switch -c 1a2b3c4-work");
+
+        assert!(!rule.is_match(&Command::new(
+            "git checkout main",
+            "Switched to branch 'main'\n"
+        )));
+    }
+
     #[test]
     fn test_git_checkout_wrapped() {
         let rule = GitCheckout::new();
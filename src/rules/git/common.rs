@@ -2,12 +2,15 @@
 //!
 //! This module contains rules for common git operations.
 
-use regex::Regex;
-
 use super::support::{
-    and_commands, get_all_matched_commands, get_closest, replace_argument, replace_command,
-    Command, GitSupport, Rule,
+    and_commands, get_all_matched_commands, get_branches, get_closest, get_remotes,
+    replace_argument, replace_command, Command, GitSupport, Rule,
 };
+use crate::regex;
+use crate::rules::patterns::git::NOT_A_REPOSITORY;
+use crate::utils::cache::command_output;
+use crate::utils::shell_quote;
+use std::env;
 
 /// Rule for handling git pull when there's no upstream set.
 ///
@@ -108,6 +111,52 @@ impl Rule for GitPullUncommittedChanges {
     }
 }
 
+/// Rule for handling git pull on diverging branches.
+///
+/// Matches when git pull refuses to reconcile divergent branches until a
+/// strategy is chosen, and suggests rebasing or merging. Git only asks when
+/// neither `pull.rebase` nor `pull.ff` is set, so there is no configured
+/// preference to put first.
+pub struct GitPullDivergent;
+
+impl GitPullDivergent {
+    /// Creates a new GitPullDivergent rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitPullDivergent)
+    }
+
+    fn commands_for(script: &str) -> Vec<String> {
+        vec![
+            replace_argument(script, "pull", "pull --rebase"),
+            replace_argument(script, "pull", "pull --no-rebase"),
+            and_commands("git config pull.rebase true", script),
+        ]
+    }
+}
+
+impl Default for GitPullDivergent {
+    fn default() -> Self {
+        GitPullDivergent
+    }
+}
+
+impl Rule for GitPullDivergent {
+    fn name(&self) -> &str {
+        "git_pull_divergent"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("pull")
+            && cmd
+                .output
+                .contains("You have divergent branches and need to specify how to reconcile them")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        Self::commands_for(&cmd.script)
+    }
+}
+
 /// Rule for handling stash operations.
 ///
 /// Matches when an operation needs stashing first.
@@ -417,6 +466,491 @@ impl Rule for GitRebaseNoChanges {
     }
 }
 
+/// Extract the upstream or `--onto` target git rebase couldn't resolve.
+fn invalid_rebase_target(output: &str) -> Option<&str> {
+    regex!(r"(?:invalid upstream|Does not point to a valid commit):? '([^']+)'")
+        .captures(output)
+        .and_then(|captures| captures.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Check if `target` counts back from HEAD, like `HEAD~5` or `HEAD^^`.
+fn is_relative_to_head(target: &str) -> bool {
+    regex!(r"^(?:HEAD|@)(?:[~^]\d*)+$").is_match(target)
+}
+
+/// Rule for handling git rebase onto a misspelled branch.
+///
+/// Matches when the upstream or `--onto` target isn't a valid commit, and
+/// suggests the closest branch name.
+pub struct GitRebaseInvalidUpstream;
+
+impl GitRebaseInvalidUpstream {
+    /// Creates a new GitRebaseInvalidUpstream rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitRebaseInvalidUpstream)
+    }
+
+    /// Replace `missing` in the script with the closest of `branches`.
+    fn suggestions(cmd: &Command, missing: &str, branches: &[String]) -> Vec<String> {
+        get_closest(missing, branches, false)
+            .map(|branch| vec![replace_argument(&cmd.script, missing, &branch)])
+            .unwrap_or_default()
+    }
+}
+
+impl Default for GitRebaseInvalidUpstream {
+    fn default() -> Self {
+        GitRebaseInvalidUpstream
+    }
+}
+
+impl Rule for GitRebaseInvalidUpstream {
+    fn name(&self) -> &str {
+        "git_rebase_invalid_upstream"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("rebase")
+            && invalid_rebase_target(&cmd.output).is_some_and(|target| !is_relative_to_head(target))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match invalid_rebase_target(&cmd.output) {
+            Some(missing) => Self::suggestions(cmd, missing, &get_branches()),
+            None => vec![],
+        }
+    }
+}
+
+/// Rule for handling an interactive rebase further back than history goes.
+///
+/// Matches when `git rebase -i HEAD~n` fails because the branch has fewer
+/// than n commits, and suggests rebasing from the root commit instead.
+pub struct GitRebaseRoot;
+
+impl GitRebaseRoot {
+    /// Creates a new GitRebaseRoot rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitRebaseRoot)
+    }
+}
+
+impl Default for GitRebaseRoot {
+    fn default() -> Self {
+        GitRebaseRoot
+    }
+}
+
+impl Rule for GitRebaseRoot {
+    fn name(&self) -> &str {
+        "git_rebase_root"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("rebase")
+            && invalid_rebase_target(&cmd.output).is_some_and(is_relative_to_head)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match invalid_rebase_target(&cmd.output) {
+            Some(target) => vec![replace_argument(&cmd.script, target, "--root")],
+            None => vec![],
+        }
+    }
+}
+
+/// Order `commands` by similarity to the `script` the user typed, most
+/// similar first.
+fn sort_by_similarity(script: &str, commands: Vec<String>) -> Vec<String> {
+    let mut scored: Vec<(f64, String)> = commands
+        .into_iter()
+        .map(|c| (strsim::jaro_winkler(script, &c), c))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Rule for handling git cherry-pick conflicts.
+///
+/// Matches when a cherry-pick stops on conflicts, or when another one is
+/// started while it is still in progress.
+pub struct GitCherryPick;
+
+impl GitCherryPick {
+    /// Creates a new GitCherryPick rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitCherryPick)
+    }
+}
+
+impl Default for GitCherryPick {
+    fn default() -> Self {
+        GitCherryPick
+    }
+}
+
+impl Rule for GitCherryPick {
+    fn name(&self) -> &str {
+        "git_cherry_pick"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("cherry-pick")
+            && (cmd.output.contains("CONFLICT")
+                || cmd.output.contains("cherry-pick is already in progress")
+                || cmd.output.contains("you have unmerged files"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let commands = vec![
+            "git add . && git cherry-pick --continue".to_string(),
+            "git cherry-pick --abort".to_string(),
+            "git cherry-pick --skip".to_string(),
+        ];
+        sort_by_similarity(&cmd.script, commands)
+    }
+}
+
+/// Rule for handling a git cherry-pick that became empty.
+///
+/// Matches when the changes of the picked commit are already in the branch,
+/// often after resolving its conflicts.
+pub struct GitCherryPickEmpty;
+
+impl GitCherryPickEmpty {
+    /// Creates a new GitCherryPickEmpty rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitCherryPickEmpty)
+    }
+}
+
+impl Default for GitCherryPickEmpty {
+    fn default() -> Self {
+        GitCherryPickEmpty
+    }
+}
+
+impl Rule for GitCherryPickEmpty {
+    fn name(&self) -> &str {
+        "git_cherry_pick_empty"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("The previous cherry-pick is now empty")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let commands = vec![
+            "git cherry-pick --skip".to_string(),
+            "git commit --allow-empty && git cherry-pick --continue".to_string(),
+            "git cherry-pick --abort".to_string(),
+        ];
+        sort_by_similarity(&cmd.script, commands)
+    }
+}
+
+/// Rule for handling git revert of a merge commit.
+///
+/// Matches when a merge commit is reverted without choosing, with `-m`,
+/// which parent to revert to.
+pub struct GitRevertMerge;
+
+impl GitRevertMerge {
+    /// Creates a new GitRevertMerge rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitRevertMerge)
+    }
+}
+
+impl Default for GitRevertMerge {
+    fn default() -> Self {
+        GitRevertMerge
+    }
+}
+
+impl Rule for GitRevertMerge {
+    fn name(&self) -> &str {
+        "git_revert_merge"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("revert")
+            && cmd.output.contains("is a merge but no -m option was given")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let parts = cmd.script_parts();
+        let Some(revert) = parts.iter().position(|p| p == "revert") else {
+            return vec![];
+        };
+        let Some(sha) = parts[revert + 1..].iter().find(|p| !p.starts_with('-')) else {
+            return vec![];
+        };
+
+        // Mainline 1 keeps the branch merged into; 2 keeps the merged branch
+        ["1", "2"]
+            .iter()
+            .map(|parent| replace_argument(&cmd.script, sha, &format!("-m {} {}", parent, sha)))
+            .collect()
+    }
+}
+
+/// Rule for handling commands that need the full history of a shallow clone.
+///
+/// Matches when a push is refused with "shallow update not allowed" or a
+/// command complains about a shallow repository, and suggests fetching the
+/// missing history first.
+pub struct GitShallowClone;
+
+impl GitShallowClone {
+    /// Creates a new GitShallowClone rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitShallowClone)
+    }
+}
+
+impl Default for GitShallowClone {
+    fn default() -> Self {
+        GitShallowClone
+    }
+}
+
+impl Rule for GitShallowClone {
+    fn name(&self) -> &str {
+        "git_shallow_clone"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        !cmd.script.contains("--unshallow")
+            && (cmd.output.contains("shallow update not allowed")
+                || cmd.output.contains("shallow repository"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![and_commands("git fetch --unshallow", &cmd.script)]
+    }
+}
+
+/// Rule for handling a commit that GPG couldn't sign.
+///
+/// Matches when gpg failed to sign the data, most often because its
+/// passphrase prompt can't find the terminal, and points `GPG_TTY` at it.
+pub struct GitGpgTty;
+
+impl GitGpgTty {
+    /// Creates a new GitGpgTty rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitGpgTty)
+    }
+}
+
+impl Default for GitGpgTty {
+    fn default() -> Self {
+        GitGpgTty
+    }
+}
+
+impl Rule for GitGpgTty {
+    fn name(&self) -> &str {
+        "git_gpg_tty"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("gpg failed to sign the data") && !cmd.script.contains("GPG_TTY")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![and_commands("export GPG_TTY=$(tty)", &cmd.script)]
+    }
+}
+
+/// Rule for committing without a signature when GPG signing fails.
+///
+/// Disabled by default, since it silently drops a signature that the
+/// repository or its remote may require.
+pub struct GitNoGpgSign;
+
+impl GitNoGpgSign {
+    /// Creates a new GitNoGpgSign rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitNoGpgSign)
+    }
+}
+
+impl Default for GitNoGpgSign {
+    fn default() -> Self {
+        GitNoGpgSign
+    }
+}
+
+impl Rule for GitNoGpgSign {
+    fn name(&self) -> &str {
+        "git_no_gpg_sign"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("commit")
+            && cmd.output.contains("gpg failed to sign the data")
+            && !cmd.script.contains("--no-gpg-sign")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![replace_argument(
+            &cmd.script,
+            "commit",
+            "commit --no-gpg-sign",
+        )]
+    }
+
+    fn priority(&self) -> i32 {
+        // Lower priority since skipping the signature can be risky
+        1100
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+}
+
+/// Rule for handling a push, pull or clone that the remote refused to
+/// authenticate.
+///
+/// Matches when SSH has no usable key or an HTTPS remote rejects the
+/// credentials, and suggests fixes that fit the remote's URL: loading a key
+/// for SSH remotes, or logging in with `gh` and switching to SSH for HTTPS
+/// remotes.
+pub struct GitAuthFailure;
+
+impl GitAuthFailure {
+    /// Creates a new GitAuthFailure rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitAuthFailure)
+    }
+
+    /// Get the remote's name and URL, from the clone URL in the script or
+    /// from `git remote -v`.
+    fn remote(cmd: &Command) -> Option<(String, String)> {
+        let parts = cmd.script_parts();
+        let sub = parts
+            .iter()
+            .position(|p| p == "push" || p == "pull" || p == "fetch" || p == "clone")?;
+        let arg = parts[sub + 1..].iter().find(|p| !p.starts_with('-'));
+
+        if parts[sub] == "clone" {
+            return arg.map(|url| (String::new(), url.clone()));
+        }
+        let name = arg.map(String::as_str).unwrap_or("origin");
+        get_remotes().into_iter().find(|(remote, _)| remote == name)
+    }
+
+    /// Turn an HTTPS remote URL into its SSH form.
+    fn https_to_ssh(url: &str) -> Option<String> {
+        let captures = regex!(r"^https?://(?:[^@/]+@)?([^/]+)/(.+?)(?:\.git)?/?$").captures(url)?;
+        Some(format!("git@{}:{}.git", &captures[1], &captures[2]))
+    }
+
+    /// Build suggestions for the remote `name` at `url`.
+    fn suggestions(cmd: &Command, name: &str, url: &str) -> Vec<String> {
+        let mut new_commands = Vec::new();
+        if url.contains("github.com") {
+            new_commands.push(and_commands("gh auth login", &cmd.script));
+        }
+        match Self::https_to_ssh(url) {
+            Some(ssh_url) if name.is_empty() => {
+                new_commands.push(replace_argument(&cmd.script, url, &ssh_url));
+            }
+            Some(ssh_url) => new_commands.push(and_commands(
+                &format!("git remote set-url {} {}", name, ssh_url),
+                &cmd.script,
+            )),
+            None => new_commands.insert(0, and_commands("ssh-add", &cmd.script)),
+        }
+        new_commands
+    }
+}
+
+impl Default for GitAuthFailure {
+    fn default() -> Self {
+        GitAuthFailure
+    }
+}
+
+impl Rule for GitAuthFailure {
+    fn name(&self) -> &str {
+        "git_auth_failure"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("Permission denied (publickey)")
+            || cmd.output.contains("Authentication failed for")
+            || cmd.output.contains("The requested URL returned error: 403")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        match Self::remote(cmd) {
+            Some((name, url)) => Self::suggestions(cmd, &name, &url),
+            None if cmd.output.contains("Permission denied (publickey)") => {
+                vec![and_commands("ssh-add", &cmd.script)]
+            }
+            None => vec![and_commands("gh auth login", &cmd.script)],
+        }
+    }
+}
+
+/// Rule for handling a commit without a configured user identity.
+///
+/// Matches when git asks who you are, and sets `user.email` and `user.name`
+/// before retrying. Values come from `GIT_AUTHOR_EMAIL` and
+/// `GIT_AUTHOR_NAME` when set, and placeholders otherwise.
+pub struct GitUserIdentity;
+
+impl GitUserIdentity {
+    /// Creates a new GitUserIdentity rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitUserIdentity)
+    }
+
+    /// Build the config commands followed by the original command.
+    fn identity_command(script: &str, email: Option<String>, name: Option<String>) -> String {
+        let email = email.unwrap_or_else(|| "you@example.com".to_string());
+        let name = name.unwrap_or_else(|| "Your Name".to_string());
+        and_commands(
+            &format!(
+                "git config --global user.email {} && git config --global user.name {}",
+                shell_quote(&email),
+                shell_quote(&name)
+            ),
+            script,
+        )
+    }
+}
+
+impl Default for GitUserIdentity {
+    fn default() -> Self {
+        GitUserIdentity
+    }
+}
+
+impl Rule for GitUserIdentity {
+    fn name(&self) -> &str {
+        "git_user_identity"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.output.contains("Please tell me who you are")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let from_env = |key| env::var(key).ok().filter(|value| !value.is_empty());
+        vec![Self::identity_command(
+            &cmd.script,
+            from_env("GIT_AUTHOR_EMAIL"),
+            from_env("GIT_AUTHOR_NAME"),
+        )]
+    }
+}
+
 /// Rule for handling git rm on modified files.
 ///
 /// Matches when git rm fails because file has local modifications.
@@ -484,6 +1018,89 @@ impl Rule for GitRmRecursive {
     }
 }
 
+/// Check if git clean refused to run without `-f`.
+fn clean_requires_force(cmd: &Command) -> bool {
+    cmd.script.contains("clean") && cmd.output.contains("clean.requireForce")
+}
+
+/// Rule for previewing a git clean that refused to run.
+///
+/// Matches when git clean needs `-f`, and suggests a dry run to see what
+/// would be removed first.
+pub struct GitCleanDryRun;
+
+impl GitCleanDryRun {
+    /// Creates a new GitCleanDryRun rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitCleanDryRun)
+    }
+}
+
+impl Default for GitCleanDryRun {
+    fn default() -> Self {
+        GitCleanDryRun
+    }
+}
+
+impl Rule for GitCleanDryRun {
+    fn name(&self) -> &str {
+        "git_clean_dry_run"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        clean_requires_force(cmd)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![replace_argument(&cmd.script, "clean", "clean -n")]
+    }
+}
+
+/// Rule for forcing a git clean that refused to run.
+///
+/// Matches when git clean needs `-f`, and suggests adding it. Untracked
+/// directories are only removed when `-d` was already given.
+///
+/// This is destructive: the removed files can't be recovered. It is
+/// disabled by default and runs behind the dry run when enabled.
+pub struct GitCleanForce;
+
+impl GitCleanForce {
+    /// Creates a new GitCleanForce rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitCleanForce)
+    }
+}
+
+impl Default for GitCleanForce {
+    fn default() -> Self {
+        GitCleanForce
+    }
+}
+
+impl Rule for GitCleanForce {
+    fn name(&self) -> &str {
+        "git_clean_force"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        clean_requires_force(cmd)
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        vec![replace_argument(&cmd.script, "clean", "clean -f")]
+    }
+
+    fn priority(&self) -> i32 {
+        // Lower priority than the dry run since this deletes files for good
+        1100
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+}
+
 /// Rule for handling git remote delete.
 ///
 /// Matches when trying to delete a remote with the wrong syntax.
@@ -518,6 +1135,69 @@ impl Rule for GitRemoteDelete {
     }
 }
 
+/// Rule for handling a misspelled remote name.
+///
+/// Matches when `git remote` can't find the named remote, or a fetch, pull
+/// or push takes the name for a repository path, and suggests the closest
+/// configured remote.
+pub struct GitRemoteTypo;
+
+impl GitRemoteTypo {
+    /// Creates a new GitRemoteTypo rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitRemoteTypo)
+    }
+
+    /// Extract the remote name git couldn't find.
+    fn missing_remote(cmd: &Command) -> Option<&str> {
+        if let Some(captures) = regex!(r"No such remote:? '([^']+)'").captures(&cmd.output) {
+            return captures.get(1).map(|m| m.as_str());
+        }
+        let parts = cmd.script_parts();
+        if !parts
+            .iter()
+            .any(|p| p == "fetch" || p == "pull" || p == "push")
+        {
+            return None;
+        }
+        regex!(r"'([^'/:\\]+)' does not appear to be a git repository")
+            .captures(&cmd.output)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// Replace `missing` in the script with the closest of `remotes`.
+    fn suggestions(cmd: &Command, missing: &str, remotes: &[String]) -> Vec<String> {
+        get_closest(missing, remotes, false)
+            .map(|remote| vec![replace_argument(&cmd.script, missing, &remote)])
+            .unwrap_or_default()
+    }
+}
+
+impl Default for GitRemoteTypo {
+    fn default() -> Self {
+        GitRemoteTypo
+    }
+}
+
+impl Rule for GitRemoteTypo {
+    fn name(&self) -> &str {
+        "git_remote_typo"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        Self::missing_remote(cmd).is_some()
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let Some(missing) = Self::missing_remote(cmd) else {
+            return vec![];
+        };
+        let remotes: Vec<String> = get_remotes().into_iter().map(|(name, _)| name).collect();
+        Self::suggestions(cmd, missing, &remotes)
+    }
+}
+
 /// Rule for handling git tag force.
 ///
 /// Matches when trying to create a tag that already exists.
@@ -613,7 +1293,7 @@ impl Rule for GitPullClone {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        cmd.script.contains("pull") && cmd.output.contains("not a git repository")
+        cmd.script.contains("pull") && NOT_A_REPOSITORY.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, _cmd: &Command) -> Vec<String> {
@@ -645,7 +1325,7 @@ impl Rule for GitCloneGitClone {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        let re = Regex::new(r"git clone git clone ").unwrap();
+        let re = regex!(r"git clone git clone ");
         re.is_match(&cmd.script)
     }
 
@@ -687,8 +1367,8 @@ impl Rule for GitBisectUsage {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the broken subcommand from the script
-        let broken_re = Regex::new(r"git bisect ([^ $]*)").unwrap();
-        let usage_re = Regex::new(r"usage: git bisect \[([^\]]+)\]").unwrap();
+        let broken_re = regex!(r"git bisect ([^ $]*)");
+        let usage_re = regex!(r"usage: git bisect \[([^\]]+)\]");
 
         if let (Some(broken_cap), Some(usage_cap)) = (
             broken_re.captures(&cmd.script),
@@ -700,7 +1380,51 @@ impl Rule for GitBisectUsage {
             return replace_command(&cmd.script, broken, &options);
         }
 
-        vec![]
+        vec![]
+    }
+}
+
+/// Rule for marking commits before a bisect has started.
+///
+/// Matches when `git bisect good`/`bad` (or another bisect step) is run
+/// before `git bisect start`, and starts the bisect first. Good revisions
+/// can be marked in one go by starting at HEAD as the bad one.
+pub struct GitBisectStart;
+
+impl GitBisectStart {
+    /// Creates a new GitBisectStart rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitBisectStart)
+    }
+}
+
+impl Default for GitBisectStart {
+    fn default() -> Self {
+        GitBisectStart
+    }
+}
+
+impl Rule for GitBisectStart {
+    fn name(&self) -> &str {
+        "git_bisect_start"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("bisect") && cmd.output.contains("You need to start by")
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        let mut new_commands = vec![and_commands("git bisect start", &cmd.script)];
+
+        let parts = cmd.script_parts();
+        if let Some(bisect) = parts.iter().position(|p| p == "bisect") {
+            let mark = parts.get(bisect + 1).map(String::as_str);
+            let revs = &parts[(bisect + 2).min(parts.len())..];
+            if matches!(mark, Some("good" | "old")) && !revs.is_empty() {
+                new_commands.push(format!("git bisect start HEAD {}", revs.join(" ")));
+            }
+        }
+        new_commands
     }
 }
 
@@ -870,9 +1594,99 @@ impl Rule for GitFixStash {
     }
 }
 
+/// Rule for handling a stash entry reference that git can't resolve.
+///
+/// Matches when a stash reference lost its `@{...}` to the shell or names
+/// an entry past the end of the stash, and suggests the quoted reference or
+/// the nearest entry from `git stash list`.
+pub struct GitStashRef;
+
+impl GitStashRef {
+    /// Creates a new GitStashRef rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitStashRef)
+    }
+
+    /// Get the number of entries in the stash.
+    fn stash_count() -> usize {
+        match command_output("git", &["stash", "list"]) {
+            Some(output) if output.status.success() => {
+                String::from_utf8_lossy(&output.stdout).lines().count()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Build the fix for a stash with `count` entries.
+    fn suggestions(cmd: &Command, count: usize) -> Vec<String> {
+        let parts = cmd.script_parts();
+        let Some(stash) = parts.iter().position(|p| p == "stash") else {
+            return vec![];
+        };
+        let Some((reference, index)) = parts[stash + 1..].iter().find_map(|part| {
+            let captures = regex!(r"^(?:stash@?\{?)?(\d+)\}?$").captures(part)?;
+            Some((part, captures[1].parse::<usize>().ok()?))
+        }) else {
+            return vec![];
+        };
+        if count == 0 {
+            return vec![];
+        }
+
+        let fixed = format!("'stash@{{{}}}'", index.min(count - 1));
+        vec![replace_argument(&cmd.script, reference, &fixed)]
+    }
+}
+
+impl Default for GitStashRef {
+    fn default() -> Self {
+        GitStashRef
+    }
+}
+
+impl Rule for GitStashRef {
+    fn name(&self) -> &str {
+        "git_stash_ref"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        cmd.script.contains("stash")
+            && (cmd.output.contains("is not a valid reference")
+                || cmd.output.contains("log for 'stash' only has"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        Self::suggestions(cmd, Self::stash_count())
+    }
+}
+
+/// Where a misplaced flag goes, relative to the command it was given to.
+#[derive(Clone, Copy)]
+enum FlagPlacement {
+    /// A separate command run before it.
+    Before,
+    /// A separate command run after it.
+    After,
+    /// The subcommand it was meant for, replacing the one given.
+    Instead,
+}
+
+/// Flags that belong to a single other subcommand, as
+/// (subcommand, flag, right subcommand, takes a value, placement).
+const MISPLACED_FLAGS: &[(&str, &str, &str, bool, FlagPlacement)] = &[
+    ("add", "-m", "commit", true, FlagPlacement::After),
+    ("add", "--message", "commit", true, FlagPlacement::After),
+    ("add", "--amend", "commit", false, FlagPlacement::After),
+    ("commit", "-A", "add", false, FlagPlacement::Before),
+    ("checkout", "-c", "switch", true, FlagPlacement::Instead),
+    ("checkout", "-C", "switch", true, FlagPlacement::Instead),
+];
+
 /// Rule for handling flags placed after filenames.
 ///
-/// Moves flags to appear before filenames in git commands.
+/// Moves flags to appear before filenames in git commands. A flag that git
+/// doesn't know for the subcommand it was given to, like `git add -m`, is
+/// moved to the subcommand it belongs to when there is only one.
 pub struct GitFlagAfterFilename;
 
 impl GitFlagAfterFilename {
@@ -880,6 +1694,51 @@ impl GitFlagAfterFilename {
     pub fn new() -> GitSupport<Self> {
         GitSupport(GitFlagAfterFilename)
     }
+
+    /// Get the unknown flag from git's error, with its dashes.
+    fn unknown_flag(output: &str) -> Option<String> {
+        let captures = regex!(r"error: unknown (switch|option) `([^']+)'").captures(output)?;
+        let dashes = if &captures[1] == "switch" { "-" } else { "--" };
+        Some(format!("{}{}", dashes, &captures[2]))
+    }
+
+    /// Move an unknown flag to the subcommand it belongs to.
+    fn move_misplaced_flag(cmd: &Command) -> Option<String> {
+        let flag = Self::unknown_flag(&cmd.output)?;
+        let mut parts = cmd.script_parts().to_vec();
+        let sub_index = parts
+            .iter()
+            .position(|p| !p.starts_with('-') && p != &parts[0])?;
+        let &(_, _, right, takes_value, placement) = MISPLACED_FLAGS
+            .iter()
+            .find(|(sub, misplaced, ..)| *sub == parts[sub_index] && *misplaced == flag)?;
+
+        let flag_index = parts.iter().position(|p| *p == flag)?;
+        let end = if takes_value {
+            (flag_index + 2).min(parts.len())
+        } else {
+            flag_index + 1
+        };
+        let moved: Vec<String> = parts.drain(flag_index..end).collect();
+
+        let join = |parts: &[String]| {
+            parts
+                .iter()
+                .map(|p| shell_quote(p))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let moved_to = format!("{} {} {}", parts[0], right, join(&moved));
+        Some(match placement {
+            FlagPlacement::Before => and_commands(&moved_to, &join(&parts)),
+            FlagPlacement::After => and_commands(&join(&parts), &moved_to),
+            FlagPlacement::Instead => {
+                parts[sub_index] = right.to_string();
+                parts.splice(sub_index + 1..sub_index + 1, moved);
+                join(&parts)
+            }
+        })
+    }
 }
 
 impl Default for GitFlagAfterFilename {
@@ -899,27 +1758,24 @@ impl Rule for GitFlagAfterFilename {
                 && cmd
                     .output
                     .contains("' must come before non-option arguments")
+            || Self::move_misplaced_flag(cmd).is_some()
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if let Some(moved) = Self::move_misplaced_flag(cmd) {
+            return vec![moved];
+        }
+
         let mut parts = cmd.script_parts().to_vec();
 
         // Extract the bad flag from the error message
-        let bad_flag = if let Some(cap) =
-            Regex::new(r"fatal: bad flag '([^']+)' used after filename")
-                .ok()
-                .and_then(|re| re.captures(&cmd.output))
-        {
-            cap.get(1).map(|m| m.as_str().to_string())
-        } else if let Some(cap) =
-            Regex::new(r"fatal: option '([^']+)' must come before non-option arguments")
-                .ok()
-                .and_then(|re| re.captures(&cmd.output))
-        {
-            cap.get(1).map(|m| m.as_str().to_string())
-        } else {
-            None
-        };
+        let bad_flag = regex!(r"fatal: bad flag '([^']+)' used after filename")
+            .captures(&cmd.output)
+            .or_else(|| {
+                regex!(r"fatal: option '([^']+)' must come before non-option arguments")
+                    .captures(&cmd.output)
+            })
+            .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()));
 
         if let Some(bad_flag) = bad_flag {
             if let Some(bad_flag_index) = parts.iter().position(|p| p == &bad_flag) {
@@ -1020,7 +1876,7 @@ impl Rule for GitLfsMistype {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the broken command from the error
-        let broken_re = Regex::new(r#"Error: unknown command "([^"]*)" for "git-lfs""#).unwrap();
+        let broken_re = regex!(r#"Error: unknown command "([^"]*)" for "git-lfs""#);
 
         if let Some(cap) = broken_re.captures(&cmd.output) {
             let broken_cmd = cap.get(1).map(|m| m.as_str()).unwrap_or("");
@@ -1082,14 +1938,7 @@ impl Rule for GitRebaseMergeDir {
         }
 
         // Sort by similarity to original command
-        let original = &cmd.script;
-        let mut scored: Vec<(f64, String)> = commands
-            .into_iter()
-            .map(|c| (strsim::jaro_winkler(original, &c), c))
-            .collect();
-        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-
-        scored.into_iter().map(|(_, c)| c).collect()
+        sort_by_similarity(&cmd.script, commands)
     }
 }
 
@@ -1202,6 +2051,39 @@ mod tests {
         assert!(!rule.is_match(&cmd));
     }
 
+    const PULL_DIVERGENT: &str =
+        "hint: You have divergent branches and need to specify how to reconcile them.\n\
+         hint: You can do so by running one of the following commands sometime before\n\
+         hint: your next pull:\n\
+         fatal: Need to specify how to reconcile divergent branches.\n";
+
+    #[test]
+    fn test_git_pull_divergent_matches() {
+        let rule = GitPullDivergent;
+        assert!(rule.is_match(&Command::new("git pull", PULL_DIVERGENT)));
+        assert!(rule.is_match(&Command::new("git pull origin main", PULL_DIVERGENT)));
+        assert!(!rule.is_match(&Command::new("git pull", "Already up to date.\n")));
+    }
+
+    #[test]
+    fn test_git_pull_divergent_commands() {
+        assert_eq!(
+            GitPullDivergent::commands_for("git pull origin main"),
+            vec![
+                "git pull --rebase origin main",
+                "git pull --no-rebase origin main",
+                "git config pull.rebase true && git pull origin main",
+            ]
+        );
+
+        assert_eq!(
+            GitPullDivergent
+                .get_new_command(&Command::new("git pull", PULL_DIVERGENT))
+                .len(),
+            3
+        );
+    }
+
     #[test]
     fn test_git_stash_matches() {
         let rule = GitStash;
@@ -1568,6 +2450,41 @@ mod tests {
         assert_eq!(new_commands[0], "git log -p README.md");
     }
 
+    #[test]
+    fn test_git_flag_after_filename_misplaced_flag() {
+        let rule = GitFlagAfterFilename;
+        let cmd = Command::new(
+            "git add src/lib.rs -m 'Fix parser'",
+            "error: unknown switch `m'\nusage: git add [<options>] [--] <pathspec>...\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git add src/lib.rs && git commit -m 'Fix parser'"]
+        );
+
+        let cmd = Command::new(
+            "git commit -A -m wip",
+            "error: unknown switch `A'\nusage: git commit [<options>] [--] <pathspec>...\n",
+        );
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git add -A && git commit -m wip"]
+        );
+
+        let cmd = Command::new(
+            "git checkout -c feature",
+            "error: unknown switch `c'\nusage: git checkout [<options>] <branch>\n",
+        );
+        assert_eq!(rule.get_new_command(&cmd), vec!["git switch -c feature"]);
+
+        // Ambiguous or unknown flags are left alone
+        assert!(!rule.is_match(&Command::new(
+            "git add -z file",
+            "error: unknown switch `z'\n",
+        )));
+    }
+
     #[test]
     fn test_git_help_aliased_matches() {
         let rule = GitHelpAliased;
@@ -1622,6 +2539,407 @@ mod tests {
         assert!(new_commands[0].contains("track"));
     }
 
+    const CHERRY_PICK_CONFLICT: &str = "Auto-merging src/main.rs\n\
+         CONFLICT (content): Merge conflict in src/main.rs\n\
+         error: could not apply 1a2b3c4... Fix parser\n\
+         hint: After resolving the conflicts, mark them with\n\
+         hint: \"git add/rm <pathspec>\", then run\n\
+         hint: \"git cherry-pick --continue\".\n";
+
+    #[test]
+    fn test_git_cherry_pick_matches() {
+        let rule = GitCherryPick;
+        assert!(rule.is_match(&Command::new(
+            "git cherry-pick 1a2b3c4",
+            CHERRY_PICK_CONFLICT
+        )));
+        assert!(rule.is_match(&Command::new(
+            "git cherry-pick 5d6e7f8",
+            "error: cherry-pick is already in progress\n\
+             hint: try \"git cherry-pick (--continue | --abort | --quit)\"\n\
+             fatal: cherry-pick failed",
+        )));
+        assert!(rule.is_match(&Command::new(
+            "git cherry-pick --continue",
+            "error: Committing is not possible because you have unmerged files.",
+        )));
+    }
+
+    #[test]
+    fn test_git_cherry_pick_no_match() {
+        let rule = GitCherryPick;
+        assert!(!rule.is_match(&Command::new(
+            "git cherry-pick 1a2b3c4",
+            "[main 9f8e7d6] Fix parser\n 1 file changed, 2 insertions(+)",
+        )));
+        assert!(!rule.is_match(&Command::new("git merge feature", CHERRY_PICK_CONFLICT)));
+    }
+
+    #[test]
+    fn test_git_cherry_pick_get_new_command() {
+        let rule = GitCherryPick;
+        let new_commands = rule.get_new_command(&Command::new(
+            "git cherry-pick 1a2b3c4",
+            CHERRY_PICK_CONFLICT,
+        ));
+        assert_eq!(new_commands.len(), 3);
+        assert!(new_commands.contains(&"git add . && git cherry-pick --continue".to_string()));
+        assert!(new_commands.contains(&"git cherry-pick --abort".to_string()));
+        assert!(new_commands.contains(&"git cherry-pick --skip".to_string()));
+
+        // Ordered by similarity to what was typed
+        let new_commands = rule.get_new_command(&Command::new(
+            "git cherry-pick --abrot",
+            "error: cherry-pick is already in progress",
+        ));
+        assert_eq!(new_commands[0], "git cherry-pick --abort");
+    }
+
+    #[test]
+    fn test_git_cherry_pick_empty() {
+        let rule = GitCherryPickEmpty;
+        let cmd = Command::new(
+            "git cherry-pick --continue",
+            "The previous cherry-pick is now empty, possibly due to conflict resolution.\n\
+             If you wish to commit it anyway, use:\n\n    git commit --allow-empty\n\n\
+             Otherwise, please use 'git cherry-pick --skip'",
+        );
+        assert!(rule.is_match(&cmd));
+        assert!(!rule.is_match(&Command::new("git cherry-pick --continue", "")));
+
+        let new_commands = rule.get_new_command(&cmd);
+        assert_eq!(new_commands.len(), 3);
+        assert!(new_commands
+            .contains(&"git commit --allow-empty && git cherry-pick --continue".to_string()));
+        assert!(new_commands.contains(&"git cherry-pick --abort".to_string()));
+
+        let new_commands = rule.get_new_command(&Command::new(
+            "git cherry-pick --skpi",
+            "The previous cherry-pick is now empty, possibly due to conflict resolution.",
+        ));
+        assert_eq!(new_commands[0], "git cherry-pick --skip");
+    }
+
+    #[test]
+    fn test_git_revert_merge() {
+        let rule = GitRevertMerge;
+        let output = "error: commit 4f2a9c1 is a merge but no -m option was given.\n\
+                      fatal: revert failed\n";
+        let cmd = Command::new("git revert 4f2a9c1", output);
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git revert -m 1 4f2a9c1", "git revert -m 2 4f2a9c1"]
+        );
+
+        let cmd = Command::new("git revert --no-edit 4f2a9c1", output);
+        assert_eq!(
+            rule.get_new_command(&cmd)[0],
+            "git revert --no-edit -m 1 4f2a9c1"
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git revert 4f2a9c1",
+            "[main 8b7c6d5] Revert \"Fix parser\"",
+        )));
+    }
+
+    #[test]
+    fn test_git_shallow_clone() {
+        let rule = GitShallowClone;
+        let cmd = Command::new(
+            "git push mirror main",
+            "To github.com:user/mirror.git\n\
+              ! [remote rejected] main -> main (shallow update not allowed)\n\
+             error: failed to push some refs to 'github.com:user/mirror.git'\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git fetch --unshallow && git push mirror main"]
+        );
+
+        let cmd = Command::new(
+            "git log --follow src/lib.rs",
+            "fatal: this operation must be run in a shallow repository\n",
+        );
+        assert!(rule.is_match(&cmd));
+
+        assert!(!rule.is_match(&Command::new(
+            "git fetch --unshallow",
+            "fatal: --unshallow on a complete repository does not make sense\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git push", "Everything up-to-date\n")));
+    }
+
+    #[test]
+    fn test_git_gpg_sign() {
+        let output = "error: gpg failed to sign the data\n\
+                      fatal: failed to write commit object\n";
+        let cmd = Command::new("git commit -m 'Fix parser'", output);
+
+        assert!(GitGpgTty.is_match(&cmd));
+        assert_eq!(
+            GitGpgTty.get_new_command(&cmd),
+            vec!["export GPG_TTY=$(tty) && git commit -m 'Fix parser'"]
+        );
+
+        assert!(GitNoGpgSign.is_match(&cmd));
+        assert!(!GitNoGpgSign.enabled_by_default());
+        assert_eq!(
+            GitNoGpgSign.get_new_command(&cmd),
+            vec!["git commit --no-gpg-sign -m 'Fix parser'"]
+        );
+
+        let cmd = Command::new("export GPG_TTY=$(tty) && git commit --no-gpg-sign", output);
+        assert!(!GitGpgTty.is_match(&cmd));
+        assert!(!GitNoGpgSign.is_match(&cmd));
+    }
+
+    #[test]
+    fn test_git_auth_failure() {
+        let rule = GitAuthFailure;
+        let cmd = Command::new(
+            "git push origin main",
+            "remote: Invalid username or password.\n\
+             fatal: Authentication failed for 'https://github.com/user/repo.git/'\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitAuthFailure::suggestions(&cmd, "origin", "https://github.com/user/repo.git"),
+            vec![
+                "gh auth login && git push origin main",
+                "git remote set-url origin git@github.com:user/repo.git && git push origin main",
+            ]
+        );
+
+        let cmd = Command::new(
+            "git push",
+            "git@gitlab.com: Permission denied (publickey).\n\
+             fatal: Could not read from remote repository.\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitAuthFailure::suggestions(&cmd, "origin", "git@gitlab.com:user/repo.git"),
+            vec!["ssh-add && git push"]
+        );
+
+        assert!(!rule.is_match(&Command::new("git push", "Everything up-to-date\n")));
+    }
+
+    #[test]
+    fn test_git_auth_failure_clone() {
+        let rule = GitAuthFailure;
+        let cmd = Command::new(
+            "git clone https://gitlab.com/org/tool",
+            "fatal: unable to access 'https://gitlab.com/org/tool/': \
+             The requested URL returned error: 403\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git clone git@gitlab.com:org/tool.git"]
+        );
+    }
+
+    #[test]
+    fn test_git_user_identity() {
+        let rule = GitUserIdentity;
+        let cmd = Command::new(
+            "git commit -m 'Initial commit'",
+            "Author identity unknown\n\n\
+             *** Please tell me who you are.\n\n\
+             Run\n\n  git config --global user.email \"you@example.com\"\n\
+               git config --global user.name \"Your Name\"\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitUserIdentity::identity_command(&cmd.script, None, None),
+            "git config --global user.email 'you@example.com' && \
+             git config --global user.name 'Your Name' && git commit -m 'Initial commit'"
+        );
+        assert_eq!(
+            GitUserIdentity::identity_command(
+                "git commit",
+                Some("ada@example.org".to_string()),
+                Some("Ada Lovelace".to_string())
+            ),
+            "git config --global user.email 'ada@example.org' && \
+             git config --global user.name 'Ada Lovelace' && git commit"
+        );
+
+        assert!(!rule.is_match(&Command::new("git commit", "nothing to commit\n")));
+    }
+
+    #[test]
+    fn test_git_bisect_start() {
+        let rule = GitBisectStart;
+        let output = "You need to start by \"git bisect start\"\n\
+                      Do you want me to do it for you [Y/n]? ";
+        let cmd = Command::new("git bisect bad", output);
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git bisect start && git bisect bad"]
+        );
+
+        let cmd = Command::new("git bisect good v2.1.0", output);
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec![
+                "git bisect start && git bisect good v2.1.0",
+                "git bisect start HEAD v2.1.0",
+            ]
+        );
+
+        // Older git quotes the command differently
+        assert!(rule.is_match(&Command::new(
+            "git bisect skip",
+            "You need to start by `git bisect start'\n",
+        )));
+        assert!(!rule.is_match(&Command::new(
+            "git bisect good",
+            "Bisecting: 3 revisions left to test after this (roughly 2 steps)\n",
+        )));
+    }
+
+    #[test]
+    fn test_git_rebase_invalid_upstream() {
+        let rule = GitRebaseInvalidUpstream;
+        let cmd = Command::new("git rebase mian", "fatal: invalid upstream 'mian'\n");
+        assert!(rule.is_match(&cmd));
+        let branches = vec!["main".to_string(), "feature/login".to_string()];
+        assert_eq!(
+            GitRebaseInvalidUpstream::suggestions(&cmd, "mian", &branches),
+            vec!["git rebase main"]
+        );
+
+        let cmd = Command::new(
+            "git rebase --onto mian feature/login",
+            "fatal: Does not point to a valid commit 'mian'\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitRebaseInvalidUpstream::suggestions(&cmd, "mian", &branches),
+            vec!["git rebase --onto main feature/login"]
+        );
+
+        assert!(!rule.is_match(&Command::new(
+            "git rebase -i HEAD~5",
+            "fatal: invalid upstream 'HEAD~5'\n",
+        )));
+    }
+
+    #[test]
+    fn test_git_rebase_root() {
+        let rule = GitRebaseRoot;
+        let cmd = Command::new("git rebase -i HEAD~5", "fatal: invalid upstream 'HEAD~5'\n");
+        assert!(rule.is_match(&cmd));
+        assert_eq!(rule.get_new_command(&cmd), vec!["git rebase -i --root"]);
+
+        assert!(!rule.is_match(&Command::new(
+            "git rebase mian",
+            "fatal: invalid upstream 'mian'\n",
+        )));
+    }
+
+    #[test]
+    fn test_git_remote_typo() {
+        let rule = GitRemoteTypo;
+        let remotes = vec!["origin".to_string(), "upstream".to_string()];
+
+        let cmd = Command::new(
+            "git remote remove orgin",
+            "error: No such remote: 'orgin'\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitRemoteTypo::suggestions(&cmd, "orgin", &remotes),
+            vec!["git remote remove origin"]
+        );
+
+        let cmd = Command::new(
+            "git fetch upstrem",
+            "fatal: 'upstrem' does not appear to be a git repository\n\
+             fatal: Could not read from remote repository.\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitRemoteTypo::suggestions(&cmd, "upstrem", &remotes),
+            vec!["git fetch upstream"]
+        );
+
+        // A path is not a remote name
+        assert!(!rule.is_match(&Command::new(
+            "git pull ../other",
+            "fatal: '../other' does not appear to be a git repository\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git fetch origin", "")));
+    }
+
+    #[test]
+    fn test_git_stash_ref() {
+        let rule = GitStashRef;
+        let cmd = Command::new(
+            "git stash pop stash{1}",
+            "error: stash{1} is not a valid reference\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitStashRef::suggestions(&cmd, 3),
+            vec!["git stash pop 'stash@{1}'"]
+        );
+
+        // Past the end of the stash, the last entry is the nearest
+        let cmd = Command::new(
+            "git stash apply stash@{5}",
+            "fatal: log for 'stash' only has 2 entries\n",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            GitStashRef::suggestions(&cmd, 2),
+            vec!["git stash apply 'stash@{1}'"]
+        );
+        assert!(GitStashRef::suggestions(&cmd, 0).is_empty());
+
+        assert!(!rule.is_match(&Command::new("git stash pop", "Dropped refs/stash@{0}\n")));
+    }
+
+    #[test]
+    fn test_git_clean() {
+        let cmd = Command::new(
+            "git clean",
+            "fatal: clean.requireForce defaults to true and neither -i, -n, nor -f given; \
+             refusing to clean\n",
+        );
+        assert!(GitCleanDryRun.is_match(&cmd));
+        assert!(GitCleanForce.is_match(&cmd));
+        assert_eq!(GitCleanDryRun.get_new_command(&cmd), vec!["git clean -n"]);
+        assert_eq!(GitCleanForce.get_new_command(&cmd), vec!["git clean -f"]);
+        assert!(GitCleanDryRun.priority() < GitCleanForce.priority());
+        assert!(!GitCleanForce.enabled_by_default());
+
+        // Directories are only removed when asked for
+        let cmd = Command::new(
+            "git clean -d",
+            "fatal: clean.requireForce defaults to true and neither -i, -n, nor -f given; \
+             refusing to clean\n",
+        );
+        assert_eq!(GitCleanForce.get_new_command(&cmd), vec!["git clean -f -d"]);
+
+        // Newer git words the error differently
+        let cmd = Command::new(
+            "git clean -x",
+            "fatal: clean.requireForce is true and -f not given: refusing to clean\n",
+        );
+        assert_eq!(
+            GitCleanDryRun.get_new_command(&cmd),
+            vec!["git clean -n -x"]
+        );
+
+        assert!(!GitCleanForce.is_match(&Command::new("git clean -n", "Would remove out/\n")));
+    }
+
     #[test]
     fn test_git_rebase_merge_dir_matches() {
         let rule = GitRebaseMergeDir;
@@ -1730,4 +3048,20 @@ mod tests {
         assert!(new_commands[0].contains("--cached"));
         assert!(new_commands[1].contains("-f"));
     }
+
+    #[test]
+    fn test_git_pull_clone_matches_localized_output() {
+        let rule = GitPullClone;
+        let cmd = Command::new(
+            "git pull https://github.com/user/repo.git",
+            "fatal: not a git repository (or any of the parent directories): .git",
+        );
+        assert!(rule.is_match(&cmd));
+        let cmd = Command::new(
+            "git pull https://github.com/user/repo.git",
+            "fatal: Kein Git-Repository (oder irgendeines der Elternverzeichnisse): .git",
+        );
+        assert!(rule.is_match(&cmd));
+        assert_eq!(rule.get_new_command(&cmd), vec!["git clone"]);
+    }
 }
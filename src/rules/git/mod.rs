@@ -10,17 +10,24 @@
 //! - `GitPushForce` - Suggests force push when regular push is rejected
 //! - `GitPushWithoutCommits` - Detects push without any commits
 //! - `GitPushDifferentBranchNames` - Handles push with different local/remote branch names
+//! - `GitPushTag` - Pushes a tag that a plain push left behind
 //!
 //! ## Checkout Rules (`checkout.rs`)
 //! - `GitCheckout` - Suggests similar branch names when checkout fails
 //! - `GitCheckoutUncommittedChanges` - Suggests stashing when checkout fails due to changes
 //! - `GitMainMaster` - Handles main/master branch confusion
+//! - `GitSwitchRestore` - Suggests git restore when git switch is given a file
+//! - `GitSwitchCreate` - Suggests similar branches or -c when git switch can't find a branch
+//! - `GitRestoreBranch` - Suggests git switch when git restore is given a branch
+//! - `GitFetchPrune` - Prunes stale remote branches when a remote ref is gone
+//! - `GitDetachedHead` - Keeps work made on a detached HEAD by branching off it
 //!
 //! ## Add Rules (`add.rs`)
 //! - `GitAdd` - Adds untracked files that need to be added
 //! - `GitAddForce` - Suggests -f flag for ignored files
 //! - `GitCommitAdd` - Suggests -a flag when committing without staged changes
 //! - `GitAddAll` - Suggests adding all files
+//! - `GitPathspecTypo` - Suggests the closest known file for a mistyped pathspec
 //!
 //! ## Branch Rules (`branch.rs`)
 //! - `GitBranchDelete` - Suggests -D when -d fails for unmerged branch
@@ -29,6 +36,8 @@
 //! - `GitBranchNotFound` - Suggests similar branch names
 //! - `GitBranchList` - Fixes branch listing syntax
 //! - `GitBranchFlagPosition` - Fixes flag position in branch commands
+//! - `GitBranchRenameExists` - Suggests -M when renaming onto an existing branch
+//! - `GitBranchRenameWorktree` - Renames a branch from the worktree it is checked out in
 //!
 //! ## Not Command Rules (`not_command.rs`)
 //! - `GitNotCommand` - Fixes unknown git commands using git's suggestions
@@ -38,8 +47,10 @@
 //! ## Common Rules (`common.rs`)
 //! - `GitPull` - Fixes pull when there's no upstream
 //! - `GitPullUncommittedChanges` - Suggests stashing for pull
+//! - `GitPullDivergent` - Suggests a strategy for pulling divergent branches
 //! - `GitStash` - Suggests stashing when needed
 //! - `GitStashPop` - Handles stash pop conflicts
+//! - `GitStashRef` - Quotes stash references and picks an existing stash entry
 //! - `GitCommitAmend` - Suggests amend for empty commit message
 //! - `GitCommitReset` - Suggests reset after nothing to commit
 //! - `GitDiffStaged` - Suggests --staged when diff is empty
@@ -47,13 +58,27 @@
 //! - `GitMergeUnrelated` - Handles unrelated histories
 //! - `GitRebase` - Handles rebase conflicts
 //! - `GitRebaseNoChanges` - Suggests skip when rebase has no changes
+//! - `GitRebaseInvalidUpstream` - Suggests the closest branch for a misspelled rebase target
+//! - `GitRebaseRoot` - Rebases from the root when HEAD~n goes past the first commit
+//! - `GitCherryPick` - Handles cherry-pick conflicts
+//! - `GitCherryPickEmpty` - Suggests skip or an empty commit for an empty cherry-pick
+//! - `GitRevertMerge` - Adds the mainline parent when reverting a merge
+//! - `GitShallowClone` - Fetches full history when a shallow clone gets in the way
+//! - `GitGpgTty` - Points GPG_TTY at the terminal when signing fails
+//! - `GitNoGpgSign` - Commits without a signature when signing fails (disabled by default)
+//! - `GitAuthFailure` - Suggests ways to authenticate when the remote refuses access
+//! - `GitUserIdentity` - Configures user.email and user.name when they are missing
 //! - `GitRmLocalModifications` - Suggests -f for rm on modified files
 //! - `GitRmRecursive` - Suggests -r for rm on directories
+//! - `GitCleanDryRun` - Previews what git clean would remove
+//! - `GitCleanForce` - Forces git clean to remove untracked files (disabled by default)
 //! - `GitRemoteDelete` - Fixes remote delete syntax
+//! - `GitRemoteTypo` - Suggests the closest remote for a misspelled remote name
 //! - `GitTagForce` - Suggests -f for existing tags
 //! - `GitHookBypass` - Suggests --no-verify to bypass hooks
 //! - `GitPullClone` - Suggests clone when pull fails in non-repo
 //! - `GitCloneGitClone` - Fixes "git clone git clone" typo
+//! - `GitBisectStart` - Starts a bisect before marking commits good or bad
 
 pub mod add;
 pub mod branch;
@@ -66,8 +91,8 @@ pub mod support;
 // Re-export support types and functions
 pub use support::{
     and_commands, expand_git_alias, get_all_matched_commands, get_branches, get_close_matches,
-    get_closest, get_current_branch, is_app, is_git_command, replace_argument, replace_command,
-    GitSupport,
+    get_closest, get_current_branch, get_files, get_remotes, is_app, is_git_command,
+    replace_argument, replace_command, GitSupport,
 };
 
 // Re-export core types (Command and Rule come from crate::core via support)
@@ -75,19 +100,23 @@ pub use crate::core::{Command, Rule};
 
 // Re-export push rules
 pub use push::{
-    GitPush, GitPushDifferentBranchNames, GitPushForce, GitPushPull, GitPushWithoutCommits,
+    GitPush, GitPushDifferentBranchNames, GitPushForce, GitPushPull, GitPushTag,
+    GitPushWithoutCommits,
 };
 
 // Re-export checkout rules
-pub use checkout::{GitCheckout, GitCheckoutUncommittedChanges, GitMainMaster};
+pub use checkout::{
+    GitCheckout, GitCheckoutUncommittedChanges, GitDetachedHead, GitFetchPrune, GitMainMaster,
+    GitRestoreBranch, GitSwitchCreate, GitSwitchRestore,
+};
 
 // Re-export add rules
-pub use add::{GitAdd, GitAddAll, GitAddForce, GitCommitAdd};
+pub use add::{GitAdd, GitAddAll, GitAddForce, GitCommitAdd, GitPathspecTypo};
 
 // Re-export branch rules
 pub use branch::{
     GitBranchDelete, GitBranchDeleteCheckedOut, GitBranchExists, GitBranchFlagPosition,
-    GitBranchList, GitBranchNotFound,
+    GitBranchList, GitBranchNotFound, GitBranchRenameExists, GitBranchRenameWorktree,
 };
 
 // Re-export not_command rules
@@ -96,7 +125,13 @@ pub use not_command::{GitCommandTypo, GitNotCommand, GitTwoDashes};
 // Re-export common rules
 pub use common::{
     // New rules
+    GitAuthFailure,
+    GitBisectStart,
     GitBisectUsage,
+    GitCherryPick,
+    GitCherryPickEmpty,
+    GitCleanDryRun,
+    GitCleanForce,
     GitCloneGitClone,
     GitCloneMissing,
     GitCommitAmend,
@@ -105,25 +140,35 @@ pub use common::{
     GitDiffStaged,
     GitFixStash,
     GitFlagAfterFilename,
+    GitGpgTty,
     GitHelpAliased,
     GitHookBypass,
     GitLfsMistype,
     GitMerge,
     GitMergeUnrelated,
+    GitNoGpgSign,
     GitPull,
     GitPullClone,
+    GitPullDivergent,
     GitPullUncommittedChanges,
     GitRebase,
+    GitRebaseInvalidUpstream,
     GitRebaseMergeDir,
     GitRebaseNoChanges,
+    GitRebaseRoot,
     GitRemoteDelete,
     GitRemoteSeturlAdd,
+    GitRemoteTypo,
+    GitRevertMerge,
     GitRmLocalModifications,
     GitRmRecursive,
     GitRmStaged,
+    GitShallowClone,
     GitStash,
     GitStashPop,
+    GitStashRef,
     GitTagForce,
+    GitUserIdentity,
 };
 
 /// Returns all git rules.
@@ -138,15 +183,22 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(GitPushForce::new()),
         Box::new(GitPushWithoutCommits::new()),
         Box::new(GitPushDifferentBranchNames::new()),
+        Box::new(GitPushTag::new()),
         // Checkout rules
         Box::new(GitCheckout::new()),
         Box::new(GitCheckoutUncommittedChanges::new()),
         Box::new(GitMainMaster::new()),
+        Box::new(GitSwitchRestore::new()),
+        Box::new(GitSwitchCreate::new()),
+        Box::new(GitRestoreBranch::new()),
+        Box::new(GitFetchPrune::new()),
+        Box::new(GitDetachedHead::new()),
         // Add rules
         Box::new(GitAdd::new()),
         Box::new(GitAddForce::new()),
         Box::new(GitCommitAdd::new()),
         Box::new(GitAddAll::new()),
+        Box::new(GitPathspecTypo::new()),
         // Branch rules
         Box::new(GitBranchDelete::new()),
         Box::new(GitBranchDeleteCheckedOut::new()),
@@ -154,6 +206,8 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(GitBranchNotFound::new()),
         Box::new(GitBranchList::new()),
         Box::new(GitBranchFlagPosition::new()),
+        Box::new(GitBranchRenameExists::new()),
+        Box::new(GitBranchRenameWorktree::new()),
         // Not command rules
         Box::new(GitNotCommand::new()),
         Box::new(GitCommandTypo::new()),
@@ -161,8 +215,10 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         // Common rules
         Box::new(GitPull::new()),
         Box::new(GitPullUncommittedChanges::new()),
+        Box::new(GitPullDivergent::new()),
         Box::new(GitStash::new()),
         Box::new(GitStashPop::new()),
+        Box::new(GitStashRef::new()),
         Box::new(GitCommitAmend::new()),
         Box::new(GitCommitReset::new()),
         Box::new(GitDiffStaged::new()),
@@ -170,15 +226,29 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(GitMergeUnrelated::new()),
         Box::new(GitRebase::new()),
         Box::new(GitRebaseNoChanges::new()),
+        Box::new(GitRebaseInvalidUpstream::new()),
+        Box::new(GitRebaseRoot::new()),
+        Box::new(GitCherryPick::new()),
+        Box::new(GitCherryPickEmpty::new()),
+        Box::new(GitRevertMerge::new()),
+        Box::new(GitShallowClone::new()),
+        Box::new(GitGpgTty::new()),
+        Box::new(GitNoGpgSign::new()),
+        Box::new(GitAuthFailure::new()),
+        Box::new(GitUserIdentity::new()),
         Box::new(GitRmLocalModifications::new()),
         Box::new(GitRmRecursive::new()),
+        Box::new(GitCleanDryRun::new()),
+        Box::new(GitCleanForce::new()),
         Box::new(GitRemoteDelete::new()),
+        Box::new(GitRemoteTypo::new()),
         Box::new(GitTagForce::new()),
         Box::new(GitHookBypass::new()),
         Box::new(GitPullClone::new()),
         Box::new(GitCloneGitClone::new()),
         // New common rules
         Box::new(GitBisectUsage::new()),
+        Box::new(GitBisectStart::new()),
         Box::new(GitCloneMissing::new()),
         Box::new(GitDiffNoIndex::new()),
         Box::new(GitFixStash::new()),
@@ -199,26 +269,37 @@ pub fn rule_names() -> Vec<&'static str> {
         "git_push_force",
         "git_push_without_commits",
         "git_push_different_branch_names",
+        "git_push_tag",
         "git_checkout",
         "git_checkout_uncommitted_changes",
         "git_main_master",
+        "git_switch_restore",
+        "git_switch_create",
+        "git_restore_branch",
+        "git_fetch_prune",
+        "git_detached_head",
         "git_add",
         "git_add_force",
         "git_commit_add",
         "git_add_all",
+        "git_pathspec_typo",
         "git_branch_delete",
         "git_branch_delete_checked_out",
         "git_branch_exists",
         "git_branch_not_found",
         "git_branch_list",
         "git_branch_0flag",
+        "git_branch_rename_exists",
+        "git_branch_rename_worktree",
         "git_not_command",
         "git_command_typo",
         "git_two_dashes",
         "git_pull",
         "git_pull_uncommitted_changes",
+        "git_pull_divergent",
         "git_stash",
         "git_stash_pop",
+        "git_stash_ref",
         "git_commit_amend",
         "git_commit_reset",
         "git_diff_staged",
@@ -226,15 +307,29 @@ pub fn rule_names() -> Vec<&'static str> {
         "git_merge_unrelated",
         "git_rebase",
         "git_rebase_no_changes",
+        "git_rebase_invalid_upstream",
+        "git_rebase_root",
+        "git_cherry_pick",
+        "git_cherry_pick_empty",
+        "git_revert_merge",
+        "git_shallow_clone",
+        "git_gpg_tty",
+        "git_no_gpg_sign",
+        "git_auth_failure",
+        "git_user_identity",
         "git_rm_local_modifications",
         "git_rm_recursive",
+        "git_clean_dry_run",
+        "git_clean_force",
         "git_remote_delete",
+        "git_remote_typo",
         "git_tag_force",
         "git_hook_bypass",
         "git_pull_clone",
         "git_clone_git_clone",
         // New rules
         "git_bisect_usage",
+        "git_bisect_start",
         "git_clone_missing",
         "git_diff_no_index",
         "git_fix_stash",
@@ -2,9 +2,8 @@
 //!
 //! This module contains rules for fixing unknown git commands.
 
-use regex::Regex;
-
 use super::support::{get_all_matched_commands, replace_command, Command, GitSupport, Rule};
+use crate::regex;
 
 /// Rule for handling unknown git commands.
 ///
@@ -38,7 +37,7 @@ impl Rule for GitNotCommand {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the broken command from the error message
-        let re = Regex::new(r"git: '([^']*)' is not a git command").unwrap();
+        let re = regex!(r"git: '([^']*)' is not a git command");
         let broken_cmd = match re.captures(&cmd.output) {
             Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or(""),
             None => return vec![],
@@ -127,7 +126,7 @@ impl Rule for GitCommandTypo {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the broken command
-        let re = Regex::new(r"git: '([^']*)' is not a git command").unwrap();
+        let re = regex!(r"git: '([^']*)' is not a git command");
         let broken_cmd = match re.captures(&cmd.output) {
             Some(captures) => captures.get(1).map(|m| m.as_str()).unwrap_or(""),
             None => return vec![],
@@ -172,7 +171,7 @@ impl Rule for GitTwoDashes {
 
     fn is_match(&self, cmd: &Command) -> bool {
         // Look for single-dash long options that should be double-dash
-        let re = Regex::new(r" -([a-z]{2,})").unwrap();
+        let re = regex!(r" -([a-z]{2,})");
         re.is_match(&cmd.script)
             && (cmd.output.contains("error: unknown switch")
                 || cmd.output.contains("error: did you mean"))
@@ -180,7 +179,7 @@ impl Rule for GitTwoDashes {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Replace single-dash long options with double-dash
-        let re = Regex::new(r" -([a-z]{2,})").unwrap();
+        let re = regex!(r" -([a-z]{2,})");
         let fixed = re.replace_all(&cmd.script, " --$1");
         vec![fixed.to_string()]
     }
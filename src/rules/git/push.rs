@@ -2,9 +2,11 @@
 //!
 //! This module contains rules for fixing common git push issues.
 
-use regex::Regex;
-
-use super::support::{and_commands, replace_argument, Command, GitSupport, Rule};
+use super::support::{and_commands, replace_argument, Command, GitSupport, Rule, RuleContext};
+use crate::core::get_history;
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::shell_quote;
 
 /// Rule for handling "git push" when there's no upstream branch set.
 ///
@@ -63,26 +65,24 @@ impl Rule for GitPush {
             }
         }
 
-        // Extract the suggested push command from git's output
-        let re = Regex::new(r"git push (.*)").unwrap();
-        if let Some(captures) = re.captures_iter(&cmd.output).last() {
-            let arguments = captures
-                .get(1)
-                .map(|m| m.as_str())
-                .unwrap_or("")
-                .replace("'", "\\'")
-                .trim()
-                .to_string();
-
-            let base_cmd = command_parts.join(" ");
-            return vec![replace_argument(
-                &base_cmd,
-                "push",
-                &format!("push {}", arguments),
-            )];
-        }
+        // Take the remote and branch from the push command git suggests, so
+        // the branch name is exactly the one git knows
+        let re = regex!(r"(?m)^\s*git push --set-upstream (\S+) (.+?)\s*$");
+        let Some(captures) = re.captures_iter(&cmd.output).last() else {
+            return vec![];
+        };
+        let arguments = format!(
+            "--set-upstream {} {}",
+            shell_quote(&captures[1]),
+            shell_quote(&captures[2])
+        );
 
-        vec![]
+        let base_cmd = command_parts.join(" ");
+        vec![
+            replace_argument(&base_cmd, "push", &format!("push {}", arguments)),
+            // Let later pushes of new branches set their upstream themselves
+            and_commands("git config --global push.autoSetupRemote true", &cmd.script),
+        ]
     }
 }
 
@@ -130,7 +130,13 @@ impl Rule for GitPushPull {
 
 /// Rule for handling push with force when needed.
 ///
-/// Matches when push is rejected and suggests force pushing.
+/// Matches when push is rejected and suggests force pushing. Suggests
+/// `--force-with-lease` unless configured otherwise:
+///
+/// ```toml
+/// [rule.git_push_force]
+/// force_with_lease = false  # suggest --force instead
+/// ```
 pub struct GitPushForce;
 
 impl GitPushForce {
@@ -160,8 +166,17 @@ impl Rule for GitPushForce {
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
         // Prefer --force-with-lease over --force for safety
-        vec![format!("{} --force-with-lease", cmd.script)]
+        let flag = if ctx.option("force_with_lease").unwrap_or(true) {
+            "--force-with-lease"
+        } else {
+            "--force"
+        };
+        vec![format!("{} {}", cmd.script, flag)]
     }
 
     fn priority(&self) -> i32 {
@@ -234,9 +249,7 @@ impl Rule for GitPushDifferentBranchNames {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the branch name from output
-        let re =
-            Regex::new(r"To push to the upstream branch on the remote, use\n\s+git push ([^\n]+)")
-                .unwrap();
+        let re = regex!(r"To push to the upstream branch on the remote, use\n\s+git push ([^\n]+)");
         if let Some(captures) = re.captures(&cmd.output) {
             let suggestion = captures.get(1).map(|m| m.as_str()).unwrap_or("");
             return vec![format!("git push {}", suggestion.trim())];
@@ -247,6 +260,106 @@ impl Rule for GitPushDifferentBranchNames {
     }
 }
 
+/// Rule for pushing a tag that was just created.
+///
+/// Matches when `git push` right after a `git tag` reports "Everything
+/// up-to-date", as plain pushes leave tags behind, and when a tag is pushed
+/// without naming the remote.
+pub struct GitPushTag;
+
+impl GitPushTag {
+    /// Creates a new GitPushTag rule wrapped with git support.
+    pub fn new() -> GitSupport<Self> {
+        GitSupport(GitPushTag)
+    }
+
+    /// The tag created by a `git tag` history entry, if it creates one.
+    fn created_tag(entry: &str) -> Option<String> {
+        let parts = shlex::split(entry)?;
+        if parts.len() < 3 || parts[0] != "git" || parts[1] != "tag" {
+            return None;
+        }
+
+        let mut args = parts[2..].iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" | "--delete" | "-l" | "--list" | "-v" | "--verify" => return None,
+                // Options taking a value
+                "-m" | "--message" | "-F" | "--file" | "-u" | "--local-user" => {
+                    args.next();
+                }
+                _ if arg.starts_with('-') => {}
+                _ => return Some(arg.clone()),
+            }
+        }
+        None
+    }
+
+    /// The tag created by the command run before `script`.
+    fn tag_from_history(script: &str, history: &[String]) -> Option<String> {
+        let mut previous = history.iter().rev().skip_while(|entry| *entry == script);
+        previous.next().and_then(|entry| Self::created_tag(entry))
+    }
+
+    /// The name git took for a remote, when it is actually a tag.
+    fn tag_as_remote(cmd: &Command) -> Option<String> {
+        let re = regex!(r"fatal: '([^']+)' does not appear to be a git repository");
+        let name = re.captures(&cmd.output)?.get(1)?.as_str();
+        let output = command_output("git", &["tag", "--list", name])?;
+        if String::from_utf8_lossy(&output.stdout).trim() == name {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for GitPushTag {
+    fn default() -> Self {
+        GitPushTag
+    }
+}
+
+impl Rule for GitPushTag {
+    fn name(&self) -> &str {
+        "git_push_tag"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        if !cmd.script.contains("push") {
+            return false;
+        }
+        if cmd.output.contains("Everything up-to-date") {
+            Self::tag_from_history(&cmd.script, &get_history(ctx.settings())).is_some()
+        } else {
+            Self::tag_as_remote(cmd).is_some()
+        }
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
+        let push_tag = if cmd.output.contains("Everything up-to-date") {
+            Self::tag_from_history(&cmd.script, &get_history(ctx.settings()))
+                .map(|tag| format!("git push origin {}", tag))
+        } else {
+            Self::tag_as_remote(cmd)
+                .map(|tag| replace_argument(&cmd.script, &tag, &format!("origin {}", tag)))
+        };
+
+        match push_tag {
+            Some(push_tag) => vec![push_tag, "git push --tags".to_string()],
+            None => vec![],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,8 +393,34 @@ mod tests {
              git push --set-upstream origin feature\n",
         );
         let new_commands = rule.get_new_command(&cmd);
-        assert!(!new_commands.is_empty());
-        assert!(new_commands[0].contains("--set-upstream origin feature"));
+        assert_eq!(
+            new_commands,
+            vec![
+                "git push --set-upstream origin feature",
+                "git config --global push.autoSetupRemote true && git push",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_git_push_uses_branch_name_from_output() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        std::env::remove_var("TF_SHELL");
+
+        let rule = GitPush;
+        let cmd = Command::new(
+            "git push --force",
+            "fatal: The current branch fix/it's-done has no upstream branch.\n\
+             To push the current branch and set the remote as upstream, use\n\n\
+             \x20   git push --set-upstream upstream fix/it's-done\n\n\
+             To have this happen automatically for branches without a tracking\n\
+             upstream, see 'push.autoSetupRemote' in 'git help config'.\n",
+        );
+        let new_commands = rule.get_new_command(&cmd);
+        assert_eq!(
+            new_commands[0],
+            "git push --set-upstream upstream 'fix/it'\\''s-done' --force"
+        );
     }
 
     #[test]
@@ -355,6 +494,27 @@ mod tests {
         assert!(new_commands[0].contains("--force-with-lease"));
     }
 
+    #[test]
+    fn test_git_push_force_option_plain_force() {
+        let rule = GitPushForce::new();
+        let cmd = Command::new(
+            "git push origin feature",
+            "! [rejected]        feature -> feature
+             error: failed to push some refs to 'github.com:user/repo.git'
+",
+        );
+        let settings = crate::config::Settings::builder()
+            .rule_option("git_push_force", "force_with_lease", false)
+            .build();
+        let ctx = RuleContext::new(&settings, "git_push_force");
+
+        assert!(rule.is_match_with_context(&cmd, &ctx));
+        assert_eq!(
+            rule.get_new_command_with_context(&cmd, &ctx),
+            vec!["git push origin feature --force"]
+        );
+    }
+
     #[test]
     fn test_git_push_without_commits_matches() {
         let rule = GitPushWithoutCommits;
@@ -376,6 +536,54 @@ mod tests {
         assert_eq!(new_commands, vec!["git commit"]);
     }
 
+    #[test]
+    fn test_git_push_tag_created_tag() {
+        assert_eq!(
+            GitPushTag::created_tag("git tag v1.2.0"),
+            Some("v1.2.0".into())
+        );
+        assert_eq!(
+            GitPushTag::created_tag("git tag -a -m 'Release 1.2' v1.2.0"),
+            Some("v1.2.0".into())
+        );
+        assert_eq!(GitPushTag::created_tag("git tag -d v1.2.0"), None);
+        assert_eq!(GitPushTag::created_tag("git tag"), None);
+        assert_eq!(GitPushTag::created_tag("git commit -m tag"), None);
+    }
+
+    #[test]
+    fn test_git_push_tag_after_tagging() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_HISTORY", "TF_ALIAS"]);
+        std::env::remove_var("TF_ALIAS");
+        std::env::set_var(
+            "TF_HISTORY",
+            "git commit -am release\ngit tag v1.2.0\ngit push",
+        );
+
+        let rule = GitPushTag;
+        let cmd = Command::new("git push", "Everything up-to-date\n");
+        assert!(rule.is_match(&cmd));
+        assert_eq!(
+            rule.get_new_command(&cmd),
+            vec!["git push origin v1.2.0", "git push --tags"]
+        );
+
+        // The tag wasn't the last thing done before pushing
+        std::env::set_var("TF_HISTORY", "git tag v1.2.0\ngit status\ngit push");
+        assert!(!rule.is_match(&cmd));
+    }
+
+    #[test]
+    fn test_git_push_tag_no_match() {
+        let rule = GitPushTag;
+        assert!(!rule.is_match(&Command::new(
+            "git push oops-no-such-remote",
+            "fatal: 'oops-no-such-remote' does not appear to be a git repository\n\
+             fatal: Could not read from remote repository.\n",
+        )));
+        assert!(!rule.is_match(&Command::new("git status", "Everything up-to-date\n")));
+    }
+
     #[test]
     fn test_git_push_wrapped() {
         let rule = GitPush::new();
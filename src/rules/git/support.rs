@@ -2,12 +2,14 @@
 //!
 //! This module provides helper functions and a wrapper type for git rules.
 
-use regex::Regex;
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::fuzzy::{get_close_matches_with, Algorithm, MatchOptions};
+use std::ops::Range;
 use std::path::Path;
-use std::process::Command as ProcessCommand;
 
 // Re-export core types for use by git rules
-pub use crate::core::{Command, Rule};
+pub use crate::core::{Command, Rule, RuleContext};
 
 /// Check if command is a git command (git or hub).
 pub fn is_git_command(cmd: &Command) -> bool {
@@ -29,35 +31,138 @@ pub fn is_app(cmd: &Command, app_names: &[&str]) -> bool {
     false
 }
 
-/// Expand git aliases from trace output.
+/// Expand git aliases, following aliases of aliases.
 ///
-/// When GIT_TRACE is enabled, git outputs alias expansion information.
-/// This function parses that output and returns a new command with
-/// the alias expanded.
+/// When GIT_TRACE is enabled, git outputs alias expansion information, which
+/// is used as is. Otherwise aliases are looked up with `git config`. Shell
+/// (`!`) aliases are only followed when they run git themselves, as in
+/// `!git log --oneline`; other shell aliases stay unexpanded.
 pub fn expand_git_alias(cmd: &Command) -> Command {
-    if !cmd.output.contains("trace: alias expansion:") {
-        return cmd.clone();
+    let traced = traced_aliases(&cmd.output);
+    let lookup = |alias: &str| {
+        if traced.is_empty() {
+            configured_alias(alias)
+        } else {
+            traced
+                .iter()
+                .find(|(name, _)| name == alias)
+                .map(|(_, expansion)| expansion.clone())
+        }
+    };
+
+    let mut script = cmd.script.clone();
+    let mut seen = Vec::new();
+    while let Some((span, alias)) = git_subcommand(&script) {
+        if seen.contains(&alias) {
+            break;
+        }
+        let Some(expansion) = lookup(&alias) else {
+            break;
+        };
+        let expansion = match expansion.strip_prefix('!') {
+            Some(shell) => match shell.trim_start().strip_prefix("git ") {
+                Some(args) => args.trim().to_string(),
+                None => break,
+            },
+            None => expansion,
+        };
+
+        // Replace only the subcommand, not option values that look like it
+        // (the `c` in `git -c x=y c`), keeping the rest of the script as typed
+        script.replace_range(span, &expansion);
+        seen.push(alias);
     }
 
-    let re = Regex::new(r"trace: alias expansion: ([^ ]*) => ([^\n]*)").unwrap();
-    if let Some(captures) = re.captures(&cmd.output) {
-        let alias = captures.get(1).map(|m| m.as_str()).unwrap_or("");
-        let expansion_raw = captures.get(2).map(|m| m.as_str()).unwrap_or("");
+    if script == cmd.script {
+        cmd.clone()
+    } else {
+        cmd.with_script(script)
+    }
+}
+
+/// Get the `(alias, expansion)` pairs from GIT_TRACE output, in order.
+fn traced_aliases(output: &str) -> Vec<(String, String)> {
+    if !output.contains("trace: alias expansion:") {
+        return Vec::new();
+    }
+    regex!(r"trace: alias expansion: ([^ ]*) => ([^\n]*)")
+        .captures_iter(output)
+        .map(|captures| {
+            (
+                captures[1].to_string(),
+                parse_git_quoted_expansion(&captures[2]),
+            )
+        })
+        .collect()
+}
 
-        // Parse the expansion (git quotes arguments like 'commit' '--amend')
-        let expansion = parse_git_quoted_expansion(expansion_raw);
+/// Look up an alias with `git config`.
+fn configured_alias(alias: &str) -> Option<String> {
+    let key = format!("alias.{}", alias);
+    let output = command_output("git", &["config", "--get", &key])?;
+    if !output.status.success() {
+        return None;
+    }
+    let expansion = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!expansion.is_empty()).then_some(expansion)
+}
 
-        // Replace the alias in the script with the expansion
-        let pattern = format!(r"\b{}\b", regex::escape(alias));
-        if let Ok(alias_re) = Regex::new(&pattern) {
-            let new_script = alias_re
-                .replace(&cmd.script, expansion.as_str())
-                .to_string();
-            return cmd.with_script(new_script);
+/// Get the git subcommand of a script and its byte range, skipping git's
+/// own options.
+fn git_subcommand(script: &str) -> Option<(Range<usize>, String)> {
+    let mut parts = shlex::split(script)?;
+    let spans = word_spans(script);
+    if spans.len() != parts.len() {
+        return None;
+    }
+    let index = subcommand_index(&parts)?;
+    Some((spans[index].clone(), parts.swap_remove(index)))
+}
+
+/// Get the byte ranges of the shell words in `script`, quotes included.
+fn word_spans(script: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in script.char_indices() {
+        if start.is_none() {
+            if c.is_whitespace() {
+                continue;
+            }
+            start = Some(i);
+        }
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => escaped = true,
+            (Some('"'), '"') => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) if c.is_whitespace() => {
+                spans.extend(start.take().map(|start| start..i));
+            }
+            (None, _) => {}
         }
     }
+    spans.extend(start.map(|start| start..script.len()));
+    spans
+}
 
-    cmd.clone()
+/// Get the index of the subcommand in the words of a git command.
+fn subcommand_index(parts: &[String]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(part) = parts.get(index) {
+        match part.as_str() {
+            // Options that take the next word as their value
+            "-C" | "-c" | "--git-dir" | "--work-tree" | "--namespace" => index += 1,
+            _ if part.starts_with('-') => {}
+            _ => return Some(index),
+        }
+        index += 1;
+    }
+    None
 }
 
 /// Parse git's quoted expansion format (e.g., "'commit' '--amend'")
@@ -95,6 +200,19 @@ impl<R: Rule> Rule for GitSupport<R> {
         self.0.get_new_command(&expanded)
     }
 
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        if !is_git_command(cmd) {
+            return false;
+        }
+        let expanded = expand_git_alias(cmd);
+        self.0.is_match_with_context(&expanded, ctx)
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
+        let expanded = expand_git_alias(cmd);
+        self.0.get_new_command_with_context(&expanded, ctx)
+    }
+
     fn priority(&self) -> i32 {
         self.0.priority()
     }
@@ -109,23 +227,19 @@ impl<R: Rule> Rule for GitSupport<R> {
 }
 
 /// Replace an argument in a command script.
+///
+/// See [`crate::utils::replace_argument`]; only whole words are replaced.
 pub fn replace_argument(script: &str, from: &str, to: &str) -> String {
-    // First try to replace at the end of the command
-    let end_pattern = format!(r" {}$", regex::escape(from));
-    if let Ok(re) = Regex::new(&end_pattern) {
-        let replaced = re.replace(script, format!(" {}", to));
-        if replaced != script {
-            return replaced.to_string();
-        }
-    }
-
-    // Otherwise replace the first occurrence in the middle
-    script.replacen(&format!(" {} ", from), &format!(" {} ", to), 1)
+    crate::utils::replace_argument(script, from, to)
 }
 
 /// Replace a command with a similar one from matched suggestions.
+///
+/// Case is ignored and swapped letters are favored, so `git STATSU` still
+/// finds `status`.
 pub fn replace_command(script: &str, broken: &str, matched: &[String]) -> Vec<String> {
-    let close_matches = get_close_matches(broken, matched, 3, 0.1);
+    let options = MatchOptions::typos().algorithm(Algorithm::JaroWinkler);
+    let close_matches = get_close_matches_with(broken, matched, 3, 0.1, options);
     close_matches
         .into_iter()
         .map(|new_cmd| replace_argument(script, broken, new_cmd.trim()))
@@ -183,13 +297,13 @@ pub fn get_all_matched_commands(stderr: &str, separators: &[&str]) -> Vec<String
 }
 
 /// Get list of git branches (local and remote).
+///
+/// `git branch` runs at most once per fix, however many rules ask.
 pub fn get_branches() -> Vec<String> {
-    let output = ProcessCommand::new("git")
-        .args(["branch", "-a", "--no-color", "--no-column"])
-        .output();
+    let output = command_output("git", &["branch", "-a", "--no-color", "--no-column"]);
 
     match output {
-        Ok(output) => {
+        Some(output) => {
             let stdout = String::from_utf8_lossy(&output.stdout);
             stdout
                 .lines()
@@ -221,16 +335,55 @@ pub fn get_branches() -> Vec<String> {
                 })
                 .collect()
         }
-        Err(_) => Vec::new(),
+        None => Vec::new(),
+    }
+}
+
+/// Get the files git knows about: tracked ones plus untracked, non-ignored ones.
+///
+/// Like [`get_branches`], each `git ls-files` runs at most once per fix.
+pub fn get_files() -> Vec<String> {
+    let mut files = ls_files(&["ls-files"]);
+    files.extend(ls_files(&["ls-files", "--others", "--exclude-standard"]));
+    files
+}
+
+fn ls_files(args: &[&str]) -> Vec<String> {
+    match command_output("git", args) {
+        Some(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
     }
 }
 
+/// Get the configured remotes as `(name, url)` pairs, from `git remote -v`.
+pub fn get_remotes() -> Vec<(String, String)> {
+    match command_output("git", &["remote", "-v"]) {
+        Some(output) if output.status.success() => {
+            parse_remotes(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Parse `git remote -v` output, keeping each remote's fetch URL.
+fn parse_remotes(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter(|line| line.ends_with("(fetch)"))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
 /// Get the current git branch name.
 pub fn get_current_branch() -> Option<String> {
-    let output = ProcessCommand::new("git")
-        .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .output()
-        .ok()?;
+    let output = command_output("git", &["rev-parse", "--abbrev-ref", "HEAD"])?;
 
     if output.status.success() {
         let branch = String::from_utf8_lossy(&output.stdout);
@@ -286,6 +439,90 @@ mod tests {
         assert_eq!(expanded.script, "git commit -m 'test'");
     }
 
+    #[test]
+    fn test_expand_git_alias_recursive() {
+        let cmd = Command::new(
+            "git c main",
+            "trace: alias expansion: c => 'co'\n\
+             trace: alias expansion: co => 'checkout'\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git checkout main");
+
+        // A typo in the alias still reaches rules like GitNotCommand
+        let cmd = Command::new(
+            "git c main",
+            "trace: alias expansion: c => 'co'\n\
+             trace: alias expansion: co => 'chekout'\n\
+             git: 'chekout' is not a git command. See 'git --help'.\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git chekout main");
+    }
+
+    #[test]
+    fn test_expand_git_alias_after_config_option() {
+        let cmd = Command::new(
+            "git -c x=y c main",
+            "trace: alias expansion: c => 'checkout'\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git -c x=y checkout main");
+
+        let cmd = Command::new(
+            "git -c 'user.name=A c' c -m \"c d\"",
+            "trace: alias expansion: c => 'commit'\n",
+        );
+        assert_eq!(
+            expand_git_alias(&cmd).script,
+            "git -c 'user.name=A c' commit -m \"c d\""
+        );
+    }
+
+    #[test]
+    fn test_word_spans() {
+        let script = "git  -c 'a b' c\\ d \"e'f\"";
+        let words: Vec<&str> = word_spans(script)
+            .into_iter()
+            .map(|span| &script[span])
+            .collect();
+        assert_eq!(words, vec!["git", "-c", "'a b'", "c\\ d", "\"e'f\""]);
+    }
+
+    #[test]
+    fn test_expand_git_alias_cycle() {
+        let cmd = Command::new(
+            "git a",
+            "trace: alias expansion: a => 'b'\ntrace: alias expansion: b => 'a'\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git a");
+    }
+
+    #[test]
+    fn test_expand_git_alias_shell() {
+        let cmd = Command::new(
+            "git lg -5",
+            "trace: alias expansion: lg => '!git log --oneline'\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git log --oneline -5");
+
+        let cmd = Command::new(
+            "git sync",
+            "trace: alias expansion: sync => '!f() { git pull && git push; }; f'\n",
+        );
+        assert_eq!(expand_git_alias(&cmd).script, "git sync");
+    }
+
+    #[test]
+    fn test_git_subcommand() {
+        assert_eq!(
+            git_subcommand("git status -s"),
+            Some((4..10, "status".to_string()))
+        );
+        assert_eq!(
+            git_subcommand("git -C ../app --no-pager log"),
+            Some((25..28, "log".to_string()))
+        );
+        assert_eq!(git_subcommand("git --version"), None);
+    }
+
     #[test]
     fn test_replace_argument() {
         assert_eq!(
@@ -306,6 +543,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_replace_argument_whole_words() {
+        assert_eq!(
+            replace_argument("git rm -r format", "rm", "mv"),
+            "git mv -r format"
+        );
+        assert_eq!(
+            replace_argument("git checkout format", "rm", "main"),
+            "git checkout format"
+        );
+    }
+
     #[test]
     fn test_get_close_matches() {
         let possibilities = vec![
@@ -318,6 +567,19 @@ mod tests {
         assert_eq!(matches[0], "commit");
     }
 
+    #[test]
+    fn test_replace_command_typos() {
+        let matched = vec!["stash".to_string(), "status".to_string()];
+        assert_eq!(
+            replace_command("git STATSU", "STATSU", &matched)[0],
+            "git status"
+        );
+        assert_eq!(
+            replace_command("git stauts -s", "stauts", &matched)[0],
+            "git status -s"
+        );
+    }
+
     #[test]
     fn test_get_all_matched_commands() {
         let output = "git: 'stats' is not a git command. See 'git --help'.\n\nThe most similar command is\n\tstatus\n\tstash";
@@ -325,6 +587,28 @@ mod tests {
         assert!(matched.contains(&"status".to_string()));
     }
 
+    #[test]
+    fn test_parse_remotes() {
+        let output = "origin\tgit@github.com:user/repo.git (fetch)\n\
+                      origin\tgit@github.com:user/repo.git (push)\n\
+                      upstream\thttps://github.com/org/repo.git (fetch)\n\
+                      upstream\thttps://github.com/org/repo.git (push)\n";
+        assert_eq!(
+            parse_remotes(output),
+            vec![
+                (
+                    "origin".to_string(),
+                    "git@github.com:user/repo.git".to_string()
+                ),
+                (
+                    "upstream".to_string(),
+                    "https://github.com/org/repo.git".to_string()
+                ),
+            ]
+        );
+        assert!(parse_remotes("").is_empty());
+    }
+
     #[test]
     fn test_and_commands() {
         assert_eq!(
@@ -15,16 +15,22 @@
 //! - [`frameworks`] - Language and framework rules (Python, Rails, React Native, Yarn, npm, etc.)
 //! - [`shell_utils`] - Shell utility rules (grep, sed, adb, hg, history, etc.)
 //! - [`misc`] - Miscellaneous correction rules
+//! - [`corrections`] - User-defined corrections from the `[corrections]` settings table
+//! - [`drop_in`] - Declarative and scripted rules from the rules directory
+//! - [`patterns`] - Error messages with their translations, for non-English locales
 
 pub mod cd;
 pub mod cloud;
+pub mod corrections;
 pub mod devtools;
 pub mod docker;
+pub mod drop_in;
 pub mod frameworks;
 pub mod git;
 pub mod misc;
 pub mod no_command;
 pub mod package_managers;
+pub mod patterns;
 pub mod shell_utils;
 pub mod sudo;
 pub mod system;
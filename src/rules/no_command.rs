@@ -2,13 +2,15 @@
 //!
 //! This rule matches commands that fail because the command is not found
 //! and suggests corrections by fuzzy matching against:
+//! - The user's shell aliases and functions
 //! - Executables in PATH
 //! - Commands from shell history (if available)
 
-use crate::core::{Command, Rule};
-use crate::utils::{get_all_executables, get_close_matches};
-use regex::Regex;
-use std::env;
+use crate::config::Settings;
+use crate::core::{get_history, Command, Rule, RuleContext};
+use crate::utils::executables::get_command_candidates;
+use crate::utils::fuzzy::{get_close_matches_with, MatchOptions};
+use crate::utils::regex_cache;
 
 /// Patterns that indicate a "command not found" error.
 const NOT_FOUND_PATTERNS: &[&str] = &[
@@ -52,7 +54,7 @@ fn extract_command_from_output(output: &str) -> Option<String> {
     ];
 
     for pattern in patterns {
-        if let Ok(re) = Regex::new(pattern) {
+        if let Ok(re) = regex_cache::get(pattern) {
             for line in output.lines() {
                 if let Some(caps) = re.captures(line) {
                     if let Some(m) = caps.get(1) {
@@ -75,8 +77,12 @@ fn extract_command_from_output(output: &str) -> Option<String> {
 /// Rule that suggests corrections for "command not found" errors.
 ///
 /// This rule uses fuzzy matching to find similar command names from:
-/// 1. Executables in the system PATH
-/// 2. Recent command history (if available via TF_HISTORY)
+/// 1. Aliases and functions passed by the shell integration
+/// 2. Executables in the system PATH
+/// 3. Recent command history (if available via TF_HISTORY)
+///
+/// An alias or function is suggested by its name, so the correction runs
+/// it the way the user defined it.
 ///
 /// # Example
 ///
@@ -94,19 +100,15 @@ pub struct NoCommand;
 
 impl NoCommand {
     /// Get commands from shell history.
-    fn get_history_commands() -> Vec<String> {
+    ///
+    /// Invocations of oops itself are skipped by [`get_history`].
+    fn get_history_commands(settings: &Settings) -> Vec<String> {
         let mut commands = Vec::new();
 
-        // Try to get history from TF_HISTORY environment variable
-        if let Ok(history) = env::var("TF_HISTORY") {
-            for line in history.lines() {
-                // Extract just the command name (first word)
-                if let Some(cmd) = line.split_whitespace().next() {
-                    // Skip oops-related commands
-                    if !["oops", "fuck", "thefuck", "tf"].contains(&cmd) {
-                        commands.push(cmd.to_string());
-                    }
-                }
+        for line in get_history(settings) {
+            // Extract just the command name (first word)
+            if let Some(cmd) = line.split_whitespace().next() {
+                commands.push(cmd.to_string());
             }
         }
 
@@ -118,11 +120,14 @@ impl NoCommand {
     }
 
     /// Build list of all possible command suggestions.
-    fn get_all_possible_commands() -> Vec<String> {
-        let mut commands: Vec<String> = get_all_executables().iter().cloned().collect();
+    fn get_all_possible_commands(settings: &Settings) -> Vec<String> {
+        let mut commands: Vec<String> = get_command_candidates()
+            .into_iter()
+            .map(|candidate| candidate.name)
+            .collect();
 
         // Add commands from history
-        let history_commands = Self::get_history_commands();
+        let history_commands = Self::get_history_commands(settings);
         for cmd in history_commands {
             if !commands.contains(&cmd) {
                 commands.push(cmd);
@@ -153,6 +158,10 @@ impl Rule for NoCommand {
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
         let parts = cmd.script_parts();
 
         if parts.is_empty() {
@@ -167,10 +176,11 @@ impl Rule for NoCommand {
             extract_command_from_output(&cmd.output).unwrap_or_else(|| misspelled.clone());
 
         // Get all possible commands
-        let all_commands = Self::get_all_possible_commands();
+        let all_commands = Self::get_all_possible_commands(ctx.settings());
 
         // Find close matches
-        let matches = get_close_matches(&cmd_to_match, &all_commands, 3, 0.6);
+        let matches =
+            get_close_matches_with(&cmd_to_match, &all_commands, 3, 0.6, MatchOptions::typos());
 
         if matches.is_empty() {
             return vec![];
@@ -318,7 +328,7 @@ mod tests {
 
     #[test]
     fn test_get_all_possible_commands() {
-        let commands = NoCommand::get_all_possible_commands();
+        let commands = NoCommand::get_all_possible_commands(&Settings::default());
         // Should find at least some executables on any system
         assert!(!commands.is_empty());
     }
@@ -326,8 +336,8 @@ mod tests {
     #[test]
     fn test_get_history_commands_empty() {
         // When TF_HISTORY is not set, should return empty vec
-        env::remove_var("TF_HISTORY");
-        let commands = NoCommand::get_history_commands();
+        std::env::remove_var("TF_HISTORY");
+        let commands = NoCommand::get_history_commands(&Settings::default());
         // May be empty or contain cached results, just verify it doesn't panic
         let _ = commands;
     }
@@ -7,8 +7,8 @@
 //! - `apt_list_upgradable` - Suggest apt list --upgradable
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Common APT operations for fuzzy matching.
 const APT_OPERATIONS: &[&str] = &[
@@ -146,11 +146,11 @@ impl Rule for AptGetSearch {
 
     fn get_new_command(&self, command: &Command) -> Vec<String> {
         // Simple regex replacement: apt-get -> apt-cache
-        if let Ok(re) = Regex::new(r"^apt-get") {
-            let new_cmd = re.replace(&command.script, "apt-cache").to_string();
-            if new_cmd != command.script {
-                return vec![new_cmd];
-            }
+        let new_cmd = regex!(r"^apt-get")
+            .replace(&command.script, "apt-cache")
+            .to_string();
+        if new_cmd != command.script {
+            return vec![new_cmd];
         }
 
         // Fallback: simple string replace
@@ -9,10 +9,13 @@
 //! - `brew_reinstall` - Suggest reinstall when install fails for already installed formula
 //! - `brew_uninstall` - Fix uninstall errors with --force flag
 //! - `brew_unknown_command` - Fix typos in brew commands
+//! - `brew_services_unknown` - Fix typos in `brew services` subcommands
+//! - `brew_cask_formula` - Add or drop `--cask` when a cask is confused with a formula
+//! - `brew_cask_app_exists` - Force or reinstall when the cask's app already exists
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Common brew commands for fuzzy matching.
 const BREW_COMMANDS: &[&str] = &[
@@ -107,22 +110,18 @@ impl BrewInstall {
     /// Parse suggestions from the "Did you mean" part of the error message.
     fn get_suggestions(output: &str) -> Vec<String> {
         // Look for the pattern: Did you mean <suggestions>?
-        let re = Regex::new(
-            r#"Warning: No available formula with the name "[^"]+"\. Did you mean (.+)\?"#,
-        );
-
-        if let Ok(re) = re {
-            if let Some(caps) = re.captures(output) {
-                if let Some(suggestions_match) = caps.get(1) {
-                    let suggestions_str = suggestions_match.as_str();
-                    // Parse "foo, bar or baz" or "foo or bar" format
-                    return suggestions_str
-                        .replace(" or ", ", ")
-                        .split(", ")
-                        .map(|s| s.trim().to_string())
-                        .filter(|s| !s.is_empty())
-                        .collect();
-                }
+        let re =
+            regex!(r#"Warning: No available formula with the name "[^"]+"\. Did you mean (.+)\?"#);
+        if let Some(caps) = re.captures(output) {
+            if let Some(suggestions_match) = caps.get(1) {
+                let suggestions_str = suggestions_match.as_str();
+                // Parse "foo, bar or baz" or "foo or bar" format
+                return suggestions_str
+                    .replace(" or ", ", ")
+                    .split(", ")
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
             }
         }
 
@@ -390,13 +389,8 @@ pub struct BrewReinstall;
 impl BrewReinstall {
     /// Check if the output indicates the formula is already installed.
     fn is_already_installed(output: &str) -> bool {
-        let warning_re = Regex::new(r"Warning: .+ is already installed and up-to-date").ok();
-        let message_re = Regex::new(r"To reinstall .+, run `brew reinstall").ok();
-
-        let has_warning = warning_re.map(|re| re.is_match(output)).unwrap_or(false);
-        let has_message = message_re.map(|re| re.is_match(output)).unwrap_or(false);
-
-        has_warning && has_message
+        regex!(r"Warning: .+ is already installed and up-to-date").is_match(output)
+            && regex!(r"To reinstall .+, run `brew reinstall").is_match(output)
     }
 }
 
@@ -502,7 +496,7 @@ pub struct BrewUnknownCommand;
 impl BrewUnknownCommand {
     /// Extract the unknown command from the error output.
     fn get_unknown_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"Error: Unknown command: ([a-z-]+)").ok()?;
+        let re = regex!(r"Error: Unknown command: ([a-z-]+)");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -554,6 +548,191 @@ impl Rule for BrewUnknownCommand {
     }
 }
 
+/// `brew services` subcommands for fuzzy matching.
+const BREW_SERVICES_COMMANDS: &[&str] = &[
+    "list", "info", "run", "start", "stop", "kill", "restart", "cleanup",
+];
+
+/// Insert `flag` after the brew subcommand, e.g. `brew install --cask x`.
+fn insert_flag(command: &Command, flag: &str) -> String {
+    let mut parts = command.script_parts().to_vec();
+    parts.insert(2.min(parts.len()), flag.to_string());
+    parts.join(" ")
+}
+
+/// Rule to fix typos in `brew services` subcommands.
+///
+/// Matches output containing:
+/// - `Error: Unknown subcommand: <cmd>`
+///
+/// # Example
+///
+/// ```text
+/// $ brew services strat postgresql
+/// Error: Unknown subcommand: strat
+///
+/// $ fuck
+/// brew services start postgresql
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrewServicesUnknown;
+
+impl BrewServicesUnknown {
+    /// Extract the unknown subcommand from the error output.
+    fn get_unknown_subcommand(output: &str) -> Option<String> {
+        let re = regex!(r"(?i)Error: Unknown subcommand: ([a-z-]+)");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for BrewServicesUnknown {
+    fn name(&self) -> &str {
+        "brew_services_unknown"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["brew"]) {
+            return false;
+        }
+
+        let parts = command.script_parts();
+        parts.get(1).map(String::as_str) == Some("services")
+            && Self::get_unknown_subcommand(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let unknown = match Self::get_unknown_subcommand(&command.output) {
+            Some(s) => s,
+            None => return vec![],
+        };
+
+        let subcommands: Vec<String> = BREW_SERVICES_COMMANDS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        get_close_matches(&unknown, &subcommands, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &unknown, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to fix commands that confuse a cask with a formula.
+///
+/// Matches output containing:
+/// - `Error: Cask '<name>' is not installed.` with `--cask`, which is
+///   dropped so the formula is used
+/// - `Error: No such keg: ...` or `Found a cask named "<name>" instead.`
+///   without `--cask`, which is added
+///
+/// # Example
+///
+/// ```text
+/// $ brew uninstall firefox
+/// Error: No such keg: /opt/homebrew/Cellar/firefox
+///
+/// $ fuck
+/// brew uninstall --cask firefox
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrewCaskFormula;
+
+impl BrewCaskFormula {
+    /// Check if the command used `--cask` for what is a formula.
+    fn is_cask_not_installed(command: &Command) -> bool {
+        command.script_parts().iter().any(|p| p == "--cask")
+            && regex!(r"Cask '[^']+' is not installed").is_match(&command.output)
+    }
+
+    /// Check if the command treated a cask as a formula.
+    fn is_formula_not_found(command: &Command) -> bool {
+        !command
+            .script_parts()
+            .iter()
+            .any(|p| p == "--cask" || p == "--formula")
+            && (command.output.contains("No such keg")
+                || command.output.contains("Found a cask named"))
+    }
+}
+
+impl Rule for BrewCaskFormula {
+    fn name(&self) -> &str {
+        "brew_cask_formula"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["brew"]) || command.script_parts().len() < 3 {
+            return false;
+        }
+
+        Self::is_cask_not_installed(command) || Self::is_formula_not_found(command)
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        if Self::is_cask_not_installed(command) {
+            let parts: Vec<&str> = command
+                .script_parts()
+                .iter()
+                .map(String::as_str)
+                .filter(|p| *p != "--cask")
+                .collect();
+            return vec![parts.join(" ")];
+        }
+
+        vec![insert_flag(command, "--cask")]
+    }
+}
+
+/// Rule to handle a cask whose app is already in place.
+///
+/// Matches output containing:
+/// - `Error: It seems there is already an App at '<path>'.`
+///
+/// Suggests overwriting the app with `--force`, or reinstalling the cask.
+///
+/// # Example
+///
+/// ```text
+/// $ brew install --cask firefox
+/// Error: It seems there is already an App at '/Applications/Firefox.app'.
+///
+/// $ fuck
+/// brew install --force --cask firefox
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrewCaskAppExists;
+
+impl Rule for BrewCaskAppExists {
+    fn name(&self) -> &str {
+        "brew_cask_app_exists"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["brew"]) {
+            return false;
+        }
+
+        !command.script_parts().iter().any(|p| p == "--force")
+            && command.output.contains("It seems there is already an App")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let parts = command.script_parts();
+        let mut fixes = vec![insert_flag(command, "--force")];
+
+        if parts.get(1).map(String::as_str) == Some("install") {
+            let mut reinstall = parts.to_vec();
+            reinstall[1] = "reinstall".to_string();
+            fixes.push(reinstall.join(" "));
+        }
+
+        fixes
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -987,4 +1166,110 @@ mod tests {
             assert!(fixes.contains(&"brew upgrade vim".to_string()));
         }
     }
+
+    mod brew_services_unknown_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(BrewServicesUnknown.name(), "brew_services_unknown");
+        }
+
+        #[test]
+        fn test_matches_unknown_subcommand() {
+            let cmd = Command::new(
+                "brew services strat postgresql",
+                "Error: Unknown subcommand: strat",
+            );
+            assert!(BrewServicesUnknown.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_brew_command() {
+            let cmd = Command::new("brew bundle strat", "Error: Unknown subcommand: strat");
+            assert!(!BrewServicesUnknown.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "brew services restat nginx",
+                "Error: Unknown subcommand: restat",
+            );
+            let fixes = BrewServicesUnknown.get_new_command(&cmd);
+            assert_eq!(fixes[0], "brew services restart nginx");
+        }
+    }
+
+    mod brew_cask_formula_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(BrewCaskFormula.name(), "brew_cask_formula");
+        }
+
+        #[test]
+        fn test_get_new_command_add_cask() {
+            let cmd = Command::new(
+                "brew uninstall firefox",
+                "Error: No such keg: /opt/homebrew/Cellar/firefox",
+            );
+            assert!(BrewCaskFormula.is_match(&cmd));
+            let fixes = BrewCaskFormula.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["brew uninstall --cask firefox"]);
+        }
+
+        #[test]
+        fn test_get_new_command_drop_cask() {
+            let cmd = Command::new(
+                "brew uninstall --cask wget",
+                "Error: Cask 'wget' is not installed.",
+            );
+            assert!(BrewCaskFormula.is_match(&cmd));
+            let fixes = BrewCaskFormula.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["brew uninstall wget"]);
+        }
+
+        #[test]
+        fn test_no_match_formula_flag() {
+            let cmd = Command::new(
+                "brew uninstall --formula firefox",
+                "Error: No such keg: /opt/homebrew/Cellar/firefox",
+            );
+            assert!(!BrewCaskFormula.is_match(&cmd));
+        }
+    }
+
+    mod brew_cask_app_exists_tests {
+        use super::*;
+
+        const OUTPUT: &str =
+            "Error: It seems there is already an App at '/Applications/Firefox.app'.";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(BrewCaskAppExists.name(), "brew_cask_app_exists");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("brew install --cask firefox", OUTPUT);
+            assert!(BrewCaskAppExists.is_match(&cmd));
+            let fixes = BrewCaskAppExists.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec![
+                    "brew install --force --cask firefox",
+                    "brew reinstall --cask firefox"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_no_match_already_forced() {
+            let cmd = Command::new("brew install --cask --force firefox", OUTPUT);
+            assert!(!BrewCaskAppExists.is_match(&cmd));
+        }
+    }
 }
@@ -2,10 +2,16 @@
 //!
 //! Contains rules for:
 //! - `cargo_no_command` - Suggest similar cargo subcommands when command not recognized
+//! - `cargo_missing_subcommand` - Install the component or crate providing a missing subcommand
+//! - `cargo_missing_dependency` - Add a crate the code uses but `Cargo.toml` doesn't declare
+//! - `cargo_unknown_feature` - Fix a misspelled feature using the ones cargo lists
+//! - `cargo_unknown_target` - Fix a misspelled `--bin` or `--example` target
 
 use crate::core::{is_app, Command, Rule};
-use crate::utils::replace_argument;
-use regex::Regex;
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::regex_cache;
+use crate::utils::{get_close_matches, replace_argument};
 
 /// Rule to suggest similar cargo subcommands when "no such subcommand" error.
 ///
@@ -26,7 +32,7 @@ impl CargoNoCommand {
     /// Extract the suggested fix from cargo's error output.
     fn get_suggested_fix(output: &str) -> Option<String> {
         // Pattern: Did you mean `fix`?
-        let re = Regex::new(r"Did you mean `([^`]+)`").ok()?;
+        let re = regex!(r"Did you mean `([^`]+)`");
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -144,6 +150,341 @@ impl Rule for CargoWrongCommand {
     }
 }
 
+/// Rule to install a cargo subcommand that isn't installed.
+///
+/// Matches errors like:
+/// - ``error: no such command: `fmt` ``
+///
+/// Subcommands shipped as rustup components (`fmt`, `clippy`) are added
+/// with `rustup component add`; well-known third-party subcommands are
+/// installed from their `cargo-<name>` crate.
+///
+/// # Example
+///
+/// ```text
+/// $ cargo audit
+/// error: no such command: `audit`
+///
+/// $ oops
+/// cargo install cargo-audit && cargo audit
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoMissingSubcommand;
+
+impl CargoMissingSubcommand {
+    /// Subcommands provided by a rustup component, and the component name.
+    const COMPONENTS: &'static [(&'static str, &'static str)] =
+        &[("fmt", "rustfmt"), ("clippy", "clippy")];
+
+    /// Subcommands installed from a `cargo-<name>` crate.
+    const CRATES: &'static [&'static str] = &[
+        "audit",
+        "bloat",
+        "deny",
+        "edit",
+        "expand",
+        "generate",
+        "llvm-cov",
+        "machete",
+        "make",
+        "nextest",
+        "outdated",
+        "tarpaulin",
+        "udeps",
+        "watch",
+    ];
+
+    /// Extract the missing subcommand from cargo's error output.
+    fn get_subcommand(output: &str) -> Option<String> {
+        let re = regex!(r"no such (?:sub)?command: [`']([^`']+)[`']");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Get the command that installs `subcommand`, if it's a known one.
+    fn get_install(subcommand: &str) -> Option<String> {
+        if let Some((_, component)) = Self::COMPONENTS.iter().find(|(s, _)| *s == subcommand) {
+            return Some(format!("rustup component add {}", component));
+        }
+
+        Self::CRATES
+            .contains(&subcommand)
+            .then(|| format!("cargo install cargo-{}", subcommand))
+    }
+}
+
+impl Rule for CargoMissingSubcommand {
+    fn name(&self) -> &str {
+        "cargo_missing_subcommand"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo"]) {
+            return false;
+        }
+
+        Self::get_subcommand(&command.output)
+            .and_then(|s| Self::get_install(&s))
+            .is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_subcommand(&command.output).and_then(|s| Self::get_install(&s)) {
+            Some(install) => vec![format!("{} && {}", install, command.script)],
+            None => vec![],
+        }
+    }
+
+    fn priority(&self) -> i32 {
+        // Ahead of the typo rules, which would suggest another subcommand
+        900
+    }
+}
+
+/// Rule to add a dependency the code uses but `Cargo.toml` doesn't declare.
+///
+/// Matches rustc diagnostics like:
+/// - ``error[E0433]: failed to resolve: use of undeclared crate or module `rand` ``
+/// - ``error[E0432]: unresolved import `serde` `` with a note that the crate is missing
+///
+/// Only fires when `cargo add` is available (cargo 1.62+, or cargo-edit).
+///
+/// # Example
+///
+/// ```text
+/// $ cargo build
+/// error[E0433]: failed to resolve: use of undeclared crate or module `rand`
+///
+/// $ oops
+/// cargo add rand && cargo build
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoMissingDependency;
+
+impl CargoMissingDependency {
+    /// Extract the crates rustc reports as missing, in order of appearance.
+    fn get_crates(output: &str) -> Vec<String> {
+        let re = regex!(
+            r"(?:use of undeclared crate or module|use of unresolved module or unlinked crate|no external crate|you might be missing crate) `([A-Za-z_][A-Za-z0-9_]*)`"
+        );
+        let mut crates: Vec<String> = Vec::new();
+        for caps in re.captures_iter(output) {
+            let name = caps[1].to_string();
+            if !crates.contains(&name) {
+                crates.push(name);
+            }
+        }
+        crates
+    }
+
+    /// Check whether `cargo add` is available.
+    fn has_cargo_add() -> bool {
+        command_output("cargo", &["add", "--help"])
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+}
+
+impl Rule for CargoMissingDependency {
+    fn name(&self) -> &str {
+        "cargo_missing_dependency"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo"]) {
+            return false;
+        }
+
+        !Self::get_crates(&command.output).is_empty() && Self::has_cargo_add()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let crates = Self::get_crates(&command.output);
+        if crates.is_empty() {
+            return vec![];
+        }
+
+        vec![format!(
+            "cargo add {} && {}",
+            crates.join(" "),
+            command.script
+        )]
+    }
+}
+
+/// Rule to fix a misspelled feature passed to cargo.
+///
+/// Matches errors like:
+/// - `error: the package 'app' does not contain this feature: serd`
+/// - `error: none of the selected packages contains these features: serd`
+///
+/// Fuzzy-matches the misspelled feature against the features cargo lists
+/// in its help lines.
+///
+/// # Example
+///
+/// ```text
+/// $ cargo build --features serd
+/// error: the package 'app' does not contain this feature: serd
+/// help: there is a similarly named feature: serde
+///
+/// $ oops
+/// cargo build --features serde
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoUnknownFeature;
+
+impl CargoUnknownFeature {
+    /// Extract the features cargo couldn't find.
+    fn get_missing(output: &str) -> Vec<String> {
+        let re = regex!(r"contains? (?:this feature|these features): ([^\n]+)");
+        match re.captures(output) {
+            Some(caps) => caps[1]
+                .split([',', ' '])
+                .map(|f| f.trim_matches('`'))
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Extract the features cargo lists in its help lines.
+    fn get_available(output: &str) -> Vec<String> {
+        let re = regex!(r"(?i)(?:similarly named feature|available features): ([^\n]+)");
+        re.captures_iter(output)
+            .flat_map(|caps| {
+                caps[1]
+                    .split([',', ' '])
+                    .map(|f| f.trim_matches('`').to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|f| !f.is_empty())
+            .collect()
+    }
+
+    /// Replace `broken` with `fixed` where it appears as a feature, alone
+    /// or in a comma-separated list.
+    fn replace_feature(script: &str, broken: &str, fixed: &str) -> String {
+        match regex_cache::get(&format!(r"(^|[\s,=/]){}($|[\s,])", regex::escape(broken))) {
+            Ok(re) => re
+                .replace(script, format!("${{1}}{}${{2}}", fixed).as_str())
+                .to_string(),
+            Err(_) => script.to_string(),
+        }
+    }
+}
+
+impl Rule for CargoUnknownFeature {
+    fn name(&self) -> &str {
+        "cargo_unknown_feature"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo"]) {
+            return false;
+        }
+
+        !Self::get_missing(&command.output).is_empty()
+            && !Self::get_available(&command.output).is_empty()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let available = Self::get_available(&command.output);
+        let mut script = command.script.clone();
+        for broken in Self::get_missing(&command.output) {
+            if let Some(fixed) = get_close_matches(&broken, &available, 1, 0.6).pop() {
+                script = Self::replace_feature(&script, &broken, &fixed);
+            }
+        }
+
+        if script == command.script {
+            vec![]
+        } else {
+            vec![script]
+        }
+    }
+}
+
+/// Rule to fix a misspelled `--bin` or `--example` target.
+///
+/// Matches errors like:
+/// - ``error: no bin target named `serevr` ``
+///
+/// Fuzzy-matches the target against the ones cargo lists after the error.
+///
+/// # Example
+///
+/// ```text
+/// $ cargo run --bin serevr
+/// error: no bin target named `serevr`.
+///
+/// Available bin targets:
+///     cli
+///     server
+///
+/// $ oops
+/// cargo run --bin server
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoUnknownTarget;
+
+impl CargoUnknownTarget {
+    /// Extract the target cargo couldn't find.
+    fn get_target(output: &str) -> Option<String> {
+        let re = regex!(r"no (?:bin|example|test|bench) target named `([^`]+)`");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the targets cargo lists, one per indented line after
+    /// "Available ... targets:", plus any similarly named one it points out.
+    fn get_available(output: &str) -> Vec<String> {
+        let mut targets: Vec<String> = output
+            .lines()
+            .skip_while(|line| !regex!(r"(?i)available \w+ targets:").is_match(line))
+            .skip(1)
+            .take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .collect();
+
+        let re = regex!(r"a target with a similar name exists: `([^`]+)`");
+        if let Some(caps) = re.captures(output) {
+            if !targets.contains(&caps[1].to_string()) {
+                targets.push(caps[1].to_string());
+            }
+        }
+        targets
+    }
+}
+
+impl Rule for CargoUnknownTarget {
+    fn name(&self) -> &str {
+        "cargo_unknown_target"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["cargo"]) {
+            return false;
+        }
+
+        Self::get_target(&command.output).is_some()
+            && !Self::get_available(&command.output).is_empty()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let target = match Self::get_target(&command.output) {
+            Some(t) => t,
+            None => return vec![],
+        };
+
+        get_close_matches(&target, &Self::get_available(&command.output), 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &target, &fixed))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,4 +630,198 @@ mod tests {
             assert_eq!(CargoWrongCommand.priority(), 1100);
         }
     }
+
+    mod cargo_missing_subcommand_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(CargoMissingSubcommand.name(), "cargo_missing_subcommand");
+        }
+
+        #[test]
+        fn test_get_new_command_component() {
+            let cmd = Command::new(
+                "cargo fmt --all",
+                "error: no such command: `fmt`\n\n\tView all installed commands with `cargo --list`",
+            );
+            assert!(CargoMissingSubcommand.is_match(&cmd));
+            let fixes = CargoMissingSubcommand.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["rustup component add rustfmt && cargo fmt --all"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_clippy() {
+            let cmd = Command::new("cargo clippy", "error: no such command: `clippy`");
+            let fixes = CargoMissingSubcommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["rustup component add clippy && cargo clippy"]);
+        }
+
+        #[test]
+        fn test_get_new_command_crate() {
+            let cmd = Command::new(
+                "cargo expand",
+                "error: no such command: `expand`\n\n\tDid you mean `doc`?",
+            );
+            assert!(CargoMissingSubcommand.is_match(&cmd));
+            let fixes = CargoMissingSubcommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["cargo install cargo-expand && cargo expand"]);
+        }
+
+        #[test]
+        fn test_no_match_unknown_subcommand() {
+            let cmd = Command::new("cargo buidl", "error: no such subcommand: `buidl`");
+            assert!(!CargoMissingSubcommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_priority() {
+            assert_eq!(CargoMissingSubcommand.priority(), 900);
+        }
+    }
+
+    mod cargo_missing_dependency_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(CargoMissingDependency.name(), "cargo_missing_dependency");
+        }
+
+        #[test]
+        fn test_get_crates_undeclared() {
+            let output = "error[E0433]: failed to resolve: use of undeclared crate or module \
+                          `rand`\n --> src/main.rs:3:13";
+            assert_eq!(CargoMissingDependency::get_crates(output), vec!["rand"]);
+        }
+
+        #[test]
+        fn test_get_crates_unresolved_import() {
+            let output = "error[E0432]: unresolved import `serde`\n --> src/lib.rs:1:5\n  |\n\
+                          1 | use serde::Deserialize;\n  |     ^^^^^ you might be missing crate `serde`\n\n\
+                          error[E0433]: failed to resolve: use of unresolved module or unlinked \
+                          crate `serde_json`\n\
+                          error[E0433]: failed to resolve: use of undeclared crate or module `serde`";
+            assert_eq!(
+                CargoMissingDependency::get_crates(output),
+                vec!["serde", "serde_json"]
+            );
+        }
+
+        #[test]
+        fn test_get_crates_local_import() {
+            let output = "error[E0432]: unresolved import `crate::config`\n  \
+                          no `config` in the root";
+            assert!(CargoMissingDependency::get_crates(output).is_empty());
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new(
+                "rustc main.rs",
+                "error[E0433]: failed to resolve: use of undeclared crate or module `rand`",
+            );
+            assert!(!CargoMissingDependency.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "cargo run --release",
+                "error[E0433]: failed to resolve: use of undeclared crate or module `rand`",
+            );
+            assert_eq!(
+                CargoMissingDependency.get_new_command(&cmd),
+                vec!["cargo add rand && cargo run --release"]
+            );
+        }
+    }
+
+    mod cargo_unknown_feature_tests {
+        use super::*;
+
+        const OUTPUT: &str = "error: the package 'app' does not contain this feature: serd\n\
+                              help: there is a similarly named feature: serde";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(CargoUnknownFeature.name(), "cargo_unknown_feature");
+        }
+
+        #[test]
+        fn test_matches_missing_feature() {
+            let cmd = Command::new("cargo build --features serd", OUTPUT);
+            assert!(CargoUnknownFeature.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_without_candidates() {
+            let cmd = Command::new(
+                "cargo build --features serd",
+                "error: the package 'app' does not contain this feature: serd",
+            );
+            assert!(!CargoUnknownFeature.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command_feature_list() {
+            let cmd = Command::new("cargo test -F tokio,serd --all-targets", OUTPUT);
+            assert_eq!(
+                CargoUnknownFeature.get_new_command(&cmd),
+                vec!["cargo test -F tokio,serde --all-targets"]
+            );
+        }
+
+        #[test]
+        fn test_get_available_list() {
+            let output = "error: none of the selected packages contains these features: fulll\n\
+                          help: available features: default, full, macros";
+            assert_eq!(
+                CargoUnknownFeature::get_available(output),
+                vec!["default", "full", "macros"]
+            );
+            assert_eq!(CargoUnknownFeature::get_missing(output), vec!["fulll"]);
+        }
+    }
+
+    mod cargo_unknown_target_tests {
+        use super::*;
+
+        const OUTPUT: &str = "error: no bin target named `serevr`.\n\n\
+                              Available bin targets:\n    cli\n    server\n\n";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(CargoUnknownTarget.name(), "cargo_unknown_target");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("cargo run --bin serevr -- --port 80", OUTPUT);
+            assert!(CargoUnknownTarget.is_match(&cmd));
+            assert_eq!(
+                CargoUnknownTarget.get_new_command(&cmd),
+                vec!["cargo run --bin server -- --port 80"]
+            );
+        }
+
+        #[test]
+        fn test_get_available_similar_name() {
+            let output = "error: no example target named `hello_wrld` in default-run packages\n\
+                          help: a target with a similar name exists: `hello_world`";
+            assert_eq!(
+                CargoUnknownTarget::get_available(output),
+                vec!["hello_world"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_error() {
+            let cmd = Command::new("cargo run", "error: could not compile `app`");
+            assert!(!CargoUnknownTarget.is_match(&cmd));
+        }
+    }
 }
@@ -4,8 +4,8 @@
 //! - `conda_mistype` - Fix mistyped conda commands
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::replace_argument;
-use regex::Regex;
 
 /// Rule to fix mistyped conda commands.
 ///
@@ -34,7 +34,7 @@ impl CondaMistype {
     /// Returns (broken_cmd, correct_cmd) if found.
     fn get_commands(output: &str) -> Option<(String, String)> {
         // Match pattern like: 'conda broken' ... 'conda correct'
-        let re = Regex::new(r"'conda ([^']*)'").ok()?;
+        let re = regex!(r"'conda ([^']*)'");
 
         let matches: Vec<_> = re
             .captures_iter(output)
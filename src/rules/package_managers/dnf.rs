@@ -4,8 +4,8 @@
 //! - `dnf_no_such_command` - Fix mistyped DNF commands
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Common DNF operations for fuzzy matching.
 const DNF_OPERATIONS: &[&str] = &[
@@ -73,7 +73,7 @@ pub struct DnfNoSuchCommand;
 impl DnfNoSuchCommand {
     /// Extract the mistyped command from the DNF error output.
     fn get_misspelled_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"No such command: ([^.]+)\.").ok()?;
+        let re = regex!(r"No such command: ([^.]+)\.");
         re.captures(output)
             .and_then(|caps| caps.get(1))
             .map(|m| m.as_str().to_string())
@@ -0,0 +1,264 @@
+//! Gentoo emerge/portage package manager rules.
+//!
+//! Contains rules for:
+//! - `emerge_masked` - Write the needed unmask changes when a package is masked
+//! - `emerge_missing_digest` - Sync the repository when an ebuild's digest is missing
+//! - `emerge_ambiguous` - Suggest the fully-qualified atoms for an ambiguous name
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::replace_argument;
+
+/// Rule to write the unmask changes portage needs for a masked package.
+///
+/// Matches errors like:
+/// - `!!! All ebuilds that could satisfy "dev-lang/rust" have been masked.`
+/// - `The following keyword changes are necessary to proceed:`
+///
+/// Suggests rerunning with `--autounmask-write`, merging the written config
+/// with `dispatch-conf`, and then running the original command.
+///
+/// # Example
+///
+/// ```text
+/// # emerge dev-lang/rust
+/// !!! All ebuilds that could satisfy "dev-lang/rust" have been masked.
+///
+/// # oops
+/// emerge --autounmask-write dev-lang/rust && dispatch-conf && emerge dev-lang/rust
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmergeMasked;
+
+impl Rule for EmergeMasked {
+    fn name(&self) -> &str {
+        "emerge_masked"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["emerge"]) {
+            return false;
+        }
+
+        command.output.contains("have been masked")
+            || command
+                .output
+                .contains("keyword changes are necessary to proceed")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        if command.script.contains("--autounmask-write") {
+            return vec![];
+        }
+
+        let unmask = command
+            .script
+            .replacen("emerge", "emerge --autounmask-write", 1);
+
+        vec![format!("{} && dispatch-conf && {}", unmask, command.script)]
+    }
+}
+
+/// Rule to sync the repository when an ebuild's digest is missing.
+///
+/// Matches errors like:
+/// - `!!! Missing digest for '/var/db/repos/gentoo/app-misc/foo/foo-1.0.ebuild'`
+///
+/// A missing digest usually means a partial or stale tree, so this suggests
+/// `emerge --sync` as root before retrying.
+///
+/// # Example
+///
+/// ```text
+/// $ emerge app-misc/foo
+/// !!! Missing digest for '/var/db/repos/gentoo/app-misc/foo/foo-1.0.ebuild'
+///
+/// $ oops
+/// sudo emerge --sync && sudo emerge app-misc/foo
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmergeMissingDigest;
+
+impl Rule for EmergeMissingDigest {
+    fn name(&self) -> &str {
+        "emerge_missing_digest"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["emerge"]) {
+            return false;
+        }
+
+        command.output.contains("Missing digest for")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!("sudo emerge --sync && sudo {}", command.script)]
+    }
+}
+
+/// Rule to disambiguate a short package name.
+///
+/// Matches errors like:
+/// - `!!! The short ebuild name "vim" is ambiguous.`
+///
+/// Suggests each fully-qualified atom listed in the error.
+///
+/// # Example
+///
+/// ```text
+/// $ emerge vim
+/// !!! The short ebuild name "vim" is ambiguous.  Please specify
+/// !!! one of the following fully-qualified ebuild names instead:
+///
+///     app-editors/vim
+///     dev-vim/vim
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmergeAmbiguous;
+
+impl EmergeAmbiguous {
+    /// Extract the ambiguous short name.
+    fn get_short_name(output: &str) -> Option<String> {
+        let re = regex!(r#"The short ebuild name "([^"]+)" is ambiguous"#);
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the fully-qualified atoms listed in the error.
+    fn get_atoms(output: &str) -> Vec<String> {
+        let re = regex!(r"^\s+([A-Za-z0-9+_.-]+/[A-Za-z0-9+_.-]+)\s*$");
+        output
+            .lines()
+            .skip_while(|line| !line.contains("fully-qualified ebuild names"))
+            .filter_map(|line| re.captures(line))
+            .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+            .collect()
+    }
+}
+
+impl Rule for EmergeAmbiguous {
+    fn name(&self) -> &str {
+        "emerge_ambiguous"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["emerge"]) {
+            return false;
+        }
+
+        Self::get_short_name(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let short_name = match Self::get_short_name(&command.output) {
+            Some(n) => n,
+            None => return vec![],
+        };
+
+        Self::get_atoms(&command.output)
+            .iter()
+            .map(|atom| replace_argument(&command.script, &short_name, atom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod emerge_masked_tests {
+        use super::*;
+
+        const OUTPUT: &str =
+            "!!! All ebuilds that could satisfy \"dev-lang/rust\" have been masked.\n\
+             !!! One of the following masked packages is required to complete your request:";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(EmergeMasked.name(), "emerge_masked");
+        }
+
+        #[test]
+        fn test_matches_keyword_changes() {
+            let cmd = Command::new(
+                "emerge app-misc/foo",
+                "The following keyword changes are necessary to proceed:",
+            );
+            assert!(EmergeMasked.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("emerge dev-lang/rust", OUTPUT);
+            assert!(EmergeMasked.is_match(&cmd));
+            assert_eq!(
+                EmergeMasked.get_new_command(&cmd),
+                vec!["emerge --autounmask-write dev-lang/rust && dispatch-conf && emerge dev-lang/rust"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("apt install rust", OUTPUT);
+            assert!(!EmergeMasked.is_match(&cmd));
+        }
+    }
+
+    mod emerge_missing_digest_tests {
+        use super::*;
+
+        const OUTPUT: &str =
+            "!!! Missing digest for '/var/db/repos/gentoo/app-misc/foo/foo-1.0.ebuild'";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(EmergeMissingDigest.name(), "emerge_missing_digest");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("emerge app-misc/foo", OUTPUT);
+            assert!(EmergeMissingDigest.is_match(&cmd));
+            assert_eq!(
+                EmergeMissingDigest.get_new_command(&cmd),
+                vec!["sudo emerge --sync && sudo emerge app-misc/foo"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("emerge app-misc/foo", ">>> Emerging (1 of 1)");
+            assert!(!EmergeMissingDigest.is_match(&cmd));
+        }
+    }
+
+    mod emerge_ambiguous_tests {
+        use super::*;
+
+        const OUTPUT: &str = "!!! The short ebuild name \"vim\" is ambiguous.  Please specify\n\
+                              !!! one of the following fully-qualified ebuild names instead:\n\n    \
+                              app-editors/vim\n    dev-vim/vim\n";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(EmergeAmbiguous.name(), "emerge_ambiguous");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("emerge --ask vim", OUTPUT);
+            assert!(EmergeAmbiguous.is_match(&cmd));
+            assert_eq!(
+                EmergeAmbiguous.get_new_command(&cmd),
+                vec!["emerge --ask app-editors/vim", "emerge --ask dev-vim/vim"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("emerge app-editors/vim", ">>> Emerging (1 of 1)");
+            assert!(!EmergeAmbiguous.is_match(&cmd));
+        }
+    }
+}
@@ -4,8 +4,8 @@
 //! - `gem_unknown_command` - Fix mistyped gem commands
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Common gem commands for fuzzy matching.
 const GEM_COMMANDS: &[&str] = &[
@@ -69,7 +69,7 @@ pub struct GemUnknownCommand;
 impl GemUnknownCommand {
     /// Extract the unknown command from the gem error output.
     fn get_unknown_command(output: &str) -> Option<String> {
-        let re = Regex::new(r"Unknown command (.+)$").ok()?;
+        let re = regex!(r"Unknown command (.+)$");
         // Search line by line since the regex uses $ for end of line
         for line in output.lines() {
             if let Some(caps) = re.captures(line) {
@@ -4,14 +4,20 @@
 //! - apt (Debian/Ubuntu)
 //! - brew (macOS Homebrew)
 //! - npm (Node.js)
+//! - nvm, fnm and volta (Node.js versions)
 //! - pip (Python)
 //! - cargo (Rust)
 //! - pacman (Arch Linux)
 //! - dnf (Fedora)
+//! - emerge (Gentoo)
 //! - yum (CentOS/RHEL)
 //! - gem (Ruby)
 //! - choco (Windows Chocolatey)
 //! - conda (Anaconda/Miniconda)
+//! - poetry (Python)
+//! - scoop (Windows)
+//! - uv (Python)
+//! - winget (Windows)
 
 pub mod apt;
 pub mod brew;
@@ -19,26 +25,41 @@ pub mod cargo;
 pub mod choco;
 pub mod conda;
 pub mod dnf;
+pub mod emerge;
 pub mod gem;
+pub mod node_version;
 pub mod npm;
 pub mod pacman;
 pub mod pip;
+pub mod poetry;
+pub mod scoop;
+pub mod uv;
+pub mod winget;
 pub mod yum;
 
 // Re-export all rules for easier access
 pub use apt::{AptGet, AptGetSearch, AptInvalidOperation, AptListUpgradable};
 pub use brew::{
-    BrewCaskDependency, BrewInstall, BrewLink, BrewReinstall, BrewUninstall, BrewUnknownCommand,
-    BrewUpdate, BrewUpdateFormula,
+    BrewCaskAppExists, BrewCaskDependency, BrewCaskFormula, BrewInstall, BrewLink, BrewReinstall,
+    BrewServicesUnknown, BrewUninstall, BrewUnknownCommand, BrewUpdate, BrewUpdateFormula,
+};
+pub use cargo::{
+    CargoMissingDependency, CargoMissingSubcommand, CargoNoCommand, CargoUnknownFeature,
+    CargoUnknownTarget, CargoWrongCommand,
 };
-pub use cargo::{CargoNoCommand, CargoWrongCommand};
 pub use choco::ChocoInstall;
 pub use conda::CondaMistype;
 pub use dnf::DnfNoSuchCommand;
+pub use emerge::{EmergeAmbiguous, EmergeMasked, EmergeMissingDigest};
 pub use gem::GemUnknownCommand;
-pub use npm::{NpmMissingScript, NpmWrongCommand};
+pub use node_version::{NodeEngineIncompatible, NodeVersionFile};
+pub use npm::{NpmCiNoLockfile, NpmGlobalEacces, NpmMissingScript, NpmWrongCommand, NpxNotFound};
 pub use pacman::{Pacman, PacmanInvalidOption, PacmanNotFound};
 pub use pip::{PipInstall, PipModuleNotFound, PipUnknownCommand};
+pub use poetry::{PoetryAddNotFound, PoetryPythonVersion, PoetryUnknownCommand};
+pub use scoop::{ScoopAppTypo, ScoopBucketMissing, ScoopHashCheck};
+pub use uv::{UvAddNoProject, UvNoVenv, UvPipInstallProject, UvUnknownCommand};
+pub use winget::{WingetAgreements, WingetNoPackage, WingetUnknownCommand};
 pub use yum::YumInvalidOperation;
 
 use crate::core::Rule;
@@ -63,8 +84,15 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(BrewReinstall),
         Box::new(BrewUninstall),
         Box::new(BrewUnknownCommand),
+        Box::new(BrewServicesUnknown),
+        Box::new(BrewCaskFormula),
+        Box::new(BrewCaskAppExists),
         // Cargo rules (Rust)
+        Box::new(CargoMissingDependency),
+        Box::new(CargoMissingSubcommand),
         Box::new(CargoNoCommand),
+        Box::new(CargoUnknownFeature),
+        Box::new(CargoUnknownTarget),
         Box::new(CargoWrongCommand),
         // Chocolatey rules (Windows)
         Box::new(ChocoInstall),
@@ -72,11 +100,21 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(CondaMistype),
         // DNF rules (Fedora)
         Box::new(DnfNoSuchCommand),
+        // Emerge rules (Gentoo)
+        Box::new(EmergeMasked),
+        Box::new(EmergeMissingDigest),
+        Box::new(EmergeAmbiguous),
         // Gem rules (Ruby)
         Box::new(GemUnknownCommand),
         // NPM rules (Node.js)
         Box::new(NpmMissingScript),
         Box::new(NpmWrongCommand),
+        Box::new(NpmCiNoLockfile),
+        Box::new(NpxNotFound),
+        Box::new(NpmGlobalEacces),
+        // Node version manager rules (nvm, fnm, volta)
+        Box::new(NodeVersionFile),
+        Box::new(NodeEngineIncompatible),
         // Pacman rules (Arch Linux)
         Box::new(Pacman),
         Box::new(PacmanInvalidOption),
@@ -85,6 +123,23 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(PipInstall),
         Box::new(PipModuleNotFound),
         Box::new(PipUnknownCommand),
+        // Poetry rules (Python)
+        Box::new(PoetryUnknownCommand),
+        Box::new(PoetryPythonVersion),
+        Box::new(PoetryAddNotFound),
+        // Scoop rules (Windows)
+        Box::new(ScoopAppTypo),
+        Box::new(ScoopBucketMissing),
+        Box::new(ScoopHashCheck),
+        // uv rules (Python)
+        Box::new(UvUnknownCommand),
+        Box::new(UvAddNoProject),
+        Box::new(UvPipInstallProject),
+        Box::new(UvNoVenv),
+        // winget rules (Windows)
+        Box::new(WingetUnknownCommand),
+        Box::new(WingetNoPackage),
+        Box::new(WingetAgreements),
         // YUM rules (CentOS/RHEL)
         Box::new(YumInvalidOperation),
     ]
@@ -0,0 +1,366 @@
+//! Node.js version manager rules (nvm, fnm, volta).
+//!
+//! Contains rules for:
+//! - `node_version_file` - Switch to the project's Node version when `node` isn't found
+//! - `node_engine_incompatible` - Switch Node versions when a package's engine doesn't match
+
+use std::path::Path;
+
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
+
+/// Files that pin a project's Node version.
+const VERSION_FILES: &[&str] = &[".nvmrc", ".node-version"];
+
+/// Get the version managers that are set up, in the order their fixes are
+/// suggested. nvm is a shell function rather than an executable, so it is
+/// found through `NVM_DIR`. Falls back to nvm when none is found.
+fn installed_managers() -> Vec<&'static str> {
+    let mut managers = Vec::new();
+    if std::env::var_os("NVM_DIR").is_some() {
+        managers.push("nvm");
+    }
+    for manager in ["fnm", "volta"] {
+        if crate::utils::which(manager.to_string()).is_some() {
+            managers.push(manager);
+        }
+    }
+    if managers.is_empty() {
+        managers.push("nvm");
+    }
+    managers
+}
+
+/// Get a version volta understands from an nvm-style one, e.g. `v18.17.0`
+/// or `lts/hydrogen`.
+fn volta_version(version: &str) -> String {
+    if version.starts_with("lts/") {
+        "lts".to_string()
+    } else {
+        version.trim_start_matches('v').to_string()
+    }
+}
+
+/// Rule to switch to the project's Node version when `node` isn't found.
+///
+/// With nvm and fnm, `node` is only on the PATH once a version has been
+/// selected, so a new shell in a project with an `.nvmrc` or
+/// `.node-version` often has none.
+///
+/// # Example
+///
+/// ```text
+/// $ npm test
+/// bash: npm: command not found
+///
+/// $ oops
+/// nvm use && npm test
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeVersionFile;
+
+impl NodeVersionFile {
+    /// Find the version file in `dir` and the version it pins.
+    fn get_version_file(dir: &Path) -> Option<(&'static str, String)> {
+        VERSION_FILES.iter().find_map(|name| {
+            let content = std::fs::read_to_string(dir.join(name)).ok()?;
+            let version = content.lines().next()?.trim();
+            if version.is_empty() {
+                None
+            } else {
+                Some((*name, version.to_string()))
+            }
+        })
+    }
+
+    /// Build the fixes for each manager. nvm only reads `.nvmrc`, so for a
+    /// `.node-version` it is given the version explicitly.
+    fn version_fixes(
+        managers: &[&str],
+        file: &str,
+        version: &str,
+        command: &Command,
+    ) -> Vec<String> {
+        let mut fixes = Vec::new();
+        for manager in managers {
+            match *manager {
+                "nvm" if file == ".nvmrc" => {
+                    fixes.push(format!("nvm use && {}", command.script));
+                    fixes.push(format!("nvm install && {}", command.script));
+                }
+                "nvm" => fixes.push(format!("nvm install {} && {}", version, command.script)),
+                "fnm" => fixes.push(format!(
+                    "fnm use --install-if-missing && {}",
+                    command.script
+                )),
+                "volta" => fixes.push(format!(
+                    "volta install node@{} && {}",
+                    volta_version(version),
+                    command.script
+                )),
+                _ => {}
+            }
+        }
+        fixes
+    }
+}
+
+impl Rule for NodeVersionFile {
+    fn name(&self) -> &str {
+        "node_version_file"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        self.is_match_with_context(command, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, command: &Command, ctx: &RuleContext) -> bool {
+        if !is_app(command, &["node", "npm", "npx", "corepack"]) {
+            return false;
+        }
+
+        command.output.contains("command not found") && Self::get_version_file(ctx.cwd()).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        match Self::get_version_file(ctx.cwd()) {
+            Some((file, version)) => {
+                Self::version_fixes(&installed_managers(), file, &version, command)
+            }
+            None => vec![],
+        }
+    }
+
+    fn priority(&self) -> i32 {
+        // Run ahead of the generic command-not-found typo rules
+        900
+    }
+}
+
+/// Rule to switch Node versions when a package's engine doesn't match.
+///
+/// Matches errors like:
+/// - `npm ERR! notsup Required: {"node":">=18.0.0"}` (npm)
+/// - `The engine "node" is incompatible with this module. Expected version ">=18".` (yarn)
+/// - `Expected version: >=18` after `ERR_PNPM_UNSUPPORTED_ENGINE` (pnpm)
+///
+/// Suggests the lowest major version the package allows.
+///
+/// # Example
+///
+/// ```text
+/// $ npm install
+/// npm ERR! code EBADENGINE
+/// npm ERR! engine Unsupported engine
+/// npm ERR! notsup Required: {"node":">=18.0.0"}
+/// npm ERR! notsup Actual:   {"npm":"8.19.4","node":"v16.20.2"}
+///
+/// $ oops
+/// nvm install 18 && npm install
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeEngineIncompatible;
+
+impl NodeEngineIncompatible {
+    /// Extract the required major version from the engine constraint.
+    fn get_version(output: &str) -> Option<String> {
+        let re = regex!(
+            r#"(?m)Required: \{[^}]*"node":\s*"([^"]+)"|The engine "node" is incompatible with this module\. Expected version "([^"]+)"|^Expected version: (.+)"#
+        );
+        let caps = re.captures(output)?;
+        let constraint = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .or_else(|| caps.get(3))?;
+        let caps = regex!(r"^\s*(?:>=|>|\^|~|=)?\s*v?(\d+)").captures(constraint.as_str())?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Build the fixes for each manager.
+    fn version_fixes(managers: &[&str], version: &str, command: &Command) -> Vec<String> {
+        let mut fixes = Vec::new();
+        for manager in managers {
+            match *manager {
+                "nvm" => {
+                    fixes.push(format!("nvm use {} && {}", version, command.script));
+                    fixes.push(format!("nvm install {} && {}", version, command.script));
+                }
+                "fnm" => fixes.push(format!(
+                    "fnm use --install-if-missing {} && {}",
+                    version, command.script
+                )),
+                "volta" => fixes.push(format!(
+                    "volta install node@{} && {}",
+                    version, command.script
+                )),
+                _ => {}
+            }
+        }
+        fixes
+    }
+}
+
+impl Rule for NodeEngineIncompatible {
+    fn name(&self) -> &str {
+        "node_engine_incompatible"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["npm", "npx", "yarn", "pnpm"]) {
+            return false;
+        }
+
+        Self::get_version(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_version(&command.output) {
+            Some(version) => Self::version_fixes(&installed_managers(), &version, command),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod node_version_file_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(NodeVersionFile.name(), "node_version_file");
+        }
+
+        #[test]
+        fn test_match_with_version_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "node_version_file").with_cwd(dir.path());
+            let cmd = Command::new("npm test", "bash: npm: command not found");
+            assert!(!NodeVersionFile.is_match_with_context(&cmd, &ctx));
+
+            std::fs::write(dir.path().join(".nvmrc"), "20\n").unwrap();
+            assert!(NodeVersionFile.is_match_with_context(&cmd, &ctx));
+
+            let cmd = Command::new("python app.py", "bash: python: command not found");
+            assert!(!NodeVersionFile.is_match_with_context(&cmd, &ctx));
+        }
+
+        #[test]
+        fn test_get_version_file() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join(".node-version"), "v18.17.0\n").unwrap();
+            assert_eq!(
+                NodeVersionFile::get_version_file(dir.path()),
+                Some((".node-version", "v18.17.0".to_string()))
+            );
+        }
+
+        #[test]
+        fn test_version_fixes_nvmrc() {
+            let cmd = Command::new("node index.js", "zsh: command not found: node");
+            assert_eq!(
+                NodeVersionFile::version_fixes(&["nvm", "fnm"], ".nvmrc", "20", &cmd),
+                vec![
+                    "nvm use && node index.js",
+                    "nvm install && node index.js",
+                    "fnm use --install-if-missing && node index.js"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_version_fixes_node_version() {
+            let cmd = Command::new("npm ci", "bash: npm: command not found");
+            assert_eq!(
+                NodeVersionFile::version_fixes(
+                    &["nvm", "volta"],
+                    ".node-version",
+                    "v18.17.0",
+                    &cmd
+                ),
+                vec![
+                    "nvm install v18.17.0 && npm ci",
+                    "volta install node@18.17.0 && npm ci"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_volta_version_lts() {
+            assert_eq!(volta_version("lts/hydrogen"), "lts");
+        }
+    }
+
+    mod node_engine_incompatible_tests {
+        use super::*;
+
+        const NPM_OUTPUT: &str = "npm ERR! code EBADENGINE\n\
+                                  npm ERR! engine Unsupported engine\n\
+                                  npm ERR! engine Not compatible with your version of node/npm: app@1.0.0\n\
+                                  npm ERR! notsup Required: {\"node\":\">=18.0.0\"}\n\
+                                  npm ERR! notsup Actual:   {\"npm\":\"8.19.4\",\"node\":\"v16.20.2\"}";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(NodeEngineIncompatible.name(), "node_engine_incompatible");
+        }
+
+        #[test]
+        fn test_matches_npm() {
+            let cmd = Command::new("npm install", NPM_OUTPUT);
+            assert!(NodeEngineIncompatible.is_match(&cmd));
+            assert_eq!(
+                NodeEngineIncompatible::get_version(NPM_OUTPUT),
+                Some("18".to_string())
+            );
+        }
+
+        #[test]
+        fn test_get_version_yarn() {
+            let output = "error vite@5.0.0: The engine \"node\" is incompatible with this \
+                          module. Expected version \"^18.0.0 || >=20.0.0\". Got \"16.20.2\"";
+            assert_eq!(
+                NodeEngineIncompatible::get_version(output),
+                Some("18".to_string())
+            );
+        }
+
+        #[test]
+        fn test_get_version_pnpm() {
+            let output = " ERR_PNPM_UNSUPPORTED_ENGINE  Unsupported environment (bad pnpm \
+                          and/or Node.js version)\n\nYour Node version is incompatible with \
+                          \"/app\".\n\nExpected version: >=20\nGot: v16.20.2";
+            assert_eq!(
+                NodeEngineIncompatible::get_version(output),
+                Some("20".to_string())
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("cargo build", NPM_OUTPUT);
+            assert!(!NodeEngineIncompatible.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_version_fixes() {
+            let cmd = Command::new("yarn install", "");
+            assert_eq!(
+                NodeEngineIncompatible::version_fixes(&["nvm", "fnm", "volta"], "18", &cmd),
+                vec![
+                    "nvm use 18 && yarn install",
+                    "nvm install 18 && yarn install",
+                    "fnm use --install-if-missing 18 && yarn install",
+                    "volta install node@18 && yarn install"
+                ]
+            );
+        }
+    }
+}
@@ -3,10 +3,13 @@
 //! Contains rules for:
 //! - `npm_missing_script` - Suggest correct script names when "missing script" error
 //! - `npm_wrong_command` - Suggest similar npm commands when command not recognized
+//! - `npm_ci_no_lockfile` - Fall back to `npm install` when `npm ci` has no lockfile
+//! - `npx_not_found` - Suggest similar executables when npx can't find a package
+//! - `npm_global_eacces` - Change the global prefix when a global install isn't permitted
 
-use crate::core::{is_app, Command, Rule};
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Rule to suggest correct npm script names when "missing script" error occurs.
 ///
@@ -20,7 +23,7 @@ pub struct NpmMissingScript;
 impl NpmMissingScript {
     /// Extract the misspelled script name from npm error output.
     fn get_misspelled_script(output: &str) -> Option<String> {
-        let re = Regex::new(r"npm ERR! missing script: (.+)").ok()?;
+        let re = regex!(r"npm ERR! missing script: (.+)");
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().trim().to_string())
     }
@@ -223,6 +226,178 @@ impl Rule for NpmWrongCommand {
     }
 }
 
+/// Rule to use `npm install` when `npm ci` has no lockfile.
+///
+/// Matches errors like:
+/// - ``The `npm ci` command can only install with an existing package-lock.json``
+///
+/// `npm install` resolves the dependencies and writes the lockfile.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpmCiNoLockfile;
+
+impl Rule for NpmCiNoLockfile {
+    fn name(&self) -> &str {
+        "npm_ci_no_lockfile"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["npm"]) {
+            return false;
+        }
+
+        let parts = command.script_parts();
+        parts.get(1).map(String::as_str) == Some("ci")
+            && command
+                .output
+                .contains("can only install with an existing package-lock.json")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![replace_argument(&command.script, "ci", "install")]
+    }
+}
+
+/// Rule to suggest similar executables when npx can't find a package.
+///
+/// Matches errors like:
+/// - `npm ERR! 404 Not Found - GET https://registry.npmjs.org/esltin - Not found`
+///
+/// Suggests the closest executables in `node_modules/.bin`, and the closest
+/// of the packages commonly run with npx.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpxNotFound;
+
+impl NpxNotFound {
+    /// Packages commonly run with npx, for fuzzy matching.
+    const NPX_PACKAGES: &'static [&'static str] = &[
+        "create-next-app",
+        "create-react-app",
+        "create-vite",
+        "eslint",
+        "http-server",
+        "jest",
+        "nodemon",
+        "npm-check-updates",
+        "playwright",
+        "prettier",
+        "serve",
+        "ts-node",
+        "tsc",
+        "tsx",
+        "typescript",
+        "vite",
+        "vitest",
+        "webpack",
+    ];
+
+    /// Extract the package the registry doesn't know.
+    fn get_package(output: &str) -> Option<String> {
+        let re = regex!(r"404 Not Found - GET https?://registry\.npmjs\.org/(\S+)");
+        let caps = re.captures(output)?;
+        caps.get(1)
+            .map(|m| m.as_str().replace("%2f", "/").replace("%2F", "/"))
+    }
+
+    /// List the executables installed in `dir`'s `node_modules/.bin`.
+    fn get_local_bins(dir: &std::path::Path) -> Vec<String> {
+        let entries = match std::fs::read_dir(dir.join("node_modules").join(".bin")) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect()
+    }
+}
+
+impl Rule for NpxNotFound {
+    fn name(&self) -> &str {
+        "npx_not_found"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["npx"]) {
+            return false;
+        }
+
+        Self::get_package(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        let package = match Self::get_package(&command.output) {
+            Some(p) => p,
+            None => return vec![],
+        };
+
+        let mut candidates = Self::get_local_bins(ctx.cwd());
+        for package in Self::NPX_PACKAGES {
+            if !candidates.iter().any(|c| c == package) {
+                candidates.push(package.to_string());
+            }
+        }
+
+        get_close_matches(&package, &candidates, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &package, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to avoid `sudo` when a global install isn't permitted.
+///
+/// Matches errors like:
+/// - `npm ERR! Error: EACCES: permission denied, mkdir '/usr/local/lib/node_modules/x'`
+///
+/// Points npm's global prefix at a user-owned directory instead, which needs
+/// its `bin` directory on the `PATH`. Options in `[rule.npm_global_eacces]`:
+/// `prefix` (default `~/.npm-global`), and `sudo = true` to suggest `sudo`
+/// instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpmGlobalEacces;
+
+impl Rule for NpmGlobalEacces {
+    fn name(&self) -> &str {
+        "npm_global_eacces"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["npm"]) {
+            return false;
+        }
+
+        let is_global = command
+            .script_parts()
+            .iter()
+            .any(|p| p == "-g" || p == "--global");
+
+        is_global && command.output.contains("EACCES")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        self.get_new_command_with_context(command, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, command: &Command, ctx: &RuleContext) -> Vec<String> {
+        if ctx.option("sudo").unwrap_or(false) {
+            return vec![format!("sudo {}", command.script)];
+        }
+
+        let prefix: String = ctx
+            .option("prefix")
+            .unwrap_or_else(|| "~/.npm-global".to_string());
+        vec![format!(
+            "npm config set prefix {} && {}",
+            prefix, command.script
+        )]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -368,4 +543,127 @@ mod tests {
             assert_eq!(fixes, vec!["npm publish package"]);
         }
     }
+
+    mod npm_ci_no_lockfile_tests {
+        use super::*;
+
+        const OUTPUT: &str = "npm ERR! code EUSAGE\nnpm ERR!\nnpm ERR! The `npm ci` command can \
+                              only install with an existing package-lock.json or\n\
+                              npm ERR! npm-shrinkwrap.json with lockfileVersion >= 1.";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(NpmCiNoLockfile.name(), "npm_ci_no_lockfile");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("npm ci --omit=dev", OUTPUT);
+            assert!(NpmCiNoLockfile.is_match(&cmd));
+            assert_eq!(
+                NpmCiNoLockfile.get_new_command(&cmd),
+                vec!["npm install --omit=dev"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_command() {
+            let cmd = Command::new("npm install", OUTPUT);
+            assert!(!NpmCiNoLockfile.is_match(&cmd));
+        }
+    }
+
+    mod npx_not_found_tests {
+        use super::*;
+
+        const OUTPUT: &str = "npm ERR! code E404\nnpm ERR! 404 Not Found - GET \
+                              https://registry.npmjs.org/esltin - Not found";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(NpxNotFound.name(), "npx_not_found");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("npx esltin src", OUTPUT);
+            assert!(NpxNotFound.is_match(&cmd));
+            let fixes = NpxNotFound.get_new_command(&cmd);
+            assert_eq!(fixes[0], "npx eslint src");
+        }
+
+        #[test]
+        fn test_get_new_command_local_bin() {
+            let dir = tempfile::tempdir().unwrap();
+            let bin = dir.path().join("node_modules").join(".bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            std::fs::write(bin.join("drizzle-kit"), "").unwrap();
+
+            let cmd = Command::new(
+                "npx drizle-kit push",
+                "npm error 404 Not Found - GET https://registry.npmjs.org/drizle-kit - Not found",
+            );
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "npx_not_found").with_cwd(dir.path());
+            let fixes = NpxNotFound.get_new_command_with_context(&cmd, &ctx);
+            assert_eq!(fixes[0], "npx drizzle-kit push");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("npm install esltin", OUTPUT);
+            assert!(!NpxNotFound.is_match(&cmd));
+        }
+    }
+
+    mod npm_global_eacces_tests {
+        use super::*;
+
+        const OUTPUT: &str = "npm ERR! code EACCES\nnpm ERR! syscall mkdir\n\
+                              npm ERR! Error: EACCES: permission denied, mkdir \
+                              '/usr/local/lib/node_modules/typescript'";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(NpmGlobalEacces.name(), "npm_global_eacces");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("npm install -g typescript", OUTPUT);
+            assert!(NpmGlobalEacces.is_match(&cmd));
+            assert_eq!(
+                NpmGlobalEacces.get_new_command(&cmd),
+                vec!["npm config set prefix ~/.npm-global && npm install -g typescript"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_options() {
+            let cmd = Command::new("npm install -g typescript", OUTPUT);
+            let settings = crate::config::Settings::builder()
+                .rule_option("npm_global_eacces", "prefix", "~/.local")
+                .build();
+            let ctx = RuleContext::new(&settings, "npm_global_eacces");
+            assert_eq!(
+                NpmGlobalEacces.get_new_command_with_context(&cmd, &ctx),
+                vec!["npm config set prefix ~/.local && npm install -g typescript"]
+            );
+
+            let settings = crate::config::Settings::builder()
+                .rule_option("npm_global_eacces", "sudo", true)
+                .build();
+            let ctx = RuleContext::new(&settings, "npm_global_eacces");
+            assert_eq!(
+                NpmGlobalEacces.get_new_command_with_context(&cmd, &ctx),
+                vec!["sudo npm install -g typescript"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_local_install() {
+            let cmd = Command::new("npm install typescript", OUTPUT);
+            assert!(!NpmGlobalEacces.is_match(&cmd));
+        }
+    }
 }
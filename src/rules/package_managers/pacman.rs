@@ -5,8 +5,8 @@
 //! - `pacman_invalid_option` - Fix invalid pacman options (lowercase -> uppercase)
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::{get_close_matches, replace_argument};
-use regex::Regex;
 
 /// Common Arch Linux package managers that use pacman-like syntax.
 const PACMAN_APPS: &[&str] = &["pacman", "yay", "pikaur", "yaourt"];
@@ -127,16 +127,13 @@ impl Rule for PacmanInvalidOption {
 
     fn get_new_command(&self, command: &Command) -> Vec<String> {
         // Find the lowercase option and convert to uppercase
-        let re = Regex::new(r" -([dfqrstuv])").ok();
-
-        if let Some(re) = re {
-            if let Some(caps) = re.captures(&command.script) {
-                let opt = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                let new_script = re
-                    .replace(&command.script, format!(" -{}", opt.to_uppercase()))
-                    .to_string();
-                return vec![new_script];
-            }
+        let re = regex!(r" -([dfqrstuv])");
+        if let Some(caps) = re.captures(&command.script) {
+            let opt = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+            let new_script = re
+                .replace(&command.script, format!(" -{}", opt.to_uppercase()))
+                .to_string();
+            return vec![new_script];
         }
 
         vec![]
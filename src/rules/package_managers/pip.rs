@@ -5,8 +5,8 @@
 //! - `pip_unknown_command` - Suggest similar pip commands when command not recognized
 
 use crate::core::{is_app, Command, Rule};
+use crate::regex;
 use crate::utils::replace_argument;
-use regex::Regex;
 
 /// Rule to suggest pip install with --user when permission denied.
 ///
@@ -64,8 +64,7 @@ impl PipUnknownCommand {
     /// Extract the broken command and suggested fix from pip error output.
     fn get_broken_and_fix(output: &str) -> Option<(String, String)> {
         // Pattern: ERROR: unknown command "broken", maybe you meant "fixed"
-        let re =
-            Regex::new(r#"ERROR: unknown command "([^"]+)".*maybe you meant "([^"]+)""#).ok()?;
+        let re = regex!(r#"ERROR: unknown command "([^"]+)".*maybe you meant "([^"]+)""#);
         let caps = re.captures(output)?;
 
         let broken = caps.get(1)?.as_str().to_string();
@@ -113,7 +112,7 @@ impl PipModuleNotFound {
     /// Extract the module name from the error output.
     fn get_module_name(output: &str) -> Option<String> {
         // Pattern: No module named 'module' or No module named "module"
-        let re = Regex::new(r#"No module named ['"]([\w\-_]+)['""]"#).ok()?;
+        let re = regex!(r#"No module named ['"]([\w\-_]+)['""]"#);
         let caps = re.captures(output)?;
         caps.get(1).map(|m| m.as_str().to_string())
     }
@@ -0,0 +1,364 @@
+//! Poetry package manager rules (Python).
+//!
+//! Contains rules for:
+//! - `poetry_unknown_command` - Suggest the commands poetry lists for an unknown one
+//! - `poetry_python_version` - Switch to a supported Python with `poetry env use`
+//! - `poetry_add_not_found` - Suggest the closest package PyPI knows for `poetry add`
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_closest, replace_argument};
+
+/// Rule to suggest poetry commands when a command doesn't exist.
+///
+/// Matches errors like:
+/// - `The command "instal" does not exist.`
+/// - `Command "instal" is not defined.` (older poetry)
+///
+/// Suggests the commands listed under "Did you mean" in poetry's output.
+///
+/// # Example
+///
+/// ```text
+/// $ poetry instal
+///
+/// The command "instal" does not exist.
+///
+/// Did you mean one of these?
+///     install
+///     self install
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoetryUnknownCommand;
+
+impl PoetryUnknownCommand {
+    /// Extract the unknown command from poetry's error output.
+    fn get_broken(output: &str) -> Option<String> {
+        let re = regex!(r#"[Cc]ommand "([^"]+)" (?:does not exist|is not defined)"#);
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract the suggested commands, listed after "Did you mean".
+    fn get_suggestions(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Did you mean"))
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Rule for PoetryUnknownCommand {
+    fn name(&self) -> &str {
+        "poetry_unknown_command"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["poetry"]) {
+            return false;
+        }
+
+        Self::get_broken(&command.output).is_some() && command.output.contains("Did you mean")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let broken = match Self::get_broken(&command.output) {
+            Some(b) => b,
+            None => return vec![],
+        };
+
+        Self::get_suggestions(&command.output)
+            .iter()
+            .map(|fixed| replace_argument(&command.script, &broken, fixed))
+            .collect()
+    }
+}
+
+/// Rule to switch to a supported Python when the active one isn't.
+///
+/// Matches errors like:
+/// - `The currently activated Python version 3.8.10 is not supported by the project (^3.11).`
+///
+/// Suggests `poetry env use` with the lowest version the project allows.
+///
+/// # Example
+///
+/// ```text
+/// $ poetry install
+/// The currently activated Python version 3.8.10 is not supported by the project (^3.11).
+/// Trying to find and use a compatible version.
+///
+/// NoCompatiblePythonVersionFound
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoetryPythonVersion;
+
+impl PoetryPythonVersion {
+    /// Extract the lowest Python version the project's constraint allows.
+    fn get_version(output: &str) -> Option<String> {
+        let re = regex!(r"is not supported by the project \(([^)]+)\)");
+        let constraint = re.captures(output)?.get(1)?.as_str();
+        let version = regex!(r"\d+\.\d+").find(constraint)?;
+        Some(version.as_str().to_string())
+    }
+}
+
+impl Rule for PoetryPythonVersion {
+    fn name(&self) -> &str {
+        "poetry_python_version"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["poetry"]) {
+            return false;
+        }
+
+        command
+            .output
+            .contains("The currently activated Python version")
+            && Self::get_version(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        match Self::get_version(&command.output) {
+            Some(version) => vec![format!(
+                "poetry env use python{} && {}",
+                version, command.script
+            )],
+            None => vec![],
+        }
+    }
+}
+
+/// Rule to fix a mistyped package name in `poetry add`.
+///
+/// Matches errors like:
+/// - `Could not find a matching version of package reqeusts`
+///
+/// Looks the name up with `poetry search` and suggests the closest package
+/// PyPI has.
+///
+/// # Example
+///
+/// ```text
+/// $ poetry add reqeusts
+/// Could not find a matching version of package reqeusts
+///
+/// $ oops
+/// poetry add requests
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoetryAddNotFound;
+
+impl PoetryAddNotFound {
+    /// Extract the package poetry couldn't find.
+    fn get_package(output: &str) -> Option<String> {
+        let re = regex!(
+            r"Could not find a matching version of package ([A-Za-z0-9._-]+)|Package ([A-Za-z0-9._-]+) not found"
+        );
+        let caps = re.captures(output)?;
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Parse package names from `poetry search` output, whose results look
+    /// like `requests (2.31.0)` followed by an indented description.
+    fn parse_search(output: &str) -> Vec<String> {
+        let re = regex!(r"^([A-Za-z0-9._-]+) \([^)]*\)");
+        output
+            .lines()
+            .filter_map(|line| re.captures(line))
+            .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+            .collect()
+    }
+
+    /// Search PyPI for packages named like `package`.
+    fn search(package: &str) -> Vec<String> {
+        match command_output("poetry", &["search", package]) {
+            Some(output) => Self::parse_search(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for PoetryAddNotFound {
+    fn name(&self) -> &str {
+        "poetry_add_not_found"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["poetry"]) {
+            return false;
+        }
+
+        let parts = command.script_parts();
+        parts.get(1).map(String::as_str) == Some("add")
+            && Self::get_package(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let package = match Self::get_package(&command.output) {
+            Some(p) => p,
+            None => return vec![],
+        };
+
+        match get_closest(&package, &Self::search(&package), 0.6, false) {
+            Some(closest) if closest != package => {
+                vec![replace_argument(&command.script, &package, &closest)]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod poetry_unknown_command_tests {
+        use super::*;
+
+        const OUTPUT: &str = "\nThe command \"instal\" does not exist.\n\n\
+                              Did you mean one of these?\n    install\n    self install\n";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(PoetryUnknownCommand.name(), "poetry_unknown_command");
+        }
+
+        #[test]
+        fn test_matches_unknown_command() {
+            let cmd = Command::new("poetry instal", OUTPUT);
+            assert!(PoetryUnknownCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_matches_older_poetry() {
+            let cmd = Command::new(
+                "poetry biuld",
+                "[CommandNotFoundException]\nCommand \"biuld\" is not defined.\n\n\
+                 Did you mean this?\n    build\n",
+            );
+            assert!(PoetryUnknownCommand.is_match(&cmd));
+            assert_eq!(
+                PoetryUnknownCommand.get_new_command(&cmd),
+                vec!["poetry build"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("pip instal", OUTPUT);
+            assert!(!PoetryUnknownCommand.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("poetry instal --no-root", OUTPUT);
+            let fixes = PoetryUnknownCommand.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["poetry install --no-root", "poetry self install --no-root"]
+            );
+        }
+    }
+
+    mod poetry_python_version_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(PoetryPythonVersion.name(), "poetry_python_version");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "poetry install",
+                "The currently activated Python version 3.8.10 is not supported by the \
+                 project (^3.11).\nTrying to find and use a compatible version.\n",
+            );
+            assert!(PoetryPythonVersion.is_match(&cmd));
+            assert_eq!(
+                PoetryPythonVersion.get_new_command(&cmd),
+                vec!["poetry env use python3.11 && poetry install"]
+            );
+        }
+
+        #[test]
+        fn test_get_version_range() {
+            let output = "The currently activated Python version 3.9.1 is not supported by \
+                          the project (>=3.10,<4.0).";
+            assert_eq!(
+                PoetryPythonVersion::get_version(output),
+                Some("3.10".to_string())
+            );
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("poetry install", "Installing dependencies from lock file");
+            assert!(!PoetryPythonVersion.is_match(&cmd));
+        }
+    }
+
+    mod poetry_add_not_found_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(PoetryAddNotFound.name(), "poetry_add_not_found");
+        }
+
+        #[test]
+        fn test_matches_not_found() {
+            let cmd = Command::new(
+                "poetry add reqeusts",
+                "Could not find a matching version of package reqeusts",
+            );
+            assert!(PoetryAddNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_subcommand() {
+            let cmd = Command::new(
+                "poetry remove reqeusts",
+                "Could not find a matching version of package reqeusts",
+            );
+            assert!(!PoetryAddNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_package() {
+            assert_eq!(
+                PoetryAddNotFound::get_package("Package reqeusts not found"),
+                Some("reqeusts".to_string())
+            );
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new(
+                "poetry add requests",
+                "Package operations: 1 install, 0 updates, 0 removals",
+            );
+            assert!(!PoetryAddNotFound.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_parse_search() {
+            let output = "requests (2.31.0)\n Python HTTP for Humans.\n\n\
+                          requests-oauthlib (1.3.1)\n OAuthlib authentication support.\n";
+            assert_eq!(
+                PoetryAddNotFound::parse_search(output),
+                vec!["requests", "requests-oauthlib"]
+            );
+        }
+    }
+}
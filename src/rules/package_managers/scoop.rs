@@ -0,0 +1,292 @@
+//! Windows Scoop package manager rules.
+//!
+//! Contains rules for:
+//! - `scoop_bucket_missing` - Add the `extras` bucket when no manifest is found
+//! - `scoop_app_typo` - Suggest similar apps from `scoop search` for a missing manifest
+//! - `scoop_hash_check` - Update Scoop and its buckets when a hash check fails
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// Extract the app Scoop couldn't find a manifest for.
+fn get_missing_app(output: &str) -> Option<String> {
+    let re = regex!(r"Could(?:n't| not) find manifest for '([^']+)'");
+    re.captures(output)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Rule to add the `extras` bucket when Scoop can't find an app.
+///
+/// Many GUI apps live in the `extras` bucket, which isn't added by default.
+///
+/// Matches errors like:
+/// - `Couldn't find manifest for 'vscode'.`
+///
+/// # Example
+///
+/// ```text
+/// > scoop install vscode
+/// Couldn't find manifest for 'vscode'.
+///
+/// > oops
+/// scoop bucket add extras && scoop install vscode
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoopBucketMissing;
+
+impl Rule for ScoopBucketMissing {
+    fn name(&self) -> &str {
+        "scoop_bucket_missing"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["scoop"]) {
+            return false;
+        }
+
+        // An app given as `bucket/app` already names its bucket
+        match get_missing_app(&command.output) {
+            Some(app) => !app.contains('/'),
+            None => false,
+        }
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!("scoop bucket add extras && {}", command.script)]
+    }
+}
+
+/// Rule to fix a misspelled app name.
+///
+/// Matches errors like:
+/// - `Couldn't find manifest for 'firefx'.`
+///
+/// Searches the added buckets with `scoop search` and suggests the closest
+/// app names.
+///
+/// # Example
+///
+/// ```text
+/// > scoop install firefx
+/// Couldn't find manifest for 'firefx'.
+///
+/// > oops
+/// scoop install firefox
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoopAppTypo;
+
+impl ScoopAppTypo {
+    /// Parse app names from `scoop search` output. Newer versions print a
+    /// table, older ones list `    app (version)` under each bucket.
+    fn parse_search(output: &str) -> Vec<String> {
+        let old_style = regex!(r"^\s+(\S+) \([^)]*\)");
+        let mut in_table = false;
+        let mut apps = vec![];
+
+        for line in output.lines() {
+            if let Some(caps) = old_style.captures(line) {
+                apps.push(caps[1].to_string());
+            } else if line.trim_start().starts_with("----") {
+                in_table = true;
+            } else if in_table {
+                if let Some(name) = line.split_whitespace().next() {
+                    apps.push(name.to_string());
+                }
+            }
+        }
+
+        apps
+    }
+
+    /// Search the added buckets for apps starting like `app`.
+    fn search(app: &str) -> Vec<String> {
+        // A typo'd name rarely matches as a whole, so search with its start
+        let query: String = app.chars().take(3).collect();
+        match command_output("scoop", &["search", &query]) {
+            Some(output) => Self::parse_search(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+}
+
+impl Rule for ScoopAppTypo {
+    fn name(&self) -> &str {
+        "scoop_app_typo"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["scoop"]) {
+            return false;
+        }
+
+        get_missing_app(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let app = match get_missing_app(&command.output) {
+            Some(a) => a,
+            None => return vec![],
+        };
+
+        get_close_matches(&app, &Self::search(&app), 3, 0.6)
+            .into_iter()
+            .filter(|fixed| *fixed != app)
+            .map(|fixed| replace_argument(&command.script, &app, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to update Scoop when a download fails its hash check.
+///
+/// A hash mismatch usually means the bucket's manifest is out of date.
+///
+/// Matches errors like:
+/// - `Hash check failed!`
+///
+/// # Example
+///
+/// ```text
+/// > scoop install git
+/// Hash check failed!
+/// App:         main/git
+///
+/// > oops
+/// scoop update && scoop install git
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoopHashCheck;
+
+impl Rule for ScoopHashCheck {
+    fn name(&self) -> &str {
+        "scoop_hash_check"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["scoop"]) {
+            return false;
+        }
+
+        command.output.contains("Hash check failed")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!("scoop update && {}", command.script)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod scoop_bucket_missing_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(ScoopBucketMissing.name(), "scoop_bucket_missing");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "scoop install vscode",
+                "Couldn't find manifest for 'vscode'.",
+            );
+            assert!(ScoopBucketMissing.is_match(&cmd));
+            assert_eq!(
+                ScoopBucketMissing.get_new_command(&cmd),
+                vec!["scoop bucket add extras && scoop install vscode"]
+            );
+        }
+
+        #[test]
+        fn test_matches_older_scoop() {
+            let cmd = Command::new(
+                "scoop install vscode",
+                "Could not find manifest for 'vscode'.",
+            );
+            assert!(ScoopBucketMissing.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_explicit_bucket() {
+            let cmd = Command::new(
+                "scoop install extras/vscod",
+                "Couldn't find manifest for 'extras/vscod'.",
+            );
+            assert!(!ScoopBucketMissing.is_match(&cmd));
+        }
+    }
+
+    mod scoop_app_typo_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(ScoopAppTypo.name(), "scoop_app_typo");
+        }
+
+        #[test]
+        fn test_matches_missing_manifest() {
+            let cmd = Command::new(
+                "scoop install firefx",
+                "Couldn't find manifest for 'firefx'.",
+            );
+            assert!(ScoopAppTypo.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_parse_search_table() {
+            let output = "Results from local buckets...\n\n\
+                          Name            Version Source Binaries\n\
+                          ----            ------- ------ --------\n\
+                          firefox         121.0   extras\n\
+                          firefox-esr     115.6.0 extras\n";
+            assert_eq!(
+                ScoopAppTypo::parse_search(output),
+                vec!["firefox", "firefox-esr"]
+            );
+        }
+
+        #[test]
+        fn test_parse_search_old_style() {
+            let output = "'extras' bucket:\n    firefox (121.0)\n    firefox-esr (115.6.0)\n";
+            assert_eq!(
+                ScoopAppTypo::parse_search(output),
+                vec!["firefox", "firefox-esr"]
+            );
+        }
+    }
+
+    mod scoop_hash_check_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(ScoopHashCheck.name(), "scoop_hash_check");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "scoop install git",
+                "Hash check failed!\nApp:         main/git\n",
+            );
+            assert!(ScoopHashCheck.is_match(&cmd));
+            assert_eq!(
+                ScoopHashCheck.get_new_command(&cmd),
+                vec!["scoop update && scoop install git"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("scoop install git", "'git' was installed successfully!");
+            assert!(!ScoopHashCheck.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! uv package manager rules (Python).
+//!
+//! Contains rules for:
+//! - `uv_unknown_command` - Suggest similar uv commands when a subcommand is not recognized
+//! - `uv_add_no_project` - Fall back to `uv pip install` when `uv add` has no project
+//! - `uv_pip_install_project` - Suggest `uv add` for `uv pip install` inside a project
+//! - `uv_no_venv` - Create a virtual environment when uv can't find one
+
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// Get the arguments after the first `skip` words of the script.
+fn args_after(command: &Command, skip: usize) -> String {
+    command
+        .script_parts()
+        .iter()
+        .skip(skip)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Rule to suggest correct uv commands when a subcommand is not recognized.
+///
+/// Matches errors like:
+/// - `error: unrecognized subcommand 'snyc'`
+///
+/// Uses uv's own tip when it gives one, and fuzzy-matches the known
+/// subcommands otherwise.
+///
+/// # Example
+///
+/// ```text
+/// $ uv snyc
+/// error: unrecognized subcommand 'snyc'
+///
+///   tip: a similar subcommand exists: 'sync'
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvUnknownCommand;
+
+impl UvUnknownCommand {
+    /// uv subcommands for fuzzy matching.
+    const UV_COMMANDS: &'static [&'static str] = &[
+        "add", "build", "cache", "export", "help", "init", "lock", "pip", "publish", "python",
+        "remove", "run", "self", "sync", "tool", "tree", "venv", "version",
+    ];
+
+    /// Extract the unrecognized subcommand.
+    fn get_broken(output: &str) -> Option<String> {
+        let re = regex!(r"unrecognized subcommand '([^']+)'");
+        let caps = re.captures(output)?;
+        caps.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Extract uv's suggestion, without the leading `uv`.
+    fn get_tip(output: &str) -> Option<String> {
+        let re = regex!(r"a similar subcommand exists: '([^']+)'");
+        let tip = re.captures(output)?.get(1)?.as_str();
+        Some(tip.strip_prefix("uv ").unwrap_or(tip).to_string())
+    }
+}
+
+impl Rule for UvUnknownCommand {
+    fn name(&self) -> &str {
+        "uv_unknown_command"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["uv"]) {
+            return false;
+        }
+
+        Self::get_broken(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let broken = match Self::get_broken(&command.output) {
+            Some(b) => b,
+            None => return vec![],
+        };
+
+        if let Some(tip) = Self::get_tip(&command.output) {
+            return vec![replace_argument(&command.script, &broken, &tip)];
+        }
+
+        let uv_commands: Vec<String> = Self::UV_COMMANDS.iter().map(|s| s.to_string()).collect();
+        get_close_matches(&broken, &uv_commands, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &broken, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to handle `uv add` outside of a project.
+///
+/// Matches errors like:
+/// - ``error: No `pyproject.toml` found in current directory or any parent directory``
+///
+/// Suggests installing into the environment with `uv pip install`, or
+/// creating a project first.
+///
+/// # Example
+///
+/// ```text
+/// $ uv add requests
+/// error: No `pyproject.toml` found in current directory or any parent directory
+///
+/// $ oops
+/// uv pip install requests
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvAddNoProject;
+
+impl Rule for UvAddNoProject {
+    fn name(&self) -> &str {
+        "uv_add_no_project"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["uv"]) {
+            return false;
+        }
+
+        let parts = command.script_parts();
+        parts.get(1).map(String::as_str) == Some("add")
+            && command.output.contains("No `pyproject.toml` found")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![
+            format!("uv pip install {}", args_after(command, 2)),
+            format!("uv init && {}", command.script),
+        ]
+    }
+}
+
+/// Rule to suggest `uv add` for `uv pip install` inside a project.
+///
+/// `uv pip install` only changes the environment, so in a project with a
+/// `pyproject.toml` the package is gone on the next `uv sync`. `uv add`
+/// records it as a dependency too.
+///
+/// # Example
+///
+/// ```text
+/// $ uv pip install requests
+/// Resolved 5 packages in 120ms
+///
+/// $ oops
+/// uv add requests
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvPipInstallProject;
+
+impl Rule for UvPipInstallProject {
+    fn name(&self) -> &str {
+        "uv_pip_install_project"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        self.is_match_with_context(command, &RuleContext::default())
+    }
+
+    fn is_match_with_context(&self, command: &Command, ctx: &RuleContext) -> bool {
+        if !is_app(command, &["uv"]) {
+            return false;
+        }
+
+        let parts = command.script_parts();
+        parts.len() > 3
+            && parts[1] == "pip"
+            && parts[2] == "install"
+            // Requirement files and editable installs have no `uv add` equivalent
+            && !parts[3..].iter().any(|p| p.starts_with('-'))
+            && ctx.cwd().join("pyproject.toml").exists()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!("uv add {}", args_after(command, 3))]
+    }
+
+    fn requires_output(&self) -> bool {
+        false
+    }
+}
+
+/// Rule to create a virtual environment when uv can't find one.
+///
+/// Matches errors like:
+/// - ``error: No virtual environment found; run `uv venv` to create an environment``
+///
+/// # Example
+///
+/// ```text
+/// $ uv pip install requests
+/// error: No virtual environment found; run `uv venv` to create an environment,
+/// or pass `--system` to install into a non-virtual environment
+///
+/// $ oops
+/// uv venv && uv pip install requests
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvNoVenv;
+
+impl Rule for UvNoVenv {
+    fn name(&self) -> &str {
+        "uv_no_venv"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["uv"]) {
+            return false;
+        }
+
+        command.output.contains("No virtual environment found")
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!("uv venv && {}", command.script)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod uv_unknown_command_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(UvUnknownCommand.name(), "uv_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command_uses_tip() {
+            let cmd = Command::new(
+                "uv install requests",
+                "error: unrecognized subcommand 'install'\n\n  \
+                 tip: a similar subcommand exists: 'uv pip install'",
+            );
+            assert!(UvUnknownCommand.is_match(&cmd));
+            let fixes = UvUnknownCommand.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["uv pip install requests"]);
+        }
+
+        #[test]
+        fn test_get_new_command_fuzzy() {
+            let cmd = Command::new("uv snyc", "error: unrecognized subcommand 'snyc'");
+            let fixes = UvUnknownCommand.get_new_command(&cmd);
+            assert!(fixes.contains(&"uv sync".to_string()));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("pip snyc", "error: unrecognized subcommand 'snyc'");
+            assert!(!UvUnknownCommand.is_match(&cmd));
+        }
+    }
+
+    mod uv_add_no_project_tests {
+        use super::*;
+
+        const OUTPUT: &str =
+            "error: No `pyproject.toml` found in current directory or any parent directory";
+
+        #[test]
+        fn test_name() {
+            assert_eq!(UvAddNoProject.name(), "uv_add_no_project");
+        }
+
+        #[test]
+        fn test_matches_no_project() {
+            let cmd = Command::new("uv add requests", OUTPUT);
+            assert!(UvAddNoProject.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_subcommand() {
+            let cmd = Command::new("uv sync", OUTPUT);
+            assert!(!UvAddNoProject.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("uv add requests httpx", OUTPUT);
+            let fixes = UvAddNoProject.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec![
+                    "uv pip install requests httpx",
+                    "uv init && uv add requests httpx"
+                ]
+            );
+        }
+    }
+
+    mod uv_pip_install_project_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(UvPipInstallProject.name(), "uv_pip_install_project");
+        }
+
+        #[test]
+        fn test_match_in_project() {
+            let dir = tempfile::tempdir().unwrap();
+            let cmd = Command::new("uv pip install requests", "");
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "uv_pip_install_project").with_cwd(dir.path());
+            assert!(!UvPipInstallProject.is_match_with_context(&cmd, &ctx));
+
+            std::fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+            assert!(UvPipInstallProject.is_match_with_context(&cmd, &ctx));
+
+            let cmd = Command::new("uv pip install -r requirements.txt", "");
+            assert!(!UvPipInstallProject.is_match_with_context(&cmd, &ctx));
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("uv pip install requests httpx", "");
+            let fixes = UvPipInstallProject.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["uv add requests httpx"]);
+        }
+
+        #[test]
+        fn test_requires_no_output() {
+            assert!(!UvPipInstallProject.requires_output());
+        }
+    }
+
+    mod uv_no_venv_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(UvNoVenv.name(), "uv_no_venv");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new(
+                "uv pip install requests",
+                "error: No virtual environment found; run `uv venv` to create an environment, \
+                 or pass `--system` to install into a non-virtual environment",
+            );
+            assert!(UvNoVenv.is_match(&cmd));
+            let fixes = UvNoVenv.get_new_command(&cmd);
+            assert_eq!(fixes, vec!["uv venv && uv pip install requests"]);
+        }
+
+        #[test]
+        fn test_no_match_success() {
+            let cmd = Command::new("uv pip install requests", "Installed 5 packages in 8ms");
+            assert!(!UvNoVenv.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Windows winget package manager rules.
+//!
+//! Contains rules for:
+//! - `winget_unknown_command` - Fix typos in winget commands
+//! - `winget_no_package` - Suggest close matches from `winget search` for a missing package
+//! - `winget_agreements` - Accept the source or package agreements winget asks for
+
+use crate::core::{is_app, Command, Rule};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{get_close_matches, replace_argument};
+
+/// winget commands for fuzzy matching.
+const WINGET_COMMANDS: &[&str] = &[
+    "install",
+    "show",
+    "source",
+    "search",
+    "list",
+    "upgrade",
+    "uninstall",
+    "hash",
+    "validate",
+    "settings",
+    "features",
+    "export",
+    "import",
+    "pin",
+    "configure",
+    "download",
+    "repair",
+];
+
+/// Rule to fix typos in winget commands.
+///
+/// Matches errors like:
+/// - `Unrecognized command: 'instal'`
+///
+/// # Example
+///
+/// ```text
+/// > winget instal vscode
+/// Unrecognized command: 'instal'
+///
+/// > oops
+/// winget install vscode
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WingetUnknownCommand;
+
+impl WingetUnknownCommand {
+    /// Extract the unrecognized command from the error output.
+    fn get_unknown_command(output: &str) -> Option<String> {
+        let re = regex!(r"Unrecognized command: '([^']+)'");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+impl Rule for WingetUnknownCommand {
+    fn name(&self) -> &str {
+        "winget_unknown_command"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["winget"]) {
+            return false;
+        }
+
+        Self::get_unknown_command(&command.output).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let unknown = match Self::get_unknown_command(&command.output) {
+            Some(c) => c,
+            None => return vec![],
+        };
+
+        let commands: Vec<String> = WINGET_COMMANDS.iter().map(|s| s.to_string()).collect();
+        get_close_matches(&unknown, &commands, 3, 0.6)
+            .into_iter()
+            .map(|fixed| replace_argument(&command.script, &unknown, &fixed))
+            .collect()
+    }
+}
+
+/// Rule to suggest a package when winget can't find the one asked for.
+///
+/// Matches errors like:
+/// - `No package found matching input criteria.`
+///
+/// Runs `winget search` for the query and suggests the package ids closest
+/// to it.
+///
+/// # Example
+///
+/// ```text
+/// > winget install vscod
+/// No package found matching input criteria.
+///
+/// > oops
+/// winget install Microsoft.VisualStudioCode
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WingetNoPackage;
+
+impl WingetNoPackage {
+    /// Get the query: the value of `--id`/`--name`/`-q`, or the first
+    /// positional argument after the command.
+    fn get_query(command: &Command) -> Option<String> {
+        let parts = command.script_parts();
+        let mut iter = parts.iter().skip(2);
+        while let Some(part) = iter.next() {
+            match part.as_str() {
+                "--id" | "--name" | "-q" | "--query" | "--moniker" => return iter.next().cloned(),
+                p if p.starts_with('-') => continue,
+                p => return Some(p.to_string()),
+            }
+        }
+        None
+    }
+
+    /// Parse the `Id` column of a `winget search` table.
+    fn parse_search(output: &str) -> Vec<String> {
+        let mut lines = output.lines();
+        let header = match lines.by_ref().find(|line| line.contains("Id")) {
+            Some(h) => h,
+            None => return vec![],
+        };
+        let start = match header.find(" Id ") {
+            Some(i) => i + 1,
+            None => return vec![],
+        };
+
+        lines
+            .filter(|line| !line.starts_with('-'))
+            .filter_map(|line| line.get(start..))
+            .filter_map(|rest| rest.split_whitespace().next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Search for packages matching `query`.
+    fn search(query: &str) -> Vec<String> {
+        match command_output("winget", &["search", query]) {
+            Some(output) => Self::parse_search(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+
+    /// Pick the ids whose last segment is closest to `query`.
+    fn closest_ids(query: &str, ids: &[String]) -> Vec<String> {
+        let names: Vec<String> = ids
+            .iter()
+            .map(|id| id.rsplit('.').next().unwrap_or(id).to_lowercase())
+            .collect();
+
+        get_close_matches(&query.to_lowercase(), &names, 3, 0.6)
+            .iter()
+            .filter_map(|name| names.iter().position(|n| n == name))
+            .map(|i| ids[i].clone())
+            .collect()
+    }
+}
+
+impl Rule for WingetNoPackage {
+    fn name(&self) -> &str {
+        "winget_no_package"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["winget"]) {
+            return false;
+        }
+
+        command
+            .output
+            .contains("No package found matching input criteria")
+            && Self::get_query(command).is_some()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        let query = match Self::get_query(command) {
+            Some(q) => q,
+            None => return vec![],
+        };
+
+        Self::closest_ids(&query, &Self::search(&query))
+            .iter()
+            .map(|id| replace_argument(&command.script, &query, id))
+            .collect()
+    }
+}
+
+/// Rule to accept the agreements winget asks for.
+///
+/// Matches output like:
+/// - `The source requires that you view the following agreements before using.`
+/// - `The publisher requires that you view the above information and accept
+///   the agreements before installing.`
+///
+/// Appends `--accept-source-agreements` and/or `--accept-package-agreements`.
+///
+/// # Example
+///
+/// ```text
+/// > winget install Git.Git
+/// The `msstore` source requires that you view the following agreements before using.
+/// Do you agree to all the source agreements terms?
+///
+/// > oops
+/// winget install Git.Git --accept-source-agreements
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WingetAgreements;
+
+impl WingetAgreements {
+    /// Get the agreement flags the output asks for that the script lacks.
+    fn missing_flags(command: &Command) -> Vec<&'static str> {
+        let mut flags = vec![];
+        if command.output.contains("source requires that you view")
+            && !command.script.contains("--accept-source-agreements")
+        {
+            flags.push("--accept-source-agreements");
+        }
+        if command
+            .output
+            .contains("accept the agreements before installing")
+            && !command.script.contains("--accept-package-agreements")
+        {
+            flags.push("--accept-package-agreements");
+        }
+        flags
+    }
+}
+
+impl Rule for WingetAgreements {
+    fn name(&self) -> &str {
+        "winget_agreements"
+    }
+
+    fn is_match(&self, command: &Command) -> bool {
+        if !is_app(command, &["winget"]) {
+            return false;
+        }
+
+        !Self::missing_flags(command).is_empty()
+    }
+
+    fn get_new_command(&self, command: &Command) -> Vec<String> {
+        vec![format!(
+            "{} {}",
+            command.script,
+            Self::missing_flags(command).join(" ")
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod winget_unknown_command_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(WingetUnknownCommand.name(), "winget_unknown_command");
+        }
+
+        #[test]
+        fn test_get_new_command() {
+            let cmd = Command::new("winget instal vscode", "Unrecognized command: 'instal'");
+            assert!(WingetUnknownCommand.is_match(&cmd));
+            let fixes = WingetUnknownCommand.get_new_command(&cmd);
+            assert_eq!(fixes[0], "winget install vscode");
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("choco instal vscode", "Unrecognized command: 'instal'");
+            assert!(!WingetUnknownCommand.is_match(&cmd));
+        }
+    }
+
+    mod winget_no_package_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(WingetNoPackage.name(), "winget_no_package");
+        }
+
+        #[test]
+        fn test_matches_no_package() {
+            let cmd = Command::new(
+                "winget install vscod",
+                "No package found matching input criteria.",
+            );
+            assert!(WingetNoPackage.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_get_query() {
+            let cmd = Command::new("winget install -e --id Git.Gti", "");
+            assert_eq!(
+                WingetNoPackage::get_query(&cmd),
+                Some("Git.Gti".to_string())
+            );
+        }
+
+        #[test]
+        fn test_parse_search() {
+            let output = "Name               Id                         Version Source\n\
+                          ----------------------------------------------------------\n\
+                          Visual Studio Code Microsoft.VisualStudioCode 1.85.1  winget\n\
+                          VSCodium           VSCodium.VSCodium          1.85.1  winget\n";
+            assert_eq!(
+                WingetNoPackage::parse_search(output),
+                vec!["Microsoft.VisualStudioCode", "VSCodium.VSCodium"]
+            );
+        }
+
+        #[test]
+        fn test_closest_ids() {
+            let ids = vec![
+                "Microsoft.VisualStudioCode".to_string(),
+                "Mozilla.Firefox".to_string(),
+            ];
+            assert_eq!(
+                WingetNoPackage::closest_ids("visualstudiocod", &ids),
+                vec!["Microsoft.VisualStudioCode"]
+            );
+        }
+    }
+
+    mod winget_agreements_tests {
+        use super::*;
+
+        #[test]
+        fn test_name() {
+            assert_eq!(WingetAgreements.name(), "winget_agreements");
+        }
+
+        #[test]
+        fn test_get_new_command_source() {
+            let cmd = Command::new(
+                "winget install Git.Git",
+                "The `msstore` source requires that you view the following agreements before using.\n\
+                 Do you agree to all the source agreements terms?",
+            );
+            assert!(WingetAgreements.is_match(&cmd));
+            assert_eq!(
+                WingetAgreements.get_new_command(&cmd),
+                vec!["winget install Git.Git --accept-source-agreements"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_package() {
+            let cmd = Command::new(
+                "winget install Spotify.Spotify --accept-source-agreements",
+                "The publisher requires that you view the above information and accept the \
+                 agreements before installing.",
+            );
+            assert_eq!(
+                WingetAgreements.get_new_command(&cmd),
+                vec!["winget install Spotify.Spotify --accept-source-agreements --accept-package-agreements"]
+            );
+        }
+
+        #[test]
+        fn test_no_match_already_accepted() {
+            let cmd = Command::new(
+                "winget install Git.Git --accept-source-agreements",
+                "The source requires that you view the following agreements before using.",
+            );
+            assert!(!WingetAgreements.is_match(&cmd));
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Error messages in the languages tools print them in.
+//!
+//! Most rules look for English error messages, which the tools translate
+//! when the user's locale isn't English. The messages here list the English
+//! text together with the translations used by glibc, coreutils, bash and
+//! git, so rules keep matching on non-English systems:
+//!
+//! ```
+//! use oops::rules::patterns::system::IS_A_DIRECTORY;
+//!
+//! assert!(IS_A_DIRECTORY.is_in("cat: /tmp: Is a directory"));
+//! assert!(IS_A_DIRECTORY.is_in("cat: /tmp: Ist ein Verzeichnis"));
+//! ```
+//!
+//! Only matching is localized. Rules that extract parts of the output still
+//! expect English messages.
+
+/// An error message and its translations.
+#[derive(Debug, Clone, Copy)]
+pub struct Message {
+    english: &'static str,
+    translations: &'static [&'static str],
+}
+
+impl Message {
+    /// Create a message from its English text and translations.
+    pub const fn new(english: &'static str, translations: &'static [&'static str]) -> Self {
+        Self {
+            english,
+            translations,
+        }
+    }
+
+    /// The English text of the message.
+    pub fn english(&self) -> &'static str {
+        self.english
+    }
+
+    /// All known spellings of the message, English first.
+    pub fn variants(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.english).chain(self.translations.iter().copied())
+    }
+
+    /// Whether `output` contains the message in any language, ignoring case.
+    pub fn is_in(&self, output: &str) -> bool {
+        let output = output.to_lowercase();
+        self.variants()
+            .any(|variant| output.contains(&variant.to_lowercase()))
+    }
+}
+
+/// Messages of the C library, coreutils and the shell.
+pub mod system {
+    use super::Message;
+
+    /// `EISDIR`
+    pub const IS_A_DIRECTORY: Message = Message::new(
+        "Is a directory",
+        &[
+            "Ist ein Verzeichnis",
+            "Est un dossier",
+            "Es un directorio",
+            "È una directory",
+            "É um diretório",
+            "Jest katalogiem",
+            "Is een map",
+            "Это каталог",
+            "是一个目录",
+            "ディレクトリです",
+        ],
+    );
+
+    /// `EACCES`
+    pub const PERMISSION_DENIED: Message = Message::new(
+        "Permission denied",
+        &[
+            "Keine Berechtigung",
+            "Permission non accordée",
+            "Permiso denegado",
+            "Permesso negato",
+            "Permissão negada",
+            "Brak dostępu",
+            "Toegang geweigerd",
+            "Отказано в доступе",
+            "权限不够",
+            "許可がありません",
+        ],
+    );
+
+    /// `ENOENT`
+    pub const NO_SUCH_FILE: Message = Message::new(
+        "No such file or directory",
+        &[
+            "Datei oder Verzeichnis nicht gefunden",
+            "Aucun fichier ou dossier de ce type",
+            "No existe el archivo o el directorio",
+            "No existe el fichero o el directorio",
+            "File o directory non esistente",
+            "Arquivo ou diretório inexistente",
+            "Arquivo ou diretório não encontrado",
+            "Nie ma takiego pliku ani katalogu",
+            "Bestand of map bestaat niet",
+            "Нет такого файла или каталога",
+            "没有那个文件或目录",
+            "そのようなファイルやディレクトリはありません",
+        ],
+    );
+
+    /// `EEXIST`
+    pub const FILE_EXISTS: Message = Message::new(
+        "File exists",
+        &[
+            "Die Datei existiert bereits",
+            "Le fichier existe",
+            "El archivo ya existe",
+            "El fichero ya existe",
+            "File esiste",
+            "Arquivo existe",
+            "Plik istnieje",
+            "Bestand bestaat al",
+            "Файл существует",
+            "文件已存在",
+            "ファイルが存在します",
+        ],
+    );
+
+    /// The shell's message for unknown commands.
+    pub const COMMAND_NOT_FOUND: Message = Message::new(
+        "command not found",
+        &[
+            "Befehl nicht gefunden",
+            "commande introuvable",
+            "orden no encontrada",
+            "no se encontró la orden",
+            "comando non trovato",
+            "comando não encontrado",
+            "nie znaleziono polecenia",
+            "opdracht niet gevonden",
+            "команда не найдена",
+            "未找到命令",
+            "コマンドが見つかりません",
+        ],
+    );
+}
+
+/// Messages of git.
+pub mod git {
+    use super::Message;
+
+    /// Running a repository command outside of a repository.
+    pub const NOT_A_REPOSITORY: Message = Message::new(
+        "not a git repository",
+        &[
+            "Kein Git-Repository",
+            "n'est un dépôt git",
+            "no es un repositorio git",
+            "non è un repository git",
+            "não é um repositório git",
+            "不是 git 仓库",
+        ],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::system::*;
+    use super::*;
+
+    #[test]
+    fn test_english_and_translations_match() {
+        assert!(PERMISSION_DENIED.is_in("bash: ./run.sh: Permission denied"));
+        assert!(PERMISSION_DENIED.is_in("bash: ./run.sh: permission denied"));
+        assert!(PERMISSION_DENIED.is_in("bash: ./run.sh: Keine Berechtigung"));
+        assert!(PERMISSION_DENIED.is_in("bash: ./run.sh: Отказано в доступе"));
+        assert!(!PERMISSION_DENIED.is_in("bash: ./run.sh: No such file or directory"));
+    }
+
+    #[test]
+    fn test_variants_start_with_english() {
+        assert_eq!(NO_SUCH_FILE.english(), "No such file or directory");
+        assert_eq!(NO_SUCH_FILE.variants().next(), Some(NO_SUCH_FILE.english()));
+        assert!(git::NOT_A_REPOSITORY.variants().count() > 1);
+    }
+}
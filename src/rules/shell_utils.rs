@@ -18,9 +18,9 @@
 //! - [`ScmCorrection`] - Source control typo fixes
 //! - [`UnknownCommand`] - Generic unknown command handling
 
-use crate::core::{is_app, Command, Rule};
+use crate::core::{by_frequency, get_history, is_app, Command, Rule, RuleContext};
+use crate::regex;
 use crate::utils::{get_close_matches, get_closest, replace_argument};
-use regex::Regex;
 use std::path::Path;
 
 // ============================================================================
@@ -459,13 +459,13 @@ impl History {
         Self
     }
 
-    fn get_history() -> Vec<String> {
-        // Get history from TF_HISTORY environment variable
-        std::env::var("TF_HISTORY")
-            .unwrap_or_default()
-            .lines()
-            .map(|s| s.to_string())
-            .collect()
+    /// Candidate commands from history, most frequently used first.
+    fn candidates(cmd: &Command, ctx: &RuleContext) -> Vec<String> {
+        let history: Vec<String> = get_history(ctx.settings())
+            .into_iter()
+            .filter(|h| *h != cmd.script)
+            .collect();
+        by_frequency(&history)
     }
 }
 
@@ -480,25 +480,20 @@ impl Rule for History {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        let history = Self::get_history();
-        let history_without_current: Vec<String> = history
-            .iter()
-            .filter(|h| *h != &cmd.script)
-            .cloned()
-            .collect();
+        self.is_match_with_context(cmd, &RuleContext::default())
+    }
 
-        !get_close_matches(&cmd.script, &history_without_current, 1, 0.6).is_empty()
+    fn is_match_with_context(&self, cmd: &Command, ctx: &RuleContext) -> bool {
+        !get_close_matches(&cmd.script, &Self::candidates(cmd, ctx), 1, 0.6).is_empty()
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        let history = Self::get_history();
-        let history_without_current: Vec<String> = history
-            .iter()
-            .filter(|h| *h != &cmd.script)
-            .cloned()
-            .collect();
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
 
-        if let Some(closest) = get_closest(&cmd.script, &history_without_current, 0.6, false) {
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
+        // Ties between equally close commands go to the most used one
+        if let Some(closest) = get_closest(&cmd.script, &Self::candidates(cmd, ctx), 0.6, false) {
             vec![closest]
         } else {
             vec![]
@@ -542,9 +537,7 @@ impl IfconfigDeviceNotFound {
         // This is a simplified version - real implementation would parse ifconfig output
         #[cfg(unix)]
         {
-            use std::process::Command as ProcessCommand;
-
-            if let Ok(output) = ProcessCommand::new("ifconfig").arg("-a").output() {
+            if let Some(output) = crate::utils::cache::command_output("ifconfig", &["-a"]) {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 return stdout
                     .lines()
@@ -628,15 +621,11 @@ impl Rule for LongFormHelp {
 
     fn is_match(&self, cmd: &Command) -> bool {
         // Check for suggested help command pattern
-        let help_regex = Regex::new(
-            r"(?i)(?:Run|Try) '([^']+)'(?: or '[^']+')? for (?:details|more information)\.?",
-        )
-        .ok();
-
-        if let Some(re) = help_regex {
-            if re.is_match(&cmd.output) {
-                return true;
-            }
+        let re = regex!(
+            r"(?i)(?:Run|Try) '([^']+)'(?: or '[^']+')? for (?:details|more information)\.?"
+        );
+        if re.is_match(&cmd.output) {
+            return true;
         }
 
         // Also match if output contains --help
@@ -645,16 +634,12 @@ impl Rule for LongFormHelp {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Try to extract suggested command from output
-        let help_regex = Regex::new(
-            r"(?i)(?:Run|Try) '([^']+)'(?: or '[^']+')? for (?:details|more information)\.?",
-        )
-        .ok();
-
-        if let Some(re) = help_regex {
-            if let Some(captures) = re.captures(&cmd.output) {
-                if let Some(suggested) = captures.get(1) {
-                    return vec![suggested.as_str().to_string()];
-                }
+        let re = regex!(
+            r"(?i)(?:Run|Try) '([^']+)'(?: or '[^']+')? for (?:details|more information)\.?"
+        );
+        if let Some(captures) = re.captures(&cmd.output) {
+            if let Some(suggested) = captures.get(1) {
+                return vec![suggested.as_str().to_string()];
             }
         }
 
@@ -943,16 +928,14 @@ impl Mercurial {
 
     fn extract_possibilities(output: &str) -> Vec<String> {
         // Try "(did you mean one of X, Y?)" pattern
-        let did_you_mean_re = Regex::new(r"\(did you mean one of ([^?]+)\?\)").ok();
-        if let Some(re) = did_you_mean_re {
-            if let Some(captures) = re.captures(output) {
-                if let Some(suggestions) = captures.get(1) {
-                    return suggestions
-                        .as_str()
-                        .split(", ")
-                        .map(|s| s.trim().to_string())
-                        .collect();
-                }
+        let re = regex!(r"\(did you mean one of ([^?]+)\?\)");
+        if let Some(captures) = re.captures(output) {
+            if let Some(suggestions) = captures.get(1) {
+                return suggestions
+                    .as_str()
+                    .split(", ")
+                    .map(|s| s.trim().to_string())
+                    .collect();
             }
         }
 
@@ -1131,32 +1114,24 @@ impl Rule for UnknownCommand {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        let unknown_re = Regex::new(r"([^:]*): Unknown command.*").ok();
-        let did_you_mean_re = Regex::new(r"Did you mean ([^?]*)\?").ok();
-
-        if let (Some(unknown), Some(did_you_mean)) = (unknown_re, did_you_mean_re) {
-            return unknown.is_match(&cmd.output) && did_you_mean.is_match(&cmd.output);
-        }
-        false
+        regex!(r"([^:]*): Unknown command.*").is_match(&cmd.output)
+            && regex!(r"Did you mean ([^?]*)\?").is_match(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
-        let unknown_re = Regex::new(r"([^:]*): Unknown command.*").ok();
-        let did_you_mean_re = Regex::new(r"Did you mean ([^?]*)\?").ok();
-
-        if let (Some(unknown), Some(did_you_mean)) = (unknown_re, did_you_mean_re) {
-            if let Some(broken_captures) = unknown.captures(&cmd.output) {
-                if let Some(broken_cmd) = broken_captures.get(1) {
-                    let suggestions: Vec<String> = did_you_mean
-                        .captures_iter(&cmd.output)
-                        .filter_map(|c| c.get(1))
-                        .map(|m| m.as_str().to_string())
-                        .collect();
-
-                    if let Some(closest) = get_closest(broken_cmd.as_str(), &suggestions, 0.6, true)
-                    {
-                        return vec![replace_argument(&cmd.script, broken_cmd.as_str(), &closest)];
-                    }
+        let unknown = regex!(r"([^:]*): Unknown command.*");
+        let did_you_mean = regex!(r"Did you mean ([^?]*)\?");
+
+        if let Some(broken_captures) = unknown.captures(&cmd.output) {
+            if let Some(broken_cmd) = broken_captures.get(1) {
+                let suggestions: Vec<String> = did_you_mean
+                    .captures_iter(&cmd.output)
+                    .filter_map(|c| c.get(1))
+                    .map(|m| m.as_str().to_string())
+                    .collect();
+
+                if let Some(closest) = get_closest(broken_cmd.as_str(), &suggestions, 0.6, true) {
+                    return vec![replace_argument(&cmd.script, broken_cmd.as_str(), &closest)];
                 }
             }
         }
@@ -1590,6 +1565,32 @@ mod tests {
             let rule = History::new();
             assert!(!rule.requires_output());
         }
+
+        #[test]
+        fn test_honors_history_limit_and_frequency() {
+            let _guard = crate::test_utils::EnvGuard::new(&["TF_HISTORY", "TF_ALIAS"]);
+            std::env::remove_var("TF_ALIAS");
+            std::env::set_var(
+                "TF_HISTORY",
+                "git status\ngit stash\ngit stash\nfuck\ngit stahs",
+            );
+            let rule = History::new();
+            let cmd = Command::new("git stahs", "");
+
+            let settings = crate::config::Settings::default();
+            let ctx = RuleContext::new(&settings, "shell_history");
+            assert_eq!(
+                rule.get_new_command_with_context(&cmd, &ctx),
+                vec!["git stash"]
+            );
+
+            // Only the current command is within the limit
+            let settings = crate::config::Settings::builder()
+                .history_limit(Some(1))
+                .build();
+            let ctx = RuleContext::new(&settings, "shell_history");
+            assert!(!rule.is_match_with_context(&cmd, &ctx));
+        }
     }
 
     // Integration tests
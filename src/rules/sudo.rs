@@ -3,13 +3,13 @@
 //! This rule matches commands that fail due to permission errors and
 //! suggests running them with `sudo`.
 
+use super::patterns::system::PERMISSION_DENIED;
 use crate::core::{Command, Rule};
 
-/// Patterns that indicate a permission error.
+/// Patterns that indicate a permission error, besides "Permission denied"
+/// in any language.
 const PERMISSION_PATTERNS: &[&str] = &[
-    "Permission denied",
     "EACCES",
-    "permission denied",
     "Operation not permitted",
     "you cannot perform this operation unless you are root",
     "must be root",
@@ -88,9 +88,10 @@ impl Rule for Sudo {
         }
 
         // Check if output contains any permission error pattern
-        PERMISSION_PATTERNS
-            .iter()
-            .any(|pattern| cmd.output.contains(pattern))
+        PERMISSION_DENIED.is_in(&cmd.output)
+            || PERMISSION_PATTERNS
+                .iter()
+                .any(|pattern| cmd.output.contains(pattern))
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
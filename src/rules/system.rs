@@ -20,8 +20,15 @@
 //! - [`Man`] - Fixes man command errors
 //! - [`ManNoSpace`] - Fixes "man-page" -> "man page"
 //! - [`Open`] - Fixes open command (macOS/Linux)
-
-use crate::core::{is_app, Command, Rule};
+//! - [`SystemctlUnit`] - Fixes unit names and user vs system scope for systemctl
+
+use super::patterns::system::{
+    COMMAND_NOT_FOUND, FILE_EXISTS, IS_A_DIRECTORY, NO_SUCH_FILE, PERMISSION_DENIED,
+};
+use crate::core::{is_app, Command, Rule, RuleContext};
+use crate::regex;
+use crate::utils::cache::command_output;
+use crate::utils::{contract_path, expand_path, get_close_matches, replace_argument, shell_quote};
 use regex::Regex;
 use std::path::Path;
 
@@ -50,7 +57,7 @@ impl Rule for CatDir {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        is_app(cmd, &["cat"]) && cmd.output.contains("Is a directory")
+        is_app(cmd, &["cat"]) && IS_A_DIRECTORY.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
@@ -94,7 +101,7 @@ impl Rule for ChmodX {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        cmd.script.starts_with("./") && cmd.output.to_lowercase().contains("permission denied")
+        cmd.script.starts_with("./") && PERMISSION_DENIED.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
@@ -107,7 +114,11 @@ impl Rule for ChmodX {
         let script_path = &parts[0];
         let chmod_path = script_path.strip_prefix("./").unwrap_or(script_path);
 
-        vec![format!("chmod +x {} && {}", chmod_path, cmd.script)]
+        vec![format!(
+            "chmod +x {} && {}",
+            shell_quote(chmod_path),
+            cmd.script
+        )]
     }
 
     fn priority(&self) -> i32 {
@@ -141,7 +152,7 @@ impl Rule for CpCreateDestination {
 
     fn is_match(&self, cmd: &Command) -> bool {
         is_app(cmd, &["cp", "mv"])
-            && (cmd.output.contains("No such file or directory")
+            && (NO_SUCH_FILE.is_in(&cmd.output)
                 || (cmd.output.starts_with("cp: directory")
                     && cmd.output.trim_end().ends_with("does not exist")))
     }
@@ -154,7 +165,7 @@ impl Rule for CpCreateDestination {
 
         // Get the last argument (destination)
         let dest = &parts[parts.len() - 1];
-        vec![format!("mkdir -p {} && {}", dest, cmd.script)]
+        vec![format!("mkdir -p {} && {}", shell_quote(dest), cmd.script)]
     }
 
     fn priority(&self) -> i32 {
@@ -187,20 +198,15 @@ impl Rule for CpOmittingDirectory {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        let output_lower = cmd.output.to_lowercase();
         is_app(cmd, &["cp"])
-            && (output_lower.contains("omitting directory")
-                || output_lower.contains("is a directory"))
+            && (cmd.output.to_lowercase().contains("omitting directory")
+                || IS_A_DIRECTORY.is_in(&cmd.output))
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Replace "cp" with "cp -a" at the start
-        if let Ok(re) = Regex::new(r"^cp\b") {
-            let new_cmd = re.replace(&cmd.script, "cp -a").to_string();
-            vec![new_cmd]
-        } else {
-            vec![]
-        }
+        let re = regex!(r"^cp\b");
+        vec![re.replace(&cmd.script, "cp -a").to_string()]
     }
 
     fn priority(&self) -> i32 {
@@ -434,7 +440,7 @@ impl FixFile {
                 if let Some(file_match) = caps.name("file") {
                     let file = file_match.as_str().to_string();
                     // Check if file exists
-                    if Path::new(&file).is_file() {
+                    if expand_path(&file).is_file() {
                         let line = caps
                             .name("line")
                             .map(|m| m.as_str().to_string())
@@ -470,7 +476,8 @@ impl Rule for FixFile {
         };
 
         if let Some((file, line, _col)) = self.search_output(&cmd.output) {
-            let editor_call = format!("{} {} +{}", editor, file, line);
+            let file = contract_path(&expand_path(&file));
+            let editor_call = format!("{} {} +{}", editor, shell_quote(&file), line);
             vec![format!("{} && {}", editor_call, cmd.script)]
         } else {
             vec![]
@@ -512,12 +519,8 @@ impl Rule for LnNoHardLink {
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Replace "ln " with "ln -s " at the start
-        if let Ok(re) = Regex::new(r"^ln\s") {
-            let new_cmd = re.replace(&cmd.script, "ln -s ").to_string();
-            vec![new_cmd]
-        } else {
-            vec![]
-        }
+        let re = regex!(r"^ln\s");
+        vec![re.replace(&cmd.script, "ln -s ").to_string()]
     }
 
     fn priority(&self) -> i32 {
@@ -565,7 +568,7 @@ impl Rule for LnSOrder {
         let parts = cmd.script_parts();
         is_app(cmd, &["ln"])
             && (parts.contains(&"-s".to_string()) || parts.contains(&"--symbolic".to_string()))
-            && cmd.output.contains("File exists")
+            && FILE_EXISTS.is_in(&cmd.output)
             && Self::get_destination(parts).is_some()
     }
 
@@ -647,6 +650,13 @@ impl Rule for LsAll {
 /// let cmd = Command::new("ls mydir", "file1 file2");
 /// assert!(rule.is_match(&cmd));
 /// ```
+///
+/// The flags can be configured:
+///
+/// ```toml
+/// [rule.ls_lah]
+/// flags = "-la"
+/// ```
 #[derive(Debug, Clone, Copy, Default)]
 pub struct LsLah;
 
@@ -661,13 +671,18 @@ impl Rule for LsLah {
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
         let parts = cmd.script_parts();
         if parts.is_empty() {
             return vec![];
         }
 
-        // Replace "ls" with "ls -lah"
-        let mut new_parts = vec!["ls -lah".to_string()];
+        // Replace "ls" with "ls -lah" (or the configured flags)
+        let flags: String = ctx.option("flags").unwrap_or_else(|| "-lah".to_string());
+        let mut new_parts = vec![format!("ls {}", flags)];
         new_parts.extend(parts.iter().skip(1).cloned());
         vec![new_parts.join(" ")]
     }
@@ -702,17 +717,13 @@ impl Rule for MkdirP {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        cmd.script.contains("mkdir") && cmd.output.contains("No such file or directory")
+        cmd.script.contains("mkdir") && NO_SUCH_FILE.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Replace "mkdir" with "mkdir -p"
-        if let Ok(re) = Regex::new(r"\bmkdir\s") {
-            let new_cmd = re.replace(&cmd.script, "mkdir -p ").to_string();
-            vec![new_cmd]
-        } else {
-            vec![]
-        }
+        let re = regex!(r"\bmkdir\s");
+        vec![re.replace(&cmd.script, "mkdir -p ").to_string()]
     }
 
     fn priority(&self) -> i32 {
@@ -745,7 +756,7 @@ impl Rule for RmDir {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        cmd.script.contains("rm") && cmd.output.to_lowercase().contains("is a directory")
+        cmd.script.contains("rm") && IS_A_DIRECTORY.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
@@ -756,13 +767,9 @@ impl Rule for RmDir {
             "-rf"
         };
 
-        if let Ok(re) = Regex::new(r"\brm\s") {
-            let replacement = format!("rm {} ", args);
-            let new_cmd = re.replace(&cmd.script, replacement.as_str()).to_string();
-            vec![new_cmd]
-        } else {
-            vec![]
-        }
+        let re = regex!(r"\brm\s");
+        let replacement = format!("rm {} ", args);
+        vec![re.replace(&cmd.script, replacement.as_str()).to_string()]
     }
 
     fn priority(&self) -> i32 {
@@ -842,31 +849,34 @@ impl Rule for Touch {
     }
 
     fn is_match(&self, cmd: &Command) -> bool {
-        is_app(cmd, &["touch"]) && cmd.output.contains("No such file or directory")
+        is_app(cmd, &["touch"]) && NO_SUCH_FILE.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
         // Extract the path from error message
         // Pattern: touch: cannot touch 'path/file': No such file or directory
         // or: touch: 'path/file': No such file or directory
-        let re = Regex::new(r"touch: (?:cannot touch ')?(.+)/[^/']+").ok();
-
-        if let Some(re) = re {
-            if let Some(caps) = re.captures(&cmd.output) {
-                if let Some(path_match) = caps.get(1) {
-                    let path = path_match.as_str().trim_end_matches('\'');
-                    return vec![format!("mkdir -p {} && {}", path, cmd.script)];
-                }
+        let re = regex!(r"touch: (?:cannot touch ')?(.+)/[^/']+");
+        if let Some(caps) = re.captures(&cmd.output) {
+            if let Some(path_match) = caps.get(1) {
+                let path = path_match.as_str().trim_end_matches('\'');
+                let path = contract_path(&expand_path(path));
+                return vec![format!("mkdir -p {} && {}", shell_quote(&path), cmd.script)];
             }
         }
 
-        // Fallback: try to get parent directory from command arguments
+        // Fallback: try to get parent directory from command arguments.
+        // Variables are expanded, since they would be quoted.
         let parts = cmd.script_parts();
         if parts.len() >= 2 {
-            let file_path = &parts[parts.len() - 1];
-            if let Some(parent) = Path::new(file_path).parent() {
+            let file_path = expand_path(&parts[parts.len() - 1]);
+            if let Some(parent) = file_path.parent() {
                 if !parent.as_os_str().is_empty() {
-                    return vec![format!("mkdir -p {} && {}", parent.display(), cmd.script)];
+                    return vec![format!(
+                        "mkdir -p {} && {}",
+                        shell_quote(&contract_path(parent)),
+                        cmd.script
+                    )];
                 }
             }
         }
@@ -895,6 +905,14 @@ impl Rule for Touch {
 /// let cmd = Command::new("man printf", "No manual entry for printf");
 /// assert!(rule.is_match(&cmd));
 /// ```
+///
+/// When no section was given, sections are suggested in the configured
+/// order (default `[3, 2]`):
+///
+/// ```toml
+/// [rule.man]
+/// sections = [1, 8]
+/// ```
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Man;
 
@@ -909,6 +927,10 @@ impl Rule for Man {
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        self.get_new_command_with_context(cmd, &RuleContext::default())
+    }
+
+    fn get_new_command_with_context(&self, cmd: &Command, ctx: &RuleContext) -> Vec<String> {
         let parts = cmd.script_parts();
         if parts.len() < 2 {
             return vec![];
@@ -937,18 +959,15 @@ impl Rule for Man {
             return vec![help_command];
         }
 
-        // Otherwise, suggest section 3, section 2, and --help
+        // Otherwise, suggest each section in order, then --help
+        let sections: Vec<u32> = ctx.option("sections").unwrap_or_else(|| vec![3, 2]);
         let mut results = Vec::new();
 
-        // Build command with section 3
-        let mut cmd3_parts = parts.to_vec();
-        cmd3_parts.insert(1, "3".to_string());
-        results.push(cmd3_parts.join(" "));
-
-        // Build command with section 2
-        let mut cmd2_parts = parts.to_vec();
-        cmd2_parts.insert(1, "2".to_string());
-        results.push(cmd2_parts.join(" "));
+        for section in sections {
+            let mut section_parts = parts.to_vec();
+            section_parts.insert(1, section.to_string());
+            results.push(section_parts.join(" "));
+        }
 
         results.push(help_command);
         results
@@ -986,7 +1005,7 @@ impl Rule for ManNoSpace {
     fn is_match(&self, cmd: &Command) -> bool {
         cmd.script.starts_with("man")
             && !cmd.script.starts_with("man ")
-            && cmd.output.to_lowercase().contains("command not found")
+            && COMMAND_NOT_FOUND.is_in(&cmd.output)
     }
 
     fn get_new_command(&self, cmd: &Command) -> Vec<String> {
@@ -1068,18 +1087,165 @@ impl Rule for Open {
 }
 
 // =============================================================================
-// Helper Functions
+// SystemctlUnit - Fixes unit names and user vs system scope
 // =============================================================================
 
-/// Simple shell quoting for safety.
-fn shell_quote(s: &str) -> String {
-    // If the string has no special characters, return as-is
-    if s.chars()
-        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
-    {
-        s.to_string()
-    } else {
-        format!("'{}'", s.replace('\'', "'\\''"))
+/// systemctl verbs that only read state, so don't need sudo.
+const SYSTEMCTL_READ_VERBS: &[&str] = &[
+    "status",
+    "show",
+    "cat",
+    "is-active",
+    "is-enabled",
+    "is-failed",
+    "list-dependencies",
+];
+
+/// Rule that fixes systemctl units that can't be found or need root.
+///
+/// Handles:
+/// - A unit that exists as a user unit rather than a system unit, or the
+///   other way round, by switching `--user` on or off
+/// - A misspelled unit, by fuzzy matching `systemctl list-unit-files`
+/// - `Interactive authentication required`, by prepending sudo
+///
+/// # Example
+///
+/// ```
+/// use oops::rules::system::SystemctlUnit;
+/// use oops::core::{Command, Rule};
+///
+/// let rule = SystemctlUnit;
+/// let cmd = Command::new(
+///     "systemctl restart nginx",
+///     "Failed to restart nginx.service: Interactive authentication required.",
+/// );
+/// assert_eq!(rule.get_new_command(&cmd)[0], "sudo systemctl restart nginx");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemctlUnit;
+
+impl SystemctlUnit {
+    /// Extract the unit systemctl couldn't find.
+    fn get_unit(output: &str) -> Option<String> {
+        let re = regex!(r"Unit (\S+?)\.? (?:not found|could not be found)");
+        re.captures(output)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Find the argument naming `unit`, which systemctl reports with a
+    /// `.service` suffix even when the command left it out.
+    fn get_unit_arg(parts: &[String], unit: &str) -> Option<String> {
+        parts
+            .iter()
+            .skip(1)
+            .find(|part| *part == unit || format!("{}.service", part) == unit)
+            .cloned()
+    }
+
+    /// Parse unit names from `systemctl list-unit-files --no-legend` output.
+    fn parse_unit_files(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Get the unit files of the user or system instance.
+    fn get_unit_files(user: bool) -> Vec<String> {
+        let mut args = vec!["list-unit-files", "--no-legend", "--no-pager"];
+        if user {
+            args.insert(0, "--user");
+        }
+        match command_output("systemctl", &args) {
+            Some(output) => Self::parse_unit_files(&String::from_utf8_lossy(&output.stdout)),
+            None => vec![],
+        }
+    }
+
+    /// Build the fixes for a unit that wasn't found, given the unit files
+    /// of the scope the command used and of the other scope.
+    fn unit_fixes(
+        cmd: &Command,
+        unit: &str,
+        same_scope: &[String],
+        other_scope: &[String],
+    ) -> Vec<String> {
+        let parts = cmd.script_parts();
+        let Some(arg) = Self::get_unit_arg(parts, unit) else {
+            return vec![];
+        };
+        let mut fixes = Vec::new();
+
+        if other_scope.iter().any(|u| u == unit) {
+            if parts.iter().any(|p| p == "--user") {
+                let script = regex!(r" --user\b").replace(&cmd.script, "").to_string();
+                let is_read = parts
+                    .iter()
+                    .any(|p| SYSTEMCTL_READ_VERBS.contains(&p.as_str()));
+                fixes.push(if is_read {
+                    script
+                } else {
+                    format!("sudo {}", script)
+                });
+            } else {
+                fixes.push(cmd.script.replacen("systemctl", "systemctl --user", 1));
+            }
+        }
+
+        // Compare without the suffix when the command left it out
+        let candidates: Vec<String> = if arg.contains('.') {
+            same_scope.to_vec()
+        } else {
+            same_scope
+                .iter()
+                .filter_map(|u| u.strip_suffix(".service"))
+                .map(str::to_string)
+                .collect()
+        };
+        fixes.extend(
+            get_close_matches(&arg, &candidates, 3, 0.6)
+                .into_iter()
+                .filter(|fixed| *fixed != arg)
+                .map(|fixed| replace_argument(&cmd.script, &arg, &fixed)),
+        );
+
+        fixes
+    }
+}
+
+impl Rule for SystemctlUnit {
+    fn name(&self) -> &str {
+        "systemctl_unit"
+    }
+
+    fn is_match(&self, cmd: &Command) -> bool {
+        is_app(cmd, &["systemctl"])
+            && (Self::get_unit(&cmd.output).is_some()
+                || cmd.output.contains("Interactive authentication required"))
+    }
+
+    fn get_new_command(&self, cmd: &Command) -> Vec<String> {
+        if cmd.output.contains("Interactive authentication required") {
+            return vec![format!("sudo {}", cmd.script)];
+        }
+
+        let Some(unit) = Self::get_unit(&cmd.output) else {
+            return vec![];
+        };
+        let user = cmd.script_parts().iter().any(|p| p == "--user");
+        Self::unit_fixes(
+            cmd,
+            &unit,
+            &Self::get_unit_files(user),
+            &Self::get_unit_files(!user),
+        )
+    }
+
+    fn requires_output(&self) -> bool {
+        true
     }
 }
 
@@ -1108,6 +1274,7 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(Man),
         Box::new(ManNoSpace),
         Box::new(Open),
+        Box::new(SystemctlUnit),
     ]
 }
 
@@ -1136,6 +1303,14 @@ mod tests {
             assert!(CatDir.is_match(&cmd));
         }
 
+        #[test]
+        fn test_matches_localized_output() {
+            let cmd = Command::new("cat /tmp", "cat: /tmp: Ist ein Verzeichnis");
+            assert!(CatDir.is_match(&cmd));
+            let cmd = Command::new("cat /tmp", "cat: /tmp: Est un dossier");
+            assert!(CatDir.is_match(&cmd));
+        }
+
         #[test]
         fn test_no_match_successful_cat() {
             let cmd = Command::new("cat file.txt", "file contents");
@@ -1210,6 +1385,16 @@ mod tests {
             let fixes = CpCreateDestination.get_new_command(&cmd);
             assert_eq!(fixes, vec!["mkdir -p /new/path/ && cp file.txt /new/path/"]);
         }
+
+        #[test]
+        fn test_get_new_command_quotes_destination() {
+            let cmd = Command::new("cp file.txt 'My Files/'", "No such file or directory");
+            let fixes = CpCreateDestination.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["mkdir -p 'My Files/' && cp file.txt 'My Files/'"]
+            );
+        }
     }
 
     // -------------------------------------------------------------------------
@@ -1419,6 +1604,19 @@ mod tests {
             assert_eq!(LsLah.name(), "ls_lah");
         }
 
+        #[test]
+        fn test_get_new_command_configured_flags() {
+            let settings = crate::config::Settings::builder()
+                .rule_option("ls_lah", "flags", "-la")
+                .build();
+            let ctx = RuleContext::new(&settings, "ls_lah");
+            let cmd = Command::new("ls mydir", "file1 file2");
+            assert_eq!(
+                LsLah.get_new_command_with_context(&cmd, &ctx),
+                vec!["ls -la mydir"]
+            );
+        }
+
         #[test]
         fn test_matches_plain_ls() {
             let cmd = Command::new("ls mydir", "file1 file2");
@@ -1582,6 +1780,49 @@ mod tests {
             let fixes = Touch.get_new_command(&cmd);
             assert!(fixes[0].contains("mkdir -p") && fixes[0].contains("touch"));
         }
+
+        #[test]
+        fn test_get_new_command_quotes_path() {
+            let cmd = Command::new(
+                "touch 'new dir/file.txt'",
+                "touch: cannot touch 'new dir/file.txt': No such file or directory",
+            );
+            let fixes = Touch.get_new_command(&cmd);
+            assert_eq!(
+                fixes,
+                vec!["mkdir -p 'new dir' && touch 'new dir/file.txt'"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_home_directory() {
+            let home = dirs::home_dir().unwrap();
+            let cmd = Command::new(
+                "touch ~/notes/todo.txt",
+                format!(
+                    "touch: cannot touch '{}/notes/todo.txt': No such file or directory",
+                    home.display()
+                ),
+            );
+            assert_eq!(
+                Touch.get_new_command(&cmd),
+                vec!["mkdir -p ~/notes && touch ~/notes/todo.txt"]
+            );
+        }
+
+        #[test]
+        fn test_get_new_command_expands_variables() {
+            let _guard = crate::test_utils::EnvGuard::new(&["OOPS_TOUCH_DIR"]);
+            std::env::set_var("OOPS_TOUCH_DIR", "/tmp/oops touch");
+            let cmd = Command::new(
+                "touch $OOPS_TOUCH_DIR/a/file.txt",
+                "No such file or directory",
+            );
+            assert_eq!(
+                Touch.get_new_command(&cmd),
+                vec!["mkdir -p '/tmp/oops touch/a' && touch $OOPS_TOUCH_DIR/a/file.txt"]
+            );
+        }
     }
 
     // -------------------------------------------------------------------------
@@ -1595,6 +1836,19 @@ mod tests {
             assert_eq!(Man.name(), "man");
         }
 
+        #[test]
+        fn test_get_new_command_configured_sections() {
+            let settings = crate::config::Settings::builder()
+                .rule_option("man", "sections", vec![1, 8])
+                .build();
+            let ctx = RuleContext::new(&settings, "man");
+            let cmd = Command::new("man mount", "Some other error");
+            assert_eq!(
+                Man.get_new_command_with_context(&cmd, &ctx),
+                vec!["man 1 mount", "man 8 mount", "mount --help"]
+            );
+        }
+
         #[test]
         fn test_matches_man_command() {
             let cmd = Command::new("man printf", "No manual entry for printf");
@@ -1699,6 +1953,83 @@ mod tests {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // SystemctlUnit Tests
+    // -------------------------------------------------------------------------
+    mod systemctl_unit {
+        use super::*;
+
+        fn units(names: &[&str]) -> Vec<String> {
+            names.iter().map(|s| s.to_string()).collect()
+        }
+
+        #[test]
+        fn test_name() {
+            assert_eq!(SystemctlUnit.name(), "systemctl_unit");
+        }
+
+        #[test]
+        fn test_matches_unit_not_found() {
+            let cmd = Command::new(
+                "systemctl start syncthing",
+                "Failed to start syncthing.service: Unit syncthing.service not found.",
+            );
+            assert!(SystemctlUnit.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_no_match_other_tool() {
+            let cmd = Command::new("service nginx start", "Unit nginx.service not found.");
+            assert!(!SystemctlUnit.is_match(&cmd));
+        }
+
+        #[test]
+        fn test_unit_fixes_user_unit() {
+            let cmd = Command::new("systemctl restart syncthing", "");
+            let fixes = SystemctlUnit::unit_fixes(
+                &cmd,
+                "syncthing.service",
+                &units(&["cron.service"]),
+                &units(&["syncthing.service"]),
+            );
+            assert_eq!(fixes, vec!["systemctl --user restart syncthing"]);
+        }
+
+        #[test]
+        fn test_unit_fixes_system_unit() {
+            let cmd = Command::new("systemctl --user restart nginx", "");
+            let fixes =
+                SystemctlUnit::unit_fixes(&cmd, "nginx.service", &[], &units(&["nginx.service"]));
+            assert_eq!(fixes, vec!["sudo systemctl restart nginx"]);
+
+            let cmd = Command::new("systemctl --user status nginx", "");
+            let fixes =
+                SystemctlUnit::unit_fixes(&cmd, "nginx.service", &[], &units(&["nginx.service"]));
+            assert_eq!(fixes, vec!["systemctl status nginx"]);
+        }
+
+        #[test]
+        fn test_unit_fixes_misspelled() {
+            let cmd = Command::new("systemctl status ngnix", "");
+            let fixes = SystemctlUnit::unit_fixes(
+                &cmd,
+                "ngnix.service",
+                &units(&["nginx.service", "cron.service", "dbus.socket"]),
+                &[],
+            );
+            assert_eq!(fixes, vec!["systemctl status nginx"]);
+        }
+
+        #[test]
+        fn test_parse_unit_files() {
+            let output = "cron.service     enabled  enabled\nssh.socket       disabled enabled\n";
+            assert_eq!(
+                SystemctlUnit::parse_unit_files(output),
+                vec!["cron.service", "ssh.socket"]
+            );
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Integration Tests
     // -------------------------------------------------------------------------
@@ -1708,7 +2039,7 @@ mod tests {
         #[test]
         fn test_all_rules_returns_all() {
             let rules = all_rules();
-            assert_eq!(rules.len(), 18);
+            assert_eq!(rules.len(), 19);
         }
 
         #[test]
@@ -1720,18 +2051,5 @@ mod tests {
             names.dedup();
             assert_eq!(names.len(), original_len);
         }
-
-        #[test]
-        fn test_shell_quote_simple() {
-            assert_eq!(shell_quote("simple"), "simple");
-            assert_eq!(shell_quote("with-dash"), "with-dash");
-            assert_eq!(shell_quote("with_underscore"), "with_underscore");
-        }
-
-        #[test]
-        fn test_shell_quote_special() {
-            assert_eq!(shell_quote("with space"), "'with space'");
-            assert_eq!(shell_quote("with'quote"), "'with'\\''quote'");
-        }
     }
 }
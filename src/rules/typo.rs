@@ -7,7 +7,7 @@
 //! - [`Systemctl`] - Fixes common systemctl typos
 
 use crate::core::{is_app, Command, Rule};
-use crate::utils::get_close_matches;
+use crate::utils::fuzzy::{get_close_matches_with, MatchOptions};
 
 /// Rule that fixes "sl" typo to "ls".
 ///
@@ -108,7 +108,7 @@ pub struct PythonCommand;
 impl PythonCommand {
     /// Check if a command exists in PATH
     fn command_exists(cmd: &str) -> bool {
-        which::which(cmd).is_ok()
+        crate::utils::uncached_which(cmd).is_some()
     }
 }
 
@@ -174,11 +174,9 @@ impl Rule for PythonCommand {
                     suggestions.push(format!("python2{}", rest));
                 }
             }
-            "python3" => {
-                // Try python
-                if Self::command_exists("python") {
-                    suggestions.push(format!("python{}", rest));
-                }
+            // Try python
+            "python3" if Self::command_exists("python") => {
+                suggestions.push(format!("python{}", rest));
             }
             "python2" => {
                 // Python 2 is deprecated, suggest python3
@@ -197,10 +195,8 @@ impl Rule for PythonCommand {
                     suggestions.push(format!("pip2{}", rest));
                 }
             }
-            "pip3" => {
-                if Self::command_exists("pip") {
-                    suggestions.push(format!("pip{}", rest));
-                }
+            "pip3" if Self::command_exists("pip") => {
+                suggestions.push(format!("pip{}", rest));
             }
             "pip2" => {
                 if Self::command_exists("pip3") {
@@ -339,7 +335,7 @@ impl Rule for Systemctl {
 
         // Check if it's a typo in the subcommand
         let commands: Vec<String> = SYSTEMCTL_COMMANDS.iter().map(|s| s.to_string()).collect();
-        let matches = get_close_matches(subcommand, &commands, 3, 0.6);
+        let matches = get_close_matches_with(subcommand, &commands, 3, 0.6, MatchOptions::typos());
 
         if !matches.is_empty() {
             // Found close matches - suggest corrections
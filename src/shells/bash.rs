@@ -91,7 +91,9 @@ impl Shell for Bash {
     TF_PYTHONIOENCODING=$PYTHONIOENCODING;
     export TF_SHELL=bash;
     export TF_ALIAS={name};
+    export TF_PWD="$PWD";
     export TF_SHELL_ALIASES=$(alias);
+    export TF_SHELL_FUNCTIONS=$(compgen -A function);
     export TF_HISTORY=$(fc -ln -10);
     export PYTHONIOENCODING=utf-8;
     TF_CMD=$(
@@ -155,7 +157,9 @@ function {name} () {{
     TF_PYTHONIOENCODING=$PYTHONIOENCODING;
     export TF_SHELL=bash;
     export TF_ALIAS={name};
+    export TF_PWD="$PWD";
     export TF_SHELL_ALIASES=$(alias);
+    export TF_SHELL_FUNCTIONS=$(compgen -A function);
     export TF_HISTORY=$(fc -ln -10);
     export PYTHONIOENCODING=utf-8;
     TF_CMD=$(
@@ -240,6 +244,14 @@ mod tests {
         assert_eq!(aliases.get("gs"), Some(&"git status".to_string()));
     }
 
+    #[test]
+    fn test_get_function_names_from_env() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL_FUNCTIONS"]);
+        env::set_var("TF_SHELL_FUNCTIONS", "mkcd\n_completion_loader\nserve\n");
+        let bash = Bash::new();
+        assert_eq!(bash.get_function_names(), vec!["mkcd", "serve"]);
+    }
+
     #[test]
     fn test_app_alias_contains_required_elements() {
         let bash = Bash::new();
@@ -249,7 +261,9 @@ mod tests {
         assert!(alias.contains("function fuck ()"));
         assert!(alias.contains("export TF_SHELL=bash"));
         assert!(alias.contains("export TF_ALIAS=fuck"));
+        assert!(alias.contains("export TF_PWD=\"$PWD\""));
         assert!(alias.contains("export TF_SHELL_ALIASES=$(alias)"));
+        assert!(alias.contains("export TF_SHELL_FUNCTIONS=$(compgen -A function)"));
         assert!(alias.contains("export TF_HISTORY=$(fc -ln -10)"));
         assert!(alias.contains("oops THEFUCK_ARGUMENT_PLACEHOLDER"));
         assert!(alias.contains("eval \"$TF_CMD\""));
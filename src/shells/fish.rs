@@ -11,7 +11,7 @@ use std::process::{Command, Stdio};
 
 use anyhow::Result;
 
-use super::Shell;
+use super::{shell_program, Shell};
 use crate::cli::THEFUCK_ARGUMENT_PLACEHOLDER;
 
 /// Fish shell implementation.
@@ -137,7 +137,7 @@ impl Shell for Fish {
         format!(
             r#"function {name} -d "Correct your previous console command"
     set -l fucked_up_command $history[1]
-    env TF_SHELL=fish TF_ALIAS={name} PYTHONIOENCODING=utf-8 oops $fucked_up_command {placeholder} $argv | read -l unfucked_command
+    env TF_SHELL=fish TF_ALIAS={name} TF_PWD=$PWD PYTHONIOENCODING=utf-8 oops $fucked_up_command {placeholder} $argv | read -l unfucked_command
     if [ "$unfucked_command" != "" ]
         eval $unfucked_command
         builtin history delete --exact --case-sensitive -- $fucked_up_command
@@ -165,6 +165,13 @@ end
         aliases
     }
 
+    fn get_function_names(&self) -> Vec<String> {
+        let overridden = self.get_overridden_aliases();
+        let mut names: Vec<String> = self.get_functions(&overridden).into_keys().collect();
+        names.sort();
+        names
+    }
+
     fn and_(&self, commands: &[&str]) -> String {
         // Fish uses "; and " for command chaining
         commands.join("; and ")
@@ -198,6 +205,17 @@ end
         Ok(())
     }
 
+    fn quote(&self, arg: &str) -> String {
+        crate::utils::quote::fish_quote(arg)
+    }
+
+    fn interactive_command(&self, script: &str) -> (String, Vec<String>) {
+        (
+            shell_program(self.name()),
+            vec!["-i".to_string(), "-c".to_string(), script.to_string()],
+        )
+    }
+
     fn get_history_file_name(&self) -> Option<String> {
         Some(self.get_history_file())
     }
@@ -241,6 +259,7 @@ mod tests {
         assert!(alias.contains("$history[1]"));
         assert!(alias.contains("TF_SHELL=fish"));
         assert!(alias.contains("TF_ALIAS=fuck"));
+        assert!(alias.contains("TF_PWD=$PWD"));
         assert!(alias.contains("eval $unfucked_command"));
         assert!(alias.contains("builtin history delete"));
         assert!(alias.contains("builtin history merge"));
@@ -254,6 +273,12 @@ mod tests {
         assert!(alias.contains("TF_ALIAS=oops"));
     }
 
+    #[test]
+    fn test_fish_interactive_command() {
+        let (_, args) = Fish::new().interactive_command("ll");
+        assert_eq!(args, vec!["-i", "-c", "ll"]);
+    }
+
     #[test]
     fn test_builtin_commands() {
         let fish = Fish::new();
@@ -52,6 +52,24 @@ pub trait Shell: Send + Sync {
     /// A HashMap mapping alias names to their expanded values.
     fn get_aliases(&self) -> HashMap<String, String>;
 
+    /// Gets the names of the user's shell functions.
+    ///
+    /// Reads the TF_SHELL_FUNCTIONS environment variable, one name per
+    /// line. Names starting with `_` are helpers (completion functions and
+    /// the like) and are skipped.
+    ///
+    /// # Returns
+    /// The function names, in the order the shell listed them.
+    fn get_function_names(&self) -> Vec<String> {
+        env::var("TF_SHELL_FUNCTIONS")
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && !name.starts_with('_'))
+            .map(String::from)
+            .collect()
+    }
+
     /// Joins commands with the shell's AND operator.
     ///
     /// # Arguments
@@ -106,6 +124,35 @@ pub trait Shell: Send + Sync {
     fn get_history_file_name(&self) -> Option<String> {
         None
     }
+
+    /// Quotes `arg` so this shell reads it as a single argument.
+    fn quote(&self, arg: &str) -> String {
+        crate::utils::quote::posix_quote(arg)
+    }
+
+    /// Builds the program and arguments running `script` in an interactive
+    /// instance of this shell, so the user's aliases and functions work.
+    ///
+    /// # Returns
+    /// The program (`$SHELL` when it is this shell) and its arguments.
+    fn interactive_command(&self, script: &str) -> (String, Vec<String>) {
+        (
+            shell_program(self.name()),
+            vec!["-ic".to_string(), script.to_string()],
+        )
+    }
+}
+
+/// Returns `$SHELL` if it points to the shell called `name`, or `name` itself.
+fn shell_program(name: &str) -> String {
+    env::var("SHELL")
+        .ok()
+        .filter(|path| {
+            std::path::Path::new(path)
+                .file_stem()
+                .is_some_and(|stem| stem == name)
+        })
+        .unwrap_or_else(|| name.to_string())
 }
 
 /// Registry of known shells.
@@ -321,4 +368,18 @@ mod tests {
         let result = shell.or_(&["cmd1", "cmd2"]);
         assert_eq!(result, "cmd1 || cmd2");
     }
+
+    #[test]
+    fn test_interactive_command_uses_matching_shell_env() {
+        let _guard = crate::test_utils::EnvGuard::new(&["SHELL"]);
+        env::set_var("SHELL", "/usr/local/bin/zsh");
+        assert_eq!(
+            Zsh::new().interactive_command("ll"),
+            (
+                "/usr/local/bin/zsh".to_string(),
+                vec!["-ic".to_string(), "ll".to_string()]
+            )
+        );
+        assert_eq!(Bash::new().interactive_command("ll").0, "bash");
+    }
 }
@@ -6,7 +6,6 @@
 //! - No TF_SHELL_ALIASES support (PowerShell handles aliases differently)
 
 use std::collections::HashMap;
-#[cfg(windows)]
 use std::env;
 
 use anyhow::Result;
@@ -72,6 +71,7 @@ impl Shell for PowerShell {
             r#"function {name} {{
     $history = (Get-History -Count 1).CommandLine;
     if (-not [string]::IsNullOrWhiteSpace($history)) {{
+        $env:TF_PWD = (Get-Location).Path;
         $fuck = $(oops $args $history);
         if (-not [string]::IsNullOrWhiteSpace($fuck)) {{
             if ($fuck.StartsWith("echo")) {{ $fuck = $fuck.Substring(5); }}
@@ -123,6 +123,22 @@ impl Shell for PowerShell {
         Ok(())
     }
 
+    fn quote(&self, arg: &str) -> String {
+        crate::utils::quote::powershell_quote(arg)
+    }
+
+    fn interactive_command(&self, script: &str) -> (String, Vec<String>) {
+        // The profile, with the user's functions and aliases, is loaded by default
+        let program = match env::var("TF_SHELL").as_deref() {
+            Ok("pwsh") => "pwsh",
+            _ => "powershell",
+        };
+        (
+            program.to_string(),
+            vec!["-Command".to_string(), script.to_string()],
+        )
+    }
+
     fn get_history_file_name(&self) -> Option<String> {
         let path = self.get_history_file();
         if path.is_empty() {
@@ -258,6 +274,19 @@ mod tests {
         assert!(aliases.is_empty());
     }
 
+    #[test]
+    fn test_powershell_interactive_command() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+        env::set_var("TF_SHELL", "pwsh");
+        assert_eq!(
+            PowerShell::new().interactive_command("ll"),
+            (
+                "pwsh".to_string(),
+                vec!["-Command".to_string(), "ll".to_string()]
+            )
+        );
+    }
+
     #[test]
     fn test_builtin_commands() {
         let ps = PowerShell::new();
@@ -11,7 +11,7 @@ use std::process::{Command, Stdio};
 
 use anyhow::Result;
 
-use super::Shell;
+use super::{shell_program, Shell};
 
 /// Tcsh shell implementation.
 #[derive(Debug, Clone, Default)]
@@ -59,11 +59,11 @@ impl Shell for Tcsh {
 
     fn app_alias(&self, alias_name: &str, _instant_mode: bool) -> String {
         // Tcsh alias that:
-        // 1. Sets TF_SHELL and TF_ALIAS environment variables
+        // 1. Sets TF_SHELL, TF_ALIAS and TF_PWD environment variables
         // 2. Gets the last command from history
         // 3. Evaluates the oops output
         format!(
-            "alias {name} 'setenv TF_SHELL tcsh && setenv TF_ALIAS {name} && \
+            "alias {name} 'setenv TF_SHELL tcsh && setenv TF_ALIAS {name} && setenv TF_PWD \"$cwd\" && \
              set fucked_cmd=`history -h 2 | head -n 1` && \
              eval `oops ${{fucked_cmd}}`'\n",
             name = alias_name
@@ -125,6 +125,14 @@ impl Shell for Tcsh {
     fn get_history_file_name(&self) -> Option<String> {
         Some(self.get_history_file())
     }
+
+    fn interactive_command(&self, script: &str) -> (String, Vec<String>) {
+        // tcsh reads ~/.tcshrc for `-c` too, and rejects `-i` together with it
+        (
+            shell_program(self.name()),
+            vec!["-c".to_string(), script.to_string()],
+        )
+    }
 }
 
 #[cfg(test)]
@@ -158,6 +166,7 @@ mod tests {
         assert!(alias.contains("alias fuck"));
         assert!(alias.contains("setenv TF_SHELL tcsh"));
         assert!(alias.contains("setenv TF_ALIAS fuck"));
+        assert!(alias.contains("setenv TF_PWD \"$cwd\""));
         assert!(alias.contains("history -h 2 | head -n 1"));
         assert!(alias.contains("eval `oops ${fucked_cmd}`"));
     }
@@ -187,6 +196,12 @@ mod tests {
         assert!(history.is_empty());
     }
 
+    #[test]
+    fn test_tcsh_interactive_command() {
+        let (_, args) = Tcsh::new().interactive_command("ll");
+        assert_eq!(args, vec!["-c", "ll"]);
+    }
+
     #[test]
     fn test_builtin_commands() {
         let tcsh = Tcsh::new();
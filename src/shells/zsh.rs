@@ -108,8 +108,11 @@ impl Shell for Zsh {
     TF_PYTHONIOENCODING=$PYTHONIOENCODING;
     export TF_SHELL=zsh;
     export TF_ALIAS={name};
+    export TF_PWD="$PWD";
     TF_SHELL_ALIASES=$(alias);
     export TF_SHELL_ALIASES;
+    TF_SHELL_FUNCTIONS=$(print -l ${{(k)functions}});
+    export TF_SHELL_FUNCTIONS;
     TF_HISTORY="$(fc -ln -10)";
     export TF_HISTORY;
     export PYTHONIOENCODING=utf-8;
@@ -283,8 +286,11 @@ impl Zsh {
     TF_PYTHONIOENCODING=$PYTHONIOENCODING;
     export TF_SHELL=zsh;
     export TF_ALIAS={name};
+    export TF_PWD="$PWD";
     TF_SHELL_ALIASES=$(alias);
     export TF_SHELL_ALIASES;
+    TF_SHELL_FUNCTIONS=$(print -l ${{(k)functions}});
+    export TF_SHELL_FUNCTIONS;
     TF_HISTORY="$(fc -ln -10)";
     export TF_HISTORY;
     export PYTHONIOENCODING=utf-8;
@@ -387,6 +393,7 @@ mod tests {
         assert!(alias.contains("export TF_ALIAS=fuck"));
         assert!(alias.contains("TF_SHELL_ALIASES=$(alias)"));
         assert!(alias.contains("export TF_SHELL_ALIASES"));
+        assert!(alias.contains("TF_SHELL_FUNCTIONS=$(print -l ${(k)functions})"));
         assert!(alias.contains("TF_HISTORY=\"$(fc -ln -10)\""));
         assert!(alias.contains("export TF_HISTORY"));
         assert!(alias.contains("oops THEFUCK_ARGUMENT_PLACEHOLDER"));
@@ -2,13 +2,26 @@
 //!
 //! This module provides cached versions of commonly used functions
 //! to improve performance by avoiding redundant computations.
+//!
+//! External commands rules run to look things up (`git branch`, `lsof`)
+//! are memoized for a single fix with [`command_output`], since several
+//! rules may ask for the same thing.
 
-use cached::proc_macro::cached;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cached::proc_macro::cached;
+use once_cell::sync::{Lazy, OnceCell};
+use tracing::debug;
+
+use crate::output::{output_with_timeout, working_dir};
 
 /// Finds the path to a program in the system PATH.
 ///
-/// This is a cached wrapper around the `which` crate's functionality.
+/// This is a cached wrapper around [`super::executables::which`].
 /// Results are memoized to avoid repeated PATH lookups for the same program.
 ///
 /// # Arguments
@@ -31,7 +44,7 @@ use std::path::PathBuf;
 /// ```
 #[cached(size = 100)]
 pub fn which(program: String) -> Option<PathBuf> {
-    ::which::which(program).ok()
+    super::executables::which(&program)
 }
 
 /// Checks if a program exists in PATH.
@@ -52,6 +65,91 @@ pub fn program_exists(program: String) -> bool {
     which(program).is_some()
 }
 
+/// How long a command run by [`command_output`] may take before it's killed.
+///
+/// Rules run these while matching, and some of them (`poetry search`,
+/// `kubectl`) go over the network, so a slow one mustn't hang oops.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A memoized command run, filled in by whichever caller runs it first.
+type MemoizedOutput = Arc<OnceCell<Option<Output>>>;
+
+/// Outputs of commands, by program and arguments.
+#[derive(Debug, Default)]
+struct CommandMemo {
+    outputs: Mutex<HashMap<Vec<String>, MemoizedOutput>>,
+}
+
+impl CommandMemo {
+    fn output(&self, program: &str, args: &[&str]) -> Option<Output> {
+        let key: Vec<String> = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect();
+        let memo = self
+            .outputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_default()
+            .clone();
+
+        // Run outside the lock, so other commands don't wait for this one
+        memo.get_or_init(|| {
+            debug!("Running {} {:?}", program, args);
+            let mut command = Command::new(program);
+            command.args(args).current_dir(working_dir());
+            let output = output_with_timeout(&mut command, COMMAND_TIMEOUT);
+            if output.is_none() {
+                debug!("{} {:?} failed or timed out", program, args);
+            }
+            output
+        })
+        .clone()
+    }
+
+    fn clear(&self) {
+        self.outputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+/// Outputs of the commands run during the current fix.
+static COMMAND_OUTPUTS: Lazy<CommandMemo> = Lazy::new(CommandMemo::default);
+
+/// Runs `program` with `args` and returns its output, or `None` if it
+/// couldn't be started or didn't finish within a few seconds.
+///
+/// The command runs in the directory the failed command was run in (see
+/// [`working_dir`]), so it sees the same repository and project files.
+///
+/// The command runs at most once per fix: later calls with the same program
+/// and arguments get the same output, and concurrent calls wait for the
+/// first one. [`clear_command_outputs`] starts over for the next fix.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::cache::command_output;
+///
+/// if let Some(output) = command_output("git", &["branch", "--no-color"]) {
+///     println!("{}", String::from_utf8_lossy(&output.stdout));
+/// }
+/// ```
+pub fn command_output(program: &str, args: &[&str]) -> Option<Output> {
+    COMMAND_OUTPUTS.output(program, args)
+}
+
+/// Forgets the outputs memoized by [`command_output`].
+///
+/// Called at the start of each fix, so a fix sees changes made by the
+/// commands run after the previous one (e.g. a new branch).
+pub fn clear_command_outputs() {
+    COMMAND_OUTPUTS.clear();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +196,43 @@ mod tests {
         let result2 = which(program.to_string());
         assert_eq!(result1, result2);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_runs_in_working_dir() {
+        let _guard = crate::test_utils::EnvGuard::new(&[crate::output::WORKING_DIR_ENV]);
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::output::WORKING_DIR_ENV, dir.path());
+
+        let output = CommandMemo::default().output("pwd", &["-P"]).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            dir.path().canonicalize().unwrap().to_string_lossy()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_runs_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = dir.path().join("runs");
+        let script = format!("echo run >> '{}'; echo hello", counter.display());
+        let memo = CommandMemo::default();
+
+        let first = memo.output("sh", &["-c", &script]).unwrap();
+        let second = memo.output("sh", &["-c", &script]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&first.stdout), "hello\n");
+        assert_eq!(first.stdout, second.stdout);
+        assert_eq!(std::fs::read_to_string(&counter).unwrap(), "run\n");
+
+        // Runs again after clearing
+        memo.clear();
+        memo.output("sh", &["-c", &script]).unwrap();
+        assert_eq!(std::fs::read_to_string(&counter).unwrap(), "run\nrun\n");
+    }
+
+    #[test]
+    fn test_command_output_missing_program() {
+        assert!(command_output("nonexistent_program_xyz_123", &[]).is_none());
+    }
 }
@@ -2,33 +2,224 @@
 //!
 //! This module provides functionality for:
 //! - Finding all executables in the system PATH
+//! - Listing commands to suggest, including the user's aliases and functions
+//! - Persisting the PATH scan between runs
 //! - Checking if a program exists in PATH
 //! - Replacing command arguments in scripts
 
-use once_cell::sync::Lazy;
-use regex::Regex;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+use crate::shells::{detect_shell, Shell};
+
+/// Name of the executables cache file in the cache directory.
+const CACHE_FILE_NAME: &str = "executables.json";
 
 /// Cached set of all executables found in PATH.
 ///
 /// This is lazily initialized on first access and cached for the lifetime
-/// of the program to avoid repeated filesystem operations.
+/// of the program to avoid repeated filesystem operations. When
+/// [`set_executables_cache`] was called first, the scan is also persisted
+/// between runs.
 static ALL_EXECUTABLES: Lazy<HashSet<String>> = Lazy::new(|| {
-    let mut executables = HashSet::new();
-
-    // Get PATH environment variable
     let path_env = env::var("PATH").unwrap_or_default();
+    match EXECUTABLES_CACHE.get() {
+        Some(cache) => cache.load_or_scan(&path_env),
+        None => scan_path(&path_env),
+    }
+});
+
+/// Where the PATH scan is persisted, if anywhere.
+static EXECUTABLES_CACHE: OnceCell<ExecutablesCache> = OnceCell::new();
+
+/// Persist the PATH scan in `dir` for `ttl`.
+///
+/// Must be called before the first [`get_all_executables`] to take effect.
+/// The scan is reused while it's younger than `ttl` and neither PATH nor
+/// the modification time of any directory in it changed.
+pub fn set_executables_cache(dir: &Path, ttl: Duration) {
+    if EXECUTABLES_CACHE
+        .set(ExecutablesCache::new(dir, ttl))
+        .is_err()
+    {
+        debug!("Executables cache already set");
+    }
+}
+
+/// A PATH scan saved in the cache file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedScan {
+    /// Hash of PATH and the modification times of its directories.
+    key: String,
+    /// When PATH was scanned, in milliseconds since the Unix epoch.
+    created: u64,
+    executables: Vec<String>,
+}
+
+/// PATH scans persisted in a cache directory.
+#[derive(Debug, Clone)]
+struct ExecutablesCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl ExecutablesCache {
+    fn new(dir: &Path, ttl: Duration) -> Self {
+        Self {
+            path: dir.join(CACHE_FILE_NAME),
+            ttl,
+        }
+    }
+
+    /// Get the executables in `path_env` from the cache, scanning and
+    /// caching them if the cached scan is stale.
+    fn load_or_scan(&self, path_env: &str) -> HashSet<String> {
+        let key = path_key(path_env);
+        if let Some(executables) = self.load(&key) {
+            debug!("Using cached executables from {}", self.path.display());
+            return executables;
+        }
+
+        let executables = scan_path(path_env);
+        if let Err(e) = self.save(&key, &executables) {
+            debug!("Failed to cache executables: {:#}", e);
+        }
+        executables
+    }
+
+    fn load(&self, key: &str) -> Option<HashSet<String>> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let scan: CachedScan = serde_json::from_str(&content).ok()?;
+        let age = now().saturating_sub(scan.created);
+        if scan.key != key || u128::from(age) >= self.ttl.as_millis() {
+            return None;
+        }
+        Some(scan.executables.into_iter().collect())
+    }
+
+    fn save(&self, key: &str, executables: &HashSet<String>) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+        let mut executables: Vec<String> = executables.iter().cloned().collect();
+        executables.sort();
+        let scan = CachedScan {
+            key: key.to_string(),
+            created: now(),
+            executables,
+        };
+        fs::write(&self.path, serde_json::to_string(&scan)?)
+            .with_context(|| format!("Failed to write cache file: {}", self.path.display()))
+    }
+}
+
+/// Split PATH using the platform-appropriate separator.
+fn split_path(path_env: &str) -> impl Iterator<Item = &str> {
+    // Windows PATH entries may be quoted
+    #[cfg(windows)]
+    return path_env.split(';').map(|dir| dir.trim_matches('"'));
+    #[cfg(not(windows))]
+    return path_env.split(':');
+}
+
+/// Extensions Windows runs without being typed, used when PATHEXT isn't set.
+#[cfg(any(windows, test))]
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// Executable extensions from PATHEXT, lowercase and with the leading dot.
+#[cfg(windows)]
+static PATH_EXTENSIONS: Lazy<Vec<String>> =
+    Lazy::new(|| parse_pathext(env::var("PATHEXT").ok().as_deref()));
+
+/// Parse a PATHEXT value like `.COM;.EXE;.BAT`.
+#[cfg(any(windows, test))]
+fn parse_pathext(value: Option<&str>) -> Vec<String> {
+    value
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or(DEFAULT_PATHEXT)
+        .split(';')
+        .map(str::trim)
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| {
+            let ext = ext.to_lowercase();
+            if ext.starts_with('.') {
+                ext
+            } else {
+                format!(".{}", ext)
+            }
+        })
+        .collect()
+}
+
+/// Check if `name` ends with one of `extensions`, ignoring case.
+#[cfg(any(windows, test))]
+fn has_extension(name: &str, extensions: &[String]) -> bool {
+    let name = name.to_lowercase();
+    extensions
+        .iter()
+        .any(|ext| name.len() > ext.len() && name.ends_with(ext.as_str()))
+}
+
+/// File names Windows tries for `program`: the name itself if it already
+/// has an executable extension, otherwise the name with each extension.
+#[cfg(any(windows, test))]
+fn windows_candidates(program: &str, extensions: &[String]) -> Vec<String> {
+    if has_extension(program, extensions) {
+        vec![program.to_string()]
+    } else {
+        extensions
+            .iter()
+            .map(|ext| format!("{}{}", program, ext))
+            .collect()
+    }
+}
 
-    // Split PATH using the platform-appropriate separator
+/// File names to look for when resolving `program`.
+fn candidate_names(program: &str) -> Vec<String> {
     #[cfg(windows)]
-    let separator = ';';
+    return windows_candidates(program, &PATH_EXTENSIONS);
     #[cfg(not(windows))]
-    let separator = ':';
+    return vec![program.to_string()];
+}
+
+/// Hash PATH and the modification times of its directories, so installing
+/// or removing a program invalidates the cached scan.
+fn path_key(path_env: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    path_env.hash(&mut hasher);
+    for path_str in split_path(path_env) {
+        let modified = fs::metadata(path_str)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+        modified.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Find all executables in the directories of `path_env`.
+fn scan_path(path_env: &str) -> HashSet<String> {
+    let mut executables = HashSet::new();
 
-    for path_str in path_env.split(separator) {
+    for path_str in split_path(path_env) {
         let path = PathBuf::from(path_str);
 
         // Skip if path doesn't exist or isn't a directory
@@ -51,12 +242,14 @@ static ALL_EXECUTABLES: Lazy<HashSet<String>> = Lazy::new(|| {
                     if let Some(name) = entry_path.file_name() {
                         let name_str = name.to_string_lossy().to_string();
 
-                        // On Windows, also add the name without extension
+                        // On Windows, also add the name without extension.
+                        // Names are case-insensitive there, so `Git.EXE`
+                        // is found as `git`.
                         #[cfg(windows)]
                         {
-                            executables.insert(name_str.clone());
+                            executables.insert(name_str.to_lowercase());
                             if let Some(stem) = entry_path.file_stem() {
-                                executables.insert(stem.to_string_lossy().to_string());
+                                executables.insert(stem.to_string_lossy().to_lowercase());
                             }
                         }
 
@@ -73,12 +266,12 @@ static ALL_EXECUTABLES: Lazy<HashSet<String>> = Lazy::new(|| {
     executables.retain(|name| !tf_entries.contains(name.as_str()));
 
     executables
-});
+}
 
 /// Check if a file is executable.
 ///
 /// On Unix, checks the executable permission bits.
-/// On Windows, checks if the file has an executable extension.
+/// On Windows, checks if the file has an extension listed in PATHEXT.
 #[cfg(unix)]
 fn is_executable(path: &Path) -> bool {
     use std::os::unix::fs::PermissionsExt;
@@ -94,23 +287,15 @@ fn is_executable(path: &Path) -> bool {
 
 #[cfg(windows)]
 fn is_executable(path: &Path) -> bool {
-    // On Windows, check for common executable extensions
-    const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "cmd", "bat", "com", "ps1", "vbs", "js", "msc"];
-
-    if let Some(ext) = path.extension() {
-        let ext_lower = ext.to_string_lossy().to_lowercase();
-        EXECUTABLE_EXTENSIONS.contains(&ext_lower.as_str())
-    } else {
-        // Also consider files without extension if they exist in PATH
-        // (some tools like WSL might create such files)
-        false
-    }
+    path.file_name()
+        .is_some_and(|name| has_extension(&name.to_string_lossy(), &PATH_EXTENSIONS))
 }
 
 /// Get all executables in PATH.
 ///
 /// Returns a reference to a cached set of all executable names found
-/// in the system PATH. The result is cached for the lifetime of the program.
+/// in the system PATH. The result is cached for the lifetime of the program,
+/// and between runs after [`set_executables_cache`].
 ///
 /// # Returns
 ///
@@ -130,9 +315,117 @@ pub fn get_all_executables() -> &'static HashSet<String> {
     &ALL_EXECUTABLES
 }
 
+/// Where a command candidate is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    /// A program in PATH.
+    Executable,
+    /// An alias defined in the user's shell.
+    Alias,
+    /// A function defined in the user's shell.
+    Function,
+}
+
+/// A command that can be suggested in place of a mistyped one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandCandidate {
+    /// The name the user types.
+    pub name: String,
+    /// Where the command is defined.
+    pub kind: CommandKind,
+}
+
+impl CommandCandidate {
+    /// Check if the command only exists in the user's shell.
+    ///
+    /// Suggestions for these use the alias or function name as typed, not
+    /// what it expands to.
+    pub fn is_shell_defined(&self) -> bool {
+        self.kind != CommandKind::Executable
+    }
+}
+
+/// Commands defined in the detected shell, cached for the lifetime of the program.
+static SHELL_COMMANDS: Lazy<Vec<CommandCandidate>> =
+    Lazy::new(|| get_shell_commands(detect_shell().as_ref()));
+
+/// Get the aliases and functions defined in `shell`.
+///
+/// Functions come first, and a name defined both ways is listed once, as a
+/// function.
+pub fn get_shell_commands(shell: &dyn Shell) -> Vec<CommandCandidate> {
+    let mut seen = HashSet::new();
+    let mut commands = Vec::new();
+
+    for name in shell.get_function_names() {
+        if seen.insert(name.clone()) {
+            commands.push(CommandCandidate {
+                name,
+                kind: CommandKind::Function,
+            });
+        }
+    }
+
+    let mut aliases: Vec<String> = shell.get_aliases().into_keys().collect();
+    aliases.sort();
+    for name in aliases {
+        if seen.insert(name.clone()) {
+            commands.push(CommandCandidate {
+                name,
+                kind: CommandKind::Alias,
+            });
+        }
+    }
+
+    commands
+}
+
+/// Get all commands that can be suggested for a mistyped one.
+///
+/// These are the user's aliases and functions, from the shell integration,
+/// followed by the executables in PATH that aren't shadowed by one. Each
+/// candidate records its [`CommandKind`].
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::executables::get_command_candidates;
+///
+/// for candidate in get_command_candidates() {
+///     if candidate.is_shell_defined() {
+///         println!("{} is defined in the shell", candidate.name);
+///     }
+/// }
+/// ```
+pub fn get_command_candidates() -> Vec<CommandCandidate> {
+    merge_candidates(&SHELL_COMMANDS, get_all_executables())
+}
+
+fn merge_candidates(
+    shell_commands: &[CommandCandidate],
+    executables: &HashSet<String>,
+) -> Vec<CommandCandidate> {
+    let shell_names: HashSet<&str> = shell_commands.iter().map(|c| c.name.as_str()).collect();
+    let mut executables: Vec<&String> = executables
+        .iter()
+        .filter(|name| !shell_names.contains(name.as_str()))
+        .collect();
+    executables.sort();
+
+    let mut candidates = shell_commands.to_vec();
+    candidates.extend(executables.into_iter().map(|name| CommandCandidate {
+        name: name.clone(),
+        kind: CommandKind::Executable,
+    }));
+    candidates
+}
+
 /// Check if a program exists in PATH and return its full path.
 ///
-/// Uses the `which` crate to find the full path to a program.
+/// A program containing a path separator (`./gradlew`) is checked as is
+/// instead of searched for. On Windows, `foo` also resolves to `foo.exe`,
+/// `foo.cmd` and the other extensions in PATHEXT, in that order, and
+/// names are matched case-insensitively.
 ///
 /// # Arguments
 ///
@@ -153,7 +446,32 @@ pub fn get_all_executables() -> &'static HashSet<String> {
 /// }
 /// ```
 pub fn which(program: &str) -> Option<PathBuf> {
-    ::which::which(program).ok()
+    if program.is_empty() {
+        return None;
+    }
+
+    let names = candidate_names(program);
+    if Path::new(program).components().count() > 1 {
+        return names
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| is_executable_file(path));
+    }
+
+    let path_env = env::var("PATH").unwrap_or_default();
+    let found = split_path(&path_env)
+        .filter(|dir| !dir.is_empty())
+        .find_map(|dir| {
+            names
+                .iter()
+                .map(|name| Path::new(dir).join(name))
+                .find(|path| is_executable_file(path))
+        });
+    found
+}
+
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file() && is_executable(path)
 }
 
 /// Check if a program exists in PATH.
@@ -171,11 +489,107 @@ pub fn program_exists(program: &str) -> bool {
     which(program).is_some()
 }
 
+/// A whitespace-separated word of a script.
+#[derive(Debug)]
+struct Token<'a> {
+    /// Where the word is in the script, quotes included.
+    start: usize,
+    end: usize,
+    /// The word as written.
+    raw: &'a str,
+    /// The word with quotes and escapes removed, as the shell passes it.
+    value: String,
+}
+
+/// Split `script` into words like a POSIX shell does, keeping where each
+/// word is.
+///
+/// Whitespace inside quotes or after a backslash doesn't split words. An
+/// unclosed quote runs to the end of the script.
+fn tokenize(script: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = script.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut value = String::new();
+        let mut end = start;
+        let mut quote = None;
+        while let Some(&(i, c)) = chars.peek() {
+            if quote.is_none() && c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            end = i + c.len_utf8();
+            match (quote, c) {
+                (None, '\'') | (None, '"') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                (None, '\\') | (Some('"'), '\\') => {
+                    if let Some((j, escaped)) = chars.next() {
+                        end = j + escaped.len_utf8();
+                        if quote.is_some() && !matches!(escaped, '"' | '\\' | '$' | '`') {
+                            value.push('\\');
+                        }
+                        value.push(escaped);
+                    }
+                }
+                (_, c) => value.push(c),
+            }
+        }
+
+        tokens.push(Token {
+            start,
+            end,
+            raw: &script[start..end],
+            value,
+        });
+    }
+
+    tokens
+}
+
+/// Find the runs of tokens that spell `words`, as written or unquoted.
+fn find_words(tokens: &[Token<'_>], words: &[&str]) -> Vec<usize> {
+    if words.is_empty() || words.len() > tokens.len() {
+        return Vec::new();
+    }
+    (0..=tokens.len() - words.len())
+        .filter(|&i| {
+            tokens[i..i + words.len()]
+                .iter()
+                .zip(words)
+                .all(|(token, word)| token.raw == *word || token.value == *word)
+        })
+        .collect()
+}
+
+/// Find where `arg` is in `tokens`: as one (quoted) word if it is one,
+/// otherwise as a run of words.
+///
+/// Returns the number of words in a run and where the runs start.
+fn find_argument(tokens: &[Token<'_>], arg: &str) -> (usize, Vec<usize>) {
+    let found = find_words(tokens, &[arg]);
+    if !found.is_empty() {
+        return (1, found);
+    }
+    let words: Vec<&str> = arg.split_whitespace().collect();
+    (words.len(), find_words(tokens, &words))
+}
+
 /// Replace an argument in a command script.
 ///
 /// This function replaces the first occurrence of an argument in a command.
 /// It tries to replace at the end of the script first (more common case),
-/// then falls back to replacing anywhere in the script.
+/// then in the middle, then at the start.
+///
+/// Only whole words are replaced, so `rm` isn't replaced inside `format`.
+/// Words are split like the shell splits them: a quoted word like
+/// `"my branch"` matches `my branch`, and `from` may span several words.
+/// `to` is inserted as is.
 ///
 /// # Arguments
 ///
@@ -195,46 +609,38 @@ pub fn program_exists(program: &str) -> bool {
 /// let script = "git statsu";
 /// let fixed = replace_argument(script, "statsu", "status");
 /// assert_eq!(fixed, "git status");
+///
+/// // Only whole words are replaced
+/// assert_eq!(replace_argument("rm format rm", "rm", "rmdir"), "rm format rmdir");
 /// ```
 pub fn replace_argument(script: &str, from: &str, to: &str) -> String {
-    // Try to replace at the end first (most common case)
-    let end_pattern = format!(r" {}$", regex::escape(from));
-    if let Ok(re) = Regex::new(&end_pattern) {
-        let replacement = format!(" {}", to);
-        let result = re.replace(script, replacement.as_str());
-        if result != script {
-            return result.to_string();
+    let tokens = tokenize(script);
+    let (len, found) = find_argument(&tokens, from);
+
+    // At the end first (most common case), then in the middle, then at the start
+    let last = tokens.len().saturating_sub(len);
+    let index = found
+        .iter()
+        .find(|&&i| i == last)
+        .or_else(|| found.iter().find(|&&i| i > 0))
+        .or_else(|| found.first());
+
+    match index {
+        Some(&i) => {
+            let start = tokens[i].start;
+            let end = tokens[i + len - 1].end;
+            format!("{}{}{}", &script[..start], to, &script[end..])
         }
+        // No replacement made, return original
+        None => script.to_string(),
     }
-
-    // Fall back to replacing in the middle (surrounded by spaces)
-    let middle_pattern = format!(r" {} ", regex::escape(from));
-    if let Ok(re) = Regex::new(&middle_pattern) {
-        let replacement = format!(" {} ", to);
-        let result = re.replace(script, replacement.as_str());
-        if result != script {
-            return result.to_string();
-        }
-    }
-
-    // If still no match, try simple replacement as last resort
-    // This handles cases where the argument might be at the start
-    let start_pattern = format!(r"^{} ", regex::escape(from));
-    if let Ok(re) = Regex::new(&start_pattern) {
-        let replacement = format!("{} ", to);
-        let result = re.replace(script, replacement.as_str());
-        if result != script {
-            return result.to_string();
-        }
-    }
-
-    // No replacement made, return original
-    script.to_string()
 }
 
 /// Replace all occurrences of an argument in a command script.
 ///
-/// Unlike `replace_argument`, this replaces all occurrences.
+/// Unlike `replace_argument`, this replaces all occurrences. Words are
+/// matched as in [`replace_argument`], and the rest of the script is kept
+/// as written.
 ///
 /// # Arguments
 ///
@@ -246,13 +652,22 @@ pub fn replace_argument(script: &str, from: &str, to: &str) -> String {
 ///
 /// The modified script with all occurrences replaced.
 pub fn replace_argument_all(script: &str, from: &str, to: &str) -> String {
-    // Split by whitespace, replace matching parts, rejoin
-    // This is more reliable than regex for handling multiple occurrences
-    script
-        .split_whitespace()
-        .map(|part| if part == from { to } else { part })
-        .collect::<Vec<_>>()
-        .join(" ")
+    let tokens = tokenize(script);
+    let (len, found) = find_argument(&tokens, from);
+
+    let mut result = String::with_capacity(script.len());
+    let mut copied = 0;
+    for i in found {
+        // Skip runs overlapping a replaced one
+        if tokens[i].start < copied {
+            continue;
+        }
+        result.push_str(&script[copied..tokens[i].start]);
+        result.push_str(to);
+        copied = tokens[i + len - 1].end;
+    }
+    result.push_str(&script[copied..]);
+    result
 }
 
 #[cfg(test)]
@@ -292,6 +707,100 @@ mod tests {
         assert!(!executables.contains("fuck"));
     }
 
+    #[cfg(unix)]
+    fn write_executable(dir: &Path, name: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_path() {
+        let bin = tempfile::tempdir().unwrap();
+        write_executable(bin.path(), "mytool");
+        write_executable(bin.path(), "thefuck");
+        fs::write(bin.path().join("README"), "").unwrap();
+
+        let path_env = format!("{}:/nonexistent", bin.path().display());
+        let executables = scan_path(&path_env);
+        assert_eq!(executables, HashSet::from(["mytool".to_string()]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_executables_cache() {
+        let bin = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        write_executable(bin.path(), "mytool");
+        let path_env = bin.path().display().to_string();
+        let cache = ExecutablesCache::new(cache_dir.path(), Duration::from_secs(60));
+
+        assert_eq!(cache.load_or_scan(&path_env).len(), 1);
+        let key = path_key(&path_env);
+        assert!(cache.load(&key).is_some());
+
+        // A cached scan is reused while PATH is unchanged
+        let scan = CachedScan {
+            key: key.clone(),
+            created: now(),
+            executables: vec!["cached".to_string()],
+        };
+        fs::write(&cache.path, serde_json::to_string(&scan).unwrap()).unwrap();
+        assert!(cache.load_or_scan(&path_env).contains("cached"));
+
+        // Other PATHs are scanned again
+        assert!(cache.load(&path_key("/nonexistent")).is_none());
+
+        // Expired scans are ignored
+        let expired = ExecutablesCache::new(cache_dir.path(), Duration::ZERO);
+        assert!(expired.load(&key).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_which_path() {
+        let dir = tempfile::tempdir().unwrap();
+        write_executable(dir.path(), "gradlew");
+        fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        let gradlew = dir.path().join("gradlew");
+        assert_eq!(which(&gradlew.display().to_string()), Some(gradlew));
+        assert_eq!(
+            which(&dir.path().join("notes.txt").display().to_string()),
+            None
+        );
+        assert_eq!(which(""), None);
+    }
+
+    #[test]
+    fn test_parse_pathext() {
+        assert_eq!(
+            parse_pathext(Some(".COM;.EXE;;PS1 ")),
+            vec![".com", ".exe", ".ps1"]
+        );
+        assert_eq!(parse_pathext(None), vec![".com", ".exe", ".bat", ".cmd"]);
+        assert_eq!(parse_pathext(Some("")), parse_pathext(None));
+    }
+
+    #[test]
+    fn test_windows_candidates() {
+        let extensions = parse_pathext(Some(".EXE;.CMD"));
+        assert_eq!(
+            windows_candidates("gradlew", &extensions),
+            vec!["gradlew.exe", "gradlew.cmd"]
+        );
+        // An executable extension is used as typed, whatever its case
+        assert_eq!(windows_candidates("git.EXE", &extensions), vec!["git.EXE"]);
+        assert_eq!(
+            windows_candidates("notes.txt", &extensions),
+            vec!["notes.txt.exe", "notes.txt.cmd"]
+        );
+        assert!(!has_extension(".exe", &extensions));
+    }
+
     #[test]
     fn test_which_existing_program() {
         #[cfg(unix)]
@@ -342,6 +851,43 @@ mod tests {
         assert_eq!(fixed, "git status");
     }
 
+    #[test]
+    fn test_get_shell_commands() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL_ALIASES", "TF_SHELL_FUNCTIONS"]);
+        env::set_var(
+            "TF_SHELL_ALIASES",
+            "alias gst='git status'\nalias mkcd='mkdir'",
+        );
+        env::set_var("TF_SHELL_FUNCTIONS", "mkcd\n_git\nserve");
+
+        let commands = get_shell_commands(&crate::shells::Bash::new());
+        let names: Vec<(&str, CommandKind)> =
+            commands.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("mkcd", CommandKind::Function),
+                ("serve", CommandKind::Function),
+                ("gst", CommandKind::Alias),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_candidates() {
+        let shell_commands = vec![CommandCandidate {
+            name: "ls".to_string(),
+            kind: CommandKind::Alias,
+        }];
+        let executables: HashSet<String> = ["ls", "git"].iter().map(|s| s.to_string()).collect();
+
+        let candidates = merge_candidates(&shell_commands, &executables);
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates[0].is_shell_defined());
+        assert_eq!(candidates[1].name, "git");
+        assert!(!candidates[1].is_shell_defined());
+    }
+
     #[test]
     fn test_replace_argument_no_match() {
         let script = "git status";
@@ -358,6 +904,67 @@ mod tests {
         assert_eq!(fixed, "git statuses");
     }
 
+    #[test]
+    fn test_replace_argument_whole_words_only() {
+        assert_eq!(
+            replace_argument("git rm format.py", "rm", "mv"),
+            "git mv format.py"
+        );
+        assert_eq!(replace_argument("ls format", "rm", "rmdir"), "ls format");
+        assert_eq!(replace_argument("rm", "rm", "rmdir"), "rmdir");
+    }
+
+    #[test]
+    fn test_replace_argument_keeps_whitespace() {
+        assert_eq!(
+            replace_argument("git  statsu\t-s", "statsu", "status"),
+            "git  status\t-s"
+        );
+        assert_eq!(
+            replace_argument("git statsu ", "statsu", "status"),
+            "git status "
+        );
+    }
+
+    #[test]
+    fn test_replace_argument_quoted() {
+        assert_eq!(
+            replace_argument(r#"git checkout "my branch""#, "my branch", "main"),
+            "git checkout main"
+        );
+        assert_eq!(
+            replace_argument("cat 'a file' b", "'a file'", "c"),
+            "cat c b"
+        );
+        // A quoted space doesn't split the word
+        assert_eq!(replace_argument(r"touch a\ b b", "b", "c"), r"touch a\ b c");
+    }
+
+    #[test]
+    fn test_replace_argument_several_words() {
+        assert_eq!(
+            replace_argument("git push origin main -f", "origin main", "upstream dev"),
+            "git push upstream dev -f"
+        );
+    }
+
+    #[test]
+    fn test_replace_argument_literal_replacement() {
+        // `$` in the replacement isn't a capture group
+        assert_eq!(replace_argument("echo HOME", "HOME", "$HOME"), "echo $HOME");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize(r#"git commit -m "fix \"it\"" 'a b'"#);
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["git", "commit", "-m", r#"fix "it""#, "a b"]);
+        assert_eq!(tokens[3].raw, r#""fix \"it\"""#);
+
+        // Unclosed quote runs to the end
+        assert_eq!(tokenize("echo 'a b").len(), 2);
+    }
+
     #[test]
     fn test_replace_argument_special_characters() {
         let script = "grep foo.bar file";
@@ -374,6 +981,14 @@ mod tests {
         assert!(fixed.contains("hello"));
     }
 
+    #[test]
+    fn test_replace_argument_all_whole_words() {
+        assert_eq!(
+            replace_argument_all("rm a.rm rm  b", "rm", "trash"),
+            "trash a.rm trash  b"
+        );
+    }
+
     #[test]
     fn test_replace_argument_with_flags() {
         let script = "npm instal -g typescript";
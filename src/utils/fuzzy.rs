@@ -1,10 +1,22 @@
 //! Fuzzy string matching utilities.
 //!
 //! This module provides fuzzy string matching functionality similar to
-//! Python's `difflib.get_close_matches`. It uses the Jaro-Winkler similarity
-//! metric from the `strsim` crate for efficient fuzzy matching.
+//! Python's `difflib.get_close_matches`. By default it uses the Jaro-Winkler
+//! similarity metric from the `strsim` crate; other [`Algorithm`]s can be
+//! chosen per call or with the `fuzzy_algorithm` setting. [`MatchOptions`]
+//! can also ignore case and favor swapped adjacent characters, the most
+//! common typos.
+//!
+//! Candidates that can't reach the cutoff are skipped before scoring, and
+//! large candidate sets (every executable in PATH) are scored in parallel.
+
+use std::borrow::Cow;
 
-use strsim::jaro_winkler;
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strsim::{jaro_winkler, normalized_damerau_levenshtein};
 
 /// Default number of close matches to return.
 pub const DEFAULT_N: usize = 3;
@@ -12,10 +24,281 @@ pub const DEFAULT_N: usize = 3;
 /// Default minimum similarity cutoff (0.0 to 1.0).
 pub const DEFAULT_CUTOFF: f64 = 0.6;
 
+/// Number of candidates from which they are scored in parallel.
+const PARALLEL_THRESHOLD: usize = 2048;
+
+/// Similarity of a word with two adjacent characters swapped, when
+/// [`MatchOptions::transpositions`] is set.
+///
+/// It's above what any algorithm gives a single wrong character in a
+/// command-length word, so `teh` is corrected to `the` rather than `ten`.
+const TRANSPOSITION_SIMILARITY: f64 = 0.95;
+
+/// A similarity metric for fuzzy matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorithm {
+    /// Jaro-Winkler, which favors a common prefix. Good for typos in
+    /// short words like subcommands.
+    #[default]
+    JaroWinkler,
+    /// Damerau-Levenshtein edit distance, counting a swap of adjacent
+    /// characters as one edit. Good for longer names like branches.
+    DamerauLevenshtein,
+    /// Skim-style subsequence matching: all characters of the word must
+    /// appear in order, so abbreviations like `co` match `checkout`.
+    Subsequence,
+}
+
+impl Algorithm {
+    /// Calculate the similarity between `a` and `b`, from 0.0 to 1.0.
+    pub fn similarity(self, a: &str, b: &str) -> f64 {
+        match self {
+            Algorithm::JaroWinkler => jaro_winkler(a, b),
+            Algorithm::DamerauLevenshtein => normalized_damerau_levenshtein(a, b),
+            Algorithm::Subsequence => subsequence_similarity(a, b),
+        }
+    }
+}
+
+/// How [`get_close_matches_with`] and [`get_closest_with`] compare words.
+///
+/// An [`Algorithm`] converts into options with nothing else set.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::fuzzy::{get_close_matches_with, MatchOptions};
+///
+/// let commands = vec!["status".to_string(), "stash".to_string()];
+/// let options = MatchOptions::new().ignore_case().transpositions();
+/// let matches = get_close_matches_with("STAUTS", &commands, 1, 0.6, options);
+/// assert_eq!(matches, vec!["status".to_string()]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchOptions {
+    algorithm: Algorithm,
+    ignore_case: bool,
+    transpositions: bool,
+}
+
+impl MatchOptions {
+    /// Options with the [`default_algorithm`] and nothing else set.
+    pub fn new() -> Self {
+        default_algorithm().into()
+    }
+
+    /// Options for correcting typed words: case is ignored and swapped
+    /// adjacent characters are favored.
+    pub fn typos() -> Self {
+        Self::new().ignore_case().transpositions()
+    }
+
+    /// Score with `algorithm`.
+    pub fn algorithm(self, algorithm: Algorithm) -> Self {
+        Self { algorithm, ..self }
+    }
+
+    /// Compare words ignoring case. Matches are still returned as given.
+    pub fn ignore_case(self) -> Self {
+        Self {
+            ignore_case: true,
+            ..self
+        }
+    }
+
+    /// Score a word that only differs by two swapped adjacent characters
+    /// (`teh` for `the`) as nearly identical.
+    pub fn transpositions(self) -> Self {
+        Self {
+            transpositions: true,
+            ..self
+        }
+    }
+
+    /// Fold `s` for comparison.
+    fn fold<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.ignore_case && s.chars().any(char::is_uppercase) {
+            Cow::Owned(s.to_lowercase())
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    /// Calculate the similarity between folded `a` and `b`.
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        let score = self.algorithm.similarity(a, b);
+        if self.transpositions && is_adjacent_transposition(a, b) {
+            score.max(TRANSPOSITION_SIMILARITY)
+        } else {
+            score
+        }
+    }
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Algorithm> for MatchOptions {
+    fn from(algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            ignore_case: false,
+            transpositions: false,
+        }
+    }
+}
+
+/// Check if `b` is `a` with exactly two adjacent characters swapped.
+fn is_adjacent_transposition(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len() != b.len() {
+        return false;
+    }
+    let Some(i) = a.iter().zip(&b).position(|(x, y)| x != y) else {
+        return false;
+    };
+    i + 1 < a.len() && a[i] == b[i + 1] && a[i + 1] == b[i] && a[i + 2..] == b[i + 2..]
+}
+
+/// Algorithm used by the functions that don't take one, set from settings.
+static DEFAULT_ALGORITHM: OnceCell<Algorithm> = OnceCell::new();
+
+/// Set the algorithm used by [`get_close_matches`] and [`get_closest`].
+///
+/// Only the first call takes effect.
+pub fn set_default_algorithm(algorithm: Algorithm) {
+    let _ = DEFAULT_ALGORITHM.set(algorithm);
+}
+
+/// Get the algorithm used by [`get_close_matches`] and [`get_closest`].
+pub fn default_algorithm() -> Algorithm {
+    DEFAULT_ALGORITHM.get().copied().unwrap_or_default()
+}
+
+/// Character counts of a word, to bound its similarity to candidates
+/// without scoring them.
+///
+/// Every algorithm only credits characters the word and the candidate have
+/// in common, so their number caps the score. Counting them is much cheaper
+/// than the algorithms themselves.
+struct Prefilter {
+    /// Count of each ASCII character, or `None` if the word isn't ASCII.
+    counts: Option<[u16; 128]>,
+    len: usize,
+}
+
+impl Prefilter {
+    fn new(word: &str) -> Self {
+        let counts = word.is_ascii().then(|| {
+            let mut counts = [0u16; 128];
+            for byte in word.bytes() {
+                counts[usize::from(byte)] = counts[usize::from(byte)].saturating_add(1);
+            }
+            counts
+        });
+        Self {
+            counts,
+            len: word.len(),
+        }
+    }
+
+    /// An upper bound of `algorithm.similarity(word, candidate)`.
+    fn max_similarity(&self, candidate: &str, algorithm: Algorithm) -> f64 {
+        let Some(counts) = self.counts.as_ref() else {
+            return 1.0;
+        };
+        if !candidate.is_ascii() || self.len == 0 || candidate.is_empty() {
+            return 1.0;
+        }
+
+        // Characters in common, counting repeated ones
+        let mut remaining = *counts;
+        let mut common = 0usize;
+        for byte in candidate.bytes() {
+            let count = &mut remaining[usize::from(byte)];
+            if *count > 0 {
+                *count -= 1;
+                common += 1;
+            }
+        }
+
+        let word_len = self.len as f64;
+        let candidate_len = candidate.len() as f64;
+        let common = common as f64;
+        match algorithm {
+            Algorithm::JaroWinkler => {
+                if common == 0.0 {
+                    return 0.0;
+                }
+                // Jaro with every common character matched in order
+                let jaro = (common / word_len + common / candidate_len + 1.0) / 3.0;
+                // The prefix boost, only given above 0.7, is at most 4 * 0.1
+                if jaro > 0.7 {
+                    jaro + 0.4 * (1.0 - jaro)
+                } else {
+                    jaro
+                }
+            }
+            // Each character of the longer string not in common costs an edit
+            Algorithm::DamerauLevenshtein => common / word_len.max(candidate_len),
+            Algorithm::Subsequence => {
+                if common < word_len {
+                    0.0
+                } else {
+                    0.5 * word_len / candidate_len + 0.5
+                }
+            }
+        }
+    }
+}
+
+/// Score `candidate` by how well `word` matches it as a subsequence.
+///
+/// Returns 0.0 unless all characters of `word` appear in `candidate` in
+/// order. Otherwise the score grows with the share of `candidate` covered,
+/// the number of consecutive characters matched, and a match at the start.
+fn subsequence_similarity(word: &str, candidate: &str) -> f64 {
+    let word: Vec<char> = word.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if word.is_empty() || candidate.is_empty() {
+        return if word == candidate { 1.0 } else { 0.0 };
+    }
+
+    // Leftmost positions in `candidate` of the characters of `word`
+    let mut positions = Vec::with_capacity(word.len());
+    let mut next = 0;
+    for c in &word {
+        match candidate[next..].iter().position(|d| d == c) {
+            Some(offset) => {
+                positions.push(next + offset);
+                next += offset + 1;
+            }
+            None => return 0.0,
+        }
+    }
+
+    let coverage = word.len() as f64 / candidate.len() as f64;
+    let contiguity = if word.len() == 1 {
+        1.0
+    } else {
+        let consecutive = positions.windows(2).filter(|w| w[1] == w[0] + 1).count();
+        consecutive as f64 / (word.len() - 1) as f64
+    };
+    let prefix = if positions[0] == 0 { 1.0 } else { 0.0 };
+
+    0.5 * coverage + 0.3 * contiguity + 0.2 * prefix
+}
+
 /// Get close matches for a word from a list of possibilities.
 ///
 /// This function is similar to Python's `difflib.get_close_matches`.
-/// It returns a list of the best "good enough" matches from the possibilities.
+/// It returns a list of the best "good enough" matches from the possibilities,
+/// scored with the [`default_algorithm`].
 ///
 /// # Arguments
 ///
@@ -42,17 +325,59 @@ pub fn get_close_matches(
     possibilities: &[String],
     n: usize,
     cutoff: f64,
+) -> Vec<String> {
+    get_close_matches_with(word, possibilities, n, cutoff, default_algorithm())
+}
+
+/// Get close matches for a word, compared with the given options or
+/// algorithm.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::fuzzy::{get_close_matches_with, Algorithm};
+///
+/// let commands = vec!["checkout".to_string(), "cherry-pick".to_string()];
+/// let matches = get_close_matches_with("co", &commands, 1, 0.3, Algorithm::Subsequence);
+/// assert_eq!(matches, vec!["checkout".to_string()]);
+/// ```
+pub fn get_close_matches_with(
+    word: &str,
+    possibilities: &[String],
+    n: usize,
+    cutoff: f64,
+    options: impl Into<MatchOptions>,
 ) -> Vec<String> {
     if possibilities.is_empty() || n == 0 {
         return Vec::new();
     }
 
-    // Compute similarity scores for all possibilities
-    let mut scored: Vec<(f64, &String)> = possibilities
-        .iter()
-        .map(|p| (jaro_winkler(word, p), p))
-        .filter(|(score, _)| *score >= cutoff)
-        .collect();
+    // Compute similarity scores for the possibilities that can reach the
+    // cutoff, keeping their order for ties. A transposition keeps all
+    // characters, so the bound still holds for it.
+    let options = options.into();
+    let word = options.fold(word);
+    let prefilter = Prefilter::new(&word);
+    let score = |p: &String| {
+        let candidate = options.fold(p);
+        // Allow for rounding in the bound
+        if prefilter.max_similarity(&candidate, options.algorithm) + 1e-9 < cutoff {
+            return None;
+        }
+        let score = options.similarity(&word, &candidate);
+        (score >= cutoff).then_some(score)
+    };
+    let mut scored: Vec<(f64, &String)> = if possibilities.len() >= PARALLEL_THRESHOLD {
+        possibilities
+            .par_iter()
+            .filter_map(|p| score(p).map(|s| (s, p)))
+            .collect()
+    } else {
+        possibilities
+            .iter()
+            .filter_map(|p| score(p).map(|s| (s, p)))
+            .collect()
+    };
 
     // Sort by score descending (highest similarity first)
     scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
@@ -108,13 +433,30 @@ pub fn get_closest(
     possibilities: &[String],
     cutoff: f64,
     fallback_to_first: bool,
+) -> Option<String> {
+    get_closest_with(
+        word,
+        possibilities,
+        cutoff,
+        fallback_to_first,
+        default_algorithm(),
+    )
+}
+
+/// Get the closest match, compared with the given options or algorithm.
+pub fn get_closest_with(
+    word: &str,
+    possibilities: &[String],
+    cutoff: f64,
+    fallback_to_first: bool,
+    options: impl Into<MatchOptions>,
 ) -> Option<String> {
     if possibilities.is_empty() {
         return None;
     }
 
     // Try to get the closest match
-    let matches = get_close_matches(word, possibilities, 1, cutoff);
+    let matches = get_close_matches_with(word, possibilities, 1, cutoff, options);
 
     if let Some(m) = matches.into_iter().next() {
         Some(m)
@@ -261,6 +603,168 @@ mod tests {
         assert!(sim < 0.5);
     }
 
+    #[test]
+    fn test_damerau_levenshtein_counts_transpositions() {
+        let algorithm = Algorithm::DamerauLevenshtein;
+        assert!((algorithm.similarity("feature", "feature") - 1.0).abs() < f64::EPSILON);
+        // One swap in a seven-letter name
+        assert!((algorithm.similarity("faeture", "feature") - 6.0 / 7.0).abs() < 1e-9);
+
+        let branches = vec!["feature/login".to_string(), "fix/logout".to_string()];
+        assert_eq!(
+            get_closest_with("feautre/login", &branches, 0.6, false, algorithm),
+            Some("feature/login".to_string())
+        );
+    }
+
+    #[test]
+    fn test_subsequence_similarity() {
+        let algorithm = Algorithm::Subsequence;
+        assert_eq!(algorithm.similarity("gti", "git"), 0.0);
+        assert!((algorithm.similarity("git", "git") - 1.0).abs() < f64::EPSILON);
+        assert_eq!(algorithm.similarity("", ""), 1.0);
+        assert_eq!(algorithm.similarity("", "git"), 0.0);
+        // A match at the start beats one in the middle
+        assert!(algorithm.similarity("co", "commit") > algorithm.similarity("co", "reco"));
+        // Contiguous characters beat scattered ones
+        assert!(algorithm.similarity("che", "checkout") > algorithm.similarity("cko", "checkout"));
+
+        let commands = vec![
+            "status".to_string(),
+            "stash".to_string(),
+            "checkout".to_string(),
+        ];
+        assert_eq!(
+            get_close_matches_with("sts", &commands, 3, 0.4, algorithm),
+            vec!["stash".to_string(), "status".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_prefilter_bounds_similarity() {
+        let words = ["gti", "statsu", "feautre/login", "co", "a", "zzzz", "ünï"];
+        let candidates = [
+            "git",
+            "status",
+            "feature/login",
+            "checkout",
+            "libreoffice-calc",
+            "a",
+            "aaaa",
+            "ünicode",
+        ];
+        for algorithm in [
+            Algorithm::JaroWinkler,
+            Algorithm::DamerauLevenshtein,
+            Algorithm::Subsequence,
+        ] {
+            for word in words {
+                let prefilter = Prefilter::new(word);
+                for candidate in candidates {
+                    let bound = prefilter.max_similarity(candidate, algorithm);
+                    let score = algorithm.similarity(word, candidate);
+                    assert!(
+                        bound + 1e-9 >= score,
+                        "{:?}: {} vs {} scored {} above bound {}",
+                        algorithm,
+                        word,
+                        candidate,
+                        score,
+                        bound
+                    );
+                }
+            }
+        }
+
+        // Candidates without enough characters in common are skipped
+        assert!(
+            Prefilter::new("gti").max_similarity("libreoffice-calc", Algorithm::JaroWinkler) < 0.6
+        );
+    }
+
+    #[test]
+    fn test_get_close_matches_large_candidate_set() {
+        let mut possibilities: Vec<String> = (0..PARALLEL_THRESHOLD * 2)
+            .map(|i| format!("tool-{}", i))
+            .collect();
+        possibilities.push("python3".to_string());
+        possibilities.push("python".to_string());
+
+        let matches = get_close_matches("pyhton", &possibilities, 3, 0.6);
+        assert_eq!(matches.first(), Some(&"python".to_string()));
+        assert!(matches.contains(&"python3".to_string()));
+
+        // Same result as scoring every candidate
+        let mut expected: Vec<(f64, &String)> = possibilities
+            .iter()
+            .map(|p| (similarity("pyhton", p), p))
+            .filter(|(score, _)| *score >= 0.6)
+            .collect();
+        expected.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let expected: Vec<String> = expected
+            .into_iter()
+            .take(3)
+            .map(|(_, p)| p.clone())
+            .collect();
+        assert_eq!(matches, expected);
+    }
+
+    #[test]
+    fn test_algorithm_names() {
+        let algorithm: Algorithm = serde_json::from_str("\"damerau_levenshtein\"").unwrap();
+        assert_eq!(algorithm, Algorithm::DamerauLevenshtein);
+        assert_eq!(
+            serde_json::to_string(&Algorithm::JaroWinkler).unwrap(),
+            "\"jaro_winkler\""
+        );
+    }
+
+    #[test]
+    fn test_is_adjacent_transposition() {
+        assert!(is_adjacent_transposition("teh", "the"));
+        assert!(is_adjacent_transposition("gti", "git"));
+        assert!(is_adjacent_transposition("statsu", "status"));
+        assert!(!is_adjacent_transposition("the", "the"));
+        assert!(!is_adjacent_transposition("teh", "ten"));
+        assert!(!is_adjacent_transposition("abcd", "badc"));
+        assert!(!is_adjacent_transposition("ab", "abc"));
+    }
+
+    #[test]
+    fn test_match_options_transpositions() {
+        let words = vec!["ten".to_string(), "the".to_string()];
+        let options = MatchOptions::new().algorithm(Algorithm::DamerauLevenshtein);
+
+        // Both are one edit away
+        assert_eq!(
+            get_close_matches_with("teh", &words, 2, 0.6, options).len(),
+            2
+        );
+        assert_eq!(
+            get_closest_with("teh", &words, 0.6, false, options.transpositions()),
+            Some("the".to_string())
+        );
+    }
+
+    #[test]
+    fn test_match_options_ignore_case() {
+        let words = vec!["Makefile".to_string(), "README".to_string()];
+
+        assert_eq!(
+            get_close_matches_with("makefile", &words, 1, 0.95, Algorithm::JaroWinkler),
+            Vec::<String>::new()
+        );
+        // Matches keep their case
+        assert_eq!(
+            get_close_matches_with("makefile", &words, 1, 0.95, MatchOptions::typos()),
+            vec!["Makefile".to_string()]
+        );
+        assert_eq!(
+            get_closest_with("readme", &words, 0.95, false, MatchOptions::typos()),
+            Some("README".to_string())
+        );
+    }
+
     #[test]
     fn test_git_command_matching() {
         // Test case similar to what oops would use
@@ -0,0 +1,349 @@
+//! Parsing shell history files and counting the commands in them.
+//!
+//! Each shell writes its history differently: bash one command per line
+//! (with `#<timestamp>` lines when `HISTTIMEFORMAT` is set), zsh
+//! `: <timestamp>:<duration>;<command>` in extended mode, and fish a YAML-like
+//! list of `- cmd:` entries. [`parse_history`] reads all of them into
+//! [`HistoryEntry`]s, and [`command_stats`] counts how often and how recently
+//! each command was used.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Format of a shell history file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// `~/.bash_history`: one command per line, optionally preceded by a
+    /// `#<timestamp>` line.
+    Bash,
+    /// `~/.zsh_history`: plain like bash, or `: <timestamp>:0;<command>`,
+    /// with multi-line commands continued by a trailing backslash.
+    Zsh,
+    /// `~/.local/share/fish/fish_history`: `- cmd: <command>` entries
+    /// followed by `  when: <timestamp>`.
+    Fish,
+}
+
+impl HistoryFormat {
+    /// The history format of the shell named `name`, if it has a known one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oops::utils::history::HistoryFormat;
+    ///
+    /// assert_eq!(HistoryFormat::from_shell("zsh"), Some(HistoryFormat::Zsh));
+    /// assert_eq!(HistoryFormat::from_shell("powershell"), None);
+    /// ```
+    pub fn from_shell(name: &str) -> Option<Self> {
+        match name {
+            "bash" | "sh" => Some(HistoryFormat::Bash),
+            "zsh" => Some(HistoryFormat::Zsh),
+            "fish" => Some(HistoryFormat::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// A command read from a history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The command as it was run. Multi-line commands keep their newlines.
+    pub command: String,
+    /// When the command was run, in seconds since the Unix epoch, if the
+    /// history records it.
+    pub timestamp: Option<u64>,
+}
+
+impl HistoryEntry {
+    fn new(command: impl Into<String>, timestamp: Option<u64>) -> Self {
+        Self {
+            command: command.into(),
+            timestamp,
+        }
+    }
+}
+
+/// Parse a history file in `format`, oldest entry first.
+///
+/// Blank commands are dropped.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::history::{parse_history, HistoryFormat};
+///
+/// let entries = parse_history(": 1700000000:0;git status\nls\n", HistoryFormat::Zsh);
+/// assert_eq!(entries[0].command, "git status");
+/// assert_eq!(entries[0].timestamp, Some(1700000000));
+/// assert_eq!(entries[1].timestamp, None);
+/// ```
+pub fn parse_history(content: &str, format: HistoryFormat) -> Vec<HistoryEntry> {
+    let entries = match format {
+        HistoryFormat::Bash => parse_bash(content),
+        HistoryFormat::Zsh => parse_zsh(content),
+        HistoryFormat::Fish => parse_fish(content),
+    };
+    entries
+        .into_iter()
+        .filter(|entry| !entry.command.trim().is_empty())
+        .collect()
+}
+
+/// Read and parse the history file at `path`.
+///
+/// Bytes that aren't UTF-8 (zsh writes some characters "metafied") are
+/// replaced rather than failing the whole file.
+pub fn read_history_file(path: &Path, format: HistoryFormat) -> Result<Vec<HistoryEntry>> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read history file: {}", path.display()))?;
+    Ok(parse_history(&String::from_utf8_lossy(&bytes), format))
+}
+
+fn parse_bash(content: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut timestamp = None;
+    for line in content.lines() {
+        if let Some(seconds) = line.strip_prefix('#').and_then(|s| s.trim().parse().ok()) {
+            timestamp = Some(seconds);
+            continue;
+        }
+        entries.push(HistoryEntry::new(line.trim_end(), timestamp.take()));
+    }
+    entries
+}
+
+fn parse_zsh(content: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let (mut command, timestamp) = match parse_zsh_extended(line) {
+            Some((timestamp, command)) => (command.to_string(), Some(timestamp)),
+            None => (line.to_string(), None),
+        };
+        // A trailing backslash continues the command on the next line
+        while command.ends_with('\\') {
+            let Some(next) = lines.next() else { break };
+            command.pop();
+            command.push('\n');
+            command.push_str(next);
+        }
+        entries.push(HistoryEntry::new(command.trim_end(), timestamp));
+    }
+    entries
+}
+
+/// Split `: <timestamp>:<duration>;<command>` into the timestamp and command.
+fn parse_zsh_extended(line: &str) -> Option<(u64, &str)> {
+    let rest = line.strip_prefix(": ")?;
+    let (meta, command) = rest.split_once(';')?;
+    let (timestamp, _duration) = meta.split_once(':')?;
+    Some((timestamp.trim().parse().ok()?, command))
+}
+
+fn parse_fish(content: &str) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    for line in content.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            entries.push(HistoryEntry::new(unescape_fish(command), None));
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ") {
+            if let Some(entry) = entries.last_mut() {
+                entry.timestamp = when.trim().parse().ok();
+            }
+        }
+    }
+    entries
+}
+
+/// Undo fish's escaping of newlines and backslashes in history commands.
+fn unescape_fish(command: &str) -> String {
+    let mut result = String::with_capacity(command.len());
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// How often and how recently a command was used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandStats {
+    /// The command, with surrounding whitespace trimmed.
+    pub command: String,
+    /// Number of times the command was run.
+    pub count: usize,
+    /// Number of commands run since its last use: 0 for the most recent.
+    pub recency: usize,
+}
+
+/// Count the unique `commands`, most frequently used first.
+///
+/// `commands` is oldest first. Commands differing only in surrounding
+/// whitespace are counted together, and ties go to the most recently used
+/// command. At most `limit` commands are returned, when given.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::history::command_stats;
+///
+/// let stats = command_stats(["make", "ls", "make ", "git status"], Some(2));
+/// assert_eq!(stats[0].command, "make");
+/// assert_eq!((stats[0].count, stats[0].recency), (2, 1));
+/// assert_eq!(stats[1].command, "git status");
+/// assert_eq!(stats.len(), 2);
+/// ```
+pub fn command_stats<I, S>(commands: I, limit: Option<usize>) -> Vec<CommandStats>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    // Count and index of the last use, by command
+    let mut seen: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut total = 0;
+    for command in commands {
+        let command = command.as_ref().trim();
+        if command.is_empty() {
+            continue;
+        }
+        let entry = seen.entry(command.to_string()).or_default();
+        entry.0 += 1;
+        entry.1 = total;
+        total += 1;
+    }
+
+    let mut stats: Vec<CommandStats> = seen
+        .into_iter()
+        .map(|(command, (count, last))| CommandStats {
+            command,
+            count,
+            recency: total - 1 - last,
+        })
+        .collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then(a.recency.cmp(&b.recency)));
+    if let Some(limit) = limit {
+        stats.truncate(limit);
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.command.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_bash() {
+        let content = "ls -la\n#1700000000\ngit status\n\ncd /tmp\n";
+        let entries = parse_history(content, HistoryFormat::Bash);
+        assert_eq!(commands(&entries), vec!["ls -la", "git status", "cd /tmp"]);
+        assert_eq!(entries[0].timestamp, None);
+        assert_eq!(entries[1].timestamp, Some(1700000000));
+        assert_eq!(entries[2].timestamp, None);
+    }
+
+    #[test]
+    fn test_parse_bash_comment_is_a_command() {
+        let entries = parse_history("# not a timestamp\n", HistoryFormat::Bash);
+        assert_eq!(commands(&entries), vec!["# not a timestamp"]);
+    }
+
+    #[test]
+    fn test_parse_zsh() {
+        let content = ": 1700000000:0;git status\nplain command\n: 1700000005:2;echo a \\\nb\n";
+        let entries = parse_history(content, HistoryFormat::Zsh);
+        assert_eq!(
+            commands(&entries),
+            vec!["git status", "plain command", "echo a \nb"]
+        );
+        assert_eq!(entries[0].timestamp, Some(1700000000));
+        assert_eq!(entries[1].timestamp, None);
+        assert_eq!(entries[2].timestamp, Some(1700000005));
+    }
+
+    #[test]
+    fn test_parse_zsh_command_with_semicolon() {
+        let entries = parse_history(": 1700000000:0;cd /tmp; ls", HistoryFormat::Zsh);
+        assert_eq!(commands(&entries), vec!["cd /tmp; ls"]);
+    }
+
+    #[test]
+    fn test_parse_fish() {
+        let content = "- cmd: git status\n  when: 1700000000\n- cmd: echo a\\nb \\\\ c\n  when: 1700000010\n  paths:\n    - /tmp\n- cmd: ls\n";
+        let entries = parse_history(content, HistoryFormat::Fish);
+        assert_eq!(
+            commands(&entries),
+            vec!["git status", "echo a\nb \\ c", "ls"]
+        );
+        assert_eq!(entries[0].timestamp, Some(1700000000));
+        assert_eq!(entries[1].timestamp, Some(1700000010));
+        assert_eq!(entries[2].timestamp, None);
+    }
+
+    #[test]
+    fn test_read_history_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        fs::write(&path, b"ls\n\xffcd\n").unwrap();
+        let entries = read_history_file(&path, HistoryFormat::Bash).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].command, "\u{fffd}cd");
+
+        assert!(read_history_file(&dir.path().join("missing"), HistoryFormat::Bash).is_err());
+    }
+
+    #[test]
+    fn test_command_stats() {
+        let stats = command_stats(
+            [
+                "ls",
+                "make",
+                "git status",
+                "make",
+                "ls",
+                "cargo test",
+                "",
+                "  ls",
+            ],
+            None,
+        );
+        let summary: Vec<(&str, usize, usize)> = stats
+            .iter()
+            .map(|s| (s.command.as_str(), s.count, s.recency))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ls", 3, 0),
+                ("make", 2, 3),
+                ("cargo test", 1, 1),
+                ("git status", 1, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_stats_limit() {
+        assert_eq!(command_stats(["a", "b", "c"], Some(2)).len(), 2);
+        assert!(command_stats(["a"], Some(0)).is_empty());
+        assert!(command_stats(Vec::<String>::new(), None).is_empty());
+    }
+}
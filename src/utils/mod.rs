@@ -4,11 +4,24 @@
 //! - [`cache`] - Memoization utilities using the `cached` crate
 //! - [`fuzzy`] - Fuzzy string matching similar to Python's difflib
 //! - [`executables`] - PATH scanning and executable lookup
+//! - [`history`] - Parsing shell history files and command frequency statistics
+//! - [`path`] - Expanding `~` and `$VARS` in paths, and contracting them back
+//! - [`quote`] - Shell quoting of arguments in corrections
+//! - [`regex_cache`] - Regexes compiled once and shared across rules
+//! - [`text`] - ANSI stripping and normalization of command output
 
 pub mod cache;
 pub mod executables;
 pub mod fuzzy;
+pub mod history;
+pub mod path;
+pub mod quote;
+pub mod regex_cache;
+pub mod text;
 
 pub use cache::which;
 pub use executables::{get_all_executables, replace_argument, which as uncached_which};
 pub use fuzzy::{get_close_matches, get_closest};
+pub use path::{contract_path, expand_path};
+pub use quote::shell_quote;
+pub use text::{normalize_output, strip_ansi};
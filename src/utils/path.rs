@@ -0,0 +1,155 @@
+//! Expanding and contracting paths the way the shell writes them.
+//!
+//! Paths in commands and their output are often written `~/src` or
+//! `$HOME/src`. Rules that look at the filesystem expand them with
+//! [`expand_path`] first, and show paths back with [`contract_path`].
+
+use std::borrow::Cow;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Expand a leading `~` and `$VAR` or `${VAR}` references in `path`.
+///
+/// Only `~` and `~/...` are expanded, not `~user`. Variables that aren't
+/// set are left as written, as is everything when the home directory is
+/// unknown.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use oops::utils::path::expand_path;
+///
+/// std::env::set_var("SRC_DIR", "/src");
+/// assert_eq!(expand_path("$SRC_DIR/oops"), PathBuf::from("/src/oops"));
+/// assert_eq!(expand_path("${SRC_DIR}/oops"), PathBuf::from("/src/oops"));
+/// ```
+pub fn expand_path(path: &str) -> PathBuf {
+    let path = expand_vars(path);
+    PathBuf::from(expand_tilde(&path).as_ref())
+}
+
+/// Expand a leading `~` or `~/` in `path`.
+fn expand_tilde(path: &str) -> Cow<'_, str> {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        #[cfg(windows)]
+        Some(rest) if rest.starts_with('\\') => rest,
+        _ => return Cow::Borrowed(path),
+    };
+    match dirs::home_dir() {
+        Some(home) => Cow::Owned(format!("{}{}", home.display(), rest)),
+        None => Cow::Borrowed(path),
+    }
+}
+
+/// Expand `$VAR` and `${VAR}` in `s`.
+fn expand_vars(s: &str) -> Cow<'_, str> {
+    if !s.contains('$') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => result.push_str(&rest[pos..pos + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+/// Write `path` with the home directory as `~`, for showing it to the user.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::path::contract_path;
+///
+/// let home = dirs::home_dir().unwrap();
+/// assert_eq!(contract_path(&home.join("src")), "~/src");
+/// assert_eq!(contract_path("/tmp".as_ref()), "/tmp");
+/// ```
+pub fn contract_path(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir().filter(|home| home.parent().is_some()) {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return if rest.as_os_str().is_empty() {
+                "~".to_string()
+            } else {
+                format!("~/{}", rest.display())
+            };
+        }
+    }
+    path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tilde() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(expand_path("~/.virtualenvs"), home.join(".virtualenvs"));
+        // Other users' homes and tildes elsewhere aren't expanded
+        assert_eq!(expand_path("~root/x"), PathBuf::from("~root/x"));
+        assert_eq!(expand_path("a/~/b"), PathBuf::from("a/~/b"));
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let _guard = crate::test_utils::EnvGuard::new(&["OOPS_TEST_DIR", "OOPS_TEST_UNSET"]);
+        env::set_var("OOPS_TEST_DIR", "/opt/tools");
+        env::remove_var("OOPS_TEST_UNSET");
+
+        assert_eq!(expand_vars("$OOPS_TEST_DIR/bin"), "/opt/tools/bin");
+        assert_eq!(expand_vars("${OOPS_TEST_DIR}bin"), "/opt/toolsbin");
+        assert_eq!(expand_vars("$OOPS_TEST_UNSET/bin"), "$OOPS_TEST_UNSET/bin");
+        assert_eq!(expand_vars("${OOPS_TEST_DIR"), "${OOPS_TEST_DIR");
+        assert_eq!(expand_vars("cost: 5$"), "cost: 5$");
+        assert_eq!(expand_vars("plain"), "plain");
+    }
+
+    #[test]
+    fn test_expand_var_then_tilde() {
+        let _guard = crate::test_utils::EnvGuard::new(&["OOPS_TEST_DIR"]);
+        env::set_var("OOPS_TEST_DIR", "~/work");
+        assert_eq!(
+            expand_path("$OOPS_TEST_DIR/oops"),
+            dirs::home_dir().unwrap().join("work/oops")
+        );
+    }
+
+    #[test]
+    fn test_contract_path() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(contract_path(&home), "~");
+        assert_eq!(contract_path(&home.join("a/b")), "~/a/b");
+        assert_eq!(contract_path(Path::new("/")), "/");
+    }
+
+    #[test]
+    fn test_contract_expanded_path() {
+        assert_eq!(contract_path(&expand_path("~/notes.txt")), "~/notes.txt");
+    }
+}
@@ -0,0 +1,164 @@
+//! Shell quoting for arguments in corrections.
+//!
+//! Rules build corrections from file names found in the command or its
+//! output. Quoting them keeps a name with spaces or quotes a single argument
+//! in the shell that runs the correction.
+
+use std::env;
+use std::path::Path;
+
+use crate::shells::get_shell_by_name;
+
+/// Check if `c` has no special meaning in any supported shell.
+fn is_safe(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':' | '=' | '+')
+}
+
+/// Split a leading `~` or `~/` off `s`, so it still expands when the rest
+/// is quoted.
+fn split_tilde(s: &str) -> (&str, &str) {
+    if s == "~" {
+        (s, "")
+    } else if let Some(rest) = s.strip_prefix("~/") {
+        ("~/", rest)
+    } else {
+        ("", s)
+    }
+}
+
+/// Quote `s` for POSIX shells (bash, zsh, sh, tcsh).
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::quote::posix_quote;
+///
+/// assert_eq!(posix_quote("notes.txt"), "notes.txt");
+/// assert_eq!(posix_quote("my notes.txt"), "'my notes.txt'");
+/// assert_eq!(posix_quote("it's"), "'it'\\''s'");
+/// assert_eq!(posix_quote("~/my notes"), "~/'my notes'");
+/// ```
+pub fn posix_quote(s: &str) -> String {
+    let (tilde, rest) = split_tilde(s);
+    if !s.is_empty() && rest.chars().all(is_safe) {
+        return s.to_string();
+    }
+    format!("{}'{}'", tilde, rest.replace('\'', "'\\''"))
+}
+
+/// Quote `s` for fish, where backslashes and quotes are escaped inside
+/// single quotes.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::quote::fish_quote;
+///
+/// assert_eq!(fish_quote("it's"), "'it\\'s'");
+/// ```
+pub fn fish_quote(s: &str) -> String {
+    let (tilde, rest) = split_tilde(s);
+    if !s.is_empty() && rest.chars().all(is_safe) {
+        return s.to_string();
+    }
+    format!(
+        "{}'{}'",
+        tilde,
+        rest.replace('\\', "\\\\").replace('\'', "\\'")
+    )
+}
+
+/// Quote `s` for PowerShell, where quotes are doubled inside single quotes.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::quote::powershell_quote;
+///
+/// assert_eq!(powershell_quote("it's"), "'it''s'");
+/// ```
+pub fn powershell_quote(s: &str) -> String {
+    if !s.is_empty() && s.chars().all(is_safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Quote `s` for the user's shell.
+///
+/// The shell is taken from `TF_SHELL`, which the shell integration sets;
+/// without it, POSIX quoting is used.
+pub fn shell_quote(s: &str) -> String {
+    let shell = env::var("TF_SHELL")
+        .ok()
+        .and_then(|shell| {
+            Path::new(&shell)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .and_then(|name| get_shell_by_name(&name));
+    match shell {
+        Some(shell) => shell.quote(s),
+        None => posix_quote(s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_quote_simple() {
+        assert_eq!(posix_quote("simple"), "simple");
+        assert_eq!(posix_quote("with-dash"), "with-dash");
+        assert_eq!(posix_quote("with_underscore"), "with_underscore");
+        assert_eq!(posix_quote("dir/sub/file.txt"), "dir/sub/file.txt");
+    }
+
+    #[test]
+    fn test_posix_quote_special() {
+        assert_eq!(posix_quote("with space"), "'with space'");
+        assert_eq!(posix_quote("with'quote"), "'with'\\''quote'");
+        assert_eq!(posix_quote("$HOME"), "'$HOME'");
+        assert_eq!(posix_quote(""), "''");
+    }
+
+    #[test]
+    fn test_quote_keeps_tilde_expansion() {
+        assert_eq!(posix_quote("~"), "~");
+        assert_eq!(posix_quote("~/src"), "~/src");
+        assert_eq!(posix_quote("~/my src"), "~/'my src'");
+        assert_eq!(fish_quote("~/my src"), "~/'my src'");
+        // Not a home directory
+        assert_eq!(posix_quote("a~b c"), "'a~b c'");
+    }
+
+    #[test]
+    fn test_fish_quote() {
+        assert_eq!(fish_quote("simple"), "simple");
+        assert_eq!(fish_quote("with space"), "'with space'");
+        assert_eq!(fish_quote("back\\slash"), "'back\\\\slash'");
+    }
+
+    #[test]
+    fn test_powershell_quote() {
+        assert_eq!(powershell_quote("simple"), "simple");
+        assert_eq!(powershell_quote("C:/Program Files"), "'C:/Program Files'");
+        assert_eq!(powershell_quote("$env:PATH"), "'$env:PATH'");
+        assert_eq!(powershell_quote("~/x y"), "'~/x y'");
+    }
+
+    #[test]
+    fn test_shell_quote_uses_tf_shell() {
+        let _guard = crate::test_utils::EnvGuard::new(&["TF_SHELL"]);
+
+        env::set_var("TF_SHELL", "powershell");
+        assert_eq!(shell_quote("it's here"), "'it''s here'");
+
+        env::set_var("TF_SHELL", "/usr/bin/fish");
+        assert_eq!(shell_quote("it's here"), "'it\\'s here'");
+
+        env::remove_var("TF_SHELL");
+        assert_eq!(shell_quote("it's here"), "'it'\\''s here'");
+    }
+}
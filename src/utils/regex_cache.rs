@@ -0,0 +1,95 @@
+//! Regexes compiled once and shared across rules.
+//!
+//! Dozens of rules run for every fix, and compiling their patterns on every
+//! `is_match` call costs more than the matching itself. The [`regex!`]
+//! macro compiles a literal pattern the first time it runs and returns the
+//! same [`Regex`] afterwards. Patterns built at runtime can go through
+//! [`get`], which interns them.
+//!
+//! [`regex!`]: crate::regex
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Compile a literal regex once and return a `&'static Regex`.
+///
+/// Panics if the pattern is invalid, which the tests of the calling rule
+/// catch.
+///
+/// # Example
+///
+/// ```
+/// let re = oops::regex!(r"git: '([^']*)' is not a git command");
+/// let caps = re.captures("git: 'psuh' is not a git command").unwrap();
+/// assert_eq!(&caps[1], "psuh");
+/// ```
+#[macro_export]
+macro_rules! regex {
+    ($pattern:literal $(,)?) => {{
+        static RE: ::once_cell::sync::OnceCell<::regex::Regex> = ::once_cell::sync::OnceCell::new();
+        RE.get_or_init(|| ::regex::Regex::new($pattern).expect("invalid regex"))
+    }};
+}
+
+/// Patterns compiled by [`get`].
+static CACHE: Lazy<Mutex<HashMap<String, Regex>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Compile `pattern`, or reuse it if it was compiled before.
+///
+/// Cloning a compiled [`Regex`] is cheap, so the cache hands out clones.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::regex_cache;
+///
+/// let token = "statsu";
+/// let re = regex_cache::get(&format!(r"\b{}\b", token)).unwrap();
+/// assert!(re.is_match("git statsu"));
+/// ```
+pub fn get(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(re) = cache.get(pattern) {
+        return Ok(re.clone());
+    }
+    let re = Regex::new(pattern)?;
+    cache.insert(pattern.to_string(), re.clone());
+    Ok(re)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_not_command() -> &'static Regex {
+        crate::regex!(r"git: '([^']*)' is not a git command")
+    }
+
+    #[test]
+    fn test_regex_macro_compiles_once() {
+        assert!(std::ptr::eq(git_not_command(), git_not_command()));
+        assert!(git_not_command().is_match("git: 'psuh' is not a git command"));
+    }
+
+    #[test]
+    fn test_get_caches_patterns() {
+        let re = get(r"^regex_cache_test \d+$").unwrap();
+        assert!(re.is_match("regex_cache_test 42"));
+        assert!(CACHE
+            .lock()
+            .unwrap()
+            .contains_key(r"^regex_cache_test \d+$"));
+        assert_eq!(
+            get(r"^regex_cache_test \d+$").unwrap().as_str(),
+            re.as_str()
+        );
+    }
+
+    #[test]
+    fn test_get_invalid_pattern() {
+        assert!(get(r"(unclosed").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Normalization of command output before rule matching.
+//!
+//! Tools like cargo, npm and kubectl color their errors and redraw progress
+//! lines with carriage returns. Rules match plain substrings, so the output
+//! is normalized once, when the [`Command`](crate::core::Command) is created.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// ANSI escape sequences: CSI (colors, cursor movement), OSC (titles,
+/// hyperlinks), character set selection and the remaining two-character escapes.
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|[()*+][0-9A-Za-z]|[@-Z\\-_])",
+    )
+    .expect("valid ANSI escape regex")
+});
+
+/// Remove ANSI escape sequences from `text`.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: failed"), "error: failed");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").into_owned()
+}
+
+/// Normalize command output for matching.
+///
+/// Strips ANSI escape sequences, turns CRLF line endings into LF, and keeps
+/// only the last state of lines redrawn with carriage returns, as a terminal
+/// would show it.
+///
+/// # Example
+///
+/// ```
+/// use oops::utils::normalize_output;
+///
+/// let output = "Downloading 10%\rDownloading 100%\r\n\x1b[31merror\x1b[0m\r\n";
+/// assert_eq!(normalize_output(output), "Downloading 100%\nerror\n");
+/// ```
+pub fn normalize_output(output: &str) -> String {
+    let stripped = strip_ansi(output);
+    if !stripped.contains('\r') {
+        return stripped;
+    }
+
+    stripped
+        .split('\n')
+        .map(|line| {
+            line.rsplit('\r')
+                .find(|segment| !segment.is_empty())
+                .unwrap_or("")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(
+            strip_ansi("\x1b[1m\x1b[31merror\x1b[0m\x1b[1m: could not compile\x1b[0m"),
+            "error: could not compile"
+        );
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gnpm ERR!"), "npm ERR!");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x07link\x1b]8;;\x07"),
+            "link"
+        );
+        assert_eq!(strip_ansi("\x1b(B\x1b[mok"), "ok");
+        assert_eq!(strip_ansi("\x1bMup"), "up");
+    }
+
+    #[test]
+    fn test_normalize_output_line_endings() {
+        assert_eq!(normalize_output("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize_output("a\nb"), "a\nb");
+    }
+
+    #[test]
+    fn test_normalize_output_progress_lines() {
+        assert_eq!(
+            normalize_output("[=>  ] 10%\r[===>] 100%\nerror Command failed"),
+            "[===>] 100%\nerror Command failed"
+        );
+        assert_eq!(normalize_output("done\r"), "done");
+    }
+}
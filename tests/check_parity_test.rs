@@ -12,7 +12,7 @@ use std::process::Command;
 #[ignore = "requires network access to GitHub API"]
 fn test_check_parity_runs() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity"])
+        .args(["run", "--bin", "check_parity"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -31,7 +31,7 @@ fn test_check_parity_runs() {
 #[ignore = "requires network access to GitHub API"]
 fn test_check_parity_json_output() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity", "--", "--output", "json"])
+        .args(["run", "--bin", "check_parity", "--", "--output", "json"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -73,7 +73,7 @@ fn test_check_parity_json_output() {
 #[ignore = "requires network access to GitHub API"]
 fn test_check_parity_finds_rules() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity"])
+        .args(["run", "--bin", "check_parity"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -94,7 +94,7 @@ fn test_check_parity_finds_rules() {
 #[ignore = "requires network access to GitHub API"]
 fn test_check_parity_verbose_output() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity", "--", "--verbose"])
+        .args(["run", "--bin", "check_parity", "--", "--verbose"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -117,7 +117,7 @@ fn test_uses_get_all_rules_function() {
     // This test verifies that we're using get_all_rules() from the library
     // rather than scanning source files
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity", "--", "--verbose"])
+        .args(["run", "--bin", "check_parity", "--", "--verbose"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -139,7 +139,7 @@ fn test_uses_get_all_rules_function() {
 fn test_fetches_from_github() {
     // This test verifies that we fetch thefuck rules from GitHub API
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity", "--", "--verbose"])
+        .args(["run", "--bin", "check_parity", "--", "--verbose"])
         .output()
         .expect("Failed to execute check_parity");
 
@@ -164,7 +164,7 @@ fn test_fetches_from_github() {
 #[ignore = "requires network access to GitHub API"]
 fn test_categorizes_missing_rules() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "check_parity"])
+        .args(["run", "--bin", "check_parity"])
         .output()
         .expect("Failed to execute check_parity");
 
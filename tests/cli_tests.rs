@@ -9,7 +9,7 @@ use predicates::prelude::*;
 
 /// Get the command for the oops binary.
 fn oops_cmd() -> Command {
-    Command::cargo_bin("oops").expect("Failed to find oops binary")
+    Command::new(assert_cmd::cargo::cargo_bin!("oops"))
 }
 
 // ============================================================================
@@ -283,6 +283,210 @@ fn test_help_includes_all_options() {
     }
 }
 
+// ============================================================================
+// Config Subcommand Tests
+// ============================================================================
+
+#[test]
+fn test_config_list() {
+    let mut cmd = oops_cmd();
+    cmd.env_remove("THEFUCK_WAIT_COMMAND")
+        .args(["config", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("wait_command"));
+}
+
+#[test]
+fn test_config_get_unknown_key() {
+    let mut cmd = oops_cmd();
+    cmd.args(["config", "get", "not_a_setting"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown setting"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_cache_clear() {
+    let config_home = tempfile::tempdir().unwrap();
+    let cache_dir = config_home.path().join("thefuck").join("cache");
+    std::fs::create_dir_all(&cache_dir).unwrap();
+    std::fs::write(cache_dir.join("executables.json"), "{}").unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .args(["cache", "clear"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cleared cache"));
+    assert!(!cache_dir.exists());
+
+    // Clearing an empty cache succeeds too
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .args(["cache", "clear"])
+        .assert()
+        .success();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_config_set_then_get() {
+    let config_home = tempfile::tempdir().unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .args(["config", "set", "wait_command", "9"])
+        .assert()
+        .success();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env_remove("THEFUCK_WAIT_COMMAND")
+        .args(["config", "get", "wait_command"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("9\n"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_config_upgrade() {
+    let config_home = tempfile::tempdir().unwrap();
+    let config_dir = config_home.path().join("thefuck");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let settings_path = config_dir.join("settings.toml");
+    let original = "# keep me\nexclude_rules = \"sudo\"\n";
+    std::fs::write(&settings_path, original).unwrap();
+
+    // Reading settings doesn't touch the file
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env_remove("THEFUCK_EXCLUDE_RULES")
+        .args(["config", "get", "exclude_rules"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&settings_path).unwrap(), original);
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .args(["config", "upgrade"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Upgraded"));
+
+    let upgraded = std::fs::read_to_string(&settings_path).unwrap();
+    assert!(upgraded.starts_with("# keep me\n"));
+    assert!(upgraded.contains("exclude_rules = [\"sudo\"]"));
+    assert!(config_dir.join("settings.toml.v0.bak").exists());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_config_get_with_profile() {
+    let config_home = tempfile::tempdir().unwrap();
+    let config_dir = config_home.path().join("thefuck");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        "require_confirmation = false\n[profile.prod]\nrequire_confirmation = true\n",
+    )
+    .unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env_remove("THEFUCK_REQUIRE_CONFIRMATION")
+        .env_remove("OOPS_PROFILE")
+        .args(["config", "get", "require_confirmation"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("false\n"));
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env_remove("THEFUCK_REQUIRE_CONFIRMATION")
+        .env("OOPS_PROFILE", "prod")
+        .args(["config", "get", "require_confirmation"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("true\n"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_config_set_rejects_invalid_value() {
+    let config_home = tempfile::tempdir().unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .args(["config", "set", "wait_command", "soon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid value"));
+}
+
+#[test]
+fn test_config_schema() {
+    let mut cmd = oops_cmd();
+    let output = cmd.args(["config", "schema"]).output().unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(schema["properties"]["exclude_rules"].is_object());
+    assert!(schema["properties"]["profile"].is_object());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_config_warns_about_unknown_keys() {
+    let config_home = tempfile::tempdir().unwrap();
+    let config_dir = config_home.path().join("thefuck");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        "wait_command = 4\nexclude_rule = [\"sudo\"]\n",
+    )
+    .unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env_remove("RUST_LOG")
+        .args(["config", "get", "wait_command"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "settings.toml:2: unknown setting `exclude_rule` (did you mean `exclude_rules`?)",
+        ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_migrate_thefuck() {
+    let config_home = tempfile::tempdir().unwrap();
+    let config_dir = config_home.path().join("thefuck");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.py"),
+        "rules = ['sudo', 'git_push']\nwait_command = 7\nrepeat = True\n",
+    )
+    .unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path());
+    for (name, _) in std::env::vars().filter(|(name, _)| name.starts_with("THEFUCK_")) {
+        cmd.env_remove(name);
+    }
+    cmd.arg("migrate-thefuck")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Migrated 2 setting(s)"))
+        .stdout(predicate::str::contains("`repeat` has no oops equivalent"));
+
+    let written = std::fs::read_to_string(config_dir.join("settings.toml")).unwrap();
+    assert!(written.contains("wait_command = 7"));
+}
+
 // ============================================================================
 // Error Message Tests
 // ============================================================================
@@ -309,16 +513,70 @@ fn test_basic_invocation() {
 #[test]
 fn test_no_history_available() {
     let mut cmd = oops_cmd();
-    cmd.env_remove("TF_HISTORY")
+    let _ = cmd
+        .env_remove("TF_HISTORY")
         .env_remove("THEFUCK_HISTORY")
         .assert();
     // Should handle gracefully
 }
 
+#[cfg(unix)]
+#[test]
+fn test_uses_captured_output_instead_of_rerunning() {
+    let config_home = tempfile::tempdir().unwrap();
+    let marker = config_home.path().join("ran");
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env("TF_HISTORY", format!("touch {}", marker.display()))
+        .env("TF_OUTPUT", "touch: cannot touch: Permission denied")
+        .args(["--yes", "--enable-experimental-instant-mode"])
+        .assert()
+        .success();
+
+    assert!(!marker.exists(), "command was re-executed");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exits_with_correction_exit_code() {
+    let config_home = tempfile::tempdir().unwrap();
+    let rules_dir = config_home.path().join("thefuck").join("rules");
+    std::fs::create_dir_all(&rules_dir).unwrap();
+    std::fs::write(
+        rules_dir.join("failing.toml"),
+        "script = \"^mkae$\"\nreplace = \"exit 5\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env("TF_HISTORY", "mkae")
+        .env("TF_OUTPUT", "")
+        .arg("--yes")
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn test_exits_with_distinct_code_without_correction() {
+    let config_home = tempfile::tempdir().unwrap();
+
+    let mut cmd = oops_cmd();
+    cmd.env("XDG_CONFIG_HOME", config_home.path())
+        .env("TF_HISTORY", "true")
+        .env("TF_OUTPUT", "")
+        .arg("--yes")
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("No corrections available"));
+}
+
 #[test]
 fn test_placeholder_handling() {
     let mut cmd = oops_cmd();
-    cmd.arg("THEFUCK_ARGUMENT_PLACEHOLDER")
+    let _ = cmd
+        .arg("THEFUCK_ARGUMENT_PLACEHOLDER")
         .arg("git")
         .arg("status")
         .env("TF_HISTORY", "")
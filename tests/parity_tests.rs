@@ -6,70 +6,10 @@
 //! Run with: `cargo test --test parity_tests`
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::Command;
 
-use oops::core::{Command as TfCommand, Rule};
+use oops::core::Command as TfCommand;
 use oops::rules::get_all_rules;
 
-// ============================================================================
-// Test Infrastructure
-// ============================================================================
-
-/// Get the path to the Python thefuck installation.
-fn get_python_thefuck_path() -> Option<PathBuf> {
-    let possible_paths = if cfg!(windows) {
-        vec![
-            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .parent()
-                .map(|p| p.to_path_buf()),
-            Some(PathBuf::from(r"C:\Python\Scripts\thefuck")),
-        ]
-    } else {
-        vec![
-            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .parent()
-                .map(|p| p.to_path_buf()),
-            Some(PathBuf::from("/usr/local/bin/thefuck")),
-        ]
-    };
-
-    for path_opt in possible_paths.into_iter().flatten() {
-        if path_opt.exists() {
-            return Some(path_opt);
-        }
-    }
-    None
-}
-
-/// Check if Python is available.
-fn python_available() -> bool {
-    Command::new("python")
-        .arg("--version")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-        || Command::new("python3")
-            .arg("--version")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-}
-
-/// Get the Python executable name.
-fn get_python_executable() -> &'static str {
-    if Command::new("python3")
-        .arg("--version")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-    {
-        "python3"
-    } else {
-        "python"
-    }
-}
-
 // ============================================================================
 // Test Case Definitions
 // ============================================================================
@@ -256,10 +196,7 @@ fn test_no_command_rule_matches() {
         .collect();
 
     let rules = get_all_rules();
-    let no_command_rule = rules
-        .iter()
-        .find(|r: &&Box<dyn Rule>| r.name() == "no_command")
-        .unwrap();
+    let no_command_rule = rules.iter().find(|r| r.name() == "no_command").unwrap();
 
     for test_case in test_cases {
         let cmd = TfCommand::new(test_case.script, test_case.output);
@@ -282,7 +219,7 @@ fn test_rule_priority_ordering() {
     let rules = get_all_rules();
     let sudo_priority = rules
         .iter()
-        .find(|r: &&Box<dyn Rule>| r.name() == "sudo")
+        .find(|r| r.name() == "sudo")
         .map(|r| r.priority());
 
     assert!(sudo_priority.is_some(), "sudo rule should exist");
@@ -298,18 +235,12 @@ fn test_no_false_positives() {
 
     // A successful command shouldn't match sudo
     let successful_cmd = TfCommand::new("ls /home", "file1 file2 file3");
-    let sudo_rule = rules
-        .iter()
-        .find(|r: &&Box<dyn Rule>| r.name() == "sudo")
-        .unwrap();
+    let sudo_rule = rules.iter().find(|r| r.name() == "sudo").unwrap();
     assert!(!sudo_rule.is_match(&successful_cmd));
 
     // A normal cd shouldn't match cd_parent
     let normal_cd = TfCommand::new("cd /home", "");
-    let cd_parent_rule = rules
-        .iter()
-        .find(|r: &&Box<dyn Rule>| r.name() == "cd_parent")
-        .unwrap();
+    let cd_parent_rule = rules.iter().find(|r| r.name() == "cd_parent").unwrap();
     assert!(!cd_parent_rule.is_match(&normal_cd));
 }
 
@@ -405,7 +336,7 @@ fn test_rule_matching(rule_name: &str, test_data: &RuleTestData) {
     let rules = get_all_rules();
     let rule = rules
         .iter()
-        .find(|r: &&Box<dyn Rule>| r.name() == rule_name)
+        .find(|r| r.name() == rule_name)
         .unwrap_or_else(|| panic!("Rule '{}' not found", rule_name));
 
     for (script, output) in &test_data.matching_cases {